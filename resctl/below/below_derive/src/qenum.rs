@@ -138,9 +138,10 @@ pub fn enum_iter_derive_impl(ast: &DeriveInput) -> syn::Result<TokenStream> {
         _ => false,
     });
 
-    // Iterate over all enums by chaining them together. Unit variants are
-    // wrapped in std::iter::once. Basically a DFS over the enum tree.
-    let all_variant_iter_chain = variants
+    // Iterate over all enums by collecting them into a Vec. Unit variants are
+    // pushed as is. Basically a DFS over the enum tree. A flat Vec keeps the
+    // iterator type shallow no matter how many variants there are.
+    let all_variant_pushes = variants
         .iter()
         .map(|variant| {
             let variant_name = &variant.ident;
@@ -149,11 +150,11 @@ pub fn enum_iter_derive_impl(ast: &DeriveInput) -> syn::Result<TokenStream> {
                     let nested_type = &unnamed.unnamed[0].ty;
                     let turbofish = ensure_turbofish(nested_type.clone());
                     Ok(quote! {
-                        .chain(#turbofish::all_variant_iter().into_iter().map(Self::#variant_name))
+                        variants.extend(#turbofish::all_variant_iter().map(Self::#variant_name));
                     })
                 }
                 syn::Fields::Unit => Ok(quote! {
-                    .chain(::std::iter::once(Self::#variant_name))
+                    variants.push(Self::#variant_name);
                 }),
                 _ => Err(variant_constraint_error(variant.span())),
             }
@@ -168,8 +169,10 @@ pub fn enum_iter_derive_impl(ast: &DeriveInput) -> syn::Result<TokenStream> {
             }
 
             pub fn all_variant_iter() -> impl ::std::iter::Iterator<Item = Self> {
-                ::std::iter::empty()
-                #(#all_variant_iter_chain)*
+                #[allow(unused_mut)]
+                let mut variants = ::std::vec::Vec::new();
+                #(#all_variant_pushes)*
+                variants.into_iter()
             }
         }
     })
@@ -143,6 +143,7 @@ struct QueriableFieldProps {
     pub preferred_name: Ident,
     pub ident: Ident,
     pub variant_name: Ident,
+    pub ty: syn::Type,
     pub option_type: Option<syn::Type>,
 }
 
@@ -211,6 +212,7 @@ fn get_queriable_field_props(field: &Field) -> syn::Result<QueriableFieldProps>
         preferred_name,
         ident,
         variant_name,
+        ty: field.ty.clone(),
         option_type,
     })
 }
@@ -273,6 +275,23 @@ pub fn queriable_derive_impl(ast: &DeriveInput) -> syn::Result<TokenStream> {
         }
     });
 
+    let field_type_match_arms = all_field_props.iter().map(|field_props| {
+        let variant_name = &field_props.variant_name;
+        match &field_props.subquery {
+            Some(subquery_field_id_type) => quote! {
+                Self::FieldId::#variant_name(field_id) => <
+                    <#subquery_field_id_type as FieldId>::Queriable as Queriable
+                >::field_type(field_id),
+            },
+            None => {
+                let field_type = field_props.option_type.as_ref().unwrap_or(&field_props.ty);
+                quote! {
+                    Self::FieldId::#variant_name => <#field_type as HasFieldType>::FIELD_TYPE,
+                }
+            }
+        }
+    });
+
     Ok(quote! {
         #[derive(
             Clone,
@@ -297,6 +316,11 @@ pub fn queriable_derive_impl(ast: &DeriveInput) -> syn::Result<TokenStream> {
                     #(#queriable_match_arms)*
                 }
             }
+            fn field_type(field_id: &Self::FieldId) -> FieldType {
+                match field_id {
+                    #(#field_type_match_arms)*
+                }
+            }
         }
     })
 }
@@ -0,0 +1,35 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+
+use libbpf_cargo::SkeletonBuilder;
+
+const PROGS: &[&str] = &["exitstat", "netbytes", "oomkill", "procevents", "runqlat"];
+
+fn main() {
+    // Skeletons cannot be generated into OUT_DIR: they carry inner
+    // attributes that cannot be include!()ed, and #[path] does not accept
+    // env!("OUT_DIR"). So they are generated next to the bpf sources and
+    // ignored by git.
+    for prog in PROGS {
+        let src = format!("./src/bpf/{}.bpf.c", prog);
+        let skel = format!("./src/bpf/{}.skel.rs", prog);
+        SkeletonBuilder::new(&src)
+            .generate(Path::new(&skel))
+            .unwrap_or_else(|e| panic!("Failed to build {} bpf skeleton: {}", prog, e));
+        println!("cargo:rerun-if-changed={}", src);
+    }
+    println!("cargo:rerun-if-changed=./src/open_source/vmlinux.h");
+}
@@ -0,0 +1,178 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use std::cell::RefCell;
+
+use command::DumpModel;
+
+/// Dumps multiple models from the same Model of each time step, so that the
+/// store is only walked and decoded once. Output of each model is tagged with
+/// the model it comes from.
+pub struct All {
+    opts: GeneralOpt,
+    dumpers: Vec<(DumpModel, Box<dyn Dumper>)>,
+    // Each dumper counts its own rounds, e.g. to print its title once.
+    rounds: RefCell<Vec<usize>>,
+}
+
+impl All {
+    pub fn new(opts: &GeneralOpt, models: Vec<DumpModel>) -> Result<Self> {
+        let detail = opts.everything || opts.detail;
+        let mut model_opts = opts.to_owned();
+        // The Entity column tells the lines of different models apart
+        if matches!(
+            opts.output_format,
+            Some(OutputFormat::Csv) | Some(OutputFormat::Tsv)
+        ) {
+            model_opts.entity_header = true;
+        }
+        let opts = &model_opts;
+        let dumpers = models
+            .into_iter()
+            .map(|model| {
+                let dumper: Box<dyn Dumper> = match model {
+                    DumpModel::System => Box::new(system::System::new(
+                        opts,
+                        expand_fields(command::DEFAULT_SYSTEM_FIELDS, detail),
+                    )?),
+                    DumpModel::Disk => Box::new(disk::Disk::new(
+                        opts,
+                        None,
+                        expand_fields(command::DEFAULT_DISK_FIELDS, detail),
+                    )?),
+                    DumpModel::Process => Box::new(process::Process::new(
+                        opts,
+                        None,
+                        expand_fields(command::DEFAULT_PROCESS_FIELDS, detail),
+                        false,
+                    )?),
+                    DumpModel::Cgroup => Box::new(cgroup::Cgroup::new(
+                        opts,
+                        None,
+                        expand_fields(command::DEFAULT_CGROUP_FIELDS, detail),
+                    )?),
+                    DumpModel::Iface => Box::new(iface::Iface::new(
+                        opts,
+                        None,
+                        expand_fields(command::DEFAULT_IFACE_FIELDS, detail),
+                    )?),
+                    DumpModel::Network => Box::new(network::Network::new(
+                        opts,
+                        expand_fields(command::DEFAULT_NETWORK_FIELDS, detail),
+                    )?),
+                    DumpModel::Transport => Box::new(transport::Transport::new(
+                        opts,
+                        expand_fields(command::DEFAULT_TRANSPORT_FIELDS, detail),
+                    )?),
+                    DumpModel::Events => Box::new(events::Events::new(
+                        opts,
+                        None,
+                        expand_fields(command::DEFAULT_EVENT_FIELDS, detail),
+                    )?),
+                    DumpModel::Oom => Box::new(oom::Oom::new(
+                        opts,
+                        None,
+                        expand_fields(command::DEFAULT_OOM_FIELDS, detail),
+                    )?),
+                    DumpModel::Procevents => Box::new(procevents::Procevents::new(
+                        opts,
+                        None,
+                        expand_fields(command::DEFAULT_PROC_EVENT_FIELDS, detail),
+                    )?),
+                };
+                Ok((model, dumper))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            opts: model_opts,
+            rounds: RefCell::new(vec![0; dumpers.len()]),
+            dumpers,
+        })
+    }
+}
+
+impl Dumper for All {
+    fn dump_model(
+        &self,
+        ctx: &CommonFieldContext,
+        model: &model::Model,
+        output: &mut dyn Write,
+        round: &mut usize,
+        comma_flag: bool,
+    ) -> Result<IterExecResult> {
+        let mut rounds = self.rounds.borrow_mut();
+        let mut outputs = Vec::new();
+        for ((dump_model, dumper), model_round) in self.dumpers.iter().zip(rounds.iter_mut()) {
+            let mut model_output: Vec<u8> = Vec::new();
+            let begin_round = *model_round;
+            if dumper.dump_model(ctx, model, &mut model_output, model_round, false)?
+                == IterExecResult::Skip
+            {
+                continue;
+            }
+            *round += *model_round - begin_round;
+            outputs.push((dump_model.to_string(), model_output));
+        }
+
+        if outputs.is_empty() {
+            return Ok(IterExecResult::Skip);
+        }
+
+        match self.opts.output_format {
+            Some(OutputFormat::Raw) | Some(OutputFormat::KeyVal) | None => {
+                for (name, model_output) in outputs {
+                    writeln!(output, "[{}]", name)?;
+                    output.write_all(&model_output)?;
+                }
+            }
+            // Lines are tagged by the Entity column
+            Some(OutputFormat::Csv) | Some(OutputFormat::Tsv) => {
+                for (_, model_output) in outputs {
+                    output.write_all(&model_output)?;
+                }
+            }
+            Some(OutputFormat::Json) => {
+                let mut jval = json!({});
+                for (name, model_output) in outputs {
+                    jval[name] = serde_json::from_slice(&model_output)?;
+                }
+                if comma_flag {
+                    write!(output, ",{}", jval)?;
+                } else {
+                    write!(output, "{}", jval)?;
+                }
+            }
+            Some(OutputFormat::NdJson) => {
+                for (name, model_output) in outputs {
+                    for line in model_output.split(|b| *b == b'\n') {
+                        if line.is_empty() {
+                            continue;
+                        }
+                        let mut jval: Value = serde_json::from_slice(line)?;
+                        jval["Entity"] = json!(name);
+                        writeln!(output, "{}", jval)?;
+                    }
+                }
+            }
+            // Rejected before dumping, a file has a single schema
+            Some(OutputFormat::Parquet) | Some(OutputFormat::Arrow) => {
+                bail!("dump all does not support parquet or arrow output")
+            }
+        }
+
+        Ok(IterExecResult::Success)
+    }
+}
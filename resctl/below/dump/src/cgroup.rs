@@ -17,6 +17,7 @@ use super::*;
 use model::CgroupModelFieldId;
 use render::RenderConfig;
 
+use std::collections::BTreeSet;
 use std::iter::FromIterator;
 
 impl HasRenderConfigForDump for model::CgroupModel {
@@ -28,9 +29,14 @@ impl HasRenderConfigForDump for model::CgroupModel {
             WiosPerSec,
         };
         use model::CgroupMemoryModelFieldId::{
-            Anon, File, Pgactivate, Pgdeactivate, Pgfault, Pglazyfree, Pglazyfreed, Pgmajfault,
-            Pgrefill, Pgscan, Pgsteal, Shmem, Slab, Sock, Swap, ThpCollapseAlloc, ThpFaultAlloc,
-            Total, WorkingsetActivate, WorkingsetNodereclaim, WorkingsetRefault,
+            Anon, EventsLocalHigh, EventsLocalLow, EventsLocalMax, EventsLocalOom,
+            EventsLocalOomKill, File, Pgactivate, Pgdeactivate, Pgfault, Pglazyfree, Pglazyfreed,
+            Pgmajfault, Pgrefill, Pgscan, PgscanDirect, PgscanKswapd, Pgsteal, PgstealDirect,
+            PgstealKswapd, Shmem, Slab, Sock, Swap, SwapEventsFail, SwapEventsHigh, SwapEventsMax,
+            ThpCollapseAlloc, ThpFaultAlloc, ThpSwpout, ThpSwpoutFallback, Total,
+            WorkingsetActivate, WorkingsetActivateAnon, WorkingsetActivateFile,
+            WorkingsetNodereclaim, WorkingsetRefault, WorkingsetRefaultAnon, WorkingsetRefaultFile,
+            WorkingsetRestoreAnon, WorkingsetRestoreFile, Zswpin, Zswpout,
         };
         use model::CgroupModelFieldId::{Cpu, Io, Mem, Name, Pressure};
         use model::CgroupPressureModelFieldId::{MemoryFullPct, MemorySomePct};
@@ -68,6 +74,28 @@ impl HasRenderConfigForDump for model::CgroupModel {
             Mem(Pglazyfreed) => rc.title("Pglazyfreed"),
             Mem(ThpFaultAlloc) => rc.title("THP Fault Alloc"),
             Mem(ThpCollapseAlloc) => rc.title("THP Collapse Alloc"),
+            Mem(WorkingsetRefaultAnon) => rc.title("Refault Anon"),
+            Mem(WorkingsetRefaultFile) => rc.title("Refault File"),
+            Mem(WorkingsetActivateAnon) => rc.title("Activate Anon"),
+            Mem(WorkingsetActivateFile) => rc.title("Activate File"),
+            Mem(WorkingsetRestoreAnon) => rc.title("Restore Anon"),
+            Mem(WorkingsetRestoreFile) => rc.title("Restore File"),
+            Mem(PgscanKswapd) => rc.title("Pgscan Kswapd"),
+            Mem(PgscanDirect) => rc.title("Pgscan Direct"),
+            Mem(PgstealKswapd) => rc.title("Pgsteal Kswapd"),
+            Mem(PgstealDirect) => rc.title("Pgsteal Direct"),
+            Mem(Zswpin) => rc.title("Zswpin"),
+            Mem(Zswpout) => rc.title("Zswpout"),
+            Mem(ThpSwpout) => rc.title("THP Swpout"),
+            Mem(ThpSwpoutFallback) => rc.title("THP Swpout Fallback"),
+            Mem(EventsLocalLow) => rc.title("Local Low"),
+            Mem(EventsLocalHigh) => rc.title("Local High"),
+            Mem(EventsLocalMax) => rc.title("Local Max"),
+            Mem(EventsLocalOom) => rc.title("Local OOM"),
+            Mem(EventsLocalOomKill) => rc.title("Local Kill"),
+            Mem(SwapEventsHigh) => rc.title("Swap High"),
+            Mem(SwapEventsMax) => rc.title("Swap Max"),
+            Mem(SwapEventsFail) => rc.title("Swap Fail"),
             Pressure(MemorySomePct) => rc.title("Memory Some Pressure"),
             Pressure(MemoryFullPct) => rc.title("Memory Pressure"),
             _ => rc,
@@ -80,6 +108,7 @@ pub struct Cgroup {
     opts: GeneralOpt,
     select: Option<CgroupModelFieldId>,
     fields: Vec<CgroupField>,
+    where_expr: Option<model::FilterExpr<CgroupModelFieldId>>,
 }
 
 impl Cgroup {
@@ -87,12 +116,30 @@ impl Cgroup {
         opts: &GeneralOpt,
         select: Option<CgroupModelFieldId>,
         fields: Vec<CgroupField>,
-    ) -> Self {
-        Self {
+    ) -> Result<Self> {
+        Ok(Self {
             opts: opts.to_owned(),
             select,
             fields,
-        }
+            where_expr: parse_where(opts)?,
+        })
+    }
+
+    /// Whether the cgroup matches --filter on the selected field and --where.
+    fn should_print(&self, model: &model::CgroupModel) -> bool {
+        let filter_match = match (self.select.as_ref(), self.opts.filter.as_ref()) {
+            (Some(field_id), Some(filter)) => filter.is_match(
+                &model
+                    .query(field_id)
+                    .map_or("?".to_owned(), |v| v.to_string()),
+            ),
+            _ => true,
+        };
+        filter_match
+            && self
+                .where_expr
+                .as_ref()
+                .map_or(true, |where_expr| where_expr.eval(model))
     }
 }
 
@@ -115,16 +162,7 @@ impl Dumper for Cgroup {
             jval: &mut Value,
         ) -> Result<()> {
             //filter
-            let should_print = match (handle.select.as_ref(), handle.opts.filter.as_ref()) {
-                (Some(field_id), Some(filter)) => filter.is_match(
-                    &model
-                        .query(&field_id)
-                        .map_or("?".to_owned(), |v| v.to_string()),
-                ),
-                _ => true,
-            };
-
-            if should_print {
+            if handle.should_print(model) {
                 match handle.opts.output_format {
                     Some(OutputFormat::Raw) | None => write!(
                         output,
@@ -139,17 +177,10 @@ impl Dumper for Cgroup {
                             handle.opts.raw,
                         )
                     )?,
-                    Some(OutputFormat::Csv) => write!(
+                    Some(OutputFormat::Csv) | Some(OutputFormat::Tsv) => write!(
                         output,
                         "{}",
-                        print::dump_csv(
-                            &handle.fields,
-                            ctx,
-                            model,
-                            *round,
-                            handle.opts.disable_title,
-                            handle.opts.raw,
-                        )
+                        print::dump_csv(&handle.fields, ctx, model, *round, "cgroup", &handle.opts)
                     )?,
                     Some(OutputFormat::KeyVal) => write!(
                         output,
@@ -160,6 +191,15 @@ impl Dumper for Cgroup {
                         *jval = print::dump_json(&handle.fields, ctx, model, handle.opts.raw);
                         jval["children"] = json!([]);
                     }
+                    // One line per cgroup, without nesting
+                    Some(OutputFormat::NdJson) => writeln!(
+                        output,
+                        "{}",
+                        print::dump_json(&handle.fields, ctx, model, handle.opts.raw)
+                    )?,
+                    Some(OutputFormat::Parquet) | Some(OutputFormat::Arrow) => {
+                        print::dump_row(&handle.fields, ctx, model)
+                    }
                 };
                 *round += 1;
             }
@@ -206,4 +246,45 @@ impl Dumper for Cgroup {
 
         Ok(IterExecResult::Success)
     }
+    fn dump_compare(
+        &self,
+        base: (&CommonFieldContext, &model::Model),
+        target: (&CommonFieldContext, &model::Model),
+        output: &mut dyn Write,
+    ) -> Result<()> {
+        let base_cgroups = model::flatten_cgroups(&base.1.cgroup);
+        let target_cgroups = model::flatten_cgroups(&target.1.cgroup);
+        let paths: BTreeSet<&str> = base_cgroups
+            .keys()
+            .chain(target_cgroups.keys())
+            .copied()
+            .collect();
+
+        let mut cgroups = Vec::new();
+        for path in paths {
+            let base_cgroup = base_cgroups.get(path).copied();
+            let target_cgroup = target_cgroups.get(path).copied();
+            // Filter on the latest state of the cgroup.
+            if let Some(cgroup) = target_cgroup.or(base_cgroup) {
+                if self.should_print(cgroup) {
+                    let presence = model::Presence::of_cgroup(base_cgroup, target_cgroup);
+                    cgroups.push((base_cgroup, target_cgroup, presence));
+                }
+            }
+        }
+
+        write!(
+            output,
+            "{}",
+            print::dump_compare(
+                &self.fields,
+                (base.0, target.0),
+                &cgroups,
+                self.opts.output_format,
+                self.opts.disable_title,
+                self.opts.raw,
+            )
+        )?;
+        Ok(())
+    }
 }
@@ -0,0 +1,248 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Columnar output of dump commands. Dumpers add each entity as a row of
+//! typed Fields with print::dump_row, which are collected here into Arrow
+//! record batches and encoded as a Parquet or an Arrow IPC file.
+
+use super::*;
+
+use std::sync::Arc;
+
+use arrow_array::{
+    ArrayRef, Float64Array, Int32Array, Int64Array, RecordBatch, StringArray, TimestampSecondArray,
+    UInt32Array, UInt64Array,
+};
+use arrow_ipc::writer::FileWriter;
+use arrow_schema::{DataType, Field as ArrowField, Schema, SchemaRef, TimeUnit};
+use model::FieldType;
+use parquet::arrow::ArrowWriter;
+
+/// Number of rows written to the file at a time.
+const BATCH_ROWS: usize = 64 * 1024;
+/// Title of the hostname column, the same as the one of the system hostname
+/// field.
+const HOSTNAME_TITLE: &str = "Hostname";
+
+/// An entity dumped for columnar output: the title, type and value of each
+/// field.
+pub type Row = Vec<(String, FieldType, Option<Field>)>;
+
+enum FileEncoder {
+    Parquet(ArrowWriter<Vec<u8>>),
+    Arrow(FileWriter<Vec<u8>>),
+}
+
+impl FileEncoder {
+    fn new(output_format: OutputFormat, schema: SchemaRef) -> Result<Self> {
+        Ok(match output_format {
+            OutputFormat::Parquet => Self::Parquet(ArrowWriter::try_new(Vec::new(), schema, None)?),
+            OutputFormat::Arrow => Self::Arrow(FileWriter::try_new(Vec::new(), &schema)?),
+            _ => bail!("{:?} is not a columnar output format", output_format),
+        })
+    }
+
+    /// Encodes batch and returns the bytes ready to be written out.
+    fn write(&mut self, batch: &RecordBatch) -> Result<Vec<u8>> {
+        Ok(match self {
+            Self::Parquet(writer) => {
+                writer.write(batch)?;
+                // Write a row group per batch so that memory usage is bounded
+                writer.flush()?;
+                std::mem::take(writer.inner_mut())
+            }
+            Self::Arrow(writer) => {
+                writer.write(batch)?;
+                std::mem::take(writer.get_mut())
+            }
+        })
+    }
+
+    /// Writes the file footer and returns the remaining bytes.
+    fn finish(self) -> Result<Vec<u8>> {
+        Ok(match self {
+            Self::Parquet(writer) => writer.into_inner()?,
+            Self::Arrow(writer) => writer.into_inner()?,
+        })
+    }
+}
+
+/// Collects the rows of dumped entities into Parquet or Arrow IPC files, one
+/// row per entity per sample, with a timestamp and a hostname column first.
+/// The hostname column is left out if the rows already have one. The type
+/// of each column is the FieldType of its field, so it does not depend on
+/// the values dumped.
+pub struct ColumnarWriter {
+    output_format: OutputFormat,
+    titles: Vec<(String, FieldType)>,
+    timestamps: Vec<i64>,
+    /// None if the rows have a hostname column.
+    hostnames: Option<Vec<String>>,
+    columns: Vec<Vec<Option<Field>>>,
+    schema: Option<SchemaRef>,
+    encoder: Option<FileEncoder>,
+}
+
+impl ColumnarWriter {
+    pub fn new(output_format: OutputFormat) -> Self {
+        Self {
+            output_format,
+            titles: Vec::new(),
+            timestamps: Vec::new(),
+            hostnames: None,
+            columns: Vec::new(),
+            schema: None,
+            encoder: None,
+        }
+    }
+
+    /// Adds rows of a sample, written out once a batch is full.
+    pub fn push_rows(
+        &mut self,
+        rows: Vec<Row>,
+        timestamp: i64,
+        hostname: &str,
+        output: &mut dyn Write,
+    ) -> Result<()> {
+        for row in rows {
+            if self.schema.is_none() {
+                self.titles = row
+                    .iter()
+                    .map(|(title, field_type, _)| (title.clone(), *field_type))
+                    .collect();
+                self.columns = vec![Vec::new(); self.titles.len()];
+                if !self.titles.iter().any(|(title, _)| title == HOSTNAME_TITLE) {
+                    self.hostnames = Some(Vec::new());
+                }
+                self.schema = Some(self.get_schema());
+            }
+            if row.len() != self.titles.len()
+                || row
+                    .iter()
+                    .zip(&self.titles)
+                    .any(|((a, a_type, _), (b, b_type))| a != b || a_type != b_type)
+            {
+                bail!("Fields of all rows must be the same for columnar output");
+            }
+            for ((_, _, value), column) in row.into_iter().zip(self.columns.iter_mut()) {
+                column.push(value);
+            }
+            self.timestamps.push(timestamp);
+            if let Some(hostnames) = self.hostnames.as_mut() {
+                hostnames.push(hostname.to_owned());
+            }
+            if self.timestamps.len() >= BATCH_ROWS {
+                self.flush(output)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the remaining rows and the file footer. Nothing is written if
+    /// there are no rows at all.
+    pub fn finish(mut self, output: &mut dyn Write) -> Result<()> {
+        self.flush(output)?;
+        if let Some(encoder) = self.encoder.take() {
+            output.write_all(&encoder.finish()?)?;
+        }
+        Ok(())
+    }
+
+    fn get_schema(&self) -> SchemaRef {
+        let mut fields = vec![ArrowField::new(
+            "Timestamp",
+            DataType::Timestamp(TimeUnit::Second, Some("+00:00".into())),
+            false,
+        )];
+        if self.hostnames.is_some() {
+            fields.push(ArrowField::new(HOSTNAME_TITLE, DataType::Utf8, false));
+        }
+        for (title, field_type) in &self.titles {
+            fields.push(ArrowField::new(title, get_data_type(*field_type), true));
+        }
+        Arc::new(Schema::new(fields))
+    }
+
+    fn flush(&mut self, output: &mut dyn Write) -> Result<()> {
+        if self.timestamps.is_empty() {
+            return Ok(());
+        }
+        let schema = self
+            .schema
+            .clone()
+            .expect("Schema is set with the first row");
+        let mut arrays: Vec<ArrayRef> = vec![Arc::new(
+            TimestampSecondArray::from(std::mem::take(&mut self.timestamps))
+                .with_timezone("+00:00"),
+        )];
+        if let Some(hostnames) = self.hostnames.as_mut() {
+            arrays.push(Arc::new(StringArray::from(std::mem::take(hostnames))));
+        }
+        let fields = &schema.fields()[arrays.len()..];
+        for (field, column) in fields.iter().zip(self.columns.iter_mut()) {
+            let values = std::mem::take(column);
+            arrays.push(to_array(field.name(), field.data_type(), values)?);
+        }
+        let batch = RecordBatch::try_new(schema.clone(), arrays)?;
+        if self.encoder.is_none() {
+            self.encoder = Some(FileEncoder::new(self.output_format, schema)?);
+        }
+        let bytes = self.encoder.as_mut().unwrap().write(&batch)?;
+        output.write_all(&bytes)?;
+        Ok(())
+    }
+}
+
+fn get_data_type(field_type: FieldType) -> DataType {
+    match field_type {
+        FieldType::U32 => DataType::UInt32,
+        FieldType::U64 => DataType::UInt64,
+        FieldType::I32 => DataType::Int32,
+        FieldType::I64 => DataType::Int64,
+        FieldType::F64 => DataType::Float64,
+        FieldType::Str | FieldType::PidState => DataType::Utf8,
+    }
+}
+
+/// Builds an array of data_type from the values of a column. Values of
+/// columns typed as strings are rendered with their Display impl.
+fn to_array(title: &str, data_type: &DataType, values: Vec<Option<Field>>) -> Result<ArrayRef> {
+    macro_rules! typed_array {
+        ($array:ident, $variant:ident) => {
+            Arc::new(
+                values
+                    .into_iter()
+                    .map(|value| match value {
+                        None => Ok(None),
+                        Some(Field::$variant(v)) => Ok(Some(v)),
+                        Some(v) => bail!("Field {} is {:?} but got value {}", title, data_type, v),
+                    })
+                    .collect::<Result<$array>>()?,
+            )
+        };
+    }
+    Ok(match data_type {
+        DataType::UInt32 => typed_array!(UInt32Array, U32),
+        DataType::UInt64 => typed_array!(UInt64Array, U64),
+        DataType::Int32 => typed_array!(Int32Array, I32),
+        DataType::Int64 => typed_array!(Int64Array, I64),
+        DataType::Float64 => typed_array!(Float64Array, F64),
+        _ => Arc::new(
+            values
+                .into_iter()
+                .map(|value| value.map(|v| v.to_string()))
+                .collect::<StringArray>(),
+        ),
+    })
+}
@@ -14,8 +14,9 @@
 
 use crate::{CommonField, DumpField};
 use model::{
-    CgroupModelFieldId, FieldId, NetworkModelFieldId, SingleDiskModelFieldId,
-    SingleNetModelFieldId, SingleProcessModelFieldId, SystemModelFieldId,
+    CgroupModelFieldId, EventModelFieldId, FieldId, NetworkModelFieldId, OomKillModelFieldId,
+    ProcEventModelFieldId, SingleDiskModelFieldId, SingleNetModelFieldId,
+    SingleProcessModelFieldId, SystemModelFieldId,
 };
 
 use anyhow::{bail, Error, Result};
@@ -175,7 +176,22 @@ impl AggField<SystemModelFieldId> for SystemAggField {
                     .into_iter()
                     .map(FieldId::Mem)
                     .collect(),
-                Self::Vm => Vm::unit_variant_iter().map(FieldId::Vm).collect(),
+                Self::Vm => vec![
+                    Vm::PgpginPerSec,
+                    Vm::PgpgoutPerSec,
+                    Vm::PswpinPerSec,
+                    Vm::PswpoutPerSec,
+                    Vm::PgstealKswapd,
+                    Vm::PgstealDirect,
+                    Vm::PgscanKswapd,
+                    Vm::PgscanDirect,
+                    Vm::OomKill,
+                    Vm::KswapdReclaimEfficiencyPct,
+                    Vm::DirectReclaimEfficiencyPct,
+                ]
+                .into_iter()
+                .map(FieldId::Vm)
+                .collect(),
                 Self::Stat => Stat::unit_variant_iter().map(FieldId::Stat).collect(),
             }
         }
@@ -212,6 +228,12 @@ static SYSTEM_LONG_ABOUT: Lazy<String> = Lazy::new(|| {
 cpus.N.<cpu_field> for individual CPU data. N is individual CPU index and
 <cpu_field> is same as cpu fields above with `cpu.` prefix stripped.
 
+softnet.N.<softnet_field> for individual CPU softnet data. N is the CPU id
+and <softnet_field> is one of [{softnet_fields}].
+
+thermal_zones.N.<thermal_field> for thermal zone data. N is the number of the
+thermal_zoneN sysfs directory and <thermal_field> is one of [{thermal_fields}].
+
 {all_memory_fields}
 
 {all_vm_fields}
@@ -243,6 +265,8 @@ $ below dump system -b "08:30:00" -e "08:30:30" -f datetime vm hostname -O csv
         common_fields = join(CommonField::unit_variant_iter()),
         system_fields = join(SystemModelFieldId::unit_variant_iter()),
         all_cpu_fields = join(SystemAggField::Cpu.expand(true)),
+        softnet_fields = join(model::SingleSoftnetModelFieldId::unit_variant_iter()),
+        thermal_fields = join(model::SingleThermalZoneModelFieldId::unit_variant_iter()),
         all_memory_fields = join(SystemAggField::Mem.expand(true)),
         all_vm_fields = join(SystemAggField::Vm.expand(true)),
         all_stat_fields = join(SystemAggField::Stat.expand(true)),
@@ -374,10 +398,12 @@ pub enum ProcessAggField {
     Cpu,
     Mem,
     Io,
+    Net,
 }
 
 impl AggField<SingleProcessModelFieldId> for ProcessAggField {
     fn expand(&self, detail: bool) -> Vec<SingleProcessModelFieldId> {
+        use model::NetBytesModelFieldId as Net;
         use model::ProcessCpuModelFieldId as Cpu;
         use model::ProcessIoModelFieldId as Io;
         use model::ProcessMemoryModelFieldId as Mem;
@@ -388,6 +414,7 @@ impl AggField<SingleProcessModelFieldId> for ProcessAggField {
                 Self::Cpu => Cpu::unit_variant_iter().map(FieldId::Cpu).collect(),
                 Self::Mem => Mem::unit_variant_iter().map(FieldId::Mem).collect(),
                 Self::Io => Io::unit_variant_iter().map(FieldId::Io).collect(),
+                Self::Net => Net::unit_variant_iter().map(FieldId::Net).collect(),
             }
         } else {
             // Default fields for each group
@@ -395,6 +422,10 @@ impl AggField<SingleProcessModelFieldId> for ProcessAggField {
                 Self::Cpu => vec![FieldId::Cpu(Cpu::UsagePct)],
                 Self::Mem => vec![FieldId::Mem(Mem::RssBytes)],
                 Self::Io => vec![FieldId::Io(Io::RbytesPerSec), FieldId::Io(Io::WbytesPerSec)],
+                Self::Net => vec![
+                    FieldId::Net(Net::SendBytesPerSec),
+                    FieldId::Net(Net::RecvBytesPerSec),
+                ],
             }
         }
     }
@@ -435,6 +466,8 @@ static PROCESS_LONG_ABOUT: Lazy<String> = Lazy::new(|| {
 
 {all_io_fields}
 
+{all_net_fields}
+
 ********************** Aggregated fields **********************
 
 * cpu: includes [{agg_cpu_fields}].
@@ -443,6 +476,9 @@ static PROCESS_LONG_ABOUT: Lazy<String> = Lazy::new(|| {
 
 * io: includes [{agg_io_fields}].
 
+* net: includes [{agg_net_fields}]. Only collected with enable_netbytes in
+  below.conf, so not included by default.
+
 * --detail: includes [<agg_field>.*] for each given aggregated field.
 
 * --default: includes [{default_fields}].
@@ -463,6 +499,16 @@ Output stats for top 5 CPU intense processes for each time slice from 08:30:00 t
 
 $ below dump process -b "08:30:00" -e "08:30:30" -s cpu.usage_pct --rsort --top 5
 
+Output stats for "java" processes using more than 50% CPU and 1G anon memory
+from 08:30:00 to 08:30:30:
+
+$ below dump process -b "08:30:00" -e "08:30:30" --where 'cpu.usage_pct > 50 && mem.anon > 1G && comm =~ "java"'
+
+Compare processes at 08:30:00 with processes at 09:00:00, including processes
+that started or exited in between:
+
+$ below dump process -b "08:30:00" --compare "09:00:00" -f comm cpu mem
+
 "#,
         about = PROCESS_ABOUT,
         common_fields = join(CommonField::unit_variant_iter()),
@@ -470,9 +516,11 @@ $ below dump process -b "08:30:00" -e "08:30:30" -s cpu.usage_pct --rsort --top
         all_cpu_fields = join(ProcessAggField::Cpu.expand(true)),
         all_memory_fields = join(ProcessAggField::Mem.expand(true)),
         all_io_fields = join(ProcessAggField::Io.expand(true)),
+        all_net_fields = join(ProcessAggField::Net.expand(true)),
         agg_cpu_fields = join(ProcessAggField::Cpu.expand(false)),
         agg_memory_fields = join(ProcessAggField::Mem.expand(false)),
         agg_io_fields = join(ProcessAggField::Io.expand(false)),
+        agg_net_fields = join(ProcessAggField::Net.expand(false)),
         default_fields = join(DEFAULT_PROCESS_FIELDS.to_owned()),
     )
 });
@@ -490,6 +538,8 @@ pub enum CgroupAggField {
     Mem,
     Io,
     Pressure,
+    Net,
+    RunqLat,
 }
 
 impl AggField<CgroupModelFieldId> for CgroupAggField {
@@ -499,6 +549,8 @@ impl AggField<CgroupModelFieldId> for CgroupAggField {
         use model::CgroupMemoryModelFieldId as Mem;
         use model::CgroupModelFieldId as FieldId;
         use model::CgroupPressureModelFieldId as Pressure;
+        use model::CgroupRunqLatModelFieldId as RunqLat;
+        use model::NetBytesModelFieldId as Net;
 
         if detail {
             match self {
@@ -508,6 +560,8 @@ impl AggField<CgroupModelFieldId> for CgroupAggField {
                 Self::Pressure => Pressure::unit_variant_iter()
                     .map(FieldId::Pressure)
                     .collect(),
+                Self::Net => Net::unit_variant_iter().map(FieldId::Net).collect(),
+                Self::RunqLat => RunqLat::unit_variant_iter().map(FieldId::RunqLat).collect(),
             }
         } else {
             // Default fields for each group
@@ -520,6 +574,14 @@ impl AggField<CgroupModelFieldId> for CgroupAggField {
                     FieldId::Pressure(Pressure::MemoryFullPct),
                     FieldId::Pressure(Pressure::IoFullPct),
                 ],
+                Self::Net => vec![
+                    FieldId::Net(Net::SendBytesPerSec),
+                    FieldId::Net(Net::RecvBytesPerSec),
+                ],
+                Self::RunqLat => vec![
+                    FieldId::RunqLat(RunqLat::P50Usec),
+                    FieldId::RunqLat(RunqLat::P99Usec),
+                ],
             }
         }
     }
@@ -557,6 +619,10 @@ static CGROUP_LONG_ABOUT: Lazy<String> = Lazy::new(|| {
 
 {all_pressure_fields}
 
+{all_net_fields}
+
+{all_runq_lat_fields}
+
 ********************** Aggregated fields **********************
 
 * cpu: includes [{agg_cpu_fields}].
@@ -567,6 +633,13 @@ static CGROUP_LONG_ABOUT: Lazy<String> = Lazy::new(|| {
 
 * pressure: includes [{agg_pressure_fields}].
 
+* net: includes [{agg_net_fields}]. Only collected with enable_netbytes in
+  below.conf, so not included by default.
+
+* runq_lat: includes [{agg_runq_lat_fields}]. Run-queue latency percentiles in
+  usecs, rounded up to a power of two. Only collected with enable_runqlat in
+  below.conf, so not included by default.
+
 * --detail: includes [<agg_field>.*] for each given aggregated field.
 
 * --default: includes [{default_fields}].
@@ -589,6 +662,14 @@ from 08:30:00 to 08:30:30 recursively:
 
 $ below dump cgroup -b "08:30:00" -e "08:30:30" -s cpu.usage_pct --rsort --top 5
 
+Output stats for cgroups under memory pressure from 08:30:00 to 08:30:30:
+
+$ below dump cgroup -b "08:30:00" -e "08:30:30" --where 'pressure.memory_full_pct > 10 || mem.events_oom_kill > 0'
+
+Compare memory usage of all cgroups at 08:30:00 and 09:00:00:
+
+$ below dump cgroup -b "08:30:00" --compare "09:00:00" -f full_path mem.total
+
 "#,
         about = CGROUP_ABOUT,
         common_fields = join(CommonField::unit_variant_iter()),
@@ -597,10 +678,14 @@ $ below dump cgroup -b "08:30:00" -e "08:30:30" -s cpu.usage_pct --rsort --top 5
         all_memory_fields = join(CgroupAggField::Mem.expand(true)),
         all_io_fields = join(CgroupAggField::Io.expand(true)),
         all_pressure_fields = join(CgroupAggField::Pressure.expand(true)),
+        all_net_fields = join(CgroupAggField::Net.expand(true)),
+        all_runq_lat_fields = join(CgroupAggField::RunqLat.expand(true)),
         agg_cpu_fields = join(CgroupAggField::Cpu.expand(false)),
         agg_memory_fields = join(CgroupAggField::Mem.expand(false)),
         agg_io_fields = join(CgroupAggField::Io.expand(false)),
         agg_pressure_fields = join(CgroupAggField::Pressure.expand(false)),
+        agg_net_fields = join(CgroupAggField::Net.expand(false)),
+        agg_runq_lat_fields = join(CgroupAggField::RunqLat.expand(false)),
         default_fields = join(DEFAULT_CGROUP_FIELDS.to_owned()),
     )
 });
@@ -891,11 +976,347 @@ $ below dump transport -b "08:30:00" -e "08:30:30" -f tcp udp -O json
     )
 });
 
+/// Represents the fields describing an event.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    below_derive::EnumFromStr,
+    below_derive::EnumToString
+)]
+pub enum EventAggField {
+    Event,
+}
+
+impl AggField<EventModelFieldId> for EventAggField {
+    fn expand(&self, _detail: bool) -> Vec<EventModelFieldId> {
+        match self {
+            Self::Event => EventModelFieldId::unit_variant_iter().collect(),
+        }
+    }
+}
+
+pub type EventOptionField = DumpOptionField<EventModelFieldId, EventAggField>;
+
+pub static DEFAULT_EVENT_FIELDS: &[EventOptionField] = &[
+    DumpOptionField::Unit(DumpField::Common(CommonField::Datetime)),
+    DumpOptionField::Agg(EventAggField::Event),
+    DumpOptionField::Unit(DumpField::Common(CommonField::Timestamp)),
+];
+
+const EVENTS_ABOUT: &str = "Dump events detected between consecutive samples";
+
+/// Generated about message for Events dump so supported fields are up-to-date.
+static EVENTS_LONG_ABOUT: Lazy<String> = Lazy::new(|| {
+    format!(
+        r#"{about}
+
+Events are OOM kills, cgroups created or removed, processes started or
+exited, pressure spikes, CPU throttling and collection skew. They are
+detected by comparing each sample with the previous one, so nothing is
+reported for changes that happened before the first dumped sample.
+
+Available kinds: {kinds}
+
+********************** Available fields **********************
+
+{common_fields}, {event_fields}
+
+********************** Aggregated fields **********************
+
+* event: includes [{agg_event_fields}].
+
+* --detail: no effect.
+
+* --default: includes [{default_fields}].
+
+* --everything: includes everything (equivalent to --default --detail).
+
+********************** Example Commands **********************
+
+Simple example:
+
+$ below dump events -b "08:30:00" -e "09:30:00"
+
+Output OOM kills in the last day as json:
+
+$ below dump events -b "1d ago" --where 'kind == "oom_kill"' -O json
+
+Output events of cgroups matching pattern "system.slice*":
+
+$ below dump events -b "08:30:00" -e "09:30:00" -s subject -F system.slice*
+
+"#,
+        about = EVENTS_ABOUT,
+        kinds = model::EventKind::unit_variant_iter()
+            .map(|kind| kind.to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+        common_fields = join(CommonField::unit_variant_iter()),
+        event_fields = join(EventModelFieldId::unit_variant_iter()),
+        agg_event_fields = join(EventAggField::Event.expand(false)),
+        default_fields = join(DEFAULT_EVENT_FIELDS.to_owned()),
+    )
+});
+
+/// Represents the fields describing an OOM kill.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    below_derive::EnumFromStr,
+    below_derive::EnumToString
+)]
+pub enum OomAggField {
+    Victim,
+    Memory,
+    Trigger,
+}
+
+impl AggField<OomKillModelFieldId> for OomAggField {
+    fn expand(&self, _detail: bool) -> Vec<OomKillModelFieldId> {
+        use model::OomKillModelFieldId::*;
+
+        match self {
+            Self::Victim => vec![Pid, Comm, Cgroup, OomScoreAdj],
+            Self::Memory => vec![RssBytes, Constraint, OomCgroup, TotalBytes],
+            Self::Trigger => vec![TriggerPid, TriggerComm],
+        }
+    }
+}
+
+pub type OomOptionField = DumpOptionField<OomKillModelFieldId, OomAggField>;
+
+pub static DEFAULT_OOM_FIELDS: &[OomOptionField] = &[
+    DumpOptionField::Unit(DumpField::Common(CommonField::Datetime)),
+    DumpOptionField::Agg(OomAggField::Victim),
+    DumpOptionField::Agg(OomAggField::Memory),
+    DumpOptionField::Agg(OomAggField::Trigger),
+    DumpOptionField::Unit(DumpField::Common(CommonField::Timestamp)),
+];
+
+const OOM_ABOUT: &str = "Dump processes killed by the OOM killer";
+
+/// Generated about message for OOM dump so supported fields are up-to-date.
+static OOM_LONG_ABOUT: Lazy<String> = Lazy::new(|| {
+    format!(
+        r#"{about}
+
+Each process killed by the OOM killer is dumped with the first sample
+collected after it was killed. Only recorded with enable_oomkill in
+below.conf, otherwise only the counts of OOM kills are available in
+`below dump system` and `below dump cgroup`.
+
+********************** Available fields **********************
+
+{common_fields}, {oom_fields}
+
+********************** Aggregated fields **********************
+
+* victim: includes [{agg_victim_fields}].
+
+* memory: includes [{agg_memory_fields}]. The constraint is none, cpuset,
+  memory_policy or memcg. For memcg, the OOM cgroup is the cgroup out of
+  memory and total_bytes its limit.
+
+* trigger: includes [{agg_trigger_fields}], the process whose allocation
+  failed.
+
+* --detail: no effect.
+
+* --default: includes [{default_fields}].
+
+* --everything: includes everything (equivalent to --default --detail).
+
+********************** Example Commands **********************
+
+Simple example:
+
+$ below dump oom -b "08:30:00" -e "09:30:00"
+
+Output OOM kills in the last day of cgroups out of memory as json:
+
+$ below dump oom -b "1d ago" --where 'constraint == "memcg"' -O json
+
+Output OOM kills of processes in cgroups matching pattern "system.slice*":
+
+$ below dump oom -b "08:30:00" -e "09:30:00" -s cgroup -F system.slice*
+
+"#,
+        about = OOM_ABOUT,
+        common_fields = join(CommonField::unit_variant_iter()),
+        oom_fields = join(OomKillModelFieldId::unit_variant_iter()),
+        agg_victim_fields = join(OomAggField::Victim.expand(false)),
+        agg_memory_fields = join(OomAggField::Memory.expand(false)),
+        agg_trigger_fields = join(OomAggField::Trigger.expand(false)),
+        default_fields = join(DEFAULT_OOM_FIELDS.to_owned()),
+    )
+});
+
+/// Represents the fields describing a process event.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    below_derive::EnumFromStr,
+    below_derive::EnumToString
+)]
+pub enum ProcEventAggField {
+    Process,
+    Lifetime,
+}
+
+impl AggField<ProcEventModelFieldId> for ProcEventAggField {
+    fn expand(&self, _detail: bool) -> Vec<ProcEventModelFieldId> {
+        use model::ProcEventModelFieldId::*;
+
+        match self {
+            Self::Process => vec![Pid, Ppid, Comm, Cgroup, Cmdline],
+            Self::Lifetime => vec![StartEpochMs, EndEpochMs, ExitCode],
+        }
+    }
+}
+
+pub type ProcEventOptionField = DumpOptionField<ProcEventModelFieldId, ProcEventAggField>;
+
+pub static DEFAULT_PROC_EVENT_FIELDS: &[ProcEventOptionField] = &[
+    DumpOptionField::Unit(DumpField::Common(CommonField::Datetime)),
+    DumpOptionField::Unit(DumpField::FieldId(ProcEventModelFieldId::Event)),
+    DumpOptionField::Unit(DumpField::FieldId(ProcEventModelFieldId::TimeEpochMs)),
+    DumpOptionField::Agg(ProcEventAggField::Process),
+    DumpOptionField::Agg(ProcEventAggField::Lifetime),
+    DumpOptionField::Unit(DumpField::Common(CommonField::Timestamp)),
+];
+
+const PROC_EVENTS_ABOUT: &str = "Dump processes forked, exec'd or exited";
+
+/// Generated about message for process events dump so supported fields are up-to-date.
+static PROC_EVENTS_LONG_ABOUT: Lazy<String> = Lazy::new(|| {
+    format!(
+        r#"{about}
+
+Each event is dumped with the first sample collected after it, including
+those of processes that started and exited in between two samples. The
+event is fork, exec or exit. Only recorded with enable_procevents in
+below.conf.
+
+********************** Available fields **********************
+
+{common_fields}, {proc_event_fields}
+
+********************** Aggregated fields **********************
+
+* process: includes [{agg_process_fields}]. The cmdline is the one of the
+  program last exec'd, or of the parent for a process forked but not
+  exec'd, and is truncated to 255 bytes.
+
+* lifetime: includes [{agg_lifetime_fields}]. The end time and exit code
+  are only set for exit events. A process killed by a signal exits with
+  128 plus the signal number.
+
+* --detail: no effect.
+
+* --default: includes [{default_fields}].
+
+* --everything: includes everything (equivalent to --default --detail).
+
+********************** Example Commands **********************
+
+Simple example:
+
+$ below dump procevents -b "08:30:00" -e "09:30:00"
+
+Output processes that exited with a failure in the last hour as json:
+
+$ below dump procevents -b "1h ago" --where 'event == "exit" && exit_code != 0' -O json
+
+Output events of processes in cgroups matching pattern "system.slice*":
+
+$ below dump procevents -b "08:30:00" -e "09:30:00" -s cgroup -F system.slice*
+
+"#,
+        about = PROC_EVENTS_ABOUT,
+        common_fields = join(CommonField::unit_variant_iter()),
+        proc_event_fields = join(ProcEventModelFieldId::unit_variant_iter()),
+        agg_process_fields = join(ProcEventAggField::Process.expand(false)),
+        agg_lifetime_fields = join(ProcEventAggField::Lifetime.expand(false)),
+        default_fields = join(DEFAULT_PROC_EVENT_FIELDS.to_owned()),
+    )
+});
+
+/// Models dumped together by `below dump all`.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    below_derive::EnumIter,
+    below_derive::EnumFromStr,
+    below_derive::EnumToString
+)]
+pub enum DumpModel {
+    System,
+    Disk,
+    Process,
+    Cgroup,
+    Iface,
+    Network,
+    Transport,
+    Events,
+    Oom,
+    Procevents,
+}
+
+const ALL_ABOUT: &str = "Dump multiple models in a single pass";
+
+/// Generated about message for dumping all models so supported models are up-to-date.
+static ALL_LONG_ABOUT: Lazy<String> = Lazy::new(|| {
+    format!(
+        r#"{about}
+
+Walks the time range once and dumps the default fields of every included
+model for each sample. Output of each model is tagged with the model:
+
+* json: an object per sample with a key per model.
+
+* ndjson: an "Entity" key in each object.
+
+* csv, tsv: an Entity column in each line as with --entity-header, and a
+  title line per model.
+
+* raw, kv: a "[model]" line before the output of each model.
+
+parquet and arrow are not supported, dump each model to its own file
+instead. --where is not supported either, while --detail, --everything
+and --raw apply to every model.
+
+Available models: {models}
+
+********************** Example Commands **********************
+
+Simple example:
+
+$ below dump all -b "08:30:00" -e "08:30:30"
+
+Output system, cgroup and process stats as ndjson:
+
+$ below dump all -b "08:30:00" -e "08:30:30" --include system,cgroup,process -O ndjson
+
+"#,
+        about = ALL_ABOUT,
+        models = join(DumpModel::unit_variant_iter()),
+    )
+});
+
 make_option! (OutputFormat {
     "raw": Raw,
     "csv": Csv,
+    "tsv": Tsv,
     "json": Json,
+    "ndjson": NdJson,
     "kv": KeyVal,
+    "parquet": Parquet,
+    "arrow": Arrow,
 });
 
 #[derive(Debug, StructOpt, Default, Clone)]
@@ -918,6 +1339,18 @@ pub struct GeneralOpt {
     /// Take a regex and apply to --select selected field. See command level doc for example.
     #[structopt(long, short = "F")]
     pub filter: Option<Regex>,
+    /// Only dump entries matching a filter expression over fields, e.g.
+    /// 'cpu.usage_pct > 50 && mem.anon > 1G && comm =~ "java"'. Supports ==, !=, <, <=,
+    /// >, >=, =~ (regex), !~, &&, ||, ! and parentheses. Numbers accept size (K, M, G, T),
+    /// time (us, ms, s, min, h, d) and % suffixes.
+    #[structopt(long = "where")]
+    pub where_expr: Option<String>,
+    /// Compare the sample at --begin with the sample at this time, same format as --begin and
+    /// not earlier than it. Each field is dumped with its value at both times and the delta, and entities that
+    /// appeared, disappeared or were recreated in between are marked. Only supported by
+    /// system, cgroup and process.
+    #[structopt(long)]
+    pub compare: Option<String>,
     /// Sort (lower to higher) by --select selected field. See command level doc for example.
     #[structopt(long)]
     pub sort: bool,
@@ -930,15 +1363,26 @@ pub struct GeneralOpt {
     /// Repeat title, for each N line, it will render a line of title. Only for raw output format.
     #[structopt(long = "repeat-title")]
     pub repeat_title: Option<usize>,
-    /// Output format. Choose from raw, csv, kv, json. Default to raw
+    /// Output format. Choose from raw, csv, tsv, kv, json, ndjson, parquet,
+    /// arrow. Default to raw. csv values are quoted per RFC 4180 when needed
+    /// and tsv values have tabs and line breaks escaped with a backslash.
+    /// Values in json and ndjson are typed: numbers are raw numbers and
+    /// missing values are null. ndjson writes one object per line per entity.
+    /// parquet and arrow (Arrow IPC file) write one row per entity per sample
+    /// with Timestamp and Hostname columns, best used with --output.
     #[structopt(long, short = "O")]
     pub output_format: Option<OutputFormat>,
     /// Output destination, default to stdout.
     #[structopt(long, short)]
     pub output: Option<String>,
-    /// Disable title in raw or csv format output
+    /// Disable title in raw, csv or tsv format output
     #[structopt(long)]
     pub disable_title: bool,
+    /// In csv or tsv output, start the title and every line with an Entity
+    /// column of the entity type, e.g. process, so that each entity type has
+    /// its own header when the output of multiple entity types is combined.
+    #[structopt(long)]
+    pub entity_header: bool,
     /// Days adjuster, same as -r option in replay.
     #[structopt(short = "r")]
     pub yesterdays: Option<String>,
@@ -990,6 +1434,11 @@ pub enum DumpCommand {
         /// Saved pattern in the dumprc file under [process] section.
         #[structopt(long, short, conflicts_with("fields"))]
         pattern: Option<String>,
+        /// Nest processes under their parents, like pstree. Values of each
+        /// process are the totals of its subtree, and --sort, --rsort and
+        /// --top apply among siblings.
+        #[structopt(long)]
+        tree: bool,
     },
     #[structopt(about = CGROUP_ABOUT, long_about = CGROUP_LONG_ABOUT.as_str())]
     Cgroup {
@@ -1041,4 +1490,54 @@ pub enum DumpCommand {
         #[structopt(long, short, conflicts_with("fields"))]
         pattern: Option<String>,
     },
+    #[structopt(about = EVENTS_ABOUT, long_about = EVENTS_LONG_ABOUT.as_str())]
+    Events {
+        /// Select which fields to display and in what order.
+        #[structopt(short, long)]
+        fields: Option<Vec<EventOptionField>>,
+        #[structopt(flatten)]
+        opts: GeneralOpt,
+        /// Select field for operation, use with --filter
+        #[structopt(long, short)]
+        select: Option<EventModelFieldId>,
+        /// Saved pattern in the dumprc file under [events] section.
+        #[structopt(long, short, conflicts_with("fields"))]
+        pattern: Option<String>,
+    },
+    #[structopt(about = OOM_ABOUT, long_about = OOM_LONG_ABOUT.as_str())]
+    Oom {
+        /// Select which fields to display and in what order.
+        #[structopt(short, long)]
+        fields: Option<Vec<OomOptionField>>,
+        #[structopt(flatten)]
+        opts: GeneralOpt,
+        /// Select field for operation, use with --filter
+        #[structopt(long, short)]
+        select: Option<OomKillModelFieldId>,
+        /// Saved pattern in the dumprc file under [oom] section.
+        #[structopt(long, short, conflicts_with("fields"))]
+        pattern: Option<String>,
+    },
+    #[structopt(about = PROC_EVENTS_ABOUT, long_about = PROC_EVENTS_LONG_ABOUT.as_str())]
+    Procevents {
+        /// Select which fields to display and in what order.
+        #[structopt(short, long)]
+        fields: Option<Vec<ProcEventOptionField>>,
+        #[structopt(flatten)]
+        opts: GeneralOpt,
+        /// Select field for operation, use with --filter
+        #[structopt(long, short)]
+        select: Option<ProcEventModelFieldId>,
+        /// Saved pattern in the dumprc file under [procevents] section.
+        #[structopt(long, short, conflicts_with("fields"))]
+        pattern: Option<String>,
+    },
+    #[structopt(about = ALL_ABOUT, long_about = ALL_LONG_ABOUT.as_str())]
+    All {
+        #[structopt(flatten)]
+        opts: GeneralOpt,
+        /// Models to dump, e.g. system,cgroup,process. Default to all of them.
+        #[structopt(long, use_delimiter = true)]
+        include: Option<Vec<DumpModel>>,
+    },
 }
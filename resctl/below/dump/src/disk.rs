@@ -22,6 +22,7 @@ pub struct Disk {
     opts: GeneralOpt,
     select: Option<SingleDiskModelFieldId>,
     fields: Vec<DiskField>,
+    where_expr: Option<model::FilterExpr<SingleDiskModelFieldId>>,
 }
 
 impl Disk {
@@ -29,12 +30,13 @@ impl Disk {
         opts: &GeneralOpt,
         select: Option<SingleDiskModelFieldId>,
         fields: Vec<DiskField>,
-    ) -> Self {
-        Self {
+    ) -> Result<Self> {
+        Ok(Self {
             opts: opts.to_owned(),
             select,
             fields,
-        }
+            where_expr: parse_where(opts)?,
+        })
     }
 }
 
@@ -51,6 +53,11 @@ impl Dumper for Disk {
             .system
             .disks
             .iter()
+            .filter(|(_, model)| {
+                self.where_expr
+                    .as_ref()
+                    .map_or(true, |where_expr| where_expr.eval(model))
+            })
             .filter_map(
                 |(_, model)| match (self.select.as_ref(), self.opts.filter.as_ref()) {
                     (Some(field_id), Some(filter))
@@ -80,7 +87,6 @@ impl Dumper for Disk {
                 disks.truncate(self.opts.top as usize);
             }
         }
-        let json = self.opts.output_format == Some(OutputFormat::Json);
         let mut json_output = json!([]);
 
         disks
@@ -100,17 +106,10 @@ impl Dumper for Disk {
                             self.opts.raw
                         )
                     )?,
-                    Some(OutputFormat::Csv) => write!(
+                    Some(OutputFormat::Csv) | Some(OutputFormat::Tsv) => write!(
                         output,
                         "{}",
-                        print::dump_csv(
-                            &self.fields,
-                            ctx,
-                            model,
-                            *round,
-                            self.opts.disable_title,
-                            self.opts.raw
-                        )
+                        print::dump_csv(&self.fields, ctx, model, *round, "disk", &self.opts)
                     )?,
                     Some(OutputFormat::KeyVal) => write!(
                         output,
@@ -121,15 +120,26 @@ impl Dumper for Disk {
                         let par = print::dump_json(&self.fields, ctx, model, self.opts.raw);
                         json_output.as_array_mut().unwrap().push(par);
                     }
+                    Some(OutputFormat::NdJson) => writeln!(
+                        output,
+                        "{}",
+                        print::dump_json(&self.fields, ctx, model, self.opts.raw)
+                    )?,
+                    Some(OutputFormat::Parquet) | Some(OutputFormat::Arrow) => {
+                        print::dump_row(&self.fields, ctx, model)
+                    }
                 }
                 *round += 1;
                 Ok(())
             })
             .collect::<Result<Vec<_>>>()?;
 
-        match (json, comma_flag) {
-            (true, true) => write!(output, ",{}", json_output)?,
-            (true, false) => write!(output, "{}", json_output)?,
+        match (self.opts.output_format, comma_flag) {
+            (Some(OutputFormat::Json), true) => write!(output, ",{}", json_output)?,
+            (Some(OutputFormat::Json), false) => write!(output, "{}", json_output)?,
+            (Some(OutputFormat::NdJson), _)
+            | (Some(OutputFormat::Parquet), _)
+            | (Some(OutputFormat::Arrow), _) => {}
             _ => write!(output, "\n")?,
         };
 
@@ -0,0 +1,139 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use std::cell::RefCell;
+
+use model::EventModelFieldId;
+
+impl HasRenderConfigForDump for model::EventModel {}
+
+pub struct Events {
+    opts: GeneralOpt,
+    select: Option<EventModelFieldId>,
+    fields: Vec<EventField>,
+    where_expr: Option<model::FilterExpr<EventModelFieldId>>,
+    // Events are detected against the previously dumped Model, so the
+    // detector keeps its state between time steps.
+    detector: RefCell<model::EventDetector>,
+}
+
+impl Events {
+    pub fn new(
+        opts: &GeneralOpt,
+        select: Option<EventModelFieldId>,
+        fields: Vec<EventField>,
+    ) -> Result<Self> {
+        Ok(Self {
+            opts: opts.to_owned(),
+            select,
+            fields,
+            where_expr: parse_where(opts)?,
+            detector: RefCell::new(Default::default()),
+        })
+    }
+}
+
+impl Dumper for Events {
+    fn dump_model(
+        &self,
+        ctx: &CommonFieldContext,
+        model: &model::Model,
+        output: &mut dyn Write,
+        round: &mut usize,
+        comma_flag: bool,
+    ) -> Result<IterExecResult> {
+        let events: Vec<_> = self
+            .detector
+            .borrow_mut()
+            .detect(model)
+            .into_iter()
+            .filter(|event| {
+                self.where_expr
+                    .as_ref()
+                    .map_or(true, |where_expr| where_expr.eval(event))
+            })
+            .filter(
+                |event| match (self.select.as_ref(), self.opts.filter.as_ref()) {
+                    (Some(field_id), Some(filter)) => filter.is_match(
+                        &event
+                            .query(field_id)
+                            .map_or("?".to_owned(), |v| v.to_string()),
+                    ),
+                    _ => true,
+                },
+            )
+            .collect();
+
+        // Most samples have no event.
+        if events.is_empty() {
+            return Ok(IterExecResult::Skip);
+        }
+
+        let mut json_output = json!([]);
+
+        for event in &events {
+            match self.opts.output_format {
+                Some(OutputFormat::Raw) | None => write!(
+                    output,
+                    "{}",
+                    print::dump_raw(
+                        &self.fields,
+                        ctx,
+                        event,
+                        *round,
+                        self.opts.repeat_title,
+                        self.opts.disable_title,
+                        self.opts.raw
+                    )
+                )?,
+                Some(OutputFormat::Csv) | Some(OutputFormat::Tsv) => write!(
+                    output,
+                    "{}",
+                    print::dump_csv(&self.fields, ctx, event, *round, "events", &self.opts)
+                )?,
+                Some(OutputFormat::KeyVal) => write!(
+                    output,
+                    "{}",
+                    print::dump_kv(&self.fields, ctx, event, self.opts.raw)
+                )?,
+                Some(OutputFormat::Json) => {
+                    let par = print::dump_json(&self.fields, ctx, event, self.opts.raw);
+                    json_output.as_array_mut().unwrap().push(par);
+                }
+                Some(OutputFormat::NdJson) => writeln!(
+                    output,
+                    "{}",
+                    print::dump_json(&self.fields, ctx, event, self.opts.raw)
+                )?,
+                Some(OutputFormat::Parquet) | Some(OutputFormat::Arrow) => {
+                    print::dump_row(&self.fields, ctx, event)
+                }
+            }
+            *round += 1;
+        }
+
+        match (self.opts.output_format, comma_flag) {
+            (Some(OutputFormat::Json), true) => write!(output, ",{}", json_output)?,
+            (Some(OutputFormat::Json), false) => write!(output, "{}", json_output)?,
+            (Some(OutputFormat::NdJson), _)
+            | (Some(OutputFormat::Parquet), _)
+            | (Some(OutputFormat::Arrow), _) => {}
+            _ => write!(output, "\n")?,
+        };
+
+        Ok(IterExecResult::Success)
+    }
+}
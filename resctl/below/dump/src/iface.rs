@@ -22,6 +22,7 @@ pub struct Iface {
     opts: GeneralOpt,
     select: Option<SingleNetModelFieldId>,
     fields: Vec<IfaceField>,
+    where_expr: Option<model::FilterExpr<SingleNetModelFieldId>>,
 }
 
 impl Iface {
@@ -29,12 +30,13 @@ impl Iface {
         opts: &GeneralOpt,
         select: Option<SingleNetModelFieldId>,
         fields: Vec<IfaceField>,
-    ) -> Self {
-        Self {
+    ) -> Result<Self> {
+        Ok(Self {
             opts: opts.to_owned(),
             select,
             fields,
-        }
+            where_expr: parse_where(opts)?,
+        })
     }
 }
 
@@ -47,13 +49,17 @@ impl Dumper for Iface {
         round: &mut usize,
         comma_flag: bool,
     ) -> Result<IterExecResult> {
-        let json = self.opts.output_format == Some(OutputFormat::Json);
         let mut json_output = json!([]);
 
         model
             .network
             .interfaces
             .iter()
+            .filter(|(_, model)| {
+                self.where_expr
+                    .as_ref()
+                    .map_or(true, |where_expr| where_expr.eval(model))
+            })
             .filter(
                 |(_, model)| match (self.select.as_ref(), self.opts.filter.as_ref()) {
                     (Some(field_id), Some(filter))
@@ -83,17 +89,10 @@ impl Dumper for Iface {
                             self.opts.raw
                         )
                     )?,
-                    Some(OutputFormat::Csv) => write!(
+                    Some(OutputFormat::Csv) | Some(OutputFormat::Tsv) => write!(
                         output,
                         "{}",
-                        print::dump_csv(
-                            &self.fields,
-                            ctx,
-                            model,
-                            *round,
-                            self.opts.disable_title,
-                            self.opts.raw
-                        )
+                        print::dump_csv(&self.fields, ctx, model, *round, "iface", &self.opts)
                     )?,
                     Some(OutputFormat::KeyVal) => write!(
                         output,
@@ -104,15 +103,26 @@ impl Dumper for Iface {
                         let par = print::dump_json(&self.fields, ctx, model, self.opts.raw);
                         json_output.as_array_mut().unwrap().push(par);
                     }
+                    Some(OutputFormat::NdJson) => writeln!(
+                        output,
+                        "{}",
+                        print::dump_json(&self.fields, ctx, model, self.opts.raw)
+                    )?,
+                    Some(OutputFormat::Parquet) | Some(OutputFormat::Arrow) => {
+                        print::dump_row(&self.fields, ctx, model)
+                    }
                 }
                 *round += 1;
                 Ok(())
             })
             .collect::<Result<Vec<_>>>()?;
 
-        match (json, comma_flag) {
-            (true, true) => write!(output, ",{}", json_output)?,
-            (true, false) => write!(output, "{}", json_output)?,
+        match (self.opts.output_format, comma_flag) {
+            (Some(OutputFormat::Json), true) => write!(output, ",{}", json_output)?,
+            (Some(OutputFormat::Json), false) => write!(output, "{}", json_output)?,
+            (Some(OutputFormat::NdJson), _)
+            | (Some(OutputFormat::Parquet), _)
+            | (Some(OutputFormat::Arrow), _) => {}
             _ => write!(output, "\n")?,
         };
 
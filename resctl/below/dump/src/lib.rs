@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cell::RefCell;
 use std::fs::File;
 use std::io::{self, Write};
 use std::path::PathBuf;
@@ -19,7 +20,7 @@ use std::str::FromStr;
 use std::sync::mpsc::Receiver;
 use std::time::SystemTime;
 
-use anyhow::{bail, Error, Result};
+use anyhow::{bail, Context, Error, Result};
 use serde_json::{json, Value};
 use toml::value::Value as TValue;
 
@@ -31,13 +32,18 @@ use store::advance::{new_advance_local, new_advance_remote};
 use store::Advance;
 use store::Direction;
 
+pub mod all;
 pub mod cgroup;
+pub mod columnar;
 pub mod command;
 pub mod disk;
+pub mod events;
 pub mod iface;
 pub mod network;
+pub mod oom;
 pub mod print;
 pub mod process;
+pub mod procevents;
 pub mod system;
 pub mod tmain;
 pub mod transport;
@@ -45,7 +51,7 @@ pub mod transport;
 pub use command::DumpCommand;
 use command::{expand_fields, GeneralOpt, OutputFormat};
 use print::HasRenderConfigForDump;
-use tmain::{dump_timeseries, Dumper, IterExecResult};
+use tmain::{dump_compare, dump_timeseries, Dumper, IterExecResult};
 
 /// Fields available to all commands. Each enum represents some semantics and
 /// knows how to extract relevant data from a CommonFieldContext.
@@ -63,8 +69,11 @@ pub enum CommonField {
 }
 
 /// Context for initializing CommonFields.
+#[derive(Default)]
 pub struct CommonFieldContext {
     pub timestamp: i64,
+    /// Rows of the sample dumped for columnar output by print::dump_row.
+    pub rows: RefCell<Vec<columnar::Row>>,
 }
 
 impl CommonField {
@@ -92,6 +101,9 @@ pub type SystemField = DumpField<model::SystemModelFieldId>;
 pub type DiskField = DumpField<model::SingleDiskModelFieldId>;
 pub type NetworkField = DumpField<model::NetworkModelFieldId>;
 pub type IfaceField = DumpField<model::SingleNetModelFieldId>;
+pub type EventField = DumpField<model::EventModelFieldId>;
+pub type OomField = DumpField<model::OomKillModelFieldId>;
+pub type ProcEventField = DumpField<model::ProcEventModelFieldId>;
 // Essentially the same as NetworkField
 pub type TransportField = DumpField<model::NetworkModelFieldId>;
 
@@ -119,6 +131,46 @@ fn get_advance(
     Ok((time_begin, time_end, advance))
 }
 
+/// Dump Models from time_begin to time_end, or compare the Models at
+/// time_begin and the time given by --compare.
+fn dump(
+    advance: Advance,
+    time_begin: SystemTime,
+    time_end: SystemTime,
+    dumper: &dyn Dumper,
+    output: &mut dyn Write,
+    opts: GeneralOpt,
+    errs: Receiver<Error>,
+) -> Result<()> {
+    match opts.compare.as_ref() {
+        Some(_)
+            if matches!(
+                opts.output_format,
+                Some(OutputFormat::Parquet) | Some(OutputFormat::Arrow)
+            ) =>
+        {
+            bail!("--compare does not support parquet or arrow output")
+        }
+        Some(compare) => {
+            let time_compare = cliutil::system_time_from_date_and_adjuster(
+                compare.as_str(),
+                opts.yesterdays.as_deref(),
+            )?;
+            dump_compare(advance, time_begin, time_compare, dumper, output)
+        }
+        None => dump_timeseries(
+            advance,
+            time_begin,
+            time_end,
+            dumper,
+            output,
+            opts.output_format,
+            opts.br,
+            errs,
+        ),
+    }
+}
+
 /// Try to read $HOME/.config/below/belowrc file and generate a list of keys which will
 /// be used as fields. Any errors happen in this function will directly trigger a panic.
 pub fn parse_pattern<T: FromStr>(
@@ -171,6 +223,21 @@ pub fn parse_pattern<T: FromStr>(
     )
 }
 
+/// Parse the --where expression into a filter over the fields of a specific
+/// model.
+pub fn parse_where<F: FromStr<Err = Error>>(
+    opts: &GeneralOpt,
+) -> Result<Option<model::FilterExpr<F>>> {
+    opts.where_expr
+        .as_ref()
+        .map(|where_expr| {
+            where_expr
+                .parse()
+                .context("Failed to parse --where expression")
+        })
+        .transpose()
+}
+
 pub fn run(
     logger: slog::Logger,
     errs: Receiver<Error>,
@@ -202,19 +269,18 @@ pub fn run(
                 },
                 detail,
             );
-            let system = system::System::new(&opts, fields);
+            let system = system::System::new(&opts, fields)?;
             let mut output: Box<dyn Write> = match opts.output.as_ref() {
                 Some(file_path) => Box::new(File::create(file_path)?),
                 None => Box::new(io::stdout()),
             };
-            dump_timeseries(
+            dump(
                 advance,
                 time_begin,
                 time_end,
                 &system,
                 output.as_mut(),
-                opts.output_format,
-                opts.br,
+                opts,
                 errs,
             )
         }
@@ -239,19 +305,18 @@ pub fn run(
                 },
                 detail,
             );
-            let disk = disk::Disk::new(&opts, select, fields);
+            let disk = disk::Disk::new(&opts, select, fields)?;
             let mut output: Box<dyn Write> = match opts.output.as_ref() {
                 Some(file_path) => Box::new(File::create(file_path)?),
                 None => Box::new(io::stdout()),
             };
-            dump_timeseries(
+            dump(
                 advance,
                 time_begin,
                 time_end,
                 &disk,
                 output.as_mut(),
-                opts.output_format,
-                opts.br,
+                opts,
                 errs,
             )
         }
@@ -260,6 +325,7 @@ pub fn run(
             opts,
             select,
             pattern,
+            tree,
         } => {
             let (time_begin, time_end, advance) = get_advance(logger, dir, host, port, &opts)?;
             let default = opts.everything || opts.default;
@@ -276,19 +342,18 @@ pub fn run(
                 },
                 detail,
             );
-            let process = process::Process::new(&opts, select, fields);
+            let process = process::Process::new(&opts, select, fields, tree)?;
             let mut output: Box<dyn Write> = match opts.output.as_ref() {
                 Some(file_path) => Box::new(File::create(file_path)?),
                 None => Box::new(io::stdout()),
             };
-            dump_timeseries(
+            dump(
                 advance,
                 time_begin,
                 time_end,
                 &process,
                 output.as_mut(),
-                opts.output_format,
-                opts.br,
+                opts,
                 errs,
             )
         }
@@ -313,19 +378,18 @@ pub fn run(
                 },
                 detail,
             );
-            let cgroup = cgroup::Cgroup::new(&opts, select, fields);
+            let cgroup = cgroup::Cgroup::new(&opts, select, fields)?;
             let mut output: Box<dyn Write> = match opts.output.as_ref() {
                 Some(file_path) => Box::new(File::create(file_path)?),
                 None => Box::new(io::stdout()),
             };
-            dump_timeseries(
+            dump(
                 advance,
                 time_begin,
                 time_end,
                 &cgroup,
                 output.as_mut(),
-                opts.output_format,
-                opts.br,
+                opts,
                 errs,
             )
         }
@@ -350,19 +414,18 @@ pub fn run(
                 },
                 detail,
             );
-            let iface = iface::Iface::new(&opts, select, fields);
+            let iface = iface::Iface::new(&opts, select, fields)?;
             let mut output: Box<dyn Write> = match opts.output.as_ref() {
                 Some(file_path) => Box::new(File::create(file_path)?),
                 None => Box::new(io::stdout()),
             };
-            dump_timeseries(
+            dump(
                 advance,
                 time_begin,
                 time_end,
                 &iface,
                 output.as_mut(),
-                opts.output_format,
-                opts.br,
+                opts,
                 errs,
             )
         }
@@ -386,19 +449,18 @@ pub fn run(
                 },
                 detail,
             );
-            let network = network::Network::new(&opts, fields);
+            let network = network::Network::new(&opts, fields)?;
             let mut output: Box<dyn Write> = match opts.output.as_ref() {
                 Some(file_path) => Box::new(File::create(file_path)?),
                 None => Box::new(io::stdout()),
             };
-            dump_timeseries(
+            dump(
                 advance,
                 time_begin,
                 time_end,
                 &network,
                 output.as_mut(),
-                opts.output_format,
-                opts.br,
+                opts,
                 errs,
             )
         }
@@ -422,19 +484,154 @@ pub fn run(
                 },
                 detail,
             );
-            let transport = transport::Transport::new(&opts, fields);
+            let transport = transport::Transport::new(&opts, fields)?;
             let mut output: Box<dyn Write> = match opts.output.as_ref() {
                 Some(file_path) => Box::new(File::create(file_path)?),
                 None => Box::new(io::stdout()),
             };
-            dump_timeseries(
+            dump(
                 advance,
                 time_begin,
                 time_end,
                 &transport,
                 output.as_mut(),
+                opts,
+                errs,
+            )
+        }
+        DumpCommand::Events {
+            fields,
+            opts,
+            select,
+            pattern,
+        } => {
+            let (time_begin, time_end, advance) = get_advance(logger, dir, host, port, &opts)?;
+            let default = opts.everything || opts.default;
+            let detail = opts.everything || opts.detail;
+            let fields = if let Some(pattern_key) = pattern {
+                parse_pattern(filename, pattern_key, "events")
+            } else {
+                fields
+            };
+            let fields = expand_fields(
+                match fields.as_ref() {
+                    Some(fields) if !default => fields,
+                    _ => command::DEFAULT_EVENT_FIELDS,
+                },
+                detail,
+            );
+            let events = events::Events::new(&opts, select, fields)?;
+            let mut output: Box<dyn Write> = match opts.output.as_ref() {
+                Some(file_path) => Box::new(File::create(file_path)?),
+                None => Box::new(io::stdout()),
+            };
+            dump(
+                advance,
+                time_begin,
+                time_end,
+                &events,
+                output.as_mut(),
+                opts,
+                errs,
+            )
+        }
+        DumpCommand::Oom {
+            fields,
+            opts,
+            select,
+            pattern,
+        } => {
+            let (time_begin, time_end, advance) = get_advance(logger, dir, host, port, &opts)?;
+            let default = opts.everything || opts.default;
+            let detail = opts.everything || opts.detail;
+            let fields = if let Some(pattern_key) = pattern {
+                parse_pattern(filename, pattern_key, "oom")
+            } else {
+                fields
+            };
+            let fields = expand_fields(
+                match fields.as_ref() {
+                    Some(fields) if !default => fields,
+                    _ => command::DEFAULT_OOM_FIELDS,
+                },
+                detail,
+            );
+            let oom = oom::Oom::new(&opts, select, fields)?;
+            let mut output: Box<dyn Write> = match opts.output.as_ref() {
+                Some(file_path) => Box::new(File::create(file_path)?),
+                None => Box::new(io::stdout()),
+            };
+            dump(
+                advance,
+                time_begin,
+                time_end,
+                &oom,
+                output.as_mut(),
+                opts,
+                errs,
+            )
+        }
+        DumpCommand::Procevents {
+            fields,
+            opts,
+            select,
+            pattern,
+        } => {
+            let (time_begin, time_end, advance) = get_advance(logger, dir, host, port, &opts)?;
+            let default = opts.everything || opts.default;
+            let detail = opts.everything || opts.detail;
+            let fields = if let Some(pattern_key) = pattern {
+                parse_pattern(filename, pattern_key, "procevents")
+            } else {
+                fields
+            };
+            let fields = expand_fields(
+                match fields.as_ref() {
+                    Some(fields) if !default => fields,
+                    _ => command::DEFAULT_PROC_EVENT_FIELDS,
+                },
+                detail,
+            );
+            let procevents = procevents::Procevents::new(&opts, select, fields)?;
+            let mut output: Box<dyn Write> = match opts.output.as_ref() {
+                Some(file_path) => Box::new(File::create(file_path)?),
+                None => Box::new(io::stdout()),
+            };
+            dump(
+                advance,
+                time_begin,
+                time_end,
+                &procevents,
+                output.as_mut(),
+                opts,
+                errs,
+            )
+        }
+        DumpCommand::All { opts, include } => {
+            if opts.where_expr.is_some() {
+                bail!("--where is not supported by dump all");
+            }
+            if matches!(
                 opts.output_format,
-                opts.br,
+                Some(OutputFormat::Parquet) | Some(OutputFormat::Arrow)
+            ) {
+                bail!("dump all does not support parquet or arrow output, dump each model instead");
+            }
+            let (time_begin, time_end, advance) = get_advance(logger, dir, host, port, &opts)?;
+            let models =
+                include.unwrap_or_else(|| command::DumpModel::unit_variant_iter().collect());
+            let all = all::All::new(&opts, models)?;
+            let mut output: Box<dyn Write> = match opts.output.as_ref() {
+                Some(file_path) => Box::new(File::create(file_path)?),
+                None => Box::new(io::stdout()),
+            };
+            dump(
+                advance,
+                time_begin,
+                time_end,
+                &all,
+                output.as_mut(),
+                opts,
                 errs,
             )
         }
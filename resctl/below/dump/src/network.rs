@@ -19,14 +19,16 @@ impl HasRenderConfigForDump for model::NetworkModel {}
 pub struct Network {
     opts: GeneralOpt,
     fields: Vec<NetworkField>,
+    where_expr: Option<model::FilterExpr<model::NetworkModelFieldId>>,
 }
 
 impl Network {
-    pub fn new(opts: &GeneralOpt, fields: Vec<NetworkField>) -> Self {
-        Self {
+    pub fn new(opts: &GeneralOpt, fields: Vec<NetworkField>) -> Result<Self> {
+        Ok(Self {
             opts: opts.to_owned(),
             fields,
-        }
+            where_expr: parse_where(opts)?,
+        })
     }
 }
 
@@ -39,6 +41,12 @@ impl Dumper for Network {
         round: &mut usize,
         comma_flag: bool,
     ) -> Result<IterExecResult> {
+        if let Some(where_expr) = self.where_expr.as_ref() {
+            if !where_expr.eval(&model.network) {
+                return Ok(IterExecResult::Skip);
+            }
+        }
+
         match self.opts.output_format {
             Some(OutputFormat::Raw) | None => write!(
                 output,
@@ -53,7 +61,7 @@ impl Dumper for Network {
                     self.opts.raw
                 )
             )?,
-            Some(OutputFormat::Csv) => write!(
+            Some(OutputFormat::Csv) | Some(OutputFormat::Tsv) => write!(
                 output,
                 "{}",
                 print::dump_csv(
@@ -61,8 +69,8 @@ impl Dumper for Network {
                     ctx,
                     &model.network,
                     *round,
-                    self.opts.disable_title,
-                    self.opts.raw
+                    "network",
+                    &self.opts
                 )
             )?,
             Some(OutputFormat::KeyVal) => write!(
@@ -79,11 +87,23 @@ impl Dumper for Network {
                     write!(output, "{}", json_output)?;
                 }
             }
+            Some(OutputFormat::NdJson) => writeln!(
+                output,
+                "{}",
+                print::dump_json(&self.fields, ctx, &model.network, self.opts.raw)
+            )?,
+            Some(OutputFormat::Parquet) | Some(OutputFormat::Arrow) => {
+                print::dump_row(&self.fields, ctx, &model.network)
+            }
         };
         *round += 1;
 
-        if self.opts.output_format != Some(OutputFormat::Json) {
-            write!(output, "\n")?;
+        match self.opts.output_format {
+            Some(OutputFormat::Json)
+            | Some(OutputFormat::NdJson)
+            | Some(OutputFormat::Parquet)
+            | Some(OutputFormat::Arrow) => {}
+            _ => write!(output, "\n")?,
         }
         Ok(IterExecResult::Success)
     }
@@ -0,0 +1,131 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use model::OomKillModelFieldId;
+
+impl HasRenderConfigForDump for model::OomKillModel {}
+
+pub struct Oom {
+    opts: GeneralOpt,
+    select: Option<OomKillModelFieldId>,
+    fields: Vec<OomField>,
+    where_expr: Option<model::FilterExpr<OomKillModelFieldId>>,
+}
+
+impl Oom {
+    pub fn new(
+        opts: &GeneralOpt,
+        select: Option<OomKillModelFieldId>,
+        fields: Vec<OomField>,
+    ) -> Result<Self> {
+        Ok(Self {
+            opts: opts.to_owned(),
+            select,
+            fields,
+            where_expr: parse_where(opts)?,
+        })
+    }
+}
+
+impl Dumper for Oom {
+    fn dump_model(
+        &self,
+        ctx: &CommonFieldContext,
+        model: &model::Model,
+        output: &mut dyn Write,
+        round: &mut usize,
+        comma_flag: bool,
+    ) -> Result<IterExecResult> {
+        let oom_kills: Vec<_> = model
+            .oom_kills
+            .iter()
+            .filter(|oom_kill| {
+                self.where_expr
+                    .as_ref()
+                    .map_or(true, |where_expr| where_expr.eval(*oom_kill))
+            })
+            .filter(
+                |oom_kill| match (self.select.as_ref(), self.opts.filter.as_ref()) {
+                    (Some(field_id), Some(filter)) => filter.is_match(
+                        &oom_kill
+                            .query(field_id)
+                            .map_or("?".to_owned(), |v| v.to_string()),
+                    ),
+                    _ => true,
+                },
+            )
+            .collect();
+
+        // Most samples have no OOM kill.
+        if oom_kills.is_empty() {
+            return Ok(IterExecResult::Skip);
+        }
+
+        let mut json_output = json!([]);
+
+        for oom_kill in oom_kills {
+            match self.opts.output_format {
+                Some(OutputFormat::Raw) | None => write!(
+                    output,
+                    "{}",
+                    print::dump_raw(
+                        &self.fields,
+                        ctx,
+                        oom_kill,
+                        *round,
+                        self.opts.repeat_title,
+                        self.opts.disable_title,
+                        self.opts.raw
+                    )
+                )?,
+                Some(OutputFormat::Csv) | Some(OutputFormat::Tsv) => write!(
+                    output,
+                    "{}",
+                    print::dump_csv(&self.fields, ctx, oom_kill, *round, "oom", &self.opts)
+                )?,
+                Some(OutputFormat::KeyVal) => write!(
+                    output,
+                    "{}",
+                    print::dump_kv(&self.fields, ctx, oom_kill, self.opts.raw)
+                )?,
+                Some(OutputFormat::Json) => {
+                    let par = print::dump_json(&self.fields, ctx, oom_kill, self.opts.raw);
+                    json_output.as_array_mut().unwrap().push(par);
+                }
+                Some(OutputFormat::NdJson) => writeln!(
+                    output,
+                    "{}",
+                    print::dump_json(&self.fields, ctx, oom_kill, self.opts.raw)
+                )?,
+                Some(OutputFormat::Parquet) | Some(OutputFormat::Arrow) => {
+                    print::dump_row(&self.fields, ctx, oom_kill)
+                }
+            }
+            *round += 1;
+        }
+
+        match (self.opts.output_format, comma_flag) {
+            (Some(OutputFormat::Json), true) => write!(output, ",{}", json_output)?,
+            (Some(OutputFormat::Json), false) => write!(output, "{}", json_output)?,
+            (Some(OutputFormat::NdJson), _)
+            | (Some(OutputFormat::Parquet), _)
+            | (Some(OutputFormat::Arrow), _) => {}
+            _ => write!(output, "\n")?,
+        };
+
+        Ok(IterExecResult::Success)
+    }
+}
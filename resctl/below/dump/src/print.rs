@@ -68,6 +68,39 @@ where
         }
         config.render(self.get_field(ctx, model), fixed_width)
     }
+
+    fn get_compare_field(
+        &self,
+        ctx: &CommonFieldContext,
+        model: Option<&F::Queriable>,
+    ) -> Option<Field> {
+        match self {
+            Self::Common(common) => common.get_field(ctx),
+            Self::FieldId(field_id) => model.and_then(|model| model.query(field_id)),
+        }
+    }
+
+    /// Render the Field in the base and the target model of a comparison,
+    /// and the delta from base to target.
+    pub fn dump_compare_field(
+        &self,
+        ctxs: (&CommonFieldContext, &CommonFieldContext),
+        models: (Option<&F::Queriable>, Option<&F::Queriable>),
+        raw: bool,
+    ) -> (String, String, String) {
+        let mut config = self.get_render_config();
+        if raw {
+            config.format = None;
+            config.suffix = None;
+        }
+        let base = self.get_compare_field(ctxs.0, models.0);
+        let target = self.get_compare_field(ctxs.1, models.1);
+        (
+            config.render(base.clone(), false),
+            config.render(target.clone(), false),
+            config.render_delta(base, target, false),
+        )
+    }
 }
 
 impl<F> DumpField<F>
@@ -110,6 +143,23 @@ pub fn dump_kv<T: HasRenderConfigForDump>(
     res
 }
 
+/// Typed JSON value of a Field. Numbers are dumped as raw JSON numbers and a
+/// missing value as null. Other fields are rendered as strings.
+fn field_to_json(field: Option<Field>, config: &RenderConfig) -> Value {
+    match field {
+        None => Value::Null,
+        Some(Field::U32(v)) => json!(v),
+        Some(Field::U64(v)) => json!(v),
+        Some(Field::I32(v)) => json!(v),
+        Some(Field::I64(v)) => json!(v),
+        // NaN and infinity are not valid JSON numbers, json! turns them into null
+        Some(Field::F64(v)) => json!(v),
+        field => json!(config.render(field, false)),
+    }
+}
+
+/// Dump an entity as a JSON object with typed values, used by both json and
+/// ndjson output.
 pub fn dump_json<T: HasRenderConfigForDump>(
     fields: &[DumpField<T::FieldId>],
     ctx: &CommonFieldContext,
@@ -118,8 +168,12 @@ pub fn dump_json<T: HasRenderConfigForDump>(
 ) -> Value {
     let mut res = json!({});
     for field in fields {
-        let config = field.get_render_config();
-        res[config.render_title(false)] = json!(field.dump_field(ctx, model, raw, false));
+        let mut config = field.get_render_config();
+        if raw {
+            config.format = None;
+            config.suffix = None;
+        }
+        res[config.render_title(false)] = field_to_json(field.get_field(ctx, model), &config);
     }
     res
 }
@@ -182,22 +236,194 @@ pub fn dump_raw_indented<T: HasRenderConfigForDump + Recursive>(
     res
 }
 
+/// Dump an entity as a row of typed Fields for columnar output, to be
+/// collected by columnar::ColumnarWriter from the rows of ctx. CommonFields
+/// are skipped as the writer adds its own timestamp column.
+pub fn dump_row<T: HasRenderConfigForDump>(
+    fields: &[DumpField<T::FieldId>],
+    ctx: &CommonFieldContext,
+    model: &T,
+) {
+    let row = fields
+        .iter()
+        .filter_map(|field| match field {
+            DumpField::Common(_) => None,
+            DumpField::FieldId(field_id) => Some((
+                T::get_render_config_for_dump(field_id).render_title(false),
+                T::field_type(field_id),
+                model.query(field_id),
+            )),
+        })
+        .collect::<columnar::Row>();
+    ctx.rows.borrow_mut().push(row);
+}
+
+/// Separator of csv or tsv output.
+fn csv_separator(output_format: Option<OutputFormat>) -> char {
+    match output_format {
+        Some(OutputFormat::Tsv) => '\t',
+        _ => ',',
+    }
+}
+
+/// Escape a value of csv or tsv output. A csv value with the separator, a
+/// quote or a line break is quoted per RFC 4180. As tsv has no quoting,
+/// backslashes, tabs and line breaks of a tsv value are escaped instead.
+pub fn escape_csv_value(value: &str, sep: char) -> String {
+    if sep == '\t' {
+        let mut res = String::with_capacity(value.len());
+        for c in value.chars() {
+            match c {
+                '\\' => res.push_str("\\\\"),
+                '\t' => res.push_str("\\t"),
+                '\n' => res.push_str("\\n"),
+                '\r' => res.push_str("\\r"),
+                c => res.push(c),
+            }
+        }
+        res
+    } else if value.contains(|c| c == sep || c == '"' || c == '\n' || c == '\r') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+fn csv_line<I: IntoIterator<Item = String>>(values: I, sep: char) -> String {
+    let mut line = values
+        .into_iter()
+        .map(|value| escape_csv_value(&value, sep))
+        .collect::<Vec<_>>()
+        .join(&sep.to_string());
+    line.push('\n');
+    line
+}
+
+/// Dump an entity as a line of csv or tsv, preceded by the title line in the
+/// first round. With --entity-header, both start with an Entity column.
 pub fn dump_csv<T: HasRenderConfigForDump>(
     fields: &[DumpField<T::FieldId>],
     ctx: &CommonFieldContext,
     model: &T,
     round: usize,
+    entity: &str,
+    opts: &GeneralOpt,
+) -> String {
+    let sep = csv_separator(opts.output_format);
+    let entity = if opts.entity_header {
+        Some(entity)
+    } else {
+        None
+    };
+    let mut res = String::new();
+    if !opts.disable_title && round == 0 {
+        let titles = fields
+            .iter()
+            .map(|field| field.get_render_config().render_title(false));
+        res.push_str(&csv_line(
+            entity
+                .map(|_| "Entity".to_owned())
+                .into_iter()
+                .chain(titles),
+            sep,
+        ));
+    }
+    let values = fields
+        .iter()
+        .map(|field| field.dump_field(ctx, model, opts.raw, false));
+    res.push_str(&csv_line(
+        entity.map(str::to_owned).into_iter().chain(values),
+        sep,
+    ));
+    res
+}
+
+/// Dump entities of the base and the target Model of a comparison, each with
+/// its Presence. For every field, the value at each time and the delta from
+/// base to target are dumped.
+pub fn dump_compare<T: HasRenderConfigForDump>(
+    fields: &[DumpField<T::FieldId>],
+    ctxs: (&CommonFieldContext, &CommonFieldContext),
+    entities: &[(Option<&T>, Option<&T>, model::Presence)],
+    output_format: Option<OutputFormat>,
     disable_title: bool,
     raw: bool,
 ) -> String {
     let mut res = String::new();
-    if !disable_title && round == 0 {
-        res.push_str(&dump_title_line(fields, ",", false));
-    }
-    for field in fields {
-        res.push_str(&field.dump_field(ctx, model, raw, false));
-        res.push(',');
+    match output_format {
+        Some(OutputFormat::Raw) | Some(OutputFormat::KeyVal) | None => {
+            for (base, target, presence) in entities {
+                for field in fields {
+                    let (base, target, delta) =
+                        field.dump_compare_field(ctxs, (*base, *target), raw);
+                    res.push_str(&format!(
+                        "{}: {} -> {} ({})\n",
+                        field.get_render_config().render_title(false),
+                        base,
+                        target,
+                        delta
+                    ));
+                }
+                if *presence != model::Presence::Both {
+                    res.push_str(&format!("Presence: {}\n", presence));
+                }
+                res.push('\n');
+            }
+        }
+        Some(OutputFormat::Csv) | Some(OutputFormat::Tsv) => {
+            let sep = csv_separator(output_format);
+            if !disable_title {
+                let mut titles = Vec::new();
+                for field in fields {
+                    let title = field.get_render_config().render_title(false);
+                    titles.push(format!("{} Base", title));
+                    titles.push(format!("{} Target", title));
+                    titles.push(format!("{} Delta", title));
+                }
+                titles.push("Presence".to_owned());
+                res.push_str(&csv_line(titles, sep));
+            }
+            for (base, target, presence) in entities {
+                let mut values = Vec::new();
+                for field in fields {
+                    let (base, target, delta) =
+                        field.dump_compare_field(ctxs, (*base, *target), raw);
+                    values.push(base);
+                    values.push(target);
+                    values.push(delta);
+                }
+                values.push(presence.to_string());
+                res.push_str(&csv_line(values, sep));
+            }
+        }
+        Some(OutputFormat::Json) | Some(OutputFormat::NdJson) => {
+            let mut jval = json!([]);
+            for (base, target, presence) in entities {
+                let mut entity = json!({});
+                for field in fields {
+                    let (base, target, delta) =
+                        field.dump_compare_field(ctxs, (*base, *target), raw);
+                    entity[field.get_render_config().render_title(false)] = json!({
+                        "base": base,
+                        "target": target,
+                        "delta": delta,
+                    });
+                }
+                entity["Presence"] = json!(presence.to_string());
+                jval.as_array_mut().unwrap().push(entity);
+            }
+            if output_format == Some(OutputFormat::NdJson) {
+                for entity in jval.as_array().unwrap() {
+                    res.push_str(&format!("{}\n", entity));
+                }
+            } else {
+                res.push_str(&jval.to_string());
+            }
+        }
+        // Rejected before comparing
+        Some(OutputFormat::Parquet) | Some(OutputFormat::Arrow) => {
+            unreachable!("--compare does not support columnar output")
+        }
     }
-    res.push('\n');
     res
 }
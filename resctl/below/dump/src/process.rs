@@ -16,12 +16,14 @@ use super::*;
 
 use model::SingleProcessModelFieldId;
 use render::RenderConfig;
+use std::collections::BTreeSet;
 
 impl HasRenderConfigForDump for model::SingleProcessModel {
     fn get_render_config_for_dump(field_id: &SingleProcessModelFieldId) -> RenderConfig {
+        use common::util::get_prefix;
         use model::ProcessCpuModelFieldId::{SystemPct, UserPct};
         use model::ProcessIoModelFieldId::RwbytesPerSec;
-        use model::SingleProcessModelFieldId::{Cpu, Io};
+        use model::SingleProcessModelFieldId::{Comm, Cpu, Io};
         use render::HasRenderConfig;
 
         let rc = model::SingleProcessModel::get_render_config_builder(field_id);
@@ -29,6 +31,8 @@ impl HasRenderConfigForDump for model::SingleProcessModel {
             Cpu(UserPct) => rc.title("User CPU"),
             Cpu(SystemPct) => rc.title("Sys CPU"),
             Io(RwbytesPerSec) => rc.title("RW"),
+            // Only indented with --tree
+            Comm => rc.indented_prefix(get_prefix(false)),
             _ => rc,
         }
         .get()
@@ -39,6 +43,8 @@ pub struct Process {
     opts: GeneralOpt,
     select: Option<SingleProcessModelFieldId>,
     fields: Vec<ProcessField>,
+    where_expr: Option<model::FilterExpr<SingleProcessModelFieldId>>,
+    tree: bool,
 }
 
 impl Process {
@@ -46,12 +52,172 @@ impl Process {
         opts: &GeneralOpt,
         select: Option<SingleProcessModelFieldId>,
         fields: Vec<ProcessField>,
-    ) -> Self {
-        Self {
+        tree: bool,
+    ) -> Result<Self> {
+        Ok(Self {
             opts: opts.to_owned(),
             select,
             fields,
+            where_expr: parse_where(opts)?,
+            tree,
+        })
+    }
+
+    /// Whether the process matches --where and --filter on the selected field.
+    fn should_print(&self, spm: &model::SingleProcessModel) -> bool {
+        self.where_expr
+            .as_ref()
+            .map_or(true, |where_expr| where_expr.eval(spm))
+            && match (self.select.as_ref(), self.opts.filter.as_ref()) {
+                (Some(field_id), Some(filter)) => filter.is_match(
+                    &spm.query(field_id)
+                        .map_or("?".to_owned(), |v| v.to_string()),
+                ),
+                _ => true,
+            }
+    }
+
+    /// Dump processes nested under their parents, with values of each
+    /// process being the totals of its subtree.
+    fn dump_tree(
+        &self,
+        ctx: &CommonFieldContext,
+        model: &model::Model,
+        output: &mut dyn Write,
+        round: &mut usize,
+        comma_flag: bool,
+    ) -> Result<IterExecResult> {
+        fn output_process(
+            handle: &Process,
+            ctx: &CommonFieldContext,
+            tree: &model::ProcessTree,
+            spm: &model::SingleProcessModel,
+            output: &mut dyn Write,
+            round: &mut usize,
+            jval: &mut Value,
+        ) -> Result<()> {
+            let json = handle.opts.output_format == Some(OutputFormat::Json);
+            //filter
+            if handle.should_print(spm) {
+                match handle.opts.output_format {
+                    Some(OutputFormat::Raw) | None => write!(
+                        output,
+                        "{}",
+                        print::dump_raw_indented(
+                            &handle.fields,
+                            ctx,
+                            spm,
+                            *round,
+                            handle.opts.repeat_title,
+                            handle.opts.disable_title,
+                            handle.opts.raw,
+                        )
+                    )?,
+                    Some(OutputFormat::Csv) | Some(OutputFormat::Tsv) => write!(
+                        output,
+                        "{}",
+                        print::dump_csv(&handle.fields, ctx, spm, *round, "process", &handle.opts)
+                    )?,
+                    Some(OutputFormat::KeyVal) => write!(
+                        output,
+                        "{}",
+                        print::dump_kv(&handle.fields, ctx, spm, handle.opts.raw)
+                    )?,
+                    Some(OutputFormat::Json) => {
+                        *jval = print::dump_json(&handle.fields, ctx, spm, handle.opts.raw);
+                        jval["children"] = json!([]);
+                    }
+                    // One line per process, without nesting
+                    Some(OutputFormat::NdJson) => writeln!(
+                        output,
+                        "{}",
+                        print::dump_json(&handle.fields, ctx, spm, handle.opts.raw)
+                    )?,
+                    Some(OutputFormat::Parquet) | Some(OutputFormat::Arrow) => {
+                        print::dump_row(&handle.fields, ctx, spm)
+                    }
+                };
+                *round += 1;
+            }
+
+            let child_pids = spm.pid.and_then(|pid| tree.children.get(&pid));
+            let mut children: Vec<_> = child_pids
+                .into_iter()
+                .flatten()
+                .filter_map(|pid| tree.processes.get(pid))
+                .collect();
+            //sort
+            if let Some(field_id) = &handle.select {
+                if handle.opts.sort {
+                    model::sort_queriables(&mut children, field_id, false);
+                }
+
+                if handle.opts.rsort {
+                    model::sort_queriables(&mut children, field_id, true);
+                }
+
+                if (handle.opts.sort || handle.opts.rsort) && handle.opts.top != 0 {
+                    children.truncate(handle.opts.top as usize);
+                }
+            }
+
+            for child_spm in children {
+                let mut child = json!({});
+                output_process(handle, ctx, tree, child_spm, output, round, &mut child)?;
+                if json && child["children"].is_array() {
+                    // Parent does not match, but child does, we should also render parent.
+                    if !jval["children"].is_array() {
+                        *jval = print::dump_json(&handle.fields, ctx, spm, handle.opts.raw);
+                        jval["children"] = json!([]);
+                    }
+                    jval["children"].as_array_mut().unwrap().push(child);
+                }
+            }
+
+            Ok(())
         }
+
+        let tree = model::ProcessTree::new(&model.process);
+        let json = self.opts.output_format == Some(OutputFormat::Json);
+        let mut json_output = json!([]);
+        // Roots are siblings too, so sort them the same way as children
+        let mut root_spms: Vec<_> = tree
+            .roots
+            .iter()
+            .filter_map(|pid| tree.processes.get(pid))
+            .collect();
+        if let Some(field_id) = &self.select {
+            if self.opts.sort || self.opts.rsort {
+                model::sort_queriables(&mut root_spms, field_id, self.opts.rsort);
+            }
+            if (self.opts.sort || self.opts.rsort) && self.opts.top != 0 {
+                root_spms.truncate(self.opts.top as usize);
+            }
+        }
+        let begin_round = *round;
+        for spm in root_spms {
+            let mut jval = json!({});
+            output_process(self, ctx, &tree, spm, output, round, &mut jval)?;
+            if json && jval["children"].is_array() {
+                json_output.as_array_mut().unwrap().push(jval);
+            }
+        }
+
+        // Return if we filtered everything.
+        if *round == begin_round {
+            return Ok(IterExecResult::Skip);
+        }
+
+        match (self.opts.output_format, comma_flag) {
+            (Some(OutputFormat::Json), true) => write!(output, ",{}", json_output)?,
+            (Some(OutputFormat::Json), false) => write!(output, "{}", json_output)?,
+            (Some(OutputFormat::NdJson), _)
+            | (Some(OutputFormat::Parquet), _)
+            | (Some(OutputFormat::Arrow), _) => {}
+            _ => write!(output, "\n")?,
+        };
+
+        Ok(IterExecResult::Success)
     }
 }
 
@@ -64,23 +230,15 @@ impl Dumper for Process {
         round: &mut usize,
         comma_flag: bool,
     ) -> Result<IterExecResult> {
+        if self.tree {
+            return self.dump_tree(ctx, model, output, round, comma_flag);
+        }
+
         let mut processes: Vec<_> = model
             .process
             .processes
-            .iter()
-            .filter_map(
-                |(_, spm)| match (self.select.as_ref(), self.opts.filter.as_ref()) {
-                    (Some(field_id), Some(filter))
-                        if !filter.is_match(
-                            &spm.query(&field_id)
-                                .map_or("?".to_owned(), |v| v.to_string()),
-                        ) =>
-                    {
-                        None
-                    }
-                    _ => Some(spm),
-                },
-            )
+            .values()
+            .filter(|spm| self.should_print(spm))
             .collect();
 
         // Return if we filtered everything.
@@ -101,7 +259,6 @@ impl Dumper for Process {
                 processes.truncate(self.opts.top as usize);
             }
         }
-        let json = self.opts.output_format == Some(OutputFormat::Json);
         let mut json_output = json!([]);
 
         processes
@@ -121,17 +278,10 @@ impl Dumper for Process {
                             self.opts.raw
                         )
                     )?,
-                    Some(OutputFormat::Csv) => write!(
+                    Some(OutputFormat::Csv) | Some(OutputFormat::Tsv) => write!(
                         output,
                         "{}",
-                        print::dump_csv(
-                            &self.fields,
-                            ctx,
-                            spm,
-                            *round,
-                            self.opts.disable_title,
-                            self.opts.raw
-                        )
+                        print::dump_csv(&self.fields, ctx, spm, *round, "process", &self.opts)
                     )?,
                     Some(OutputFormat::KeyVal) => write!(
                         output,
@@ -142,18 +292,75 @@ impl Dumper for Process {
                         let par = print::dump_json(&self.fields, ctx, spm, self.opts.raw);
                         json_output.as_array_mut().unwrap().push(par);
                     }
+                    Some(OutputFormat::NdJson) => writeln!(
+                        output,
+                        "{}",
+                        print::dump_json(&self.fields, ctx, spm, self.opts.raw)
+                    )?,
+                    Some(OutputFormat::Parquet) | Some(OutputFormat::Arrow) => {
+                        print::dump_row(&self.fields, ctx, spm)
+                    }
                 }
                 *round += 1;
                 Ok(())
             })
             .collect::<Result<Vec<_>>>()?;
 
-        match (json, comma_flag) {
-            (true, true) => write!(output, ",{}", json_output)?,
-            (true, false) => write!(output, "{}", json_output)?,
+        match (self.opts.output_format, comma_flag) {
+            (Some(OutputFormat::Json), true) => write!(output, ",{}", json_output)?,
+            (Some(OutputFormat::Json), false) => write!(output, "{}", json_output)?,
+            (Some(OutputFormat::NdJson), _)
+            | (Some(OutputFormat::Parquet), _)
+            | (Some(OutputFormat::Arrow), _) => {}
             _ => write!(output, "\n")?,
         };
 
         Ok(IterExecResult::Success)
     }
+    fn dump_compare(
+        &self,
+        base: (&CommonFieldContext, &model::Model),
+        target: (&CommonFieldContext, &model::Model),
+        output: &mut dyn Write,
+    ) -> Result<()> {
+        let base_processes = &base.1.process.processes;
+        let target_processes = &target.1.process.processes;
+        let elapsed = target
+            .1
+            .timestamp
+            .duration_since(base.1.timestamp)
+            .unwrap_or_default();
+        let pids: BTreeSet<&i32> = base_processes
+            .keys()
+            .chain(target_processes.keys())
+            .collect();
+
+        let mut processes = Vec::new();
+        for pid in pids {
+            let base_process = base_processes.get(pid);
+            let target_process = target_processes.get(pid);
+            // Filter on the latest state of the process.
+            if let Some(spm) = target_process.or(base_process) {
+                if self.should_print(spm) {
+                    let presence =
+                        model::Presence::of_process(base_process, target_process, elapsed);
+                    processes.push((base_process, target_process, presence));
+                }
+            }
+        }
+
+        write!(
+            output,
+            "{}",
+            print::dump_compare(
+                &self.fields,
+                (base.0, target.0),
+                &processes,
+                self.opts.output_format,
+                self.opts.disable_title,
+                self.opts.raw,
+            )
+        )?;
+        Ok(())
+    }
 }
@@ -19,14 +19,16 @@ impl HasRenderConfigForDump for model::SystemModel {}
 pub struct System {
     opts: GeneralOpt,
     fields: Vec<SystemField>,
+    where_expr: Option<model::FilterExpr<model::SystemModelFieldId>>,
 }
 
 impl System {
-    pub fn new(opts: &GeneralOpt, fields: Vec<SystemField>) -> Self {
-        Self {
+    pub fn new(opts: &GeneralOpt, fields: Vec<SystemField>) -> Result<Self> {
+        Ok(Self {
             opts: opts.to_owned(),
             fields,
-        }
+            where_expr: parse_where(opts)?,
+        })
     }
 }
 
@@ -39,6 +41,12 @@ impl Dumper for System {
         round: &mut usize,
         comma_flag: bool,
     ) -> Result<IterExecResult> {
+        if let Some(where_expr) = self.where_expr.as_ref() {
+            if !where_expr.eval(&model.system) {
+                return Ok(IterExecResult::Skip);
+            }
+        }
+
         match self.opts.output_format {
             Some(OutputFormat::Raw) | None => write!(
                 output,
@@ -53,7 +61,7 @@ impl Dumper for System {
                     self.opts.raw
                 )
             )?,
-            Some(OutputFormat::Csv) => write!(
+            Some(OutputFormat::Csv) | Some(OutputFormat::Tsv) => write!(
                 output,
                 "{}",
                 print::dump_csv(
@@ -61,8 +69,8 @@ impl Dumper for System {
                     ctx,
                     &model.system,
                     *round,
-                    self.opts.disable_title,
-                    self.opts.raw
+                    "system",
+                    &self.opts
                 )
             )?,
             Some(OutputFormat::KeyVal) => write!(
@@ -78,10 +86,43 @@ impl Dumper for System {
                     write!(output, "{}", par.to_string())?;
                 }
             }
+            Some(OutputFormat::NdJson) => writeln!(
+                output,
+                "{}",
+                print::dump_json(&self.fields, ctx, &model.system, self.opts.raw)
+            )?,
+            Some(OutputFormat::Parquet) | Some(OutputFormat::Arrow) => {
+                print::dump_row(&self.fields, ctx, &model.system)
+            }
         };
 
         *round += 1;
 
         Ok(IterExecResult::Success)
     }
+    fn dump_compare(
+        &self,
+        base: (&CommonFieldContext, &model::Model),
+        target: (&CommonFieldContext, &model::Model),
+        output: &mut dyn Write,
+    ) -> Result<()> {
+        let systems = [(
+            Some(&base.1.system),
+            Some(&target.1.system),
+            model::Presence::Both,
+        )];
+        write!(
+            output,
+            "{}",
+            print::dump_compare(
+                &self.fields,
+                (base.0, target.0),
+                &systems,
+                self.opts.output_format,
+                self.opts.disable_title,
+                self.opts.raw,
+            )
+        )?;
+        Ok(())
+    }
 }
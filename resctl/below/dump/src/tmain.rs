@@ -33,6 +33,22 @@ pub trait Dumper {
         // value is the LAST value.
         comma_flag: bool,
     ) -> Result<IterExecResult>;
+
+    /// Dumps the base and the target Model side by side with the delta of
+    /// each field. Only implemented by some Dumpers.
+    fn dump_compare(
+        &self,
+        _base: (&CommonFieldContext, &model::Model),
+        _target: (&CommonFieldContext, &model::Model),
+        _output: &mut dyn Write,
+    ) -> Result<()> {
+        bail!("--compare is not supported by this command")
+    }
+}
+
+fn is_broken_pipe(e: &Error) -> bool {
+    e.downcast_ref::<std::io::Error>()
+        .map_or(false, |e| e.kind() == std::io::ErrorKind::BrokenPipe)
 }
 
 /// Called by dump commands to dump Models in continuous time steps. The actual
@@ -62,7 +78,19 @@ pub fn dump_timeseries(
     cliutil::check_initial_sample_time_in_time_range(model.timestamp, time_begin, time_end)?;
 
     let json = output_format == Some(OutputFormat::Json);
-    let csv = output_format == Some(OutputFormat::Csv);
+    let csv = matches!(
+        output_format,
+        Some(OutputFormat::Csv) | Some(OutputFormat::Tsv)
+    );
+    // Each line of ndjson is a complete object, no separator is needed
+    let ndjson = output_format == Some(OutputFormat::NdJson);
+    // Rows of each sample are collected into columns and written in batches
+    let mut columnar = match output_format {
+        Some(format @ OutputFormat::Parquet) | Some(format @ OutputFormat::Arrow) => {
+            Some(columnar::ColumnarWriter::new(format))
+        }
+        _ => None,
+    };
 
     let mut round = 0;
 
@@ -80,17 +108,31 @@ pub fn dump_timeseries(
                 .timestamp
                 .duration_since(SystemTime::UNIX_EPOCH)?
                 .as_secs() as i64,
+            ..Default::default()
         };
         // Base on the exec result, we will determine if we need to generate the line breaker, etc
         let comma_flag = round != 0;
-        let res = match dumper.dump_model(&ctx, &model, output, &mut round, comma_flag) {
+        let res = match columnar.as_mut() {
+            // Rows are collected in ctx, nothing is written to the output
+            Some(writer) => dumper
+                .dump_model(&ctx, &model, &mut io::sink(), &mut round, comma_flag)
+                .and_then(|res| {
+                    writer.push_rows(
+                        ctx.rows.replace(Vec::new()),
+                        ctx.timestamp,
+                        &model.system.hostname,
+                        output,
+                    )?;
+                    Ok(res)
+                }),
+            None => dumper.dump_model(&ctx, &model, output, &mut round, comma_flag),
+        };
+        let res = match res {
             Ok(res) => res,
             Err(e) => {
                 // Swallow BrokenPipe error for write. Rust runtime will ignore SIGPIPE by default and
                 // propagating EPIPE upwards to the application in the form of an IoError::BrokenPipe.
-                if e.downcast_ref::<std::io::Error>()
-                    .map_or(false, |e| e.kind() == std::io::ErrorKind::BrokenPipe)
-                {
+                if is_broken_pipe(&e) {
                     return Ok(());
                 } else {
                     return Err(e);
@@ -113,7 +155,7 @@ pub fn dump_timeseries(
 
         if json {
             write!(output, "\n")?;
-        } else if br.is_some() && !csv {
+        } else if br.is_some() && !csv && !ndjson {
             write!(output, "{}\n", br.as_ref().unwrap())?;
         }
     }
@@ -122,7 +164,58 @@ pub fn dump_timeseries(
         write!(output, "]")?;
     }
 
+    if let Some(writer) = columnar {
+        writer.finish(output)?;
+    }
+
     cliutil::check_final_sample_time_with_requested_time(model.timestamp, time_end);
 
     Ok(())
 }
+
+/// Called by dump commands with --compare to dump the Models at two points in
+/// time side by side. The sample at time_base is the base of the comparison
+/// and should not be later than the one at time_target.
+pub fn dump_compare(
+    mut advance: Advance,
+    time_base: SystemTime,
+    time_target: SystemTime,
+    dumper: &dyn Dumper,
+    output: &mut dyn Write,
+) -> Result<()> {
+    if time_target < time_base {
+        bail!(
+            "--compare time should not be earlier than --begin, \
+            compare a sample with a later one instead"
+        );
+    }
+    let mut jump_to = |timestamp: SystemTime| -> Result<(CommonFieldContext, model::Model)> {
+        let model = match advance.jump_sample_to(timestamp) {
+            Some(m) => m,
+            None => bail!(
+                "No sample could be found at {}!\n\
+                You may have provided a time in the future or no data was recorded at the provided time. \
+                Please check your input and timezone.",
+                timestamp_to_datetime(
+                    &(timestamp.duration_since(SystemTime::UNIX_EPOCH)?.as_secs() as i64)
+                )
+            ),
+        };
+        let ctx = CommonFieldContext {
+            timestamp: model
+                .timestamp
+                .duration_since(SystemTime::UNIX_EPOCH)?
+                .as_secs() as i64,
+            ..Default::default()
+        };
+        Ok((ctx, model))
+    };
+    let (ctx_base, base) = jump_to(time_base)?;
+    let (ctx_target, target) = jump_to(time_target)?;
+
+    match dumper.dump_compare((&ctx_base, &base), (&ctx_target, &target), output) {
+        // Swallow BrokenPipe error for write, same as dump_timeseries.
+        Err(e) if !is_broken_pipe(&e) => Err(e),
+        _ => Ok(()),
+    }
+}
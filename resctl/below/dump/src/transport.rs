@@ -17,14 +17,16 @@ use super::*;
 pub struct Transport {
     opts: GeneralOpt,
     fields: Vec<TransportField>,
+    where_expr: Option<model::FilterExpr<model::NetworkModelFieldId>>,
 }
 
 impl Transport {
-    pub fn new(opts: &GeneralOpt, fields: Vec<TransportField>) -> Self {
-        Self {
+    pub fn new(opts: &GeneralOpt, fields: Vec<TransportField>) -> Result<Self> {
+        Ok(Self {
             opts: opts.to_owned(),
             fields,
-        }
+            where_expr: parse_where(opts)?,
+        })
     }
 }
 
@@ -37,6 +39,12 @@ impl Dumper for Transport {
         round: &mut usize,
         comma_flag: bool,
     ) -> Result<IterExecResult> {
+        if let Some(where_expr) = self.where_expr.as_ref() {
+            if !where_expr.eval(&model.network) {
+                return Ok(IterExecResult::Skip);
+            }
+        }
+
         match self.opts.output_format {
             Some(OutputFormat::Raw) | None => write!(
                 output,
@@ -51,7 +59,7 @@ impl Dumper for Transport {
                     self.opts.raw
                 )
             )?,
-            Some(OutputFormat::Csv) => write!(
+            Some(OutputFormat::Csv) | Some(OutputFormat::Tsv) => write!(
                 output,
                 "{}",
                 print::dump_csv(
@@ -59,8 +67,8 @@ impl Dumper for Transport {
                     ctx,
                     &model.network,
                     *round,
-                    self.opts.disable_title,
-                    self.opts.raw
+                    "transport",
+                    &self.opts
                 )
             )?,
             Some(OutputFormat::KeyVal) => write!(
@@ -77,11 +85,23 @@ impl Dumper for Transport {
                     write!(output, "{}", json_output)?;
                 }
             }
+            Some(OutputFormat::NdJson) => writeln!(
+                output,
+                "{}",
+                print::dump_json(&self.fields, ctx, &model.network, self.opts.raw)
+            )?,
+            Some(OutputFormat::Parquet) | Some(OutputFormat::Arrow) => {
+                print::dump_row(&self.fields, ctx, &model.network)
+            }
         };
         *round += 1;
 
-        if self.opts.output_format != Some(OutputFormat::Json) {
-            write!(output, "\n")?;
+        match self.opts.output_format {
+            Some(OutputFormat::Json)
+            | Some(OutputFormat::NdJson)
+            | Some(OutputFormat::Parquet)
+            | Some(OutputFormat::Arrow) => {}
+            _ => write!(output, "\n")?,
         }
 
         Ok(IterExecResult::Success)
@@ -0,0 +1,229 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Alerting rules evaluated against each new Model. A rule is a filter
+//! expression over the fields of the system, of each cgroup or of each
+//! process, and fires for a subject once the expression has held for it
+//! long enough.
+use super::*;
+
+use anyhow::bail;
+
+enum AlertCondition {
+    System(FilterExpr<SystemModelFieldId>),
+    Cgroup(FilterExpr<CgroupModelFieldId>),
+    Process(FilterExpr<SingleProcessModelFieldId>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertState {
+    Firing,
+    Resolved,
+}
+
+impl fmt::Display for AlertState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlertState::Firing => "firing".fmt(f),
+            AlertState::Resolved => "resolved".fmt(f),
+        }
+    }
+}
+
+/// Record of a rule starting or stopping to fire for a subject.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Alert {
+    /// Unix timestamp of the Model the state changed in.
+    pub timestamp: u64,
+    pub rule: String,
+    /// "system", the cgroup path, or the pid and comm of the process.
+    pub subject: String,
+    pub state: AlertState,
+    pub condition: String,
+}
+
+pub struct AlertRule {
+    name: String,
+    condition_str: String,
+    condition: AlertCondition,
+    for_duration: Duration,
+    /// Subjects for which the condition holds, with the time since when it
+    /// holds and whether the rule fired for them.
+    matching: BTreeMap<String, (SystemTime, bool)>,
+}
+
+impl AlertRule {
+    /// Create a rule firing when `condition` held for `for_duration`.
+    /// `entity` is one of "system", "cgroup" or "process" and `condition` a
+    /// filter expression over its fields, same as `below dump --where`.
+    pub fn new(name: &str, entity: &str, condition: &str, for_duration: Duration) -> Result<Self> {
+        let parsed = match entity {
+            "system" => AlertCondition::System(condition.parse()?),
+            "cgroup" => AlertCondition::Cgroup(condition.parse()?),
+            "process" => AlertCondition::Process(condition.parse()?),
+            _ => bail!("Unknown alert entity: {}", entity),
+        };
+        Ok(Self {
+            name: name.to_owned(),
+            condition_str: condition.to_owned(),
+            condition: parsed,
+            for_duration,
+            matching: BTreeMap::new(),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Subjects for which the condition holds in the Model.
+    fn get_matching_subjects(&self, model: &Model) -> BTreeSet<String> {
+        match &self.condition {
+            AlertCondition::System(expr) => {
+                if expr.eval(&model.system) {
+                    std::iter::once("system".to_owned()).collect()
+                } else {
+                    BTreeSet::new()
+                }
+            }
+            AlertCondition::Cgroup(expr) => flatten_cgroups(&model.cgroup)
+                .into_iter()
+                .filter(|(_, cgroup)| expr.eval(cgroup))
+                .map(|(path, _)| if path.is_empty() { "<root>" } else { path }.to_owned())
+                .collect(),
+            AlertCondition::Process(expr) => model
+                .process
+                .processes
+                .iter()
+                .filter(|(_, spm)| expr.eval(spm))
+                .map(|(pid, spm)| format!("{} {}", pid, spm.comm.as_deref().unwrap_or("?")))
+                .collect(),
+        }
+    }
+
+    fn new_alert(&self, timestamp: SystemTime, subject: String, state: AlertState) -> Alert {
+        Alert {
+            timestamp: timestamp
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            rule: self.name.clone(),
+            subject,
+            state,
+            condition: self.condition_str.clone(),
+        }
+    }
+
+    /// Evaluate the rule against a Model later than the Models evaluated
+    /// before, and return the subjects the rule started or stopped firing
+    /// for.
+    pub fn evaluate(&mut self, model: &Model) -> Vec<Alert> {
+        let timestamp = model.timestamp;
+        let subjects = self.get_matching_subjects(model);
+        let mut alerts = Vec::new();
+
+        let mut matching = BTreeMap::new();
+        for subject in subjects {
+            let (since, fired) = self.matching.remove(&subject).unwrap_or((timestamp, false));
+            let held = timestamp.duration_since(since).unwrap_or_default();
+            if !fired && held >= self.for_duration {
+                alerts.push(self.new_alert(timestamp, subject.clone(), AlertState::Firing));
+                matching.insert(subject, (since, true));
+            } else {
+                matching.insert(subject, (since, fired));
+            }
+        }
+
+        // Remaining subjects do not match any more
+        for (subject, (_, fired)) in std::mem::replace(&mut self.matching, matching) {
+            if fired {
+                alerts.push(self.new_alert(timestamp, subject, AlertState::Resolved));
+            }
+        }
+        alerts
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_model(secs: u64, memory_full_pct: f64) -> Model {
+        let cgroup = |name: &str, memory_full_pct: f64| CgroupModel {
+            name: name.to_owned(),
+            full_path: format!("/{}", name),
+            pressure: Some(CgroupPressureModel {
+                memory_full_pct: Some(memory_full_pct),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let mut root = cgroup("", 0.0);
+        root.full_path = String::new();
+        root.children.insert(cgroup("a", memory_full_pct));
+        root.children.insert(cgroup("b", 0.0));
+        Model {
+            time_elapsed: Duration::from_secs(5),
+            timestamp: std::time::UNIX_EPOCH + Duration::from_secs(secs),
+            system: Default::default(),
+            cgroup: root,
+            process: Default::default(),
+            network: Default::default(),
+            high_res: false,
+            oom_kills: Vec::new(),
+            proc_events: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_alert_rule() {
+        let mut rule = AlertRule::new(
+            "memory_pressure",
+            "cgroup",
+            "pressure.memory_full_pct > 20",
+            Duration::from_secs(10),
+        )
+        .expect("Failed to create rule");
+
+        // Not held for long enough
+        assert_eq!(rule.evaluate(&get_model(100, 30.0)), vec![]);
+        assert_eq!(rule.evaluate(&get_model(105, 30.0)), vec![]);
+        assert_eq!(
+            rule.evaluate(&get_model(110, 30.0)),
+            vec![Alert {
+                timestamp: 110,
+                rule: "memory_pressure".to_owned(),
+                subject: "/a".to_owned(),
+                state: AlertState::Firing,
+                condition: "pressure.memory_full_pct > 20".to_owned(),
+            }]
+        );
+        // Fires only once
+        assert_eq!(rule.evaluate(&get_model(115, 30.0)), vec![]);
+        let alerts = rule.evaluate(&get_model(120, 10.0));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].state, AlertState::Resolved);
+
+        // The condition must hold again for the whole duration
+        assert_eq!(rule.evaluate(&get_model(125, 30.0)), vec![]);
+        assert_eq!(rule.evaluate(&get_model(130, 10.0)), vec![]);
+        assert_eq!(rule.evaluate(&get_model(135, 30.0)), vec![]);
+    }
+
+    #[test]
+    fn test_alert_rule_invalid() {
+        assert!(AlertRule::new("a", "disk", "name == sda", Duration::default()).is_err());
+        assert!(AlertRule::new("a", "system", "no_such_field > 1", Duration::default()).is_err());
+    }
+}
@@ -33,6 +33,25 @@ pub struct CgroupModel {
     pub io_total: Option<CgroupIoModel>,
     #[queriable(subquery)]
     pub pressure: Option<CgroupPressureModel>,
+    /// Of processes in the cgroup and its descendants
+    #[queriable(subquery)]
+    pub net: Option<NetBytesModel>,
+    #[queriable(subquery)]
+    pub runq_lat: Option<CgroupRunqLatModel>,
+    #[queriable(ignore)]
+    pub hugetlb: Option<BTreeMap<String, CgroupHugetlbModel>>,
+    #[queriable(subquery)]
+    #[queriable(preferred_name = hugetlb)]
+    pub hugetlb_total: Option<CgroupHugetlbModel>,
+    #[queriable(ignore)]
+    pub rdma: Option<BTreeMap<String, CgroupRdmaModel>>,
+    #[queriable(subquery)]
+    #[queriable(preferred_name = rdma)]
+    pub rdma_total: Option<CgroupRdmaModel>,
+    /// Keyed by misc resource, e.g. sev. Resources are unrelated, so there
+    /// is no total.
+    #[queriable(subquery)]
+    pub misc: Option<BTreeMap<String, CgroupMiscModel>>,
     #[queriable(ignore)]
     pub children: BTreeSet<CgroupModel>,
     #[queriable(ignore)]
@@ -80,39 +99,48 @@ impl CgroupModel {
                 (None, None) => Some((s, d)),
                 _ => None,
             });
-        let (cpu, io, io_total, recreate_flag) = if let Some((last, delta)) = last_if_inode_matches
-        {
-            // We have cumulative data, create cpu, io models
-            let cpu = match (last.cpu_stat.as_ref(), sample.cpu_stat.as_ref()) {
-                (Some(begin), Some(end)) => Some(CgroupCpuModel::new(begin, end, delta)),
-                _ => None,
-            };
-            let io = match (last.io_stat.as_ref(), sample.io_stat.as_ref()) {
-                (Some(begin), Some(end)) => Some(
-                    end.iter()
-                        .filter_map(|(device_name, end_io_stat)| {
-                            begin.get(device_name).map(|begin_io_stat| {
-                                (
-                                    device_name.clone(),
-                                    CgroupIoModel::new(&begin_io_stat, &end_io_stat, delta),
-                                )
+        let (cpu, io, io_total, net, runq_lat, recreate_flag) =
+            if let Some((last, delta)) = last_if_inode_matches {
+                // We have cumulative data, create cpu, io models
+                let cpu = match (last.cpu_stat.as_ref(), sample.cpu_stat.as_ref()) {
+                    (Some(begin), Some(end)) => Some(CgroupCpuModel::new(begin, end, delta)),
+                    _ => None,
+                };
+                let io = match (last.io_stat.as_ref(), sample.io_stat.as_ref()) {
+                    (Some(begin), Some(end)) => Some(
+                        end.iter()
+                            .filter_map(|(device_name, end_io_stat)| {
+                                begin.get(device_name).map(|begin_io_stat| {
+                                    (
+                                        device_name.clone(),
+                                        CgroupIoModel::new(begin_io_stat, end_io_stat, delta),
+                                    )
+                                })
                             })
-                        })
-                        .collect::<BTreeMap<String, CgroupIoModel>>(),
-                ),
-                _ => None,
-            };
-            let io_total = io.as_ref().map(|io_map| {
-                io_map
-                    .iter()
-                    .fold(CgroupIoModel::empty(), |acc, (_, model)| acc + model)
-            });
+                            .collect::<BTreeMap<String, CgroupIoModel>>(),
+                    ),
+                    _ => None,
+                };
+                let io_total = io.as_ref().map(|io_map| {
+                    io_map
+                        .iter()
+                        .fold(CgroupIoModel::empty(), |acc, (_, model)| acc + model)
+                });
 
-            (cpu, io, io_total, false)
-        } else {
-            // No cumulative data or inode number is different
-            (None, None, None, last.is_some())
-        };
+                let net = match (last.net_bytes.as_ref(), sample.net_bytes.as_ref()) {
+                    (Some(begin), Some(end)) => Some(NetBytesModel::new(begin, end, delta)),
+                    _ => None,
+                };
+                let runq_lat = match (last.runq_latency.as_ref(), sample.runq_latency.as_ref()) {
+                    (Some(begin), Some(end)) => CgroupRunqLatModel::new(begin, end),
+                    _ => None,
+                };
+
+                (cpu, io, io_total, net, runq_lat, false)
+            } else {
+                // No cumulative data or inode number is different
+                (None, None, None, None, None, last.is_some())
+            };
 
         let memory = Some(CgroupMemoryModel::new(sample, last));
 
@@ -121,6 +149,44 @@ impl CgroupModel {
             .as_ref()
             .map(|p| CgroupPressureModel::new(p));
 
+        let hugetlb = sample.hugetlb.as_ref().map(|hugetlb_map| {
+            hugetlb_map
+                .iter()
+                .map(|(size, stat)| (size.clone(), CgroupHugetlbModel::new(stat)))
+                .collect::<BTreeMap<String, CgroupHugetlbModel>>()
+        });
+        let hugetlb_total = hugetlb.as_ref().map(|hugetlb_map| {
+            hugetlb_map
+                .iter()
+                .fold(CgroupHugetlbModel::empty(), |acc, (_, model)| acc + model)
+        });
+
+        let rdma = sample.rdma_current.as_ref().map(|rdma_map| {
+            rdma_map
+                .iter()
+                .map(|(device, stat)| (device.clone(), CgroupRdmaModel::new(stat)))
+                .collect::<BTreeMap<String, CgroupRdmaModel>>()
+        });
+        let rdma_total = rdma.as_ref().map(|rdma_map| {
+            rdma_map
+                .iter()
+                .fold(CgroupRdmaModel::empty(), |acc, (_, model)| acc + model)
+        });
+
+        let misc = sample.misc_current.as_ref().map(|misc_map| {
+            misc_map
+                .iter()
+                .map(|(resource, current)| {
+                    (
+                        resource.clone(),
+                        CgroupMiscModel {
+                            current: Some(*current),
+                        },
+                    )
+                })
+                .collect()
+        });
+
         // recursively calculate view of children
         // `children` is optional, but we treat it the same as an empty map
         let empty = BTreeMap::new();
@@ -155,6 +221,13 @@ impl CgroupModel {
             io,
             io_total,
             pressure,
+            net,
+            runq_lat,
+            hugetlb,
+            hugetlb_total,
+            rdma,
+            rdma_total,
+            misc,
             children,
             count: nr_descendants + 1,
             depth,
@@ -277,6 +350,66 @@ impl std::ops::Add<&CgroupIoModel> for CgroupIoModel {
     }
 }
 
+/// How long tasks in the cgroup and its descendants waited on a run queue
+/// before running during the interval, accounted by bpf in log2 buckets. Each
+/// value is the upper bound of the bucket it falls in. None if no task ran.
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    below_derive::Queriable
+)]
+pub struct CgroupRunqLatModel {
+    pub p50_usec: Option<u64>,
+    pub p99_usec: Option<u64>,
+    pub max_usec: Option<u64>,
+}
+
+impl CgroupRunqLatModel {
+    /// None if the histogram is not monotonic from `begin` to `end`, e.g.
+    /// below restarted in between.
+    pub fn new(
+        begin: &procfs::RunqLatency,
+        end: &procfs::RunqLatency,
+    ) -> Option<CgroupRunqLatModel> {
+        if begin.buckets.len() > end.buckets.len() {
+            return None;
+        }
+        let buckets = end
+            .buckets
+            .iter()
+            .enumerate()
+            .map(|(i, count)| count.checked_sub(*begin.buckets.get(i).unwrap_or(&0)))
+            .collect::<Option<Vec<u64>>>()?;
+        let total: u64 = buckets.iter().sum();
+        // Upper bound of bucket i, see procfs::RunqLatency
+        let upper_bound = |i: usize| 1u64 << (i + 1);
+        let percentile = |pct: u64| {
+            if total == 0 {
+                return None;
+            }
+            // Rank of the percentile, at least the first wait
+            let rank = std::cmp::max((total * pct + 99) / 100, 1);
+            let mut seen = 0;
+            buckets.iter().position(|count| {
+                seen += count;
+                seen >= rank
+            })
+        };
+        Some(CgroupRunqLatModel {
+            p50_usec: percentile(50).map(upper_bound),
+            p99_usec: percentile(99).map(upper_bound),
+            max_usec: buckets
+                .iter()
+                .rposition(|count| *count > 0)
+                .map(upper_bound),
+        })
+    }
+}
+
 #[derive(
     Clone,
     Debug,
@@ -306,6 +439,13 @@ pub struct CgroupMemoryModel {
     pub unevictable: Option<u64>,
     pub slab_reclaimable: Option<u64>,
     pub slab_unreclaimable: Option<u64>,
+    pub pagetables: Option<u64>,
+    pub percpu: Option<u64>,
+    pub swapcached: Option<u64>,
+    pub zswap: Option<u64>,
+    pub zswapped: Option<u64>,
+    pub file_thp: Option<u64>,
+    pub shmem_thp: Option<u64>,
     pub pgfault: Option<u64>,
     pub pgmajfault: Option<u64>,
     pub workingset_refault: Option<u64>,
@@ -320,12 +460,40 @@ pub struct CgroupMemoryModel {
     pub pglazyfreed: Option<u64>,
     pub thp_fault_alloc: Option<u64>,
     pub thp_collapse_alloc: Option<u64>,
+    pub workingset_refault_anon: Option<u64>,
+    pub workingset_refault_file: Option<u64>,
+    pub workingset_activate_anon: Option<u64>,
+    pub workingset_activate_file: Option<u64>,
+    pub workingset_restore_anon: Option<u64>,
+    pub workingset_restore_file: Option<u64>,
+    pub pgscan_kswapd: Option<u64>,
+    pub pgscan_direct: Option<u64>,
+    pub pgsteal_kswapd: Option<u64>,
+    pub pgsteal_direct: Option<u64>,
+    pub zswpin: Option<u64>,
+    pub zswpout: Option<u64>,
+    pub thp_swpout: Option<u64>,
+    pub thp_swpout_fallback: Option<u64>,
     pub memory_high: Option<i64>,
     pub events_low: Option<u64>,
     pub events_high: Option<u64>,
     pub events_max: Option<u64>,
     pub events_oom: Option<u64>,
     pub events_oom_kill: Option<u64>,
+    pub events_local_low: Option<u64>,
+    pub events_local_high: Option<u64>,
+    pub events_local_max: Option<u64>,
+    pub events_local_oom: Option<u64>,
+    pub events_local_oom_kill: Option<u64>,
+    pub swap_events_high: Option<u64>,
+    pub swap_events_max: Option<u64>,
+    pub swap_events_fail: Option<u64>,
+    /// Raw values of memory.stat keys not modelled above, e.g. added by
+    /// newer kernels. Counters are not converted to rates as the kernel does
+    /// not tell them apart from sizes.
+    #[queriable(ignore)]
+    #[serde(default)]
+    pub other_stat: BTreeMap<String, u64>,
 }
 
 impl std::ops::Add for CgroupMemoryModel {
@@ -352,6 +520,13 @@ impl std::ops::Add for CgroupMemoryModel {
             unevictable: opt_add(self.unevictable, other.unevictable),
             slab_reclaimable: opt_add(self.slab_reclaimable, other.slab_reclaimable),
             slab_unreclaimable: opt_add(self.slab_unreclaimable, other.slab_unreclaimable),
+            pagetables: opt_add(self.pagetables, other.pagetables),
+            percpu: opt_add(self.percpu, other.percpu),
+            swapcached: opt_add(self.swapcached, other.swapcached),
+            zswap: opt_add(self.zswap, other.zswap),
+            zswapped: opt_add(self.zswapped, other.zswapped),
+            file_thp: opt_add(self.file_thp, other.file_thp),
+            shmem_thp: opt_add(self.shmem_thp, other.shmem_thp),
             pgfault: opt_add(self.pgfault, other.pgfault),
             pgmajfault: opt_add(self.pgmajfault, other.pgmajfault),
             workingset_refault: opt_add(self.workingset_refault, other.workingset_refault),
@@ -369,12 +544,59 @@ impl std::ops::Add for CgroupMemoryModel {
             pglazyfreed: opt_add(self.pglazyfreed, other.pglazyfreed),
             thp_fault_alloc: opt_add(self.thp_fault_alloc, other.thp_fault_alloc),
             thp_collapse_alloc: opt_add(self.thp_collapse_alloc, other.thp_collapse_alloc),
+            workingset_refault_anon: opt_add(
+                self.workingset_refault_anon,
+                other.workingset_refault_anon,
+            ),
+            workingset_refault_file: opt_add(
+                self.workingset_refault_file,
+                other.workingset_refault_file,
+            ),
+            workingset_activate_anon: opt_add(
+                self.workingset_activate_anon,
+                other.workingset_activate_anon,
+            ),
+            workingset_activate_file: opt_add(
+                self.workingset_activate_file,
+                other.workingset_activate_file,
+            ),
+            workingset_restore_anon: opt_add(
+                self.workingset_restore_anon,
+                other.workingset_restore_anon,
+            ),
+            workingset_restore_file: opt_add(
+                self.workingset_restore_file,
+                other.workingset_restore_file,
+            ),
+            pgscan_kswapd: opt_add(self.pgscan_kswapd, other.pgscan_kswapd),
+            pgscan_direct: opt_add(self.pgscan_direct, other.pgscan_direct),
+            pgsteal_kswapd: opt_add(self.pgsteal_kswapd, other.pgsteal_kswapd),
+            pgsteal_direct: opt_add(self.pgsteal_direct, other.pgsteal_direct),
+            zswpin: opt_add(self.zswpin, other.zswpin),
+            zswpout: opt_add(self.zswpout, other.zswpout),
+            thp_swpout: opt_add(self.thp_swpout, other.thp_swpout),
+            thp_swpout_fallback: opt_add(self.thp_swpout_fallback, other.thp_swpout_fallback),
             memory_high: None,
             events_low: opt_add(self.events_low, other.events_low),
             events_high: opt_add(self.events_high, other.events_high),
             events_max: opt_add(self.events_max, other.events_max),
             events_oom: opt_add(self.events_oom, other.events_oom),
             events_oom_kill: opt_add(self.events_oom_kill, other.events_oom_kill),
+            events_local_low: opt_add(self.events_local_low, other.events_local_low),
+            events_local_high: opt_add(self.events_local_high, other.events_local_high),
+            events_local_max: opt_add(self.events_local_max, other.events_local_max),
+            events_local_oom: opt_add(self.events_local_oom, other.events_local_oom),
+            events_local_oom_kill: opt_add(self.events_local_oom_kill, other.events_local_oom_kill),
+            swap_events_high: opt_add(self.swap_events_high, other.swap_events_high),
+            swap_events_max: opt_add(self.swap_events_max, other.swap_events_max),
+            swap_events_fail: opt_add(self.swap_events_fail, other.swap_events_fail),
+            other_stat: {
+                let mut other_stat = self.other_stat;
+                for (key, value) in other.other_stat {
+                    *other_stat.entry(key).or_default() += value;
+                }
+                other_stat
+            },
         }
     }
 }
@@ -397,6 +619,25 @@ impl CgroupMemoryModel {
             model.events_oom = events.oom.map(|v| v as u64);
             model.events_oom_kill = events.oom_kill.map(|v| v as u64);
         }
+        if let Some((last, delta)) = last {
+            if let (Some(last_events), Some(events)) =
+                (&last.memory_events_local, &sample.memory_events_local)
+            {
+                model.events_local_low = count_per_sec!(last_events.low, events.low, delta, u64);
+                model.events_local_high = count_per_sec!(last_events.high, events.high, delta, u64);
+                model.events_local_max = count_per_sec!(last_events.max, events.max, delta, u64);
+                model.events_local_oom = count_per_sec!(last_events.oom, events.oom, delta, u64);
+                model.events_local_oom_kill =
+                    count_per_sec!(last_events.oom_kill, events.oom_kill, delta, u64);
+            }
+            if let (Some(last_events), Some(events)) =
+                (&last.memory_swap_events, &sample.memory_swap_events)
+            {
+                model.swap_events_high = count_per_sec!(last_events.high, events.high, delta, u64);
+                model.swap_events_max = count_per_sec!(last_events.max, events.max, delta, u64);
+                model.swap_events_fail = count_per_sec!(last_events.fail, events.fail, delta, u64);
+            }
+        }
         if let Some(stat) = &sample.memory_stat {
             model.anon = stat.anon.map(|v| v as u64);
             model.file = stat.file.map(|v| v as u64);
@@ -415,6 +656,14 @@ impl CgroupMemoryModel {
             model.unevictable = stat.unevictable.map(|v| v as u64);
             model.slab_reclaimable = stat.slab_reclaimable.map(|v| v as u64);
             model.slab_unreclaimable = stat.slab_unreclaimable.map(|v| v as u64);
+            model.pagetables = stat.pagetables.map(|v| v as u64);
+            model.percpu = stat.percpu.map(|v| v as u64);
+            model.swapcached = stat.swapcached.map(|v| v as u64);
+            model.zswap = stat.zswap.map(|v| v as u64);
+            model.zswapped = stat.zswapped.map(|v| v as u64);
+            model.file_thp = stat.file_thp.map(|v| v as u64);
+            model.shmem_thp = stat.shmem_thp.map(|v| v as u64);
+            model.other_stat = stat.other.clone();
 
             if let Some((
                 CgroupSample {
@@ -464,6 +713,60 @@ impl CgroupMemoryModel {
                     delta,
                     u64
                 );
+                model.workingset_refault_anon = count_per_sec!(
+                    last_stat.workingset_refault_anon,
+                    stat.workingset_refault_anon,
+                    delta,
+                    u64
+                );
+                model.workingset_refault_file = count_per_sec!(
+                    last_stat.workingset_refault_file,
+                    stat.workingset_refault_file,
+                    delta,
+                    u64
+                );
+                model.workingset_activate_anon = count_per_sec!(
+                    last_stat.workingset_activate_anon,
+                    stat.workingset_activate_anon,
+                    delta,
+                    u64
+                );
+                model.workingset_activate_file = count_per_sec!(
+                    last_stat.workingset_activate_file,
+                    stat.workingset_activate_file,
+                    delta,
+                    u64
+                );
+                model.workingset_restore_anon = count_per_sec!(
+                    last_stat.workingset_restore_anon,
+                    stat.workingset_restore_anon,
+                    delta,
+                    u64
+                );
+                model.workingset_restore_file = count_per_sec!(
+                    last_stat.workingset_restore_file,
+                    stat.workingset_restore_file,
+                    delta,
+                    u64
+                );
+                model.pgscan_kswapd =
+                    count_per_sec!(last_stat.pgscan_kswapd, stat.pgscan_kswapd, delta, u64);
+                model.pgscan_direct =
+                    count_per_sec!(last_stat.pgscan_direct, stat.pgscan_direct, delta, u64);
+                model.pgsteal_kswapd =
+                    count_per_sec!(last_stat.pgsteal_kswapd, stat.pgsteal_kswapd, delta, u64);
+                model.pgsteal_direct =
+                    count_per_sec!(last_stat.pgsteal_direct, stat.pgsteal_direct, delta, u64);
+                model.zswpin = count_per_sec!(last_stat.zswpin, stat.zswpin, delta, u64);
+                model.zswpout = count_per_sec!(last_stat.zswpout, stat.zswpout, delta, u64);
+                model.thp_swpout =
+                    count_per_sec!(last_stat.thp_swpout, stat.thp_swpout, delta, u64);
+                model.thp_swpout_fallback = count_per_sec!(
+                    last_stat.thp_swpout_fallback,
+                    stat.thp_swpout_fallback,
+                    delta,
+                    u64
+                );
             }
         }
 
@@ -502,3 +805,135 @@ impl CgroupPressureModel {
         }
     }
 }
+
+/// Usage of a single resource of the misc controller
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    below_derive::Queriable
+)]
+pub struct CgroupMiscModel {
+    pub current: Option<u64>,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    below_derive::Queriable
+)]
+pub struct CgroupHugetlbModel {
+    pub current: Option<u64>,
+    pub max: Option<i64>,
+    pub events_max: Option<u64>,
+}
+
+impl CgroupHugetlbModel {
+    fn new(stat: &cgroupfs::HugetlbStat) -> CgroupHugetlbModel {
+        CgroupHugetlbModel {
+            current: stat.current,
+            max: stat.max,
+            events_max: stat.events.as_ref().and_then(|events| events.max),
+        }
+    }
+
+    pub fn empty() -> CgroupHugetlbModel {
+        CgroupHugetlbModel {
+            current: Some(0),
+            max: None,
+            events_max: Some(0),
+        }
+    }
+}
+
+impl std::ops::Add<&CgroupHugetlbModel> for CgroupHugetlbModel {
+    type Output = Self;
+
+    fn add(self, other: &Self) -> Self {
+        Self {
+            current: opt_add(self.current, other.current),
+            // Limits of different page sizes are not comparable
+            max: None,
+            events_max: opt_add(self.events_max, other.events_max),
+        }
+    }
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    below_derive::Queriable
+)]
+pub struct CgroupRdmaModel {
+    pub hca_handle: Option<u64>,
+    pub hca_object: Option<u64>,
+}
+
+impl CgroupRdmaModel {
+    fn new(stat: &cgroupfs::RdmaStat) -> CgroupRdmaModel {
+        CgroupRdmaModel {
+            hca_handle: stat.hca_handle,
+            hca_object: stat.hca_object,
+        }
+    }
+
+    pub fn empty() -> CgroupRdmaModel {
+        CgroupRdmaModel {
+            hca_handle: Some(0),
+            hca_object: Some(0),
+        }
+    }
+}
+
+impl std::ops::Add<&CgroupRdmaModel> for CgroupRdmaModel {
+    type Output = Self;
+
+    fn add(self, other: &Self) -> Self {
+        Self {
+            hca_handle: opt_add(self.hca_handle, other.hca_handle),
+            hca_object: opt_add(self.hca_object, other.hca_object),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn runq_latency(buckets: Vec<u64>) -> procfs::RunqLatency {
+        procfs::RunqLatency { buckets }
+    }
+
+    #[test]
+    fn test_runq_lat_model() {
+        // 100 waits in the interval: 90 under 2us, 9 of [4, 8)us and one of
+        // [64, 128)us. Buckets past the end of begin are new.
+        let begin = runq_latency(vec![10, 5, 1]);
+        let end = runq_latency(vec![100, 5, 10, 0, 0, 0, 1]);
+        let model = CgroupRunqLatModel::new(&begin, &end).unwrap();
+        assert_eq!(model.p50_usec, Some(2));
+        assert_eq!(model.p99_usec, Some(8));
+        assert_eq!(model.max_usec, Some(128));
+
+        let model = CgroupRunqLatModel::new(&end, &end);
+        assert_eq!(model, Some(CgroupRunqLatModel::default()));
+
+        // Any bucket decreasing means the histogram was reset
+        assert_eq!(CgroupRunqLatModel::new(&end, &begin), None);
+        assert_eq!(
+            CgroupRunqLatModel::new(&begin, &runq_latency(vec![100, 4, 10])),
+            None
+        );
+    }
+}
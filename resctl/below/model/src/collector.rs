@@ -22,6 +22,10 @@ use slog::{self, error};
 pub struct Collector {
     last: Option<(Sample, Instant)>,
     exit_data: Arc<Mutex<procfs::PidMap>>,
+    net_bytes: Option<Arc<Mutex<procfs::NetBytesMaps>>>,
+    runq_latency: Option<Arc<Mutex<procfs::RunqLatencyMap>>>,
+    oom_kills: Option<Arc<Mutex<Vec<procfs::OomKill>>>>,
+    proc_events: Option<Arc<Mutex<Vec<procfs::ProcEvent>>>>,
 }
 
 impl Collector {
@@ -29,13 +33,57 @@ impl Collector {
         Collector {
             last: None,
             exit_data,
+            net_bytes: None,
+            runq_latency: None,
+            oom_kills: None,
+            proc_events: None,
         }
     }
 
+    /// Also collect the socket bytes of processes and cgroups accumulated
+    /// by bpf into `net_bytes`, or stop doing so if None.
+    pub fn set_net_bytes(&mut self, net_bytes: Option<Arc<Mutex<procfs::NetBytesMaps>>>) {
+        self.net_bytes = net_bytes;
+    }
+
+    /// Also collect the run-queue latency histograms of cgroups accumulated
+    /// by bpf into `runq_latency`, or stop doing so if None.
+    pub fn set_runq_latency(&mut self, runq_latency: Option<Arc<Mutex<procfs::RunqLatencyMap>>>) {
+        self.runq_latency = runq_latency;
+    }
+
+    /// Also collect the OOM kills reported by bpf into `oom_kills`, or stop
+    /// doing so if None.
+    pub fn set_oom_kills(&mut self, oom_kills: Option<Arc<Mutex<Vec<procfs::OomKill>>>>) {
+        self.oom_kills = oom_kills;
+    }
+
+    /// Also collect the process events reported by bpf into `proc_events`,
+    /// or stop doing so if None.
+    pub fn set_proc_events(&mut self, proc_events: Option<Arc<Mutex<Vec<procfs::ProcEvent>>>>) {
+        self.proc_events = proc_events;
+    }
+
     /// Collect a new `Sample`, returning an updated Model
     pub fn update_model(&mut self, logger: &slog::Logger) -> Result<Model> {
         let now = Instant::now();
-        let sample = collect_sample(&self.exit_data, true, logger, false, &None)?;
+        let mut sample = collect_sample(
+            &self.exit_data,
+            self.net_bytes.as_ref(),
+            self.runq_latency.as_ref(),
+            true,
+            logger,
+            false,
+            &None,
+        )?;
+        if let Some(oom_kills) = self.oom_kills.as_ref() {
+            sample.oom_kills =
+                std::mem::take(&mut *oom_kills.lock().expect("tried to acquire poisoned lock"));
+        }
+        if let Some(proc_events) = self.proc_events.as_ref() {
+            sample.proc_events =
+                std::mem::take(&mut *proc_events.lock().expect("tried to acquire poisoned lock"));
+        }
         let last = self.last.replace((sample, now));
         let model = Model::new(
             SystemTime::now(),
@@ -108,6 +156,212 @@ fn merge_procfs_and_exit_data(
     procfs_data
 }
 
+/// Map the inode number of each cgroup to its path, e.g. "/system.slice".
+fn get_cgroup_paths(cgroup: &CgroupSample) -> BTreeMap<u64, String> {
+    fn insert_paths(cgroup: &CgroupSample, path: &str, paths: &mut BTreeMap<u64, String>) {
+        if let Some(inode_number) = cgroup.inode_number {
+            paths.insert(inode_number as u64, path.to_owned());
+        }
+        for (name, child) in cgroup.children.iter().flatten() {
+            let child_path = format!("{}/{}", path.trim_end_matches('/'), name);
+            insert_paths(child, &child_path, paths);
+        }
+    }
+    let mut paths = BTreeMap::new();
+    insert_paths(cgroup, "/", &mut paths);
+    paths
+}
+
+/// Resolve the cgroup ids of exited processes captured by bpf into paths.
+/// The cgroup of a process may already be removed, then its path is unknown.
+fn resolve_exit_cgroups(exit_data: &mut procfs::PidMap, cgroup: &CgroupSample) {
+    if exit_data
+        .values()
+        .all(|pidinfo| pidinfo.cgroup_id.is_none())
+    {
+        return;
+    }
+    let paths = get_cgroup_paths(cgroup);
+    for pidinfo in exit_data.values_mut() {
+        // Before kernel 5.5, the cgroup id has the inode generation in the
+        // upper 32 bits.
+        if let Some(path) = pidinfo.cgroup_id.and_then(|id| {
+            paths
+                .get(&id)
+                .or_else(|| paths.get(&(id & u32::MAX as u64)))
+        }) {
+            pidinfo.cgroup = path.clone();
+        }
+    }
+}
+
+fn add_net_bytes(a: &procfs::NetBytes, b: &procfs::NetBytes) -> procfs::NetBytes {
+    procfs::NetBytes {
+        tcp_send_bytes: opt_add(a.tcp_send_bytes, b.tcp_send_bytes),
+        tcp_recv_bytes: opt_add(a.tcp_recv_bytes, b.tcp_recv_bytes),
+        udp_send_bytes: opt_add(a.udp_send_bytes, b.udp_send_bytes),
+        udp_recv_bytes: opt_add(a.udp_recv_bytes, b.udp_recv_bytes),
+    }
+}
+
+/// Sum the values accumulated by bpf for each cgroup id by inode number of
+/// the cgroups in `cgroup`. The value of a cgroup that is gone is folded
+/// into its nearest ancestor that still exists, or into the root if the
+/// cgroup was never seen, so that `values` doesn't grow forever while the
+/// totals of ancestors never decrease. `paths` keeps the last known path of
+/// each cgroup id in `values` for this purpose.
+fn fold_cgroup_values<T: Default>(
+    cgroup: &CgroupSample,
+    values: &mut BTreeMap<u64, T>,
+    paths: &mut BTreeMap<u64, String>,
+    add: impl Fn(&mut T, &T),
+) -> BTreeMap<u64, T> {
+    let cgroup_paths = get_cgroup_paths(cgroup);
+    let inode_numbers: BTreeMap<&str, u64> = cgroup_paths
+        .iter()
+        .map(|(inode_number, path)| (path.as_str(), *inode_number))
+        .collect();
+    let get_ancestor = |path: &str| {
+        let mut path = path;
+        while let Some(idx) = path.rfind('/') {
+            path = if idx == 0 { "/" } else { &path[..idx] };
+            if let Some(inode_number) = inode_numbers.get(path) {
+                return Some(*inode_number);
+            }
+            if path == "/" {
+                break;
+            }
+        }
+        None
+    };
+
+    let mut by_inode: BTreeMap<u64, T> = BTreeMap::new();
+    let mut removed = Vec::new();
+    for (id, value) in values.iter() {
+        // Before kernel 5.5, the cgroup id has the inode generation in the
+        // upper 32 bits.
+        match [*id, id & u32::MAX as u64]
+            .iter()
+            .find(|inode_number| cgroup_paths.contains_key(inode_number))
+        {
+            Some(inode_number) => {
+                paths.insert(*id, cgroup_paths[inode_number].clone());
+                add(by_inode.entry(*inode_number).or_default(), value);
+            }
+            None => removed.push(*id),
+        }
+    }
+    for id in removed {
+        let value = values.remove(&id).expect("Removed cgroup id is in values");
+        let ancestor = paths
+            .remove(&id)
+            .and_then(|path| get_ancestor(&path))
+            .or_else(|| cgroup.inode_number.map(|inode_number| inode_number as u64));
+        if let Some(ancestor) = ancestor {
+            add(values.entry(ancestor).or_default(), &value);
+            add(by_inode.entry(ancestor).or_default(), &value);
+            paths.insert(ancestor, cgroup_paths[&ancestor].clone());
+        }
+    }
+    by_inode
+}
+
+/// Assign the socket bytes accumulated by bpf to processes and cgroups, a
+/// cgroup getting those of its descendants too. Processes and cgroups
+/// without any traffic get zeros. Entries of processes that are gone are
+/// dropped, and those of cgroups folded into their parent, so that
+/// `net_bytes` doesn't grow forever.
+fn assign_net_bytes(
+    processes: &mut procfs::PidMap,
+    cgroup: &mut CgroupSample,
+    net_bytes: &mut procfs::NetBytesMaps,
+) {
+    let zero = procfs::NetBytes {
+        tcp_send_bytes: Some(0),
+        tcp_recv_bytes: Some(0),
+        udp_send_bytes: Some(0),
+        udp_recv_bytes: Some(0),
+    };
+
+    net_bytes
+        .tgids
+        .retain(|tgid, _| processes.contains_key(tgid));
+    for (pid, pidinfo) in processes.iter_mut() {
+        pidinfo.net_bytes = Some(net_bytes.tgids.get(pid).unwrap_or(&zero).clone());
+    }
+
+    let by_inode = fold_cgroup_values(
+        cgroup,
+        &mut net_bytes.cgroups,
+        &mut net_bytes.cgroup_paths,
+        |a, b| *a = add_net_bytes(a, b),
+    );
+
+    fn assign(
+        cgroup: &mut CgroupSample,
+        by_inode: &BTreeMap<u64, procfs::NetBytes>,
+        zero: &procfs::NetBytes,
+    ) -> procfs::NetBytes {
+        let mut total = cgroup
+            .inode_number
+            .and_then(|inode_number| by_inode.get(&(inode_number as u64)))
+            .map_or_else(|| zero.clone(), |bytes| add_net_bytes(zero, bytes));
+        for child in cgroup
+            .children
+            .iter_mut()
+            .flat_map(|children| children.values_mut())
+        {
+            total = add_net_bytes(&total, &assign(child, by_inode, zero));
+        }
+        cgroup.net_bytes = Some(total.clone());
+        total
+    }
+    assign(cgroup, &by_inode, &zero);
+}
+
+fn add_runq_latency(a: &mut procfs::RunqLatency, b: &procfs::RunqLatency) {
+    if a.buckets.len() < b.buckets.len() {
+        a.buckets.resize(b.buckets.len(), 0);
+    }
+    for (a, b) in a.buckets.iter_mut().zip(&b.buckets) {
+        *a += b;
+    }
+}
+
+/// Assign the run-queue latency histograms accumulated by bpf to cgroups, a
+/// cgroup getting those of its descendants too. Entries of cgroups that are
+/// gone are folded into their parent so that `runq_latency` doesn't grow
+/// forever.
+fn assign_runq_latency(cgroup: &mut CgroupSample, runq_latency: &mut procfs::RunqLatencyMap) {
+    let by_inode = fold_cgroup_values(
+        cgroup,
+        &mut runq_latency.cgroups,
+        &mut runq_latency.cgroup_paths,
+        add_runq_latency,
+    );
+
+    fn assign(
+        cgroup: &mut CgroupSample,
+        by_inode: &BTreeMap<u64, procfs::RunqLatency>,
+    ) -> procfs::RunqLatency {
+        let mut total = cgroup
+            .inode_number
+            .and_then(|inode_number| by_inode.get(&(inode_number as u64)))
+            .cloned()
+            .unwrap_or_default();
+        for child in cgroup
+            .children
+            .iter_mut()
+            .flat_map(|children| children.values_mut())
+        {
+            add_runq_latency(&mut total, &assign(child, by_inode));
+        }
+        cgroup.runq_latency = Some(total.clone());
+        total
+    }
+    assign(cgroup, &by_inode);
+}
+
 /// This function will test if all field of DiskStat are zero, if so we will need to skip
 /// this sample inside collector.
 fn is_all_zero_disk_stats(disk_stats: &procfs::DiskStat) -> bool {
@@ -127,34 +381,53 @@ fn is_all_zero_disk_stats(disk_stats: &procfs::DiskStat) -> bool {
 
 pub fn collect_sample(
     exit_data: &Arc<Mutex<procfs::PidMap>>,
+    net_bytes: Option<&Arc<Mutex<procfs::NetBytesMaps>>>,
+    runq_latency: Option<&Arc<Mutex<procfs::RunqLatencyMap>>>,
     collect_io_stat: bool,
     logger: &slog::Logger,
     disable_disk_stat: bool,
     cgroup_re: &Option<Regex>,
 ) -> Result<Sample> {
     let mut reader = procfs::ProcReader::new();
+    let sysfs_reader = procfs::SysfsReader::new();
 
     // Take mutex, then take all values out of shared map and replace with default map
     //
     // NB: unconditionally drain the exit buffer otherwise we can leak the entries
-    let exit_pidmap =
+    let mut exit_pidmap =
         std::mem::take(&mut *exit_data.lock().expect("tried to acquire poisoned lock"));
+    let mut cgroup = collect_cgroup_sample(
+        &cgroupfs::CgroupReader::root()?,
+        collect_io_stat,
+        logger,
+        cgroup_re,
+    )?;
+    resolve_exit_cgroups(&mut exit_pidmap, &cgroup);
+    let mut processes = merge_procfs_and_exit_data(
+        reader
+            .read_all_pids()?
+            .into_iter()
+            .map(|(k, v)| (k, v.into()))
+            .collect(),
+        exit_pidmap,
+    );
+    if let Some(net_bytes) = net_bytes {
+        assign_net_bytes(
+            &mut processes,
+            &mut cgroup,
+            &mut net_bytes.lock().expect("tried to acquire poisoned lock"),
+        );
+    }
+    if let Some(runq_latency) = runq_latency {
+        assign_runq_latency(
+            &mut cgroup,
+            &mut runq_latency.lock().expect("tried to acquire poisoned lock"),
+        );
+    }
 
     Ok(Sample {
-        cgroup: collect_cgroup_sample(
-            &cgroupfs::CgroupReader::root()?,
-            collect_io_stat,
-            logger,
-            &cgroup_re,
-        )?,
-        processes: merge_procfs_and_exit_data(
-            reader
-                .read_all_pids()?
-                .into_iter()
-                .map(|(k, v)| (k, v.into()))
-                .collect(),
-            exit_pidmap,
-        ),
+        cgroup,
+        processes,
         netstats: match procfs::NetReader::new().and_then(|v| v.read_netstat()) {
             Ok(ns) => ns.into(),
             Err(e) => {
@@ -162,6 +435,10 @@ pub fn collect_sample(
                 Default::default()
             }
         },
+        high_res: false,
+        // Taken by the caller from bpf, as they are not cumulative
+        oom_kills: Vec::new(),
+        proc_events: Vec::new(),
         system: SystemSample {
             stat: reader.read_stat()?.into(),
             meminfo: reader.read_meminfo()?.into(),
@@ -181,6 +458,41 @@ pub fn collect_sample(
                     None
                 }
             },
+            interrupts: match reader.read_interrupts() {
+                Ok(interrupts) => Some(interrupts),
+                Err(e) => {
+                    error!(logger, "{:#}", e);
+                    None
+                }
+            },
+            softirqs: match reader.read_softirqs() {
+                Ok(softirqs) => Some(softirqs),
+                Err(e) => {
+                    error!(logger, "{:#}", e);
+                    None
+                }
+            },
+            softnet: match reader.read_softnet_stat() {
+                Ok(softnet) => Some(softnet),
+                Err(e) => {
+                    error!(logger, "{:#}", e);
+                    None
+                }
+            },
+            cpu_freq: match sysfs_reader.read_cpu_freq() {
+                Ok(cpu_freq) => Some(cpu_freq),
+                Err(e) => {
+                    error!(logger, "{:#}", e);
+                    None
+                }
+            },
+            thermal_zones: match sysfs_reader.read_thermal_zones() {
+                Ok(thermal_zones) => Some(thermal_zones),
+                Err(e) => {
+                    error!(logger, "{:#}", e);
+                    None
+                }
+            },
             disks: match (disable_disk_stat, reader.read_disk_stats()) {
                 (false, Ok(disks)) => disks
                     .into_iter()
@@ -299,6 +611,11 @@ fn collect_cgroup_sample(
         memory_swap_current: wrap(reader.read_memory_swap_current().map(|v| v as i64))?,
         memory_high: reader.read_memory_high()?.map(Into::into),
         memory_events: wrap(reader.read_memory_events())?.map(Into::into),
+        memory_events_local: wrap(reader.read_memory_events_local())?.map(Into::into),
+        memory_swap_events: wrap(reader.read_memory_swap_events())?.map(Into::into),
+        hugetlb: wrap(reader.read_hugetlb_stat())?,
+        rdma_current: wrap(reader.read_rdma_current())?,
+        misc_current: wrap(reader.read_misc_current())?,
         inode_number: match reader.read_inode_number() {
             Ok(st_ino) => Some(st_ino as i64),
             Err(e) => {
@@ -306,6 +623,10 @@ fn collect_cgroup_sample(
                 None
             }
         },
+        // Assigned once processes are collected, see assign_net_bytes
+        net_bytes: None,
+        // Assigned once all cgroups are collected, see assign_runq_latency
+        runq_latency: None,
     })
 }
 
@@ -353,3 +674,179 @@ macro_rules! get_option_rate {
             .map(|s| s as u64);
     };
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_cgroup(inode_number: i64, children: Vec<(&str, CgroupSample)>) -> CgroupSample {
+        CgroupSample {
+            inode_number: Some(inode_number),
+            children: Some(
+                children
+                    .into_iter()
+                    .map(|(name, child)| (name.to_owned(), child))
+                    .collect(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_resolve_exit_cgroups() {
+        let cgroup = get_cgroup(
+            1,
+            vec![(
+                "system.slice",
+                get_cgroup(2, vec![("below.service", get_cgroup(3, vec![]))]),
+            )],
+        );
+        let mut exit_data = procfs::PidMap::new();
+        for (pid, cgroup_id) in &[
+            (10, Some(1)),
+            (11, Some(3)),
+            // Inode generation in the upper 32 bits
+            (12, Some((1 << 32) | 2)),
+            // Removed cgroup
+            (13, Some(4)),
+            (14, None),
+        ] {
+            exit_data.insert(
+                *pid,
+                procfs::PidInfo {
+                    cgroup: "?".to_owned(),
+                    cgroup_id: *cgroup_id,
+                    ..Default::default()
+                },
+            );
+        }
+
+        resolve_exit_cgroups(&mut exit_data, &cgroup);
+        let cgroups = exit_data
+            .values()
+            .map(|pidinfo| pidinfo.cgroup.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            cgroups,
+            vec![
+                "/",
+                "/system.slice/below.service",
+                "/system.slice",
+                "?",
+                "?"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_assign_net_bytes() {
+        let tcp_send = |bytes| procfs::NetBytes {
+            tcp_send_bytes: Some(bytes),
+            ..Default::default()
+        };
+        let mut cgroup = get_cgroup(
+            1,
+            vec![(
+                "system.slice",
+                get_cgroup(2, vec![("below.service", get_cgroup(3, vec![]))]),
+            )],
+        );
+        let mut processes = procfs::PidMap::new();
+        processes.insert(10, Default::default());
+        processes.insert(11, Default::default());
+        let mut net_bytes = procfs::NetBytesMaps::default();
+        net_bytes.tgids.insert(10, tcp_send(100));
+        // Exited process
+        net_bytes.tgids.insert(12, tcp_send(10));
+        net_bytes.cgroups.insert(1, tcp_send(1));
+        // Inode generation in the upper 32 bits
+        net_bytes.cgroups.insert((1 << 32) | 3, tcp_send(200));
+        // Cgroup removed before it was seen
+        net_bytes.cgroups.insert(4, tcp_send(20));
+
+        assign_net_bytes(&mut processes, &mut cgroup, &mut net_bytes);
+        let tcp_send_bytes =
+            |bytes: &Option<procfs::NetBytes>| bytes.as_ref().unwrap().tcp_send_bytes;
+        assert_eq!(tcp_send_bytes(&processes[&10].net_bytes), Some(100));
+        assert_eq!(tcp_send_bytes(&processes[&11].net_bytes), Some(0));
+        assert_eq!(
+            processes[&11].net_bytes.as_ref().unwrap().udp_recv_bytes,
+            Some(0)
+        );
+
+        let system_slice = &cgroup.children.as_ref().unwrap()["system.slice"];
+        let below_service = &system_slice.children.as_ref().unwrap()["below.service"];
+        assert_eq!(tcp_send_bytes(&cgroup.net_bytes), Some(221));
+        assert_eq!(tcp_send_bytes(&system_slice.net_bytes), Some(200));
+        assert_eq!(tcp_send_bytes(&below_service.net_bytes), Some(200));
+
+        // Entries of processes that are gone are dropped, those of cgroups
+        // are folded into the root if never seen
+        assert_eq!(net_bytes.tgids.keys().collect::<Vec<_>>(), vec![&10]);
+        assert_eq!(
+            net_bytes.cgroups.keys().collect::<Vec<_>>(),
+            vec![&1, &((1 << 32) | 3)]
+        );
+        assert_eq!(net_bytes.cgroups[&1].tcp_send_bytes, Some(21));
+
+        // Bytes of a removed cgroup are kept by its parent
+        let mut cgroup = get_cgroup(1, vec![("system.slice", get_cgroup(2, vec![]))]);
+        assign_net_bytes(&mut processes, &mut cgroup, &mut net_bytes);
+        let system_slice = &cgroup.children.as_ref().unwrap()["system.slice"];
+        assert_eq!(tcp_send_bytes(&cgroup.net_bytes), Some(221));
+        assert_eq!(tcp_send_bytes(&system_slice.net_bytes), Some(200));
+        assert_eq!(net_bytes.cgroups.keys().collect::<Vec<_>>(), vec![&1, &2]);
+    }
+
+    #[test]
+    fn test_assign_runq_latency() {
+        let mut cgroup = get_cgroup(
+            1,
+            vec![(
+                "system.slice",
+                get_cgroup(2, vec![("below.service", get_cgroup(3, vec![]))]),
+            )],
+        );
+        let mut runq_latency = procfs::RunqLatencyMap::default();
+        runq_latency
+            .cgroups
+            .insert(1, procfs::RunqLatency { buckets: vec![1] });
+        // Inode generation in the upper 32 bits
+        runq_latency.cgroups.insert(
+            (1 << 32) | 3,
+            procfs::RunqLatency {
+                buckets: vec![2, 0, 5],
+            },
+        );
+        // Cgroup removed before it was seen
+        runq_latency
+            .cgroups
+            .insert(4, procfs::RunqLatency { buckets: vec![7] });
+
+        assign_runq_latency(&mut cgroup, &mut runq_latency);
+        let buckets = |cgroup: &CgroupSample| cgroup.runq_latency.as_ref().unwrap().buckets.clone();
+        let system_slice = &cgroup.children.as_ref().unwrap()["system.slice"];
+        let below_service = &system_slice.children.as_ref().unwrap()["below.service"];
+        assert_eq!(buckets(&cgroup), vec![10, 0, 5]);
+        assert_eq!(buckets(system_slice), vec![2, 0, 5]);
+        assert_eq!(buckets(below_service), vec![2, 0, 5]);
+
+        // Entries of cgroups are folded into the root if never seen
+        assert_eq!(
+            runq_latency.cgroups.keys().collect::<Vec<_>>(),
+            vec![&1, &((1 << 32) | 3)]
+        );
+        assert_eq!(runq_latency.cgroups[&1].buckets, vec![8]);
+
+        // Histogram of a removed cgroup is kept by its parent
+        let mut cgroup = get_cgroup(1, vec![("system.slice", get_cgroup(2, vec![]))]);
+        assign_runq_latency(&mut cgroup, &mut runq_latency);
+        let system_slice = &cgroup.children.as_ref().unwrap()["system.slice"];
+        assert_eq!(buckets(&cgroup), vec![10, 0, 5]);
+        assert_eq!(buckets(system_slice), vec![2, 0, 5]);
+        assert_eq!(
+            runq_latency.cgroups.keys().collect::<Vec<_>>(),
+            vec![&1, &2]
+        );
+    }
+}
@@ -0,0 +1,199 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Helpers for comparing Models collected at two points in time. The earlier
+//! Model is called the base and the later one the target.
+use super::*;
+
+/// Whether an entity, i.e. a cgroup or a process, exists in the base and the
+/// target Model of a comparison.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Presence {
+    /// Exists in both Models.
+    Both,
+    /// Only exists in the target Model.
+    Appeared,
+    /// Only exists in the base Model.
+    Disappeared,
+    /// Exists in both Models but was destroyed and created again in between,
+    /// e.g. a recreated cgroup or a reused pid.
+    Recreated,
+}
+
+impl fmt::Display for Presence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Presence::Both => "-".fmt(f),
+            Presence::Appeared => "appeared".fmt(f),
+            Presence::Disappeared => "disappeared".fmt(f),
+            Presence::Recreated => "recreated".fmt(f),
+        }
+    }
+}
+
+impl Presence {
+    /// Presence of a cgroup. A cgroup is recreated if its inode changed.
+    pub fn of_cgroup(base: Option<&CgroupModel>, target: Option<&CgroupModel>) -> Self {
+        match (base, target) {
+            (Some(base), Some(target)) => {
+                if target.recreate_flag || base.inode_number != target.inode_number {
+                    Presence::Recreated
+                } else {
+                    Presence::Both
+                }
+            }
+            (None, Some(_)) => Presence::Appeared,
+            (Some(_), None) => Presence::Disappeared,
+            (None, None) => Presence::Both,
+        }
+    }
+
+    /// Presence of a process. A pid is reused if the process in the target
+    /// Model started after the base Model was collected, or has another comm.
+    pub fn of_process(
+        base: Option<&SingleProcessModel>,
+        target: Option<&SingleProcessModel>,
+        elapsed: Duration,
+    ) -> Self {
+        match (base, target) {
+            (Some(base), Some(target)) => {
+                let started_after_base = target
+                    .uptime_secs
+                    .map_or(false, |uptime| uptime < elapsed.as_secs());
+                if started_after_base || base.comm != target.comm {
+                    Presence::Recreated
+                } else {
+                    Presence::Both
+                }
+            }
+            (None, Some(_)) => Presence::Appeared,
+            (Some(_), None) => Presence::Disappeared,
+            (None, None) => Presence::Both,
+        }
+    }
+}
+
+/// Collect all cgroups in the tree by their full path.
+pub fn flatten_cgroups(cgroup: &CgroupModel) -> BTreeMap<&str, &CgroupModel> {
+    fn collect<'a>(cgroup: &'a CgroupModel, res: &mut BTreeMap<&'a str, &'a CgroupModel>) {
+        res.insert(&cgroup.full_path, cgroup);
+        for child in &cgroup.children {
+            collect(child, res);
+        }
+    }
+
+    let mut res = BTreeMap::new();
+    collect(cgroup, &mut res);
+    res
+}
+
+/// Return the difference from the base Field to the target Field as whether
+/// it is negative and its absolute value, in the type of the Fields or its
+/// unsigned counterpart for signed Fields, so that the delta cannot overflow.
+/// Return None for non-numeric or mismatched Fields.
+pub fn field_delta(base: &Field, target: &Field) -> Option<(bool, Field)> {
+    match (base, target) {
+        (Field::U32(b), Field::U32(t)) => {
+            Some((t < b, Field::U32(if t < b { b - t } else { t - b })))
+        }
+        (Field::U64(b), Field::U64(t)) => {
+            Some((t < b, Field::U64(if t < b { b - t } else { t - b })))
+        }
+        (Field::I32(b), Field::I32(t)) => Some((
+            t < b,
+            Field::U32((i64::from(*t) - i64::from(*b)).unsigned_abs() as u32),
+        )),
+        (Field::I64(b), Field::I64(t)) => Some((
+            t < b,
+            Field::U64((i128::from(*t) - i128::from(*b)).unsigned_abs() as u64),
+        )),
+        (Field::F64(b), Field::F64(t)) => Some((t < b, Field::F64((t - b).abs()))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_field_delta() {
+        assert_eq!(
+            field_delta(&Field::U64(10), &Field::U64(4)),
+            Some((true, Field::U64(6)))
+        );
+        assert_eq!(
+            field_delta(&Field::U64(4), &Field::U64(10)),
+            Some((false, Field::U64(6)))
+        );
+        assert_eq!(
+            field_delta(&Field::I64(-1), &Field::I64(3)),
+            Some((false, Field::U64(4)))
+        );
+        assert_eq!(
+            field_delta(&Field::I32(i32::MAX), &Field::I32(i32::MIN)),
+            Some((true, Field::U32(u32::MAX)))
+        );
+        assert_eq!(
+            field_delta(&Field::I64(i64::MIN), &Field::I64(i64::MAX)),
+            Some((false, Field::U64(u64::MAX)))
+        );
+        assert_eq!(
+            field_delta(&Field::F64(2.5), &Field::F64(1.0)),
+            Some((true, Field::F64(1.5)))
+        );
+        assert_eq!(
+            field_delta(&Field::Str("a".into()), &Field::Str("b".into())),
+            None
+        );
+        assert_eq!(field_delta(&Field::U64(1), &Field::I64(1)), None);
+    }
+
+    #[test]
+    fn test_presence() {
+        let cgroup = |inode_number| CgroupModel {
+            inode_number,
+            ..Default::default()
+        };
+        let (a, b) = (cgroup(Some(1)), cgroup(Some(2)));
+        assert_eq!(Presence::of_cgroup(Some(&a), Some(&a)), Presence::Both);
+        assert_eq!(Presence::of_cgroup(Some(&a), Some(&b)), Presence::Recreated);
+        assert_eq!(Presence::of_cgroup(None, Some(&b)), Presence::Appeared);
+        assert_eq!(Presence::of_cgroup(Some(&a), None), Presence::Disappeared);
+
+        let process = |comm: &str, uptime_secs| SingleProcessModel {
+            comm: Some(comm.into()),
+            uptime_secs: Some(uptime_secs),
+            ..Default::default()
+        };
+        let elapsed = Duration::from_secs(60);
+        let (old, young) = (process("below", 100), process("below", 30));
+        assert_eq!(
+            Presence::of_process(Some(&old), Some(&old), elapsed),
+            Presence::Both
+        );
+        assert_eq!(
+            Presence::of_process(Some(&old), Some(&young), elapsed),
+            Presence::Recreated
+        );
+        assert_eq!(
+            Presence::of_process(Some(&old), Some(&process("bash", 100)), elapsed),
+            Presence::Recreated
+        );
+        assert_eq!(
+            Presence::of_process(None, Some(&young), elapsed),
+            Presence::Appeared
+        );
+    }
+}
@@ -0,0 +1,538 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Detect notable events from consecutive Models, so a timeline of the
+//! interesting moments in the stored history can be built.
+use super::*;
+
+/// Kind of a notable event.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    below_derive::EnumIter
+)]
+pub enum EventKind {
+    OomKill,
+    CgroupCreated,
+    CgroupRemoved,
+    ProcessStarted,
+    ProcessExited,
+    PressureSpike,
+    CpuThrottled,
+    CollectionSkew,
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventKind::OomKill => "oom_kill".fmt(f),
+            EventKind::CgroupCreated => "cgroup_created".fmt(f),
+            EventKind::CgroupRemoved => "cgroup_removed".fmt(f),
+            EventKind::ProcessStarted => "process_started".fmt(f),
+            EventKind::ProcessExited => "process_exited".fmt(f),
+            EventKind::PressureSpike => "pressure_spike".fmt(f),
+            EventKind::CpuThrottled => "cpu_throttled".fmt(f),
+            EventKind::CollectionSkew => "collection_skew".fmt(f),
+        }
+    }
+}
+
+impl From<EventKind> for Field {
+    fn from(kind: EventKind) -> Field {
+        Field::Str(kind.to_string())
+    }
+}
+
+impl HasFieldType for EventKind {
+    const FIELD_TYPE: FieldType = FieldType::Str;
+}
+
+/// A notable event detected in a sample.
+#[derive(Clone, Debug, Serialize, Deserialize, below_derive::Queriable)]
+pub struct EventModel {
+    pub kind: EventKind,
+    /// The cgroup path, the pid and comm of the process, or "system".
+    pub subject: String,
+    pub detail: String,
+}
+
+impl EventModel {
+    fn new(kind: EventKind, subject: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            kind,
+            subject: subject.into(),
+            detail: detail.into(),
+        }
+    }
+}
+
+struct CgroupState {
+    inode_number: Option<u64>,
+    oom_kill: Option<u64>,
+    pressure_spike: bool,
+    throttled: bool,
+}
+
+/// Detects events from Models fed in chronological order. Changes such as
+/// a cgroup being created are detected against the previously fed Model,
+/// and conditions such as a pressure spike are reported when they begin.
+pub struct EventDetector {
+    /// Memory or I/O full pressure above which a cgroup has a pressure spike.
+    pub pressure_full_pct: f64,
+    /// CPU some pressure above which a cgroup has a pressure spike.
+    pub cpu_pressure_some_pct: f64,
+    /// Percent of throttled CPU periods above which a cgroup is throttled.
+    pub throttled_pct: f64,
+    initialized: bool,
+    oom_kill: Option<u64>,
+    lowest_time_elapsed: Option<Duration>,
+    cgroups: BTreeMap<String, CgroupState>,
+    processes: BTreeMap<i32, Option<String>>,
+}
+
+impl Default for EventDetector {
+    fn default() -> Self {
+        Self {
+            pressure_full_pct: 10.0,
+            cpu_pressure_some_pct: 50.0,
+            throttled_pct: 10.0,
+            initialized: false,
+            oom_kill: None,
+            lowest_time_elapsed: None,
+            cgroups: BTreeMap::new(),
+            processes: BTreeMap::new(),
+        }
+    }
+}
+
+fn cgroup_subject(full_path: &str) -> &str {
+    if full_path.is_empty() {
+        "<root>"
+    } else {
+        full_path
+    }
+}
+
+fn process_subject(pid: i32, comm: Option<&str>) -> String {
+    format!("{} {}", pid, comm.unwrap_or("?"))
+}
+
+fn oom_kill_detail(oom_kill: &OomKillModel) -> String {
+    let mib = |bytes: Option<u64>| {
+        bytes.map_or("?".to_owned(), |bytes| {
+            format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+        })
+    };
+    let mut detail = format!(
+        "in {}, rss {}, oom_score_adj {}",
+        oom_kill.cgroup.as_deref().unwrap_or("?"),
+        mib(oom_kill.rss_bytes),
+        oom_kill
+            .oom_score_adj
+            .map_or("?".to_owned(), |adj| adj.to_string()),
+    );
+    match oom_kill.constraint.as_deref() {
+        Some("memcg") => {
+            detail += &format!(
+                ", limit {} of {} hit",
+                mib(oom_kill.total_bytes),
+                oom_kill.oom_cgroup.as_deref().unwrap_or("?")
+            )
+        }
+        Some(constraint) => detail += &format!(", constraint {}", constraint),
+        None => {}
+    }
+    detail
+}
+
+impl EventDetector {
+    /// Describe the pressure spike of a cgroup, if any.
+    fn get_pressure_spike(&self, pressure: &CgroupPressureModel) -> Option<String> {
+        let over = |pct: Option<f64>, threshold: f64| pct.filter(|pct| *pct > threshold);
+        if let Some(pct) = over(pressure.memory_full_pct, self.pressure_full_pct) {
+            Some(format!("memory full pressure {:.2}%", pct))
+        } else if let Some(pct) = over(pressure.io_full_pct, self.pressure_full_pct) {
+            Some(format!("io full pressure {:.2}%", pct))
+        } else if let Some(pct) = over(pressure.cpu_some_pct, self.cpu_pressure_some_pct) {
+            Some(format!("cpu some pressure {:.2}%", pct))
+        } else {
+            None
+        }
+    }
+
+    /// Report the OOM kills in the cgroup tree since the previous Model.
+    /// memory.events counts the kills of the whole subtree, so a kill is
+    /// only attributed to the deepest cgroup whose count increased by more
+    /// than the counts of its children. Return the increase of the subtree.
+    fn detect_oom_kills(&self, cgroup: &CgroupModel, events: &mut Vec<EventModel>) -> u64 {
+        let children: u64 = cgroup
+            .children
+            .iter()
+            .map(|child| self.detect_oom_kills(child, events))
+            .sum();
+        let count = cgroup.memory.as_ref().and_then(|mem| mem.events_oom_kill);
+        let increase = match (self.cgroups.get(&cgroup.full_path), count) {
+            (Some(prev), Some(count))
+                if !cgroup.recreate_flag && prev.inode_number == cgroup.inode_number =>
+            {
+                prev.oom_kill
+                    .map(|prev_count| count.saturating_sub(prev_count))
+            }
+            // All kills of a cgroup created since the previous Model are new
+            (_, Some(count)) if self.initialized => Some(count),
+            _ => None,
+        };
+        match increase {
+            Some(increase) if increase > children => {
+                events.push(EventModel::new(
+                    EventKind::OomKill,
+                    cgroup_subject(&cgroup.full_path),
+                    format!("{} processes killed", increase - children),
+                ));
+                increase
+            }
+            _ => children,
+        }
+    }
+
+    fn detect_cgroups(&mut self, model: &Model, events: &mut Vec<EventModel>) {
+        let mut cgroups = BTreeMap::new();
+        for (path, cgroup) in flatten_cgroups(&model.cgroup) {
+            let subject = cgroup_subject(path);
+            let pressure_spike = cgroup
+                .pressure
+                .as_ref()
+                .and_then(|pressure| self.get_pressure_spike(pressure));
+            let throttled_pct = cgroup
+                .cpu
+                .as_ref()
+                .and_then(|cpu| cpu.throttled_pct)
+                .filter(|pct| *pct > self.throttled_pct);
+            let state = CgroupState {
+                inode_number: cgroup.inode_number,
+                oom_kill: cgroup.memory.as_ref().and_then(|mem| mem.events_oom_kill),
+                pressure_spike: pressure_spike.is_some(),
+                throttled: throttled_pct.is_some(),
+            };
+
+            let prev = self.cgroups.remove(path);
+            match &prev {
+                None if self.initialized => {
+                    events.push(EventModel::new(EventKind::CgroupCreated, subject, ""));
+                }
+                Some(prev) if cgroup.recreate_flag || prev.inode_number != state.inode_number => {
+                    events.push(EventModel::new(
+                        EventKind::CgroupRemoved,
+                        subject,
+                        "recreated",
+                    ));
+                    events.push(EventModel::new(
+                        EventKind::CgroupCreated,
+                        subject,
+                        "recreated",
+                    ));
+                }
+                _ => {}
+            }
+            if let Some(detail) = pressure_spike {
+                if !prev.as_ref().map_or(false, |prev| prev.pressure_spike) {
+                    events.push(EventModel::new(EventKind::PressureSpike, subject, detail));
+                }
+            }
+            if let Some(pct) = throttled_pct {
+                if !prev.as_ref().map_or(false, |prev| prev.throttled) {
+                    events.push(EventModel::new(
+                        EventKind::CpuThrottled,
+                        subject,
+                        format!("throttled {:.2}% of periods", pct),
+                    ));
+                }
+            }
+            cgroups.insert(path.to_owned(), state);
+        }
+
+        // Remaining cgroups do not exist any more
+        for path in self.cgroups.keys() {
+            events.push(EventModel::new(
+                EventKind::CgroupRemoved,
+                cgroup_subject(path),
+                "",
+            ));
+        }
+        self.cgroups = cgroups;
+    }
+
+    fn detect_processes(&mut self, model: &Model, events: &mut Vec<EventModel>) {
+        let mut processes = BTreeMap::new();
+        for (pid, spm) in &model.process.processes {
+            let subject = process_subject(*pid, spm.comm.as_deref());
+            match self.processes.remove(pid) {
+                None if self.initialized => {
+                    events.push(EventModel::new(
+                        EventKind::ProcessStarted,
+                        subject,
+                        spm.cmdline.as_deref().unwrap_or(""),
+                    ));
+                }
+                // The pid is reused if the process started after the
+                // previous sample.
+                Some(prev_comm)
+                    if prev_comm != spm.comm
+                        || spm
+                            .uptime_secs
+                            .map_or(false, |uptime| uptime < model.time_elapsed.as_secs()) =>
+                {
+                    events.push(EventModel::new(
+                        EventKind::ProcessExited,
+                        process_subject(*pid, prev_comm.as_deref()),
+                        "pid reused",
+                    ));
+                    events.push(EventModel::new(
+                        EventKind::ProcessStarted,
+                        subject,
+                        spm.cmdline.as_deref().unwrap_or(""),
+                    ));
+                }
+                _ => {}
+            }
+            processes.insert(*pid, spm.comm.clone());
+        }
+
+        // Remaining processes do not exist any more
+        for (pid, comm) in &self.processes {
+            events.push(EventModel::new(
+                EventKind::ProcessExited,
+                process_subject(*pid, comm.as_deref()),
+                "",
+            ));
+        }
+        self.processes = processes;
+    }
+
+    /// Detect events in the Model, which must be later than the Models fed
+    /// before.
+    pub fn detect(&mut self, model: &Model) -> Vec<EventModel> {
+        let mut events = Vec::new();
+
+        // Processes OOM killed are reported one by one when bpf tells which
+        // they are, as a count otherwise. Kills that are not attributed to a
+        // cgroup are reported for the system.
+        for oom_kill in &model.oom_kills {
+            events.push(EventModel::new(
+                EventKind::OomKill,
+                process_subject(oom_kill.pid.unwrap_or(-1), oom_kill.comm.as_deref()),
+                oom_kill_detail(oom_kill),
+            ));
+        }
+        let oom_kill = model.system.vm.oom_kill;
+        if model.oom_kills.is_empty() {
+            let cgroup_oom_kills = self.detect_oom_kills(&model.cgroup, &mut events);
+            if let (Some(prev_count), Some(count)) = (self.oom_kill, oom_kill) {
+                let increase = count.saturating_sub(prev_count);
+                if increase > cgroup_oom_kills {
+                    events.push(EventModel::new(
+                        EventKind::OomKill,
+                        "system",
+                        format!("{} processes killed", increase - cgroup_oom_kills),
+                    ));
+                }
+            }
+        }
+        self.oom_kill = oom_kill;
+
+        // Same as the status bar of the TUI, 1 second jitter happens pretty
+        // often due to integer rounding.
+        // High resolution samples are not evenly spaced.
+        let elapsed = model.time_elapsed.as_secs();
+        if elapsed != 0 && !model.high_res {
+            if let Some(lowest) = self.lowest_time_elapsed {
+                if elapsed >= lowest.as_secs() + 2 {
+                    events.push(EventModel::new(
+                        EventKind::CollectionSkew,
+                        "system",
+                        format!(
+                            "{}s since the previous sample, expected {}s",
+                            elapsed,
+                            lowest.as_secs()
+                        ),
+                    ));
+                }
+            }
+            if self
+                .lowest_time_elapsed
+                .map_or(true, |lowest| model.time_elapsed < lowest)
+            {
+                self.lowest_time_elapsed = Some(model.time_elapsed);
+            }
+        }
+
+        self.detect_cgroups(model, &mut events);
+        self.detect_processes(model, &mut events);
+        self.initialized = true;
+        events
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_model(time_elapsed: u64, oom_kill: u64, cgroups: &[(&str, u64, f64)]) -> Model {
+        let mut model = Model {
+            time_elapsed: Duration::from_secs(time_elapsed),
+            timestamp: SystemTime::UNIX_EPOCH,
+            system: Default::default(),
+            cgroup: Default::default(),
+            process: Default::default(),
+            network: Default::default(),
+            high_res: false,
+            oom_kills: Vec::new(),
+            proc_events: Vec::new(),
+        };
+        model.system.vm.oom_kill = Some(oom_kill);
+        for (name, inode_number, memory_full_pct) in cgroups {
+            model.cgroup.children.insert(CgroupModel {
+                name: name.to_string(),
+                full_path: format!("/{}", name),
+                inode_number: Some(*inode_number),
+                depth: 1,
+                pressure: Some(CgroupPressureModel {
+                    memory_full_pct: Some(*memory_full_pct),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            });
+        }
+        model
+    }
+
+    fn get_events(events: Vec<EventModel>) -> Vec<(EventKind, String)> {
+        events
+            .into_iter()
+            .map(|event| (event.kind, event.subject))
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_events() {
+        let mut detector = EventDetector::default();
+        // Nothing is created in the first Model, but a pressure spike is.
+        assert_eq!(
+            get_events(detector.detect(&get_model(5, 1, &[("a", 1, 0.0), ("b", 2, 50.0)]))),
+            vec![(EventKind::PressureSpike, "/b".to_owned())]
+        );
+        // The pressure spike is only reported when it begins.
+        assert_eq!(
+            get_events(detector.detect(&get_model(5, 1, &[("a", 1, 0.0), ("b", 2, 50.0)]))),
+            vec![]
+        );
+        assert_eq!(
+            get_events(detector.detect(&get_model(12, 3, &[("a", 3, 0.0), ("c", 4, 0.0)]))),
+            vec![
+                (EventKind::OomKill, "system".to_owned()),
+                (EventKind::CollectionSkew, "system".to_owned()),
+                (EventKind::CgroupRemoved, "/a".to_owned()),
+                (EventKind::CgroupCreated, "/a".to_owned()),
+                (EventKind::CgroupCreated, "/c".to_owned()),
+                (EventKind::CgroupRemoved, "/b".to_owned()),
+            ]
+        );
+        // Processes reported by bpf are listed instead of the count.
+        let mut model = get_model(5, 5, &[("a", 3, 0.0), ("c", 4, 0.0)]);
+        model.oom_kills.push(OomKillModel {
+            pid: Some(10),
+            comm: Some("stress".to_owned()),
+            cgroup: Some("/c".to_owned()),
+            rss_bytes: Some(512 * 1024 * 1024),
+            oom_score_adj: Some(0),
+            constraint: Some("memcg".to_owned()),
+            oom_cgroup: Some("/c".to_owned()),
+            total_bytes: Some(1024 * 1024 * 1024),
+            ..Default::default()
+        });
+        let events = detector.detect(&model);
+        assert_eq!(
+            events[0].detail,
+            "in /c, rss 512.0 MiB, oom_score_adj 0, limit 1024.0 MiB of /c hit"
+        );
+        assert_eq!(
+            get_events(events),
+            vec![(EventKind::OomKill, "10 stress".to_owned())]
+        );
+    }
+
+    #[test]
+    fn test_detect_oom_kills() {
+        let cgroup = |full_path: &str, oom_kill: u64, children: Vec<CgroupModel>| CgroupModel {
+            name: full_path.rsplit('/').next().unwrap().to_owned(),
+            full_path: full_path.to_owned(),
+            inode_number: Some(full_path.len() as u64),
+            memory: Some(CgroupMemoryModel {
+                events_oom_kill: Some(oom_kill),
+                ..Default::default()
+            }),
+            children: children.into_iter().collect(),
+            ..Default::default()
+        };
+        let get_tree_model = |system: u64, a: u64, b: u64, c: u64| {
+            let mut model = get_model(5, system, &[]);
+            model.cgroup.children.insert(cgroup(
+                "/a",
+                a,
+                vec![cgroup("/a/b", b, vec![]), cgroup("/a/c", c, vec![])],
+            ));
+            model
+        };
+
+        let mut detector = EventDetector::default();
+        assert_eq!(
+            get_events(detector.detect(&get_tree_model(1, 1, 1, 0))),
+            vec![]
+        );
+        // Each kill is reported once, in the deepest cgroup it happened in,
+        // and kills outside of the cgroups are reported for the system.
+        let events = detector.detect(&get_tree_model(6, 5, 3, 1));
+        assert_eq!(
+            events
+                .iter()
+                .map(|event| (event.subject.as_str(), event.detail.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("/a/b", "2 processes killed"),
+                ("/a/c", "1 processes killed"),
+                ("/a", "1 processes killed"),
+                ("system", "1 processes killed"),
+            ]
+        );
+        // Processes reported by bpf replace the counts of cgroups too
+        let mut model = get_tree_model(7, 6, 4, 1);
+        model.oom_kills.push(OomKillModel {
+            pid: Some(10),
+            comm: Some("stress".to_owned()),
+            cgroup: Some("/a/b".to_owned()),
+            ..Default::default()
+        });
+        assert_eq!(
+            get_events(detector.detect(&model)),
+            vec![(EventKind::OomKill, "10 stress".to_owned())]
+        );
+    }
+}
@@ -0,0 +1,443 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A small expression language for filtering Queriables, e.g.
+//!
+//!   cpu.usage_pct > 50 && mem.anon > 1G && comm =~ "java"
+//!
+//! Grammar:
+//!
+//!   expr    := and ( "||" and )*
+//!   and     := unary ( "&&" unary )*
+//!   unary   := "!" unary | "(" expr ")" | field op literal
+//!   op      := "==" | "!=" | "<" | "<=" | ">" | ">=" | "=~" | "!~"
+//!   literal := number [unit] | number "%" "of" field | "quoted string" | word
+//!
+//! Fields are the same strings accepted by the FieldId's FromStr, e.g. the
+//! ones listed by `below dump <subcommand> --help`. Numbers may carry a unit
+//! suffix: binary size units (K, M, G, T, P with optional "B" or "iB"),
+//! time units (us, ms, s, min, h, d, converted to seconds) or "%" which is
+//! ignored, unless it is followed by "of" and another field to compare
+//! against a percentage of that field, e.g. `mem.available < 5% of mem.total`.
+
+use super::*;
+
+use anyhow::bail;
+use regex::Regex;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FilterOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Match,
+    NotMatch,
+}
+
+#[derive(Clone, Debug)]
+pub enum FilterLiteral {
+    Num(f64),
+    Str(String),
+    Regex(Regex),
+}
+
+/// Parsed filter expression over fields of type F.
+#[derive(Clone, Debug)]
+pub enum FilterExpr<F> {
+    Cmp(F, FilterOp, FilterLiteral),
+    /// Compare a field against a ratio of another field.
+    CmpRatio(F, FilterOp, f64, F),
+    Not(Box<FilterExpr<F>>),
+    And(Box<FilterExpr<F>>, Box<FilterExpr<F>>),
+    Or(Box<FilterExpr<F>>, Box<FilterExpr<F>>),
+}
+
+impl<F: FieldId> FilterExpr<F> {
+    /// Evaluate the expression against the given model. A comparison on a
+    /// field that has no value (or a value of a different kind than the
+    /// literal) evaluates to false.
+    pub fn eval(&self, model: &F::Queriable) -> bool {
+        match self {
+            Self::Cmp(field_id, op, literal) => match model.query(field_id) {
+                Some(field) => compare(&field, *op, literal),
+                None => false,
+            },
+            Self::CmpRatio(field_id, op, ratio, other_id) => match (
+                model.query(field_id),
+                model.query(other_id).as_ref().and_then(field_as_f64),
+            ) {
+                (Some(field), Some(other)) => {
+                    compare(&field, *op, &FilterLiteral::Num(ratio * other))
+                }
+                _ => false,
+            },
+            Self::Not(expr) => !expr.eval(model),
+            Self::And(lhs, rhs) => lhs.eval(model) && rhs.eval(model),
+            Self::Or(lhs, rhs) => lhs.eval(model) || rhs.eval(model),
+        }
+    }
+}
+
+fn field_as_f64(field: &Field) -> Option<f64> {
+    match field {
+        Field::U32(v) => Some(*v as f64),
+        Field::U64(v) => Some(*v as f64),
+        Field::I32(v) => Some(*v as f64),
+        Field::I64(v) => Some(*v as f64),
+        Field::F64(v) => Some(*v),
+        _ => None,
+    }
+}
+
+fn compare(field: &Field, op: FilterOp, literal: &FilterLiteral) -> bool {
+    use std::cmp::Ordering;
+
+    let ordering = match literal {
+        FilterLiteral::Regex(re) => {
+            let is_match = re.is_match(&field.to_string());
+            return if op == FilterOp::Match {
+                is_match
+            } else {
+                !is_match
+            };
+        }
+        FilterLiteral::Num(num) => match field_as_f64(field) {
+            Some(val) => val.partial_cmp(num),
+            None => None,
+        },
+        FilterLiteral::Str(s) => Some(field.to_string().as_str().cmp(s.as_str())),
+    };
+    match (op, ordering) {
+        (FilterOp::Eq, Some(o)) => o == Ordering::Equal,
+        (FilterOp::Ne, Some(o)) => o != Ordering::Equal,
+        (FilterOp::Lt, Some(o)) => o == Ordering::Less,
+        (FilterOp::Le, Some(o)) => o != Ordering::Greater,
+        (FilterOp::Gt, Some(o)) => o == Ordering::Greater,
+        (FilterOp::Ge, Some(o)) => o != Ordering::Less,
+        _ => false,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Num(f64),
+    Pct(f64),
+    Str(String),
+    Op(FilterOp),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+}
+
+/// Multiplier for a unit suffix following a number literal.
+fn unit_multiplier(unit: &str) -> Option<f64> {
+    let size = |exp: i32| Some(1024f64.powi(exp));
+    match unit {
+        "" | "%" | "s" | "B" | "b" => Some(1.0),
+        "us" => Some(1e-6),
+        "ms" => Some(1e-3),
+        "min" => Some(60.0),
+        "h" => Some(3600.0),
+        "d" => Some(86400.0),
+        _ => match unit
+            .trim_end_matches(|c| c == 'B' || c == 'b')
+            .trim_end_matches('i')
+            .to_ascii_uppercase()
+            .as_str()
+        {
+            "K" => size(1),
+            "M" => size(2),
+            "G" => size(3),
+            "T" => size(4),
+            "P" => size(5),
+            _ => None,
+        },
+    }
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>> {
+    let chars = s.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let two_char_token = match (c, next) {
+            ('&', Some('&')) => Some(Token::And),
+            ('|', Some('|')) => Some(Token::Or),
+            ('=', Some('=')) => Some(Token::Op(FilterOp::Eq)),
+            ('!', Some('=')) => Some(Token::Op(FilterOp::Ne)),
+            ('<', Some('=')) => Some(Token::Op(FilterOp::Le)),
+            ('>', Some('=')) => Some(Token::Op(FilterOp::Ge)),
+            ('=', Some('~')) => Some(Token::Op(FilterOp::Match)),
+            ('!', Some('~')) => Some(Token::Op(FilterOp::NotMatch)),
+            _ => None,
+        };
+        if let Some(token) = two_char_token {
+            tokens.push(token);
+            i += 2;
+            continue;
+        }
+        match c {
+            _ if c.is_whitespace() => i += 1,
+            '(' | ')' | '<' | '>' | '!' => {
+                tokens.push(match c {
+                    '(' => Token::LParen,
+                    ')' => Token::RParen,
+                    '<' => Token::Op(FilterOp::Lt),
+                    '>' => Token::Op(FilterOp::Gt),
+                    _ => Token::Not,
+                });
+                i += 1;
+            }
+            '"' => {
+                let mut val = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        Some('"') => break,
+                        // Only \" and \\ are unescaped so regexes keep their
+                        // backslashes
+                        Some('\\') if matches!(chars.get(i + 1), Some('"') | Some('\\')) => {
+                            val.push(chars[i + 1]);
+                            i += 2;
+                        }
+                        Some(c) => {
+                            val.push(*c);
+                            i += 1;
+                        }
+                        None => bail!("Unterminated string literal in filter: {}", s),
+                    }
+                }
+                tokens.push(Token::Str(val));
+                i += 1;
+            }
+            _ if c.is_ascii_digit() || (c == '-' && next.map_or(false, |n| n.is_ascii_digit())) => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let num = chars[start..i].iter().collect::<String>();
+                let unit_start = i;
+                while i < chars.len() && (chars[i].is_ascii_alphabetic() || chars[i] == '%') {
+                    i += 1;
+                }
+                let unit = chars[unit_start..i].iter().collect::<String>();
+                let num = num
+                    .parse::<f64>()
+                    .map_err(|_| anyhow!("Invalid number in filter: {}", num))?;
+                if unit == "%" {
+                    tokens.push(Token::Pct(num));
+                    continue;
+                }
+                let multiplier = unit_multiplier(&unit)
+                    .ok_or_else(|| anyhow!("Unknown unit in filter: {}{}", num, unit))?;
+                tokens.push(Token::Num(num * multiplier));
+            }
+            _ if c.is_alphanumeric() || c == '_' || c == '.' || c == '/' || c == '-' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric()
+                        || chars[i] == '_'
+                        || chars[i] == '.'
+                        || chars[i] == '/'
+                        || chars[i] == '-')
+                {
+                    i += 1;
+                }
+                tokens.push(Token::Word(chars[start..i].iter().collect()));
+            }
+            _ => bail!("Unexpected character '{}' in filter: {}", c, s),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<F> {
+    tokens: Vec<Token>,
+    pos: usize,
+    _field: std::marker::PhantomData<F>,
+}
+
+impl<F: FromStr<Err = anyhow::Error>> Parser<F> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<FilterExpr<F>> {
+        let mut expr = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            expr = FilterExpr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<FilterExpr<F>> {
+        let mut expr = self.parse_unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.next();
+            expr = FilterExpr::And(Box::new(expr), Box::new(self.parse_unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary(&mut self) -> Result<FilterExpr<F>> {
+        match self.next() {
+            Some(Token::Not) => Ok(FilterExpr::Not(Box::new(self.parse_unary()?))),
+            Some(Token::LParen) => {
+                let expr = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(expr),
+                    _ => bail!("Expected ')' in filter"),
+                }
+            }
+            Some(Token::Word(field)) => {
+                let field_id = F::from_str(&field)?;
+                let op = match self.next() {
+                    Some(Token::Op(op)) => op,
+                    _ => bail!("Expected comparison operator after {}", field),
+                };
+                let literal = match (op, self.next()) {
+                    (FilterOp::Match, Some(Token::Str(s)))
+                    | (FilterOp::Match, Some(Token::Word(s)))
+                    | (FilterOp::NotMatch, Some(Token::Str(s)))
+                    | (FilterOp::NotMatch, Some(Token::Word(s))) => FilterLiteral::Regex(
+                        Regex::new(&s).map_err(|e| anyhow!("Invalid regex {}: {}", s, e))?,
+                    ),
+                    (FilterOp::Match, _) | (FilterOp::NotMatch, _) => {
+                        bail!("Expected regex after {} =~", field)
+                    }
+                    (_, Some(Token::Pct(pct)))
+                        if self.peek() == Some(&Token::Word("of".to_owned())) =>
+                    {
+                        self.next();
+                        let other_id = match self.next() {
+                            Some(Token::Word(other)) => F::from_str(&other)?,
+                            _ => bail!("Expected field after {}% of", pct),
+                        };
+                        return Ok(FilterExpr::CmpRatio(field_id, op, pct / 100.0, other_id));
+                    }
+                    (_, Some(Token::Num(num))) | (_, Some(Token::Pct(num))) => {
+                        FilterLiteral::Num(num)
+                    }
+                    (_, Some(Token::Str(s))) | (_, Some(Token::Word(s))) => FilterLiteral::Str(s),
+                    _ => bail!("Expected value after {}", field),
+                };
+                Ok(FilterExpr::Cmp(field_id, op, literal))
+            }
+            Some(token) => bail!("Unexpected token {:?} in filter", token),
+            None => bail!("Unexpected end of filter"),
+        }
+    }
+}
+
+impl<F: FromStr<Err = anyhow::Error>> FromStr for FilterExpr<F> {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parser = Parser {
+            tokens: tokenize(s)?,
+            pos: 0,
+            _field: std::marker::PhantomData,
+        };
+        let expr = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            bail!("Unexpected token {:?} in filter", token);
+        }
+        Ok(expr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> FilterExpr<SingleProcessModelFieldId> {
+        s.parse().expect("Failed to parse filter")
+    }
+
+    fn get_process() -> SingleProcessModel {
+        SingleProcessModel {
+            pid: Some(1234),
+            comm: Some("java".to_string()),
+            mem: Some(ProcessMemoryModel {
+                anon: Some(2 << 30),
+                rss_bytes: Some(3 << 30),
+                ..Default::default()
+            }),
+            cpu: Some(ProcessCpuModel {
+                usage_pct: Some(75.0),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_filter_eval() {
+        let process = get_process();
+        assert!(parse(r#"cpu.usage_pct > 50 && mem.anon > 1G && comm =~ "java""#).eval(&process));
+        assert!(parse("cpu.usage_pct > 50 && mem.anon > 1GiB").eval(&process));
+        assert!(!parse("mem.anon > 3G").eval(&process));
+        assert!(parse("mem.anon > 3G || pid == 1234").eval(&process));
+        assert!(parse("!(comm == python) && comm != bash").eval(&process));
+        assert!(parse(r#"comm !~ "^py""#).eval(&process));
+        assert!(parse(r#"comm =~ "^\w+$""#).eval(&process));
+        assert!(parse("cpu.usage_pct <= 75%").eval(&process));
+        assert!(parse("mem.anon > 50% of mem.rss_bytes").eval(&process));
+        assert!(!parse("mem.anon > 90% of mem.rss_bytes").eval(&process));
+        // Missing values never match
+        assert!(!parse("io.rbytes_per_sec >= 0").eval(&process));
+        assert!(!parse("io.rbytes_per_sec < 0").eval(&process));
+        assert!(!parse("mem.anon > 50% of mem.vm_size").eval(&process));
+    }
+
+    #[test]
+    fn test_filter_parse_error() {
+        for s in &[
+            "",
+            "java",
+            "cpu.usage_pct",
+            "cpu.usage_pct > ",
+            "no_such_field > 1",
+            "cpu.usage_pct > 1X",
+            "mem.anon > 50% of",
+            "mem.anon > 50% of no_such_field",
+            "(pid == 1",
+            "pid == 1 pid == 2",
+            r#"comm =~ "[""#,
+        ] {
+            assert!(
+                FilterExpr::<SingleProcessModelFieldId>::from_str(s).is_err(),
+                "Parsed invalid filter: {}",
+                s
+            );
+        }
+    }
+}
@@ -21,19 +21,33 @@ use serde::{Deserialize, Serialize};
 
 #[macro_use]
 pub mod collector;
+pub mod alert;
 pub mod cgroup;
+pub mod compare;
+pub mod events;
+pub mod filter;
 pub mod network;
+pub mod oom;
 pub mod process;
+pub mod procevents;
 pub mod sample;
 mod sample_model;
 pub mod system;
+pub mod window;
 
+pub use alert::*;
 pub use cgroup::*;
 pub use collector::*;
+pub use compare::*;
+pub use events::*;
+pub use filter::*;
 pub use network::*;
+pub use oom::*;
 pub use process::*;
+pub use procevents::*;
 pub use sample::*;
 pub use system::*;
+pub use window::*;
 
 #[cfg(fbcode_build)]
 mod facebook;
@@ -127,6 +141,51 @@ impl From<procfs::PidState> for Field {
     }
 }
 
+/// Type of the value of a Field, known without any value, e.g. for a schema.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FieldType {
+    U32,
+    U64,
+    I32,
+    I64,
+    F64,
+    Str,
+    PidState,
+}
+
+/// Types of model fields, converted into Fields of FieldType.
+pub trait HasFieldType: Into<Field> {
+    const FIELD_TYPE: FieldType;
+}
+
+impl HasFieldType for u32 {
+    const FIELD_TYPE: FieldType = FieldType::U32;
+}
+
+impl HasFieldType for u64 {
+    const FIELD_TYPE: FieldType = FieldType::U64;
+}
+
+impl HasFieldType for i32 {
+    const FIELD_TYPE: FieldType = FieldType::I32;
+}
+
+impl HasFieldType for i64 {
+    const FIELD_TYPE: FieldType = FieldType::I64;
+}
+
+impl HasFieldType for f64 {
+    const FIELD_TYPE: FieldType = FieldType::F64;
+}
+
+impl HasFieldType for String {
+    const FIELD_TYPE: FieldType = FieldType::Str;
+}
+
+impl HasFieldType for procfs::PidState {
+    const FIELD_TYPE: FieldType = FieldType::PidState;
+}
+
 impl<T: Into<Field> + Clone> From<&T> for Field {
     fn from(v: &T) -> Self {
         v.clone().into()
@@ -151,6 +210,7 @@ impl std::ops::Add for Field {
 impl PartialEq for Field {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
+            (Field::U32(s), Field::U32(o)) => s == o,
             (Field::U64(s), Field::U64(o)) => s == o,
             (Field::I32(s), Field::I32(o)) => s == o,
             (Field::I64(s), Field::I64(o)) => s == o,
@@ -165,6 +225,7 @@ impl PartialEq for Field {
 impl PartialOrd for Field {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         match (self, other) {
+            (Field::U32(s), Field::U32(o)) => s.partial_cmp(o),
             (Field::U64(s), Field::U64(o)) => s.partial_cmp(o),
             (Field::I32(s), Field::I32(o)) => s.partial_cmp(o),
             (Field::I64(s), Field::I64(o)) => s.partial_cmp(o),
@@ -196,6 +257,8 @@ impl fmt::Display for Field {
 pub trait Queriable {
     type FieldId: FieldId<Queriable = Self>;
     fn query(&self, field_id: &Self::FieldId) -> Option<Field>;
+    /// Type of the Field queried with field_id, whether it has a value or not.
+    fn field_type(field_id: &Self::FieldId) -> FieldType;
 }
 
 /// Marker trait to bind FieldId back to Queriable for type inference.
@@ -275,6 +338,74 @@ impl<T: Queriable> Queriable for Vec<T> {
         self.get(field_id.idx)
             .and_then(|f| f.query(&field_id.subquery_id))
     }
+    fn field_type(field_id: &Self::FieldId) -> FieldType {
+        T::field_type(&field_id.subquery_id)
+    }
+}
+
+/// Like VecFieldId, but selects the entry of a map by its key, for entries
+/// identified by numbers that may have gaps, e.g. thermal zones.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BTreeMapFieldId<K, Q: Queriable> {
+    pub key: K,
+    pub subquery_id: Q::FieldId,
+}
+
+impl<K: Ord, Q: Queriable + Sized> FieldId for BTreeMapFieldId<K, Q> {
+    type Queriable = BTreeMap<K, Q>;
+}
+
+/// Placeholder methods in case they are moved to a trait later.
+impl<K, Q: Queriable> BTreeMapFieldId<K, Q> {
+    pub fn unit_variant_iter() -> impl std::iter::Iterator<Item = Self> {
+        std::iter::empty()
+    }
+    pub fn all_variant_iter() -> impl std::iter::Iterator<Item = Self> {
+        std::iter::empty()
+    }
+}
+
+impl<K: std::string::ToString, Q: Queriable> std::string::ToString for BTreeMapFieldId<K, Q>
+where
+    <Q as Queriable>::FieldId: std::string::ToString,
+{
+    fn to_string(&self) -> String {
+        format!("{}.{}", self.key.to_string(), self.subquery_id.to_string())
+    }
+}
+
+impl<K, Q: Queriable> std::str::FromStr for BTreeMapFieldId<K, Q>
+where
+    K: std::str::FromStr,
+    <K as std::str::FromStr>::Err: Into<anyhow::Error>,
+    <Q as Queriable>::FieldId: std::str::FromStr,
+    <<Q as Queriable>::FieldId as std::str::FromStr>::Err: Into<anyhow::Error>,
+{
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if let Some(dot_idx) = s.find('.') {
+            Ok(Self {
+                key: K::from_str(&s[..dot_idx]).map_err(Into::into)?,
+                subquery_id: Q::FieldId::from_str(&s[dot_idx + 1..]).map_err(Into::into)?,
+            })
+        } else {
+            Err(anyhow!(
+                "Unable to find a variant of the given enum matching string `{}`.",
+                s,
+            ))
+        }
+    }
+}
+
+impl<K: Ord, T: Queriable> Queriable for BTreeMap<K, T> {
+    type FieldId = BTreeMapFieldId<K, T>;
+    fn query(&self, field_id: &Self::FieldId) -> Option<Field> {
+        self.get(&field_id.key)
+            .and_then(|f| f.query(&field_id.subquery_id))
+    }
+    fn field_type(field_id: &Self::FieldId) -> FieldType {
+        T::field_type(&field_id.subquery_id)
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -285,6 +416,15 @@ pub struct Model {
     pub cgroup: CgroupModel,
     pub process: ProcessModel,
     pub network: NetworkModel,
+    /// See Sample::high_res.
+    #[serde(default)]
+    pub high_res: bool,
+    /// See Sample::oom_kills.
+    #[serde(default)]
+    pub oom_kills: Vec<OomKillModel>,
+    /// See Sample::proc_events.
+    #[serde(default)]
+    pub proc_events: Vec<ProcEventModel>,
 }
 
 impl Model {
@@ -306,6 +446,9 @@ impl Model {
             .aggr_top_level_val(),
             process: ProcessModel::new(&sample.processes, last.map(|(s, d)| (&s.processes, d))),
             network: NetworkModel::new(&sample.netstats, last.map(|(s, d)| (&s.netstats, d))),
+            high_res: sample.high_res,
+            oom_kills: sample.oom_kills.iter().map(OomKillModel::new).collect(),
+            proc_events: sample.proc_events.iter().map(ProcEventModel::new).collect(),
         }
     }
 }
@@ -325,4 +468,30 @@ mod tests {
     fn test_deserialize_sample_model_json() {
         get_sample_model();
     }
+
+    #[test]
+    fn test_field_type() {
+        // Types do not depend on values, e.g. of processes without cpu stats
+        assert_eq!(
+            SingleProcessModel::field_type(&SingleProcessModelFieldId::Pid),
+            FieldType::I32
+        );
+        assert_eq!(
+            SingleProcessModel::field_type(&SingleProcessModelFieldId::State),
+            FieldType::PidState
+        );
+        assert_eq!(
+            SingleProcessModel::field_type(&SingleProcessModelFieldId::Cpu(
+                ProcessCpuModelFieldId::NumThreads
+            )),
+            FieldType::U64
+        );
+        assert_eq!(
+            SystemModel::field_type(&SystemModelFieldId::ThermalZones(BTreeMapFieldId {
+                key: 7,
+                subquery_id: SingleThermalZoneModelFieldId::TempCelsius,
+            })),
+            FieldType::F64
+        );
+    }
 }
@@ -449,3 +449,72 @@ impl SingleNetModel {
         }
     }
 }
+
+/// Bytes sent and received over inet sockets by a process or a cgroup,
+/// accounted by bpf. Stream sockets are counted as TCP and datagram sockets
+/// as UDP.
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    below_derive::Queriable
+)]
+pub struct NetBytesModel {
+    pub tcp_send_bytes_per_sec: Option<f64>,
+    pub tcp_recv_bytes_per_sec: Option<f64>,
+    pub udp_send_bytes_per_sec: Option<f64>,
+    pub udp_recv_bytes_per_sec: Option<f64>,
+    pub send_bytes_per_sec: Option<f64>,
+    pub recv_bytes_per_sec: Option<f64>,
+}
+
+impl NetBytesModel {
+    pub fn new(begin: &procfs::NetBytes, end: &procfs::NetBytes, delta: Duration) -> NetBytesModel {
+        let tcp_send_bytes_per_sec =
+            count_per_sec!(begin.tcp_send_bytes, end.tcp_send_bytes, delta);
+        let tcp_recv_bytes_per_sec =
+            count_per_sec!(begin.tcp_recv_bytes, end.tcp_recv_bytes, delta);
+        let udp_send_bytes_per_sec =
+            count_per_sec!(begin.udp_send_bytes, end.udp_send_bytes, delta);
+        let udp_recv_bytes_per_sec =
+            count_per_sec!(begin.udp_recv_bytes, end.udp_recv_bytes, delta);
+        NetBytesModel {
+            send_bytes_per_sec: opt_add(tcp_send_bytes_per_sec, udp_send_bytes_per_sec),
+            recv_bytes_per_sec: opt_add(tcp_recv_bytes_per_sec, udp_recv_bytes_per_sec),
+            tcp_send_bytes_per_sec,
+            tcp_recv_bytes_per_sec,
+            udp_send_bytes_per_sec,
+            udp_recv_bytes_per_sec,
+        }
+    }
+}
+
+impl std::ops::Add for NetBytesModel {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Self {
+            tcp_send_bytes_per_sec: opt_add(
+                self.tcp_send_bytes_per_sec,
+                other.tcp_send_bytes_per_sec,
+            ),
+            tcp_recv_bytes_per_sec: opt_add(
+                self.tcp_recv_bytes_per_sec,
+                other.tcp_recv_bytes_per_sec,
+            ),
+            udp_send_bytes_per_sec: opt_add(
+                self.udp_send_bytes_per_sec,
+                other.udp_send_bytes_per_sec,
+            ),
+            udp_recv_bytes_per_sec: opt_add(
+                self.udp_recv_bytes_per_sec,
+                other.udp_recv_bytes_per_sec,
+            ),
+            send_bytes_per_sec: opt_add(self.send_bytes_per_sec, other.send_bytes_per_sec),
+            recv_bytes_per_sec: opt_add(self.recv_bytes_per_sec, other.recv_bytes_per_sec),
+        }
+    }
+}
@@ -0,0 +1,56 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+/// A process killed by the OOM killer since the previous sample, reported by
+/// bpf.
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    below_derive::Queriable
+)]
+pub struct OomKillModel {
+    pub pid: Option<i32>,
+    pub comm: Option<String>,
+    pub cgroup: Option<String>,
+    pub rss_bytes: Option<u64>,
+    pub oom_score_adj: Option<i32>,
+    pub constraint: Option<String>,
+    pub oom_cgroup: Option<String>,
+    pub total_bytes: Option<u64>,
+    pub trigger_pid: Option<i32>,
+    pub trigger_comm: Option<String>,
+}
+
+impl OomKillModel {
+    pub fn new(sample: &procfs::OomKill) -> OomKillModel {
+        OomKillModel {
+            pid: sample.pid,
+            comm: sample.comm.clone(),
+            cgroup: sample.cgroup.clone(),
+            rss_bytes: sample.rss_bytes,
+            oom_score_adj: sample.oom_score_adj,
+            constraint: sample.constraint.clone(),
+            oom_cgroup: sample.oom_cgroup.clone(),
+            total_bytes: sample.total_bytes,
+            trigger_pid: sample.trigger_pid,
+            trigger_comm: sample.trigger_comm.clone(),
+        }
+    }
+}
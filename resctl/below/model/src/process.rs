@@ -14,7 +14,7 @@
 
 use super::*;
 
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct ProcessModel {
     pub processes: BTreeMap<i32, SingleProcessModel>,
 }
@@ -37,7 +37,7 @@ impl ProcessModel {
     }
 }
 
-#[derive(Default, Serialize, Deserialize, below_derive::Queriable)]
+#[derive(Clone, Default, Serialize, Deserialize, below_derive::Queriable)]
 pub struct SingleProcessModel {
     pub pid: Option<i32>,
     pub ppid: Option<i32>,
@@ -51,8 +51,14 @@ pub struct SingleProcessModel {
     pub mem: Option<ProcessMemoryModel>,
     #[queriable(subquery)]
     pub cpu: Option<ProcessCpuModel>,
+    #[queriable(subquery)]
+    pub net: Option<NetBytesModel>,
     pub cmdline: Option<String>,
     pub exe_path: Option<String>,
+    // Level in a ProcessTree, not set for processes in ProcessModel
+    #[queriable(ignore)]
+    #[serde(skip)]
+    pub depth: u32,
 }
 
 impl SingleProcessModel {
@@ -70,14 +76,144 @@ impl SingleProcessModel {
             io: last.map(|(l, d)| ProcessIoModel::new(&l.io, &sample.io, d)),
             mem: last.map(|(l, d)| ProcessMemoryModel::new(&l, &sample, d)),
             cpu: last.map(|(l, d)| ProcessCpuModel::new(&l.stat, &sample.stat, d)),
+            net: last.and_then(|(l, d)| match (&l.net_bytes, &sample.net_bytes) {
+                (Some(begin), Some(end)) => Some(NetBytesModel::new(begin, end, d)),
+                _ => None,
+            }),
             cmdline: if let Some(cmd_vec) = sample.cmdline_vec.as_ref() {
                 Some(cmd_vec.join(" "))
             } else {
                 Some("?".into())
             },
             exe_path: sample.exe_path.clone(),
+            depth: 0,
+        }
+    }
+}
+
+impl Recursive for SingleProcessModel {
+    fn get_depth(&self) -> usize {
+        self.depth as usize
+    }
+}
+
+/// Processes arranged by ppid, like pstree. The cpu, mem, io and net of every
+/// process in the tree are the totals of its subtree, and its depth is its
+/// level in the tree. Processes whose parent is not known are roots.
+#[derive(Default)]
+pub struct ProcessTree {
+    pub processes: BTreeMap<i32, SingleProcessModel>,
+    pub children: BTreeMap<i32, Vec<i32>>,
+    pub roots: Vec<i32>,
+}
+
+impl ProcessTree {
+    pub fn new(model: &ProcessModel) -> ProcessTree {
+        let mut children: BTreeMap<i32, Vec<i32>> = BTreeMap::new();
+        let mut roots = Vec::new();
+        for (pid, spm) in &model.processes {
+            match spm.ppid {
+                Some(ppid) if ppid != *pid && model.processes.contains_key(&ppid) => {
+                    children.entry(ppid).or_default().push(*pid)
+                }
+                _ => roots.push(*pid),
+            }
+        }
+
+        // Processes in a ppid cycle (possible if a pid is reused while we
+        // read procfs) can't be reached from a root and are left out.
+        let mut processes = BTreeMap::new();
+        let mut preorder = Vec::new();
+        let mut stack: Vec<(i32, u32)> = roots.iter().rev().map(|pid| (*pid, 0)).collect();
+        while let Some((pid, depth)) = stack.pop() {
+            let mut spm = model.processes[&pid].clone();
+            spm.depth = depth;
+            processes.insert(pid, spm);
+            preorder.push(pid);
+            if let Some(child_pids) = children.get(&pid) {
+                stack.extend(child_pids.iter().rev().map(|child| (*child, depth + 1)));
+            }
+        }
+
+        // Fold every process into its parent, descendants before ancestors
+        for pid in preorder.iter().rev() {
+            let spm = &processes[pid];
+            let ppid = match spm.ppid {
+                Some(ppid) if spm.depth > 0 => ppid,
+                _ => continue,
+            };
+            let (cpu, mem, io, net) = (
+                spm.cpu.clone(),
+                spm.mem.clone(),
+                spm.io.clone(),
+                spm.net.clone(),
+            );
+            let parent = processes
+                .get_mut(&ppid)
+                .expect("Parent of a process in the tree is not in the tree");
+            parent.cpu = opt_add(parent.cpu.take(), cpu);
+            parent.mem = opt_add(parent.mem.take(), mem);
+            parent.io = opt_add(parent.io.take(), io);
+            parent.net = opt_add(parent.net.take(), net);
+        }
+
+        ProcessTree {
+            processes,
+            children,
+            roots,
+        }
+    }
+
+    /// Visits processes depth-first with siblings ordered by `sort_order`, or
+    /// by pid if there is none. Children of a process are only visited if
+    /// `visit` returns true for it.
+    pub fn walk<F>(
+        &self,
+        sort_order: Option<&SingleProcessModelFieldId>,
+        reverse: bool,
+        mut visit: F,
+    ) where
+        F: FnMut(&SingleProcessModel) -> bool,
+    {
+        let sorted = |pids: &[i32]| {
+            let mut siblings: Vec<&SingleProcessModel> = pids
+                .iter()
+                .filter_map(|pid| self.processes.get(pid))
+                .collect();
+            if let Some(sort_order) = sort_order {
+                sort_queriables(&mut siblings, sort_order, reverse);
+            }
+            siblings
+        };
+
+        let mut stack = sorted(&self.roots);
+        stack.reverse();
+        while let Some(spm) = stack.pop() {
+            if !visit(spm) {
+                continue;
+            }
+            if let Some(child_pids) = spm.pid.and_then(|pid| self.children.get(&pid)) {
+                stack.extend(sorted(child_pids).into_iter().rev());
+            }
         }
     }
+
+    /// Returns `pids` together with the pids of all their ancestors, i.e.
+    /// the processes to keep in a tree filtered down to `pids`.
+    pub fn with_ancestors<I: IntoIterator<Item = i32>>(&self, pids: I) -> BTreeSet<i32> {
+        let mut result = BTreeSet::new();
+        for pid in pids {
+            let mut pid = Some(pid);
+            while let Some(spm) = pid.and_then(|pid| self.processes.get(&pid)) {
+                // Stop once we meet a branch we've already walked
+                if !result.insert(spm.pid.unwrap_or_default()) || spm.depth == 0 {
+                    break;
+                }
+                pid = spm.ppid;
+            }
+        }
+        result
+    }
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, below_derive::Queriable)]
@@ -102,6 +238,18 @@ impl ProcessIoModel {
     }
 }
 
+impl std::ops::Add for ProcessIoModel {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Self {
+            rbytes_per_sec: opt_add(self.rbytes_per_sec, other.rbytes_per_sec),
+            wbytes_per_sec: opt_add(self.wbytes_per_sec, other.wbytes_per_sec),
+            rwbytes_per_sec: opt_add(self.rwbytes_per_sec, other.rwbytes_per_sec),
+        }
+    }
+}
+
 #[derive(Clone, Default, Serialize, Deserialize, below_derive::Queriable)]
 pub struct ProcessCpuModel {
     pub usage_pct: Option<f64>,
@@ -124,6 +272,19 @@ impl ProcessCpuModel {
     }
 }
 
+impl std::ops::Add for ProcessCpuModel {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Self {
+            usage_pct: opt_add(self.usage_pct, other.usage_pct),
+            user_pct: opt_add(self.user_pct, other.user_pct),
+            system_pct: opt_add(self.system_pct, other.system_pct),
+            num_threads: opt_add(self.num_threads, other.num_threads),
+        }
+    }
+}
+
 #[derive(Clone, Default, Serialize, Deserialize, below_derive::Queriable)]
 pub struct ProcessMemoryModel {
     pub minorfaults_per_sec: Option<f64>,
@@ -158,3 +319,93 @@ impl ProcessMemoryModel {
         }
     }
 }
+
+impl std::ops::Add for ProcessMemoryModel {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Self {
+            minorfaults_per_sec: opt_add(self.minorfaults_per_sec, other.minorfaults_per_sec),
+            majorfaults_per_sec: opt_add(self.majorfaults_per_sec, other.majorfaults_per_sec),
+            rss_bytes: opt_add(self.rss_bytes, other.rss_bytes),
+            vm_size: opt_add(self.vm_size, other.vm_size),
+            lock: opt_add(self.lock, other.lock),
+            pin: opt_add(self.pin, other.pin),
+            anon: opt_add(self.anon, other.anon),
+            file: opt_add(self.file, other.file),
+            shmem: opt_add(self.shmem, other.shmem),
+            pte: opt_add(self.pte, other.pte),
+            swap: opt_add(self.swap, other.swap),
+            huge_tlb: opt_add(self.huge_tlb, other.huge_tlb),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn process(pid: i32, ppid: i32, usage_pct: f64) -> SingleProcessModel {
+        SingleProcessModel {
+            pid: Some(pid),
+            ppid: Some(ppid),
+            cpu: Some(ProcessCpuModel {
+                usage_pct: Some(usage_pct),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_process_tree() {
+        let mut model = ProcessModel::default();
+        for &(pid, ppid, usage_pct) in &[
+            (1, 0, 1.0),
+            (2, 1, 10.0),
+            (3, 2, 20.0),
+            (4, 1, 40.0),
+            (5, 2, 5.0),
+            // Parent already exited
+            (6, 100, 2.0),
+            // A ppid cycle can't be placed in the tree
+            (7, 8, 1.0),
+            (8, 7, 1.0),
+        ] {
+            model.processes.insert(pid, process(pid, ppid, usage_pct));
+        }
+
+        let tree = ProcessTree::new(&model);
+        assert_eq!(tree.roots, vec![1, 6]);
+        let usage = |pid| tree.processes[&pid].cpu.as_ref().unwrap().usage_pct;
+        assert_eq!(usage(1), Some(76.0));
+        assert_eq!(usage(2), Some(35.0));
+        assert_eq!(usage(3), Some(20.0));
+        assert_eq!(usage(6), Some(2.0));
+        assert!(!tree.processes.contains_key(&7));
+
+        let mut visited = Vec::new();
+        tree.walk(None, false, |spm| {
+            visited.push((spm.pid.unwrap(), spm.depth));
+            true
+        });
+        assert_eq!(
+            visited,
+            vec![(1, 0), (2, 1), (3, 2), (5, 2), (4, 1), (6, 0)]
+        );
+
+        // Sort siblings by cpu and don't descend into pid 2
+        let cpu = SingleProcessModelFieldId::Cpu(ProcessCpuModelFieldId::UsagePct);
+        let mut visited = Vec::new();
+        tree.walk(Some(&cpu), true, |spm| {
+            visited.push(spm.pid.unwrap());
+            spm.pid != Some(2)
+        });
+        assert_eq!(visited, vec![1, 4, 2, 6]);
+
+        assert_eq!(
+            tree.with_ancestors(vec![3, 5, 6]),
+            [1, 2, 3, 5, 6].iter().cloned().collect()
+        );
+    }
+}
@@ -0,0 +1,140 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+/// A process forked, exec'd or exited since the previous sample, reported by
+/// bpf.
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    below_derive::Queriable
+)]
+pub struct ProcEventModel {
+    pub event: Option<String>,
+    pub time_epoch_ms: Option<u64>,
+    pub pid: Option<i32>,
+    pub ppid: Option<i32>,
+    pub comm: Option<String>,
+    pub cmdline: Option<String>,
+    pub cgroup: Option<String>,
+    pub start_epoch_ms: Option<u64>,
+    pub end_epoch_ms: Option<u64>,
+    pub exit_code: Option<i32>,
+}
+
+impl ProcEventModel {
+    pub fn new(sample: &procfs::ProcEvent) -> ProcEventModel {
+        ProcEventModel {
+            event: sample.event.clone(),
+            time_epoch_ms: sample.time_epoch_ms,
+            pid: sample.pid,
+            ppid: sample.ppid,
+            comm: sample.comm.clone(),
+            cmdline: sample.cmdline_vec.as_ref().map(|c| c.join(" ")),
+            cgroup: sample.cgroup.clone(),
+            start_epoch_ms: sample.start_epoch_ms,
+            end_epoch_ms: sample.end_epoch_ms,
+            exit_code: sample.exit_code,
+        }
+    }
+}
+
+/// The processes that lived during the interval of a sample, i.e. those
+/// started or exited in it, ordered by start time. Each process is described
+/// by its last event, which is its exit if it exited. A process is told apart
+/// by its pid and start time, as pids are reused.
+pub fn get_lived_processes(proc_events: &[ProcEventModel]) -> Vec<ProcEventModel> {
+    let mut processes = BTreeMap::new();
+    for proc_event in proc_events {
+        let key = (proc_event.start_epoch_ms, proc_event.pid);
+        let process = processes.entry(key).or_insert_with(|| proc_event.clone());
+        // Events are reported in order, except across CPUs
+        if process.time_epoch_ms <= proc_event.time_epoch_ms {
+            let cmdline = proc_event
+                .cmdline
+                .clone()
+                .or_else(|| process.cmdline.take());
+            *process = ProcEventModel {
+                cmdline,
+                ..proc_event.clone()
+            };
+        } else if process.cmdline.is_none() {
+            process.cmdline = proc_event.cmdline.clone();
+        }
+    }
+    processes.into_iter().map(|(_, process)| process).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn proc_event(
+        event: &str,
+        time_epoch_ms: u64,
+        pid: i32,
+        start_epoch_ms: u64,
+        cmdline: Option<&str>,
+    ) -> ProcEventModel {
+        ProcEventModel {
+            event: Some(event.to_owned()),
+            time_epoch_ms: Some(time_epoch_ms),
+            pid: Some(pid),
+            cmdline: cmdline.map(|c| c.to_owned()),
+            start_epoch_ms: Some(start_epoch_ms),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_get_lived_processes() {
+        let proc_events = vec![
+            proc_event("fork", 100, 10, 100, Some("bash")),
+            proc_event("exec", 101, 10, 100, Some("sleep 1")),
+            // Exit of a process started before the interval
+            proc_event("exit", 102, 5, 50, Some("make")),
+            // Exit reported by another CPU before the exec
+            proc_event("exit", 300, 11, 200, None),
+            proc_event("exec", 201, 11, 200, Some("ls -l")),
+            // Pid 10 is reused
+            proc_event("exit", 400, 10, 100, Some("sleep 1")),
+            proc_event("fork", 500, 10, 500, Some("bash")),
+        ];
+        let lived: Vec<_> = get_lived_processes(&proc_events)
+            .into_iter()
+            .map(|p| {
+                (
+                    p.event.unwrap(),
+                    p.pid.unwrap(),
+                    p.start_epoch_ms.unwrap(),
+                    p.cmdline,
+                )
+            })
+            .collect();
+        assert_eq!(
+            lived,
+            vec![
+                ("exit".to_owned(), 5, 50, Some("make".to_owned())),
+                ("exit".to_owned(), 10, 100, Some("sleep 1".to_owned())),
+                ("exit".to_owned(), 11, 200, Some("ls -l".to_owned())),
+                ("fork".to_owned(), 10, 500, Some("bash".to_owned())),
+            ]
+        );
+    }
+}
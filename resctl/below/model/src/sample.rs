@@ -20,6 +20,17 @@ pub struct Sample {
     pub processes: procfs::PidMap,
     pub system: SystemSample,
     pub netstats: procfs::NetStat,
+    /// Collected at high resolution around an incident, so the sample is
+    /// closer to the previous one than the record interval.
+    #[serde(default)]
+    pub high_res: bool,
+    /// Processes OOM killed since the previous sample, empty if not enabled
+    #[serde(default)]
+    pub oom_kills: Vec<procfs::OomKill>,
+    /// Processes forked, exec'd or exited since the previous sample, empty
+    /// if not enabled
+    #[serde(default)]
+    pub proc_events: Vec<procfs::ProcEvent>,
 }
 
 #[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
@@ -33,7 +44,17 @@ pub struct CgroupSample {
     pub memory_swap_current: Option<i64>,
     pub memory_high: Option<i64>,
     pub memory_events: Option<cgroupfs::MemoryEvents>,
+    pub memory_events_local: Option<cgroupfs::MemoryEvents>,
+    pub memory_swap_events: Option<cgroupfs::MemorySwapEvents>,
+    pub hugetlb: Option<BTreeMap<String, cgroupfs::HugetlbStat>>,
+    pub rdma_current: Option<BTreeMap<String, cgroupfs::RdmaStat>>,
+    pub misc_current: Option<BTreeMap<String, u64>>,
     pub inode_number: Option<i64>,
+    /// Of the processes in the cgroup and its descendants, None if not
+    /// enabled
+    pub net_bytes: Option<procfs::NetBytes>,
+    /// Of the tasks in the cgroup and its descendants, None if not enabled
+    pub runq_latency: Option<procfs::RunqLatency>,
 }
 
 #[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
@@ -45,4 +66,9 @@ pub struct SystemSample {
     pub disks: procfs::DiskMap,
     pub kernel_version: Option<String>,
     pub os_release: Option<String>,
+    pub interrupts: Option<procfs::InterruptMap>,
+    pub softirqs: Option<procfs::InterruptMap>,
+    pub softnet: Option<Vec<procfs::SoftnetStat>>,
+    pub cpu_freq: Option<procfs::CpuFreqMap>,
+    pub thermal_zones: Option<procfs::ThermalZoneMap>,
 }
@@ -49,7 +49,10 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
             "softirq_pct": 0.2,
             "stolen_pct": 0.0,
             "guest_pct": 0.0,
-            "guest_nice_pct": 0.0
+            "guest_nice_pct": 0.0,
+            "scaling_cur_freq_mhz": 2400,
+            "core_throttle_per_sec": 0.0,
+            "package_throttle_per_sec": 0.0
         },
         "cpus": [
             {
@@ -64,7 +67,10 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
                 "softirq_pct": 0.2,
                 "stolen_pct": 0.0,
                 "guest_pct": 0.0,
-                "guest_nice_pct": 0.0
+                "guest_nice_pct": 0.0,
+                "scaling_cur_freq_mhz": 2400,
+                "core_throttle_per_sec": 0.0,
+                "package_throttle_per_sec": 0.0
             },
             {
                 "idx": 1,
@@ -78,7 +84,10 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
                 "softirq_pct": 0.2,
                 "stolen_pct": 0.0,
                 "guest_pct": 0.0,
-                "guest_nice_pct": 0.0
+                "guest_nice_pct": 0.0,
+                "scaling_cur_freq_mhz": 2400,
+                "core_throttle_per_sec": 0.0,
+                "package_throttle_per_sec": 0.0
             }
         ],
         "mem": {
@@ -176,6 +185,63 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
                 "major": 20,
                 "minor": 0
             }
+        },
+        "interrupts": {
+            "24": {
+                "name": "24",
+                "total_per_sec": 3000.0,
+                "top_cpu": 0,
+                "top_cpu_per_sec": 2000.0,
+                "description": "PCI-MSI 524288-edge eth0-TxRx-0",
+                "cpus_per_sec": {"0": 2000.0, "1": 1000.0}
+            },
+            "LOC": {
+                "name": "LOC",
+                "total_per_sec": 2000.0,
+                "top_cpu": 1,
+                "top_cpu_per_sec": 1000.0,
+                "description": "Local timer interrupts",
+                "cpus_per_sec": {"0": 1000.0, "1": 1000.0}
+            }
+        },
+        "softirqs": {
+            "NET_RX": {
+                "name": "NET_RX",
+                "total_per_sec": 5000.0,
+                "top_cpu": 0,
+                "top_cpu_per_sec": 4500.0,
+                "description": null,
+                "cpus_per_sec": {"0": 4500.0, "1": 500.0}
+            },
+            "TIMER": {
+                "name": "TIMER",
+                "total_per_sec": 500.0,
+                "top_cpu": 0,
+                "top_cpu_per_sec": 250.0,
+                "description": null,
+                "cpus_per_sec": {"0": 250.0, "1": 250.0}
+            }
+        },
+        "softnet": {
+            "0": {
+                "idx": 0,
+                "processed_per_sec": 4500.0,
+                "dropped_per_sec": 0.0,
+                "time_squeeze_per_sec": 1.0
+            },
+            "1": {
+                "idx": 1,
+                "processed_per_sec": 500.0,
+                "dropped_per_sec": 0.0,
+                "time_squeeze_per_sec": 0.0
+            }
+        },
+        "thermal_zones": {
+            "0": {
+                "idx": 0,
+                "zone_type": "x86_pkg_temp",
+                "temp_celsius": 45.0
+            }
         }
     },
     "cgroup": {
@@ -234,6 +300,11 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
             "memory_some_pct": 0.6,
             "memory_full_pct": 0.3
         },
+        "runq_lat": {
+            "p50_usec": 4,
+            "p99_usec": 512,
+            "max_usec": 8192
+        },
         "children": [
             {
                 "name": "init.scope",
@@ -542,6 +613,14 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
                     "system_pct": 0.5,
                     "num_threads": 1
                 },
+                "net": {
+                    "tcp_send_bytes_per_sec": 100.0,
+                    "tcp_recv_bytes_per_sec": 200.0,
+                    "udp_send_bytes_per_sec": 0.0,
+                    "udp_recv_bytes_per_sec": 50.0,
+                    "send_bytes_per_sec": 100.0,
+                    "recv_bytes_per_sec": 250.0
+                },
                 "cmdline": "/usr/lib/systemd/systemd",
                 "exe_path": "/usr/lib/systemd/systemd"
             }
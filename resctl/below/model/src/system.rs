@@ -40,19 +40,29 @@ pub struct SystemModel {
     pub vm: VmModel,
     #[queriable(ignore)]
     pub disks: BTreeMap<String, SingleDiskModel>,
+    #[queriable(ignore)]
+    pub interrupts: BTreeMap<String, SingleInterruptModel>,
+    #[queriable(ignore)]
+    pub softirqs: BTreeMap<String, SingleInterruptModel>,
+    /// Keyed by CPU id, as rows of offline CPUs are missing
+    #[queriable(subquery)]
+    pub softnet: BTreeMap<u32, SingleSoftnetModel>,
+    /// Keyed by N of thermal_zoneN, which may have gaps
+    #[queriable(subquery)]
+    pub thermal_zones: BTreeMap<u32, SingleThermalZoneModel>,
 }
 
 impl SystemModel {
     pub fn new(sample: &SystemSample, last: Option<(&SystemSample, Duration)>) -> SystemModel {
         let stat = ProcStatModel::new(&sample.stat);
-        let total_cpu = match (
+        let mut total_cpu = match (
             last.and_then(|(last, _)| last.stat.total_cpu.as_ref()),
             sample.stat.total_cpu.as_ref(),
         ) {
             (Some(prev), Some(curr)) => SingleCpuModel::new(-1, &prev, &curr),
             _ => Default::default(),
         };
-        let cpus = match (
+        let cpus: Vec<SingleCpuModel> = match (
             last.and_then(|(last, _)| last.stat.cpus.as_ref()),
             sample.stat.cpus.as_ref(),
         ) {
@@ -63,6 +73,25 @@ impl SystemModel {
             (_, Some(curr)) => curr.iter().map(|_| Default::default()).collect(),
             _ => Default::default(),
         };
+        let cpus: Vec<SingleCpuModel> = match sample.cpu_freq.as_ref() {
+            Some(cpu_freq) => cpus
+                .into_iter()
+                .map(|cpu| {
+                    let idx = cpu.idx as u32;
+                    cpu.with_freq(
+                        cpu_freq.get(&idx),
+                        last.and_then(|(last, duration)| {
+                            last.cpu_freq
+                                .as_ref()
+                                .and_then(|f| f.get(&idx))
+                                .map(|f| (f, duration))
+                        }),
+                    )
+                })
+                .collect(),
+            None => cpus,
+        };
+        total_cpu.aggr_freq(&cpus, sample.cpu_freq.as_ref());
         let mem = Some(MemoryModel::new(&sample.meminfo)).unwrap_or_default();
         let vm = last
             .map(|(last, duration)| VmModel::new(&last.vmstat, &sample.vmstat, duration))
@@ -86,6 +115,52 @@ impl SystemModel {
                 },
             );
         });
+        let interrupts = SingleInterruptModel::new_map(
+            last.and_then(|(last, _)| last.interrupts.as_ref()),
+            sample.interrupts.as_ref(),
+            last.map(|(_, duration)| duration),
+        );
+        let softirqs = SingleInterruptModel::new_map(
+            last.and_then(|(last, _)| last.softirqs.as_ref()),
+            sample.softirqs.as_ref(),
+            last.map(|(_, duration)| duration),
+        );
+        let thermal_zones = sample
+            .thermal_zones
+            .as_ref()
+            .map(|zones| {
+                zones
+                    .iter()
+                    .map(|(idx, zone)| (*idx, SingleThermalZoneModel::new(*idx, zone)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let last_softnet = last.and_then(|(last, duration)| {
+            last.softnet
+                .as_ref()
+                .map(|softnet| (softnet_by_cpu(softnet), duration))
+        });
+        let softnet = sample
+            .softnet
+            .as_ref()
+            .map(|softnet| {
+                softnet_by_cpu(softnet)
+                    .into_iter()
+                    .map(|(cpu, curr)| {
+                        let model = match &last_softnet {
+                            Some((prev, duration)) if prev.contains_key(&cpu) => {
+                                SingleSoftnetModel::new(cpu as i32, prev[&cpu], curr, *duration)
+                            }
+                            _ => SingleSoftnetModel {
+                                idx: cpu as i32,
+                                ..Default::default()
+                            },
+                        };
+                        (cpu, model)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
 
         SystemModel {
             hostname: sample.hostname.clone(),
@@ -97,6 +172,10 @@ impl SystemModel {
             mem,
             vm,
             disks,
+            interrupts,
+            softirqs,
+            softnet,
+            thermal_zones,
         }
     }
 }
@@ -154,6 +233,9 @@ pub struct SingleCpuModel {
     pub stolen_pct: Option<f64>,
     pub guest_pct: Option<f64>,
     pub guest_nice_pct: Option<f64>,
+    pub scaling_cur_freq_mhz: Option<u64>,
+    pub core_throttle_per_sec: Option<f64>,
+    pub package_throttle_per_sec: Option<f64>,
 }
 
 impl SingleCpuModel {
@@ -213,6 +295,7 @@ impl SingleCpuModel {
                     stolen_pct: Some(stolen_usec as f64 * 100.0 / total_usec as f64),
                     guest_pct: Some(guest_usec as f64 * 100.0 / total_usec as f64),
                     guest_nice_pct: Some(guest_nice_usec as f64 * 100.0 / total_usec as f64),
+                    ..Default::default()
                 }
             }
             _ => SingleCpuModel {
@@ -221,6 +304,68 @@ impl SingleCpuModel {
             },
         }
     }
+
+    fn with_freq(
+        mut self,
+        end: Option<&procfs::CpuFreqStat>,
+        last: Option<(&procfs::CpuFreqStat, Duration)>,
+    ) -> SingleCpuModel {
+        if let Some(end) = end {
+            self.scaling_cur_freq_mhz = end.scaling_cur_freq_khz.map(|v| v / 1000);
+            if let Some((begin, duration)) = last {
+                self.core_throttle_per_sec =
+                    count_per_sec!(begin.core_throttle_count, end.core_throttle_count, duration);
+                self.package_throttle_per_sec = count_per_sec!(
+                    begin.package_throttle_count,
+                    end.package_throttle_count,
+                    duration
+                );
+            }
+        }
+        self
+    }
+
+    /// Frequency of the aggregated CPU is the average of all CPUs reporting
+    /// one, while throttling events are summed. Each CPU reports the counts of
+    /// its core and package, so they are only counted once per core and
+    /// package. CPUs without topology are counted on their own.
+    fn aggr_freq(&mut self, cpus: &[SingleCpuModel], cpu_freq: Option<&procfs::CpuFreqMap>) {
+        let freqs: Vec<u64> = cpus.iter().filter_map(|c| c.scaling_cur_freq_mhz).collect();
+        if !freqs.is_empty() {
+            self.scaling_cur_freq_mhz = Some(freqs.iter().sum::<u64>() / freqs.len() as u64);
+        }
+        let topology = |cpu: &SingleCpuModel| {
+            cpu_freq
+                .and_then(|cpu_freq| cpu_freq.get(&(cpu.idx as u32)))
+                .map_or((None, None), |stat| {
+                    (stat.physical_package_id, stat.core_id)
+                })
+        };
+        let mut cores = BTreeMap::new();
+        let mut packages = BTreeMap::new();
+        for cpu in cpus {
+            let (package_id, core_id) = topology(cpu);
+            let own_idx = Some(cpu.idx);
+            let core_key = match (package_id, core_id) {
+                (Some(_), Some(_)) => (package_id, core_id, None),
+                _ => (None, None, own_idx),
+            };
+            let package_key = match package_id {
+                Some(_) => (package_id, None),
+                None => (None, own_idx),
+            };
+            if let Some(per_sec) = cpu.core_throttle_per_sec {
+                cores.entry(core_key).or_insert(per_sec);
+            }
+            if let Some(per_sec) = cpu.package_throttle_per_sec {
+                packages.entry(package_key).or_insert(per_sec);
+            }
+        }
+        self.core_throttle_per_sec = cores.values().fold(None, |acc, v| opt_add(acc, Some(*v)));
+        self.package_throttle_per_sec = packages
+            .values()
+            .fold(None, |acc, v| opt_add(acc, Some(*v)));
+    }
 }
 
 #[derive(
@@ -341,6 +486,43 @@ pub struct VmModel {
     pub pgscan_kswapd: Option<u64>,
     pub pgscan_direct: Option<u64>,
     pub oom_kill: Option<u64>,
+    pub pgfault_per_sec: Option<f64>,
+    pub pgmajfault_per_sec: Option<f64>,
+    pub pgactivate_per_sec: Option<f64>,
+    pub pgdeactivate_per_sec: Option<f64>,
+    pub pgrefill_per_sec: Option<f64>,
+    pub pgscan_direct_throttle_per_sec: Option<f64>,
+    pub kswapd_inodesteal_per_sec: Option<f64>,
+    pub kswapd_low_wmark_hit_quickly_per_sec: Option<f64>,
+    pub kswapd_high_wmark_hit_quickly_per_sec: Option<f64>,
+    pub pageoutrun_per_sec: Option<f64>,
+    pub allocstall_per_sec: Option<f64>,
+    pub compact_migrate_scanned_per_sec: Option<f64>,
+    pub compact_free_scanned_per_sec: Option<f64>,
+    pub compact_isolated_per_sec: Option<f64>,
+    pub compact_stall_per_sec: Option<f64>,
+    pub compact_fail_per_sec: Option<f64>,
+    pub compact_success_per_sec: Option<f64>,
+    pub compact_daemon_wake_per_sec: Option<f64>,
+    pub thp_fault_alloc_per_sec: Option<f64>,
+    pub thp_fault_fallback_per_sec: Option<f64>,
+    pub thp_collapse_alloc_per_sec: Option<f64>,
+    pub thp_collapse_alloc_failed_per_sec: Option<f64>,
+    pub thp_split_page_per_sec: Option<f64>,
+    pub thp_split_page_failed_per_sec: Option<f64>,
+    pub thp_deferred_split_page_per_sec: Option<f64>,
+    pub thp_split_pmd_per_sec: Option<f64>,
+    pub thp_swpout_per_sec: Option<f64>,
+    pub thp_swpout_fallback_per_sec: Option<f64>,
+    pub workingset_refault_per_sec: Option<f64>,
+    pub workingset_refault_anon_per_sec: Option<f64>,
+    pub workingset_refault_file_per_sec: Option<f64>,
+    pub workingset_activate_per_sec: Option<f64>,
+    pub workingset_restore_per_sec: Option<f64>,
+    pub workingset_nodereclaim_per_sec: Option<f64>,
+    // Pages reclaimed per page scanned during the interval
+    pub kswapd_reclaim_efficiency_pct: Option<f64>,
+    pub direct_reclaim_efficiency_pct: Option<f64>,
 }
 
 impl VmModel {
@@ -355,6 +537,312 @@ impl VmModel {
             pgscan_kswapd: count_per_sec!(begin.pgscan_kswapd, end.pgscan_kswapd, duration, u64),
             pgscan_direct: count_per_sec!(begin.pgscan_direct, end.pgscan_direct, duration, u64),
             oom_kill: end.oom_kill.map(|v| v as u64),
+            pgfault_per_sec: count_per_sec!(begin.pgfault, end.pgfault, duration),
+            pgmajfault_per_sec: count_per_sec!(begin.pgmajfault, end.pgmajfault, duration),
+            pgactivate_per_sec: count_per_sec!(begin.pgactivate, end.pgactivate, duration),
+            pgdeactivate_per_sec: count_per_sec!(begin.pgdeactivate, end.pgdeactivate, duration),
+            pgrefill_per_sec: count_per_sec!(begin.pgrefill, end.pgrefill, duration),
+            pgscan_direct_throttle_per_sec: count_per_sec!(
+                begin.pgscan_direct_throttle,
+                end.pgscan_direct_throttle,
+                duration
+            ),
+            kswapd_inodesteal_per_sec: count_per_sec!(
+                begin.kswapd_inodesteal,
+                end.kswapd_inodesteal,
+                duration
+            ),
+            kswapd_low_wmark_hit_quickly_per_sec: count_per_sec!(
+                begin.kswapd_low_wmark_hit_quickly,
+                end.kswapd_low_wmark_hit_quickly,
+                duration
+            ),
+            kswapd_high_wmark_hit_quickly_per_sec: count_per_sec!(
+                begin.kswapd_high_wmark_hit_quickly,
+                end.kswapd_high_wmark_hit_quickly,
+                duration
+            ),
+            pageoutrun_per_sec: count_per_sec!(begin.pageoutrun, end.pageoutrun, duration),
+            allocstall_per_sec: count_per_sec!(begin.allocstall, end.allocstall, duration),
+            compact_migrate_scanned_per_sec: count_per_sec!(
+                begin.compact_migrate_scanned,
+                end.compact_migrate_scanned,
+                duration
+            ),
+            compact_free_scanned_per_sec: count_per_sec!(
+                begin.compact_free_scanned,
+                end.compact_free_scanned,
+                duration
+            ),
+            compact_isolated_per_sec: count_per_sec!(
+                begin.compact_isolated,
+                end.compact_isolated,
+                duration
+            ),
+            compact_stall_per_sec: count_per_sec!(begin.compact_stall, end.compact_stall, duration),
+            compact_fail_per_sec: count_per_sec!(begin.compact_fail, end.compact_fail, duration),
+            compact_success_per_sec: count_per_sec!(
+                begin.compact_success,
+                end.compact_success,
+                duration
+            ),
+            compact_daemon_wake_per_sec: count_per_sec!(
+                begin.compact_daemon_wake,
+                end.compact_daemon_wake,
+                duration
+            ),
+            thp_fault_alloc_per_sec: count_per_sec!(
+                begin.thp_fault_alloc,
+                end.thp_fault_alloc,
+                duration
+            ),
+            thp_fault_fallback_per_sec: count_per_sec!(
+                begin.thp_fault_fallback,
+                end.thp_fault_fallback,
+                duration
+            ),
+            thp_collapse_alloc_per_sec: count_per_sec!(
+                begin.thp_collapse_alloc,
+                end.thp_collapse_alloc,
+                duration
+            ),
+            thp_collapse_alloc_failed_per_sec: count_per_sec!(
+                begin.thp_collapse_alloc_failed,
+                end.thp_collapse_alloc_failed,
+                duration
+            ),
+            thp_split_page_per_sec: count_per_sec!(
+                begin.thp_split_page,
+                end.thp_split_page,
+                duration
+            ),
+            thp_split_page_failed_per_sec: count_per_sec!(
+                begin.thp_split_page_failed,
+                end.thp_split_page_failed,
+                duration
+            ),
+            thp_deferred_split_page_per_sec: count_per_sec!(
+                begin.thp_deferred_split_page,
+                end.thp_deferred_split_page,
+                duration
+            ),
+            thp_split_pmd_per_sec: count_per_sec!(begin.thp_split_pmd, end.thp_split_pmd, duration),
+            thp_swpout_per_sec: count_per_sec!(begin.thp_swpout, end.thp_swpout, duration),
+            thp_swpout_fallback_per_sec: count_per_sec!(
+                begin.thp_swpout_fallback,
+                end.thp_swpout_fallback,
+                duration
+            ),
+            workingset_refault_per_sec: count_per_sec!(
+                begin.workingset_refault,
+                end.workingset_refault,
+                duration
+            ),
+            workingset_refault_anon_per_sec: count_per_sec!(
+                begin.workingset_refault_anon,
+                end.workingset_refault_anon,
+                duration
+            ),
+            workingset_refault_file_per_sec: count_per_sec!(
+                begin.workingset_refault_file,
+                end.workingset_refault_file,
+                duration
+            ),
+            workingset_activate_per_sec: count_per_sec!(
+                begin.workingset_activate,
+                end.workingset_activate,
+                duration
+            ),
+            workingset_restore_per_sec: count_per_sec!(
+                begin.workingset_restore,
+                end.workingset_restore,
+                duration
+            ),
+            workingset_nodereclaim_per_sec: count_per_sec!(
+                begin.workingset_nodereclaim,
+                end.workingset_nodereclaim,
+                duration
+            ),
+            kswapd_reclaim_efficiency_pct: reclaim_efficiency_pct(
+                (begin.pgsteal_kswapd, end.pgsteal_kswapd),
+                (begin.pgscan_kswapd, end.pgscan_kswapd),
+            ),
+            direct_reclaim_efficiency_pct: reclaim_efficiency_pct(
+                (begin.pgsteal_direct, end.pgsteal_direct),
+                (begin.pgscan_direct, end.pgscan_direct),
+            ),
+        }
+    }
+}
+
+/// Percentage of scanned pages that were reclaimed between two samples of the
+/// (steal, scan) counters. None if nothing was scanned.
+fn reclaim_efficiency_pct(
+    steal: (Option<u64>, Option<u64>),
+    scan: (Option<u64>, Option<u64>),
+) -> Option<f64> {
+    match (steal, scan) {
+        ((Some(steal_begin), Some(steal_end)), (Some(scan_begin), Some(scan_end)))
+            if steal_end >= steal_begin && scan_end > scan_begin =>
+        {
+            Some((steal_end - steal_begin) as f64 * 100.0 / (scan_end - scan_begin) as f64)
+        }
+        _ => None,
+    }
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    below_derive::Queriable
+)]
+pub struct SingleThermalZoneModel {
+    pub idx: i32,
+    pub zone_type: Option<String>,
+    pub temp_celsius: Option<f64>,
+}
+
+impl SingleThermalZoneModel {
+    fn new(idx: u32, zone: &procfs::ThermalZoneStat) -> SingleThermalZoneModel {
+        SingleThermalZoneModel {
+            idx: idx as i32,
+            zone_type: zone.zone_type.clone(),
+            temp_celsius: zone.temp_millicelsius.map(|v| v as f64 / 1000.0),
+        }
+    }
+}
+
+/// Rows of /proc/net/softnet_stat keyed by CPU id. Rows of offline CPUs are
+/// missing, so the row index is only used if the kernel does not report it.
+fn softnet_by_cpu(softnet: &[procfs::SoftnetStat]) -> BTreeMap<u32, &procfs::SoftnetStat> {
+    softnet
+        .iter()
+        .enumerate()
+        .map(|(row, stat)| (stat.cpu.map_or(row as u32, |cpu| cpu as u32), stat))
+        .collect()
+}
+
+/// Rates of a single line of /proc/interrupts or /proc/softirqs
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    below_derive::Queriable
+)]
+pub struct SingleInterruptModel {
+    pub name: Option<String>,
+    pub total_per_sec: Option<f64>,
+    // CPU handling the most of this interrupt during the interval
+    pub top_cpu: Option<i32>,
+    pub top_cpu_per_sec: Option<f64>,
+    pub description: Option<String>,
+    // Keyed by CPU id
+    #[queriable(ignore)]
+    pub cpus_per_sec: BTreeMap<u32, f64>,
+}
+
+impl SingleInterruptModel {
+    fn new(
+        name: &str,
+        begin: &procfs::InterruptStat,
+        end: &procfs::InterruptStat,
+        duration: Duration,
+    ) -> SingleInterruptModel {
+        // CPUs going offline or online in between only have a rate if they
+        // were online at both ends
+        let cpus_per_sec: BTreeMap<u32, f64> = match (begin.cpus.as_ref(), end.cpus.as_ref()) {
+            (Some(begin), Some(end)) => end
+                .iter()
+                .filter_map(|(cpu, e)| {
+                    begin.get(cpu).map(|b| {
+                        (
+                            *cpu,
+                            count_per_sec!(Some(*b), Some(*e), duration).unwrap_or_default(),
+                        )
+                    })
+                })
+                .collect(),
+            _ => BTreeMap::new(),
+        };
+        let top = cpus_per_sec
+            .iter()
+            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+        SingleInterruptModel {
+            name: Some(name.to_owned()),
+            total_per_sec: if cpus_per_sec.is_empty() {
+                None
+            } else {
+                Some(cpus_per_sec.values().sum())
+            },
+            top_cpu: top.map(|(cpu, _)| *cpu as i32),
+            top_cpu_per_sec: top.map(|(_, rate)| *rate),
+            description: end.description.clone(),
+            cpus_per_sec,
+        }
+    }
+
+    fn new_map(
+        begin: Option<&procfs::InterruptMap>,
+        end: Option<&procfs::InterruptMap>,
+        duration: Option<Duration>,
+    ) -> BTreeMap<String, SingleInterruptModel> {
+        end.map(|end| {
+            end.iter()
+                .map(|(name, end_stat)| {
+                    let model = match (begin.and_then(|b| b.get(name)), duration) {
+                        (Some(begin_stat), Some(duration)) => {
+                            SingleInterruptModel::new(name, begin_stat, end_stat, duration)
+                        }
+                        _ => SingleInterruptModel {
+                            name: Some(name.clone()),
+                            description: end_stat.description.clone(),
+                            ..Default::default()
+                        },
+                    };
+                    (name.clone(), model)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+    }
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    below_derive::Queriable
+)]
+pub struct SingleSoftnetModel {
+    pub idx: i32,
+    pub processed_per_sec: Option<f64>,
+    pub dropped_per_sec: Option<f64>,
+    pub time_squeeze_per_sec: Option<f64>,
+}
+
+impl SingleSoftnetModel {
+    fn new(
+        idx: i32,
+        begin: &procfs::SoftnetStat,
+        end: &procfs::SoftnetStat,
+        duration: Duration,
+    ) -> SingleSoftnetModel {
+        SingleSoftnetModel {
+            idx,
+            processed_per_sec: count_per_sec!(begin.processed, end.processed, duration),
+            dropped_per_sec: count_per_sec!(begin.dropped, end.dropped, duration),
+            time_squeeze_per_sec: count_per_sec!(begin.time_squeeze, end.time_squeeze, duration),
         }
     }
 }
@@ -434,3 +922,62 @@ impl SingleDiskModel {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_aggr_freq() {
+        let cpu = |idx, core_throttle, package_throttle| SingleCpuModel {
+            idx,
+            core_throttle_per_sec: Some(core_throttle),
+            package_throttle_per_sec: Some(package_throttle),
+            ..Default::default()
+        };
+        // CPU 0 and 1 are siblings of a core, CPU 2 is another core of the
+        // package and CPU 3 has no topology.
+        let cpus = vec![
+            cpu(0, 1.0, 5.0),
+            cpu(1, 1.0, 5.0),
+            cpu(2, 2.0, 5.0),
+            cpu(3, 4.0, 8.0),
+        ];
+        let topology = |physical_package_id, core_id| procfs::CpuFreqStat {
+            physical_package_id: Some(physical_package_id),
+            core_id: Some(core_id),
+            ..Default::default()
+        };
+        let cpu_freq: procfs::CpuFreqMap = vec![
+            (0, topology(0, 0)),
+            (1, topology(0, 0)),
+            (2, topology(0, 1)),
+        ]
+        .into_iter()
+        .collect();
+
+        let mut total_cpu = SingleCpuModel::default();
+        total_cpu.aggr_freq(&cpus, Some(&cpu_freq));
+        assert_eq!(total_cpu.core_throttle_per_sec, Some(7.0));
+        assert_eq!(total_cpu.package_throttle_per_sec, Some(13.0));
+    }
+
+    #[test]
+    fn test_interrupt_offline_cpu() {
+        let stat = |cpus: Vec<(u32, u64)>| procfs::InterruptStat {
+            cpus: Some(cpus.into_iter().collect()),
+            description: None,
+        };
+        // CPU 1 goes offline and CPU 3 comes online in between
+        let begin = stat(vec![(0, 10), (1, 100), (2, 30)]);
+        let end = stat(vec![(0, 20), (2, 80), (3, 1000)]);
+        let model = SingleInterruptModel::new("NET_RX", &begin, &end, Duration::from_secs(1));
+        assert_eq!(
+            model.cpus_per_sec,
+            vec![(0, 10.0), (2, 50.0)].into_iter().collect()
+        );
+        assert_eq!(model.total_per_sec, Some(60.0));
+        assert_eq!(model.top_cpu, Some(2));
+        assert_eq!(model.top_cpu_per_sec, Some(50.0));
+    }
+}
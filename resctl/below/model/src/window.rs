@@ -0,0 +1,528 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Aggregate Models over a window of samples. Every rate or gauge field of
+//! the aggregated Model holds a statistic of that field over the samples, so
+//! a spike is not missed when looking at a long time range. Other fields,
+//! e.g. identifiers, limits and cumulative counters, take their latest value.
+use super::*;
+
+use anyhow::bail;
+
+/// Statistic of a numeric field over the samples in a window.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WindowStat {
+    Mean,
+    Max,
+    P95,
+}
+
+impl fmt::Display for WindowStat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WindowStat::Mean => "mean".fmt(f),
+            WindowStat::Max => "max".fmt(f),
+            WindowStat::P95 => "p95".fmt(f),
+        }
+    }
+}
+
+impl std::str::FromStr for WindowStat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "mean" => Ok(WindowStat::Mean),
+            "max" => Ok(WindowStat::Max),
+            "p95" => Ok(WindowStat::P95),
+            _ => Err(anyhow!("Unknown window statistic: {}", s)),
+        }
+    }
+}
+
+impl WindowStat {
+    /// Cycle through the statistics.
+    pub fn next(self) -> Self {
+        match self {
+            WindowStat::Mean => WindowStat::Max,
+            WindowStat::Max => WindowStat::P95,
+            WindowStat::P95 => WindowStat::Mean,
+        }
+    }
+
+    /// Compute the statistic of non-empty values.
+    fn compute(self, values: &mut [f64]) -> f64 {
+        match self {
+            WindowStat::Mean => values.iter().sum::<f64>() / values.len() as f64,
+            WindowStat::Max => values.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+            WindowStat::P95 => {
+                values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+                // Nearest-rank percentile
+                let rank = (values.len() as f64 * 0.95).ceil() as usize;
+                values[rank.max(1) - 1]
+            }
+        }
+    }
+}
+
+/// Numeric field types that can hold a statistic.
+trait WindowValue: Copy {
+    fn to_f64(self) -> f64;
+    fn from_f64(value: f64) -> Self;
+}
+
+impl WindowValue for f64 {
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+}
+
+impl WindowValue for u64 {
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    // Integer fields are rounded
+    fn from_f64(value: f64) -> Self {
+        value.round() as u64
+    }
+}
+
+/// Compute the statistic of a field over the samples where it has a value.
+fn aggregate_field<T: WindowValue>(
+    values: impl Iterator<Item = Option<T>>,
+    stat: WindowStat,
+) -> Option<T> {
+    let mut values: Vec<f64> = values.flatten().map(WindowValue::to_f64).collect();
+    if values.is_empty() {
+        None
+    } else {
+        Some(T::from_f64(stat.compute(&mut values)))
+    }
+}
+
+/// Models that can be aggregated over samples ordered from oldest to newest.
+trait Aggregate: Clone {
+    fn aggregate(samples: &[&Self], stat: WindowStat) -> Self;
+}
+
+/// Implement Aggregate by computing the statistic of the listed rate and
+/// gauge fields. The other fields keep their value of the newest sample.
+macro_rules! impl_aggregate {
+    ($model:ty, $($field:ident),+ $(,)?) => {
+        impl Aggregate for $model {
+            fn aggregate(samples: &[&Self], stat: WindowStat) -> Self {
+                let mut res = (*samples.last().expect("No sample to aggregate")).clone();
+                $(
+                    res.$field = aggregate_field(samples.iter().map(|s| s.$field), stat);
+                )+
+                res
+            }
+        }
+    };
+}
+
+impl_aggregate!(
+    CgroupCpuModel,
+    usage_pct,
+    user_pct,
+    system_pct,
+    nr_periods_per_sec,
+    nr_throttled_per_sec,
+    throttled_pct,
+);
+
+impl_aggregate!(
+    CgroupIoModel,
+    rbytes_per_sec,
+    wbytes_per_sec,
+    rios_per_sec,
+    wios_per_sec,
+    dbytes_per_sec,
+    dios_per_sec,
+    rwbytes_per_sec,
+);
+
+// memory_high and the cumulative events_* counters keep their latest value,
+// as do the raw other_stat values.
+impl_aggregate!(
+    CgroupMemoryModel,
+    total,
+    swap,
+    anon,
+    file,
+    kernel_stack,
+    slab,
+    sock,
+    shmem,
+    file_mapped,
+    file_dirty,
+    file_writeback,
+    anon_thp,
+    inactive_anon,
+    active_anon,
+    inactive_file,
+    active_file,
+    unevictable,
+    slab_reclaimable,
+    slab_unreclaimable,
+    pagetables,
+    percpu,
+    swapcached,
+    zswap,
+    zswapped,
+    file_thp,
+    shmem_thp,
+    pgfault,
+    pgmajfault,
+    workingset_refault,
+    workingset_activate,
+    workingset_nodereclaim,
+    pgrefill,
+    pgscan,
+    pgsteal,
+    pgactivate,
+    pgdeactivate,
+    pglazyfree,
+    pglazyfreed,
+    thp_fault_alloc,
+    thp_collapse_alloc,
+    workingset_refault_anon,
+    workingset_refault_file,
+    workingset_activate_anon,
+    workingset_activate_file,
+    workingset_restore_anon,
+    workingset_restore_file,
+    pgscan_kswapd,
+    pgscan_direct,
+    pgsteal_kswapd,
+    pgsteal_direct,
+    zswpin,
+    zswpout,
+    thp_swpout,
+    thp_swpout_fallback,
+    events_local_low,
+    events_local_high,
+    events_local_max,
+    events_local_oom,
+    events_local_oom_kill,
+    swap_events_high,
+    swap_events_max,
+    swap_events_fail,
+);
+
+impl_aggregate!(
+    CgroupPressureModel,
+    cpu_some_pct,
+    io_some_pct,
+    io_full_pct,
+    memory_some_pct,
+    memory_full_pct,
+);
+
+impl_aggregate!(CgroupRunqLatModel, p50_usec, p99_usec, max_usec);
+
+// max is a limit and events_max a cumulative counter
+impl_aggregate!(CgroupHugetlbModel, current);
+
+impl_aggregate!(CgroupRdmaModel, hca_handle, hca_object);
+
+impl_aggregate!(CgroupMiscModel, current);
+
+impl_aggregate!(
+    ProcessIoModel,
+    rbytes_per_sec,
+    wbytes_per_sec,
+    rwbytes_per_sec
+);
+
+impl_aggregate!(
+    ProcessCpuModel,
+    usage_pct,
+    user_pct,
+    system_pct,
+    num_threads
+);
+
+impl_aggregate!(
+    ProcessMemoryModel,
+    minorfaults_per_sec,
+    majorfaults_per_sec,
+    rss_bytes,
+    vm_size,
+    lock,
+    pin,
+    anon,
+    file,
+    shmem,
+    pte,
+    swap,
+    huge_tlb,
+);
+
+impl_aggregate!(
+    NetBytesModel,
+    tcp_send_bytes_per_sec,
+    tcp_recv_bytes_per_sec,
+    udp_send_bytes_per_sec,
+    udp_recv_bytes_per_sec,
+    send_bytes_per_sec,
+    recv_bytes_per_sec,
+);
+
+/// Aggregate an optional submodel over the samples where it exists.
+fn aggregate_opt<'a, T: 'a + Aggregate>(
+    samples: impl Iterator<Item = Option<&'a T>>,
+    stat: WindowStat,
+) -> Option<T> {
+    let samples: Vec<&T> = samples.flatten().collect();
+    if samples.is_empty() {
+        None
+    } else {
+        Some(T::aggregate(&samples, stat))
+    }
+}
+
+/// Aggregate an optional map of submodels. A key is in the result if it
+/// exists in any sample.
+fn aggregate_map<'a, K: 'a + Ord + Clone, T: 'a + Aggregate>(
+    samples: impl Iterator<Item = Option<&'a BTreeMap<K, T>>>,
+    stat: WindowStat,
+) -> Option<BTreeMap<K, T>> {
+    let mut by_key: BTreeMap<&K, Vec<&T>> = BTreeMap::new();
+    let mut exists = false;
+    for map in samples.flatten() {
+        exists = true;
+        for (key, value) in map {
+            by_key.entry(key).or_default().push(value);
+        }
+    }
+    if !exists {
+        return None;
+    }
+    Some(
+        by_key
+            .into_iter()
+            .map(|(key, values)| (key.clone(), T::aggregate(&values, stat)))
+            .collect(),
+    )
+}
+
+fn aggregate_cgroup(cgroups: &[&CgroupModel], stat: WindowStat) -> CgroupModel {
+    let latest = cgroups.last().expect("No sample to aggregate");
+    // Children are aligned by name instead of their position in the tree.
+    let mut children: BTreeMap<&str, Vec<&CgroupModel>> = BTreeMap::new();
+    for cgroup in cgroups {
+        for child in &cgroup.children {
+            children.entry(&child.name).or_default().push(child);
+        }
+    }
+    let children: BTreeSet<CgroupModel> = children
+        .values()
+        .map(|samples| aggregate_cgroup(samples, stat))
+        .collect();
+
+    CgroupModel {
+        name: latest.name.clone(),
+        full_path: latest.full_path.clone(),
+        inode_number: latest.inode_number,
+        depth: latest.depth,
+        cpu: aggregate_opt(cgroups.iter().map(|c| c.cpu.as_ref()), stat),
+        memory: aggregate_opt(cgroups.iter().map(|c| c.memory.as_ref()), stat),
+        io: aggregate_map(cgroups.iter().map(|c| c.io.as_ref()), stat),
+        io_total: aggregate_opt(cgroups.iter().map(|c| c.io_total.as_ref()), stat),
+        pressure: aggregate_opt(cgroups.iter().map(|c| c.pressure.as_ref()), stat),
+        net: aggregate_opt(cgroups.iter().map(|c| c.net.as_ref()), stat),
+        runq_lat: aggregate_opt(cgroups.iter().map(|c| c.runq_lat.as_ref()), stat),
+        hugetlb: aggregate_map(cgroups.iter().map(|c| c.hugetlb.as_ref()), stat),
+        hugetlb_total: aggregate_opt(cgroups.iter().map(|c| c.hugetlb_total.as_ref()), stat),
+        rdma: aggregate_map(cgroups.iter().map(|c| c.rdma.as_ref()), stat),
+        rdma_total: aggregate_opt(cgroups.iter().map(|c| c.rdma_total.as_ref()), stat),
+        misc: aggregate_map(cgroups.iter().map(|c| c.misc.as_ref()), stat),
+        // The union of the children of all samples
+        count: 1 + children.iter().map(|child| child.count).sum::<u32>(),
+        children,
+        recreate_flag: latest.recreate_flag,
+    }
+}
+
+/// Aggregate the cgroup trees of samples ordered from oldest to newest. A
+/// cgroup is in the result if it exists in any sample, and its statistics
+/// are computed over the samples where it exists.
+pub fn aggregate_cgroups(cgroups: &[&CgroupModel], stat: WindowStat) -> Result<CgroupModel> {
+    if cgroups.is_empty() {
+        bail!("No sample to aggregate");
+    }
+    Ok(aggregate_cgroup(cgroups, stat))
+}
+
+fn aggregate_process(processes: &[&SingleProcessModel], stat: WindowStat) -> SingleProcessModel {
+    SingleProcessModel {
+        io: aggregate_opt(processes.iter().map(|p| p.io.as_ref()), stat),
+        mem: aggregate_opt(processes.iter().map(|p| p.mem.as_ref()), stat),
+        cpu: aggregate_opt(processes.iter().map(|p| p.cpu.as_ref()), stat),
+        net: aggregate_opt(processes.iter().map(|p| p.net.as_ref()), stat),
+        ..(*processes.last().expect("No sample to aggregate")).clone()
+    }
+}
+
+/// Aggregate the processes of samples ordered from oldest to newest. A
+/// process is in the result if it exists in any sample, and its statistics
+/// are computed over the samples where it exists.
+pub fn aggregate_processes(processes: &[&ProcessModel], stat: WindowStat) -> Result<ProcessModel> {
+    if processes.is_empty() {
+        bail!("No sample to aggregate");
+    }
+    let mut by_pid: BTreeMap<i32, Vec<&SingleProcessModel>> = BTreeMap::new();
+    for process in processes {
+        for (pid, spm) in &process.processes {
+            by_pid.entry(*pid).or_default().push(spm);
+        }
+    }
+    Ok(ProcessModel {
+        processes: by_pid
+            .into_iter()
+            .map(|(pid, samples)| (pid, aggregate_process(&samples, stat)))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn process(pid: i32, usage_pct: f64, num_threads: u64) -> SingleProcessModel {
+        SingleProcessModel {
+            pid: Some(pid),
+            comm: Some(format!("proc{}", pid)),
+            cpu: Some(ProcessCpuModel {
+                usage_pct: Some(usage_pct),
+                num_threads: Some(num_threads),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_aggregate_processes() {
+        let samples: Vec<ProcessModel> = (1..=20)
+            .map(|i| {
+                let mut processes = BTreeMap::new();
+                processes.insert(1, process(1, i as f64, i));
+                // Process 2 only lives in the last sample
+                if i == 20 {
+                    processes.insert(2, process(2, 50.0, 1));
+                }
+                ProcessModel { processes }
+            })
+            .collect();
+        let samples: Vec<&ProcessModel> = samples.iter().collect();
+
+        let query = |model: &ProcessModel, pid: i32, field_id: &str| {
+            model.processes[&pid].query(&field_id.parse().unwrap())
+        };
+
+        let mean = aggregate_processes(&samples, WindowStat::Mean).unwrap();
+        assert_eq!(query(&mean, 1, "cpu.usage_pct"), Some(Field::F64(10.5)));
+        // Integer fields are rounded
+        assert_eq!(query(&mean, 1, "cpu.num_threads"), Some(Field::U64(11)));
+        assert_eq!(query(&mean, 1, "pid"), Some(Field::I32(1)));
+        assert_eq!(
+            query(&mean, 1, "comm"),
+            Some(Field::Str("proc1".to_owned()))
+        );
+        assert_eq!(query(&mean, 2, "cpu.usage_pct"), Some(Field::F64(50.0)));
+
+        let max = aggregate_processes(&samples, WindowStat::Max).unwrap();
+        assert_eq!(query(&max, 1, "cpu.usage_pct"), Some(Field::F64(20.0)));
+
+        let p95 = aggregate_processes(&samples, WindowStat::P95).unwrap();
+        assert_eq!(query(&p95, 1, "cpu.usage_pct"), Some(Field::F64(19.0)));
+    }
+
+    #[test]
+    fn test_aggregate_cgroups() {
+        let cgroup = |name: &str, total: u64, children: Vec<CgroupModel>| CgroupModel {
+            name: name.to_owned(),
+            full_path: format!("/{}", name),
+            memory: Some(CgroupMemoryModel {
+                total: Some(total),
+                ..Default::default()
+            }),
+            children: children.into_iter().collect(),
+            ..Default::default()
+        };
+        let old = cgroup("", 10, vec![cgroup("a", 4, vec![])]);
+        let new = cgroup("", 30, vec![cgroup("b", 8, vec![])]);
+
+        let max = aggregate_cgroups(&[&old, &new], WindowStat::Max).unwrap();
+        assert_eq!(max.memory.as_ref().unwrap().total, Some(30));
+        let children: Vec<(&str, Option<u64>)> = max
+            .children
+            .iter()
+            .map(|child| (child.name.as_str(), child.memory.as_ref().unwrap().total))
+            .collect();
+        assert_eq!(children, vec![("a", Some(4)), ("b", Some(8))]);
+
+        let mean = aggregate_cgroups(&[&old, &new], WindowStat::Mean).unwrap();
+        assert_eq!(mean.memory.as_ref().unwrap().total, Some(20));
+        assert_eq!(mean.count, 3);
+    }
+
+    #[test]
+    fn test_aggregate_keeps_latest_non_rates() {
+        let cgroup =
+            |inode_number: u64, memory_high: i64, events_oom_kill: u64, pgfault: u64| CgroupModel {
+                inode_number: Some(inode_number),
+                depth: 1,
+                memory: Some(CgroupMemoryModel {
+                    memory_high: Some(memory_high),
+                    events_oom_kill: Some(events_oom_kill),
+                    pgfault: Some(pgfault),
+                    ..Default::default()
+                }),
+                count: 1,
+                ..Default::default()
+            };
+        let old = cgroup(10, 1 << 30, 2, 100);
+        let new = cgroup(20, -1, 6, 300);
+
+        let mean = aggregate_cgroups(&[&old, &new], WindowStat::Mean).unwrap();
+        assert_eq!(mean.inode_number, Some(20));
+        assert_eq!(mean.depth, 1);
+        let memory = mean.memory.unwrap();
+        assert_eq!(memory.memory_high, Some(-1));
+        assert_eq!(memory.events_oom_kill, Some(6));
+        assert_eq!(memory.pgfault, Some(200));
+
+        let samples: Vec<ProcessModel> = [10, 30]
+            .iter()
+            .map(|uptime_secs| {
+                let mut spm = process(1, 10.0, 1);
+                spm.uptime_secs = Some(*uptime_secs);
+                let mut processes = BTreeMap::new();
+                processes.insert(1, spm);
+                ProcessModel { processes }
+            })
+            .collect();
+        let samples: Vec<&ProcessModel> = samples.iter().collect();
+        let mean = aggregate_processes(&samples, WindowStat::Mean).unwrap();
+        assert_eq!(mean.processes[&1].uptime_secs, Some(30));
+    }
+}
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
+
 use super::*;
 
 use RenderFormat::{MaxOrReadableSize, PageReadableSize, Precision, ReadableSize};
@@ -28,6 +30,13 @@ impl HasRenderConfig for model::CgroupModel {
             Io(field_id) => model::CgroupIoModel::get_render_config_builder(field_id),
             Mem(field_id) => model::CgroupMemoryModel::get_render_config_builder(field_id),
             Pressure(field_id) => model::CgroupPressureModel::get_render_config_builder(field_id),
+            Net(field_id) => model::NetBytesModel::get_render_config_builder(field_id),
+            RunqLat(field_id) => model::CgroupRunqLatModel::get_render_config_builder(field_id),
+            Hugetlb(field_id) => model::CgroupHugetlbModel::get_render_config_builder(field_id),
+            Rdma(field_id) => model::CgroupRdmaModel::get_render_config_builder(field_id),
+            Misc(field_id) => {
+                BTreeMap::<String, model::CgroupMiscModel>::get_render_config_builder(field_id)
+            }
         }
     }
 }
@@ -63,6 +72,33 @@ impl HasRenderConfig for model::CgroupIoModel {
     }
 }
 
+impl HasRenderConfig for model::NetBytesModel {
+    fn get_render_config_builder(field_id: &Self::FieldId) -> RenderConfigBuilder {
+        use model::NetBytesModelFieldId::*;
+        let rc = RenderConfigBuilder::new();
+        match field_id {
+            TcpSendBytesPerSec => rc.title("TCP Send").suffix("/s").format(ReadableSize),
+            TcpRecvBytesPerSec => rc.title("TCP Recv").suffix("/s").format(ReadableSize),
+            UdpSendBytesPerSec => rc.title("UDP Send").suffix("/s").format(ReadableSize),
+            UdpRecvBytesPerSec => rc.title("UDP Recv").suffix("/s").format(ReadableSize),
+            SendBytesPerSec => rc.title("Net Send").suffix("/s").format(ReadableSize),
+            RecvBytesPerSec => rc.title("Net Recv").suffix("/s").format(ReadableSize),
+        }
+    }
+}
+
+impl HasRenderConfig for model::CgroupRunqLatModel {
+    fn get_render_config_builder(field_id: &Self::FieldId) -> RenderConfigBuilder {
+        use model::CgroupRunqLatModelFieldId::*;
+        let rc = RenderConfigBuilder::new();
+        match field_id {
+            P50Usec => rc.title("RunqLat P50").suffix(" us"),
+            P99Usec => rc.title("RunqLat P99").suffix(" us"),
+            MaxUsec => rc.title("RunqLat Max").suffix(" us"),
+        }
+    }
+}
+
 impl HasRenderConfig for model::CgroupMemoryModel {
     fn get_render_config_builder(field_id: &Self::FieldId) -> RenderConfigBuilder {
         use model::CgroupMemoryModelFieldId::*;
@@ -76,6 +112,14 @@ impl HasRenderConfig for model::CgroupMemoryModel {
             EventsMax => rc.title("Events Max"),
             EventsOom => rc.title("Events OOM"),
             EventsOomKill => rc.title("Events Kill"),
+            EventsLocalLow => rc.title("Local Low/s"),
+            EventsLocalHigh => rc.title("Local High/s"),
+            EventsLocalMax => rc.title("Local Max/s"),
+            EventsLocalOom => rc.title("Local OOM/s"),
+            EventsLocalOomKill => rc.title("Local Kill/s"),
+            SwapEventsHigh => rc.title("Swap High/s"),
+            SwapEventsMax => rc.title("Swap Max/s"),
+            SwapEventsFail => rc.title("Swap Fail/s"),
             Anon => rc.title("Anon").format(ReadableSize),
             File => rc.title("File").format(ReadableSize),
             KernelStack => rc.title("Kernel Stack").format(ReadableSize),
@@ -93,6 +137,13 @@ impl HasRenderConfig for model::CgroupMemoryModel {
             Unevictable => rc.title("Unevictable").format(ReadableSize),
             SlabReclaimable => rc.title("Slab Reclaimable").format(ReadableSize),
             SlabUnreclaimable => rc.title("Slab Unreclaimable").format(ReadableSize),
+            Pagetables => rc.title("Page Tables").format(ReadableSize),
+            Percpu => rc.title("Percpu").format(ReadableSize),
+            Swapcached => rc.title("Swap Cached").format(ReadableSize),
+            Zswap => rc.title("Zswap").format(ReadableSize),
+            Zswapped => rc.title("Zswapped").format(ReadableSize),
+            FileThp => rc.title("File THP").format(ReadableSize),
+            ShmemThp => rc.title("Shmem THP").format(ReadableSize),
             Pgfault => rc.title("Pgfault/s"),
             Pgmajfault => rc.title("Pgmajfault/s"),
             WorkingsetRefault => rc.title("Workingset Refault/s"),
@@ -107,6 +158,20 @@ impl HasRenderConfig for model::CgroupMemoryModel {
             Pglazyfreed => rc.title("Pglazyfreed/s"),
             ThpFaultAlloc => rc.title("THP Fault Alloc/s"),
             ThpCollapseAlloc => rc.title("THP Collapse Alloc/s"),
+            WorkingsetRefaultAnon => rc.title("Refault Anon/s"),
+            WorkingsetRefaultFile => rc.title("Refault File/s"),
+            WorkingsetActivateAnon => rc.title("Activate Anon/s"),
+            WorkingsetActivateFile => rc.title("Activate File/s"),
+            WorkingsetRestoreAnon => rc.title("Restore Anon/s"),
+            WorkingsetRestoreFile => rc.title("Restore File/s"),
+            PgscanKswapd => rc.title("Pgscan Kswapd/s"),
+            PgscanDirect => rc.title("Pgscan Direct/s"),
+            PgstealKswapd => rc.title("Pgsteal Kswapd/s"),
+            PgstealDirect => rc.title("Pgsteal Direct/s"),
+            Zswpin => rc.title("Zswpin/s"),
+            Zswpout => rc.title("Zswpout/s"),
+            ThpSwpout => rc.title("THP Swpout/s"),
+            ThpSwpoutFallback => rc.title("THP Swpout Fallback/s"),
         }
     }
 }
@@ -131,6 +196,49 @@ impl HasRenderConfig for model::CgroupPressureModel {
     }
 }
 
+impl HasRenderConfig for model::CgroupMiscModel {
+    fn get_render_config_builder(field_id: &Self::FieldId) -> RenderConfigBuilder {
+        use model::CgroupMiscModelFieldId::*;
+        let rc = RenderConfigBuilder::new();
+        match field_id {
+            Current => rc.title("Current"),
+        }
+    }
+}
+
+impl HasRenderConfig for BTreeMap<String, model::CgroupMiscModel> {
+    fn get_render_config_builder(field_id: &Self::FieldId) -> RenderConfigBuilder {
+        let mut rc = model::CgroupMiscModel::get_render_config_builder(&field_id.subquery_id).get();
+        rc.title = rc
+            .title
+            .map(|title| format!("Misc {} {}", field_id.key, title));
+        rc.into()
+    }
+}
+
+impl HasRenderConfig for model::CgroupHugetlbModel {
+    fn get_render_config_builder(field_id: &Self::FieldId) -> RenderConfigBuilder {
+        use model::CgroupHugetlbModelFieldId::*;
+        let rc = RenderConfigBuilder::new();
+        match field_id {
+            Current => rc.title("Hugetlb").format(ReadableSize),
+            Max => rc.title("Hugetlb Max").format(MaxOrReadableSize),
+            EventsMax => rc.title("Hugetlb Max Events"),
+        }
+    }
+}
+
+impl HasRenderConfig for model::CgroupRdmaModel {
+    fn get_render_config_builder(field_id: &Self::FieldId) -> RenderConfigBuilder {
+        use model::CgroupRdmaModelFieldId::*;
+        let rc = RenderConfigBuilder::new();
+        match field_id {
+            HcaHandle => rc.title("RDMA HCA Handles"),
+            HcaObject => rc.title("RDMA HCA Objects"),
+        }
+    }
+}
+
 impl HasRenderConfig for model::NetworkModel {
     fn get_render_config_builder(field_id: &Self::FieldId) -> RenderConfigBuilder {
         use model::NetworkModelFieldId::*;
@@ -338,6 +446,7 @@ impl HasRenderConfig for model::SingleProcessModel {
             Io(field_id) => model::ProcessIoModel::get_render_config_builder(field_id),
             Mem(field_id) => model::ProcessMemoryModel::get_render_config_builder(field_id),
             Cpu(field_id) => model::ProcessCpuModel::get_render_config_builder(field_id),
+            Net(field_id) => model::NetBytesModel::get_render_config_builder(field_id),
             Cmdline => rc.title("Cmdline").width(50),
             ExePath => rc.title("Exe Path"),
         }
@@ -403,6 +512,12 @@ impl HasRenderConfig for model::SystemModel {
             Cpus(field_id) => Vec::<model::SingleCpuModel>::get_render_config_builder(field_id),
             Mem(field_id) => model::MemoryModel::get_render_config_builder(field_id),
             Vm(field_id) => model::VmModel::get_render_config_builder(field_id),
+            Softnet(field_id) => {
+                BTreeMap::<u32, model::SingleSoftnetModel>::get_render_config_builder(field_id)
+            }
+            ThermalZones(field_id) => {
+                BTreeMap::<u32, model::SingleThermalZoneModel>::get_render_config_builder(field_id)
+            }
         }
     }
 }
@@ -439,6 +554,9 @@ impl HasRenderConfig for model::SingleCpuModel {
             StolenPct => rc.title("Stolen").suffix("%").format(Precision(2)),
             GuestPct => rc.title("Guest").suffix("%").format(Precision(2)),
             GuestNicePct => rc.title("Guest Nice").suffix("%").format(Precision(2)),
+            ScalingCurFreqMhz => rc.title("Freq").suffix(" MHz"),
+            CoreThrottlePerSec => rc.title("Core Throttle").format(Precision(2)).suffix("/s"),
+            PackageThrottlePerSec => rc.title("Pkg Throttle").format(Precision(2)).suffix("/s"),
         }
     }
 }
@@ -515,6 +633,116 @@ impl HasRenderConfig for model::VmModel {
             PgscanKswapd => rc.title("Pgscan Kswapd").suffix(" pages/s"),
             PgscanDirect => rc.title("Pgscan Direct").suffix(" pages/s"),
             OomKill => rc.title("OOM Kills"),
+            PgfaultPerSec => rc.title("Page Faults").format(Precision(1)).suffix("/s"),
+            PgmajfaultPerSec => rc.title("Major Faults").format(Precision(1)).suffix("/s"),
+            PgactivatePerSec => rc
+                .title("Pgactivate")
+                .format(Precision(1))
+                .suffix(" pages/s"),
+            PgdeactivatePerSec => rc
+                .title("Pgdeactivate")
+                .format(Precision(1))
+                .suffix(" pages/s"),
+            PgrefillPerSec => rc.title("Pgrefill").format(Precision(1)).suffix(" pages/s"),
+            PgscanDirectThrottlePerSec => rc
+                .title("Direct Throttle")
+                .format(Precision(1))
+                .suffix("/s"),
+            KswapdInodestealPerSec => rc
+                .title("Kswapd Inodesteal")
+                .format(Precision(1))
+                .suffix("/s"),
+            KswapdLowWmarkHitQuicklyPerSec => rc
+                .title("Kswapd Low Wmark")
+                .format(Precision(1))
+                .suffix("/s"),
+            KswapdHighWmarkHitQuicklyPerSec => rc
+                .title("Kswapd High Wmark")
+                .format(Precision(1))
+                .suffix("/s"),
+            PageoutrunPerSec => rc.title("Kswapd Runs").format(Precision(1)).suffix("/s"),
+            AllocstallPerSec => rc.title("Allocstall").format(Precision(1)).suffix("/s"),
+            CompactMigrateScannedPerSec => rc
+                .title("Compact Migrate Scan")
+                .format(Precision(1))
+                .suffix(" pages/s"),
+            CompactFreeScannedPerSec => rc
+                .title("Compact Free Scan")
+                .format(Precision(1))
+                .suffix(" pages/s"),
+            CompactIsolatedPerSec => rc
+                .title("Compact Isolated")
+                .format(Precision(1))
+                .suffix(" pages/s"),
+            CompactStallPerSec => rc.title("Compact Stall").format(Precision(1)).suffix("/s"),
+            CompactFailPerSec => rc.title("Compact Fail").format(Precision(1)).suffix("/s"),
+            CompactSuccessPerSec => rc
+                .title("Compact Success")
+                .format(Precision(1))
+                .suffix("/s"),
+            CompactDaemonWakePerSec => rc.title("Kcompactd Wake").format(Precision(1)).suffix("/s"),
+            ThpFaultAllocPerSec => rc
+                .title("THP Fault Alloc")
+                .format(Precision(1))
+                .suffix("/s"),
+            ThpFaultFallbackPerSec => rc
+                .title("THP Fault Fallback")
+                .format(Precision(1))
+                .suffix("/s"),
+            ThpCollapseAllocPerSec => rc
+                .title("THP Collapse Alloc")
+                .format(Precision(1))
+                .suffix("/s"),
+            ThpCollapseAllocFailedPerSec => rc
+                .title("THP Collapse Failed")
+                .format(Precision(1))
+                .suffix("/s"),
+            ThpSplitPagePerSec => rc.title("THP Split").format(Precision(1)).suffix("/s"),
+            ThpSplitPageFailedPerSec => rc
+                .title("THP Split Failed")
+                .format(Precision(1))
+                .suffix("/s"),
+            ThpDeferredSplitPagePerSec => rc
+                .title("THP Deferred Split")
+                .format(Precision(1))
+                .suffix("/s"),
+            ThpSplitPmdPerSec => rc.title("THP Split PMD").format(Precision(1)).suffix("/s"),
+            ThpSwpoutPerSec => rc.title("THP Swap Out").format(Precision(1)).suffix("/s"),
+            ThpSwpoutFallbackPerSec => rc
+                .title("THP Swpout Fallback")
+                .format(Precision(1))
+                .suffix("/s"),
+            WorkingsetRefaultPerSec => rc
+                .title("WS Refault")
+                .format(Precision(1))
+                .suffix(" pages/s"),
+            WorkingsetRefaultAnonPerSec => rc
+                .title("WS Refault Anon")
+                .format(Precision(1))
+                .suffix(" pages/s"),
+            WorkingsetRefaultFilePerSec => rc
+                .title("WS Refault File")
+                .format(Precision(1))
+                .suffix(" pages/s"),
+            WorkingsetActivatePerSec => rc
+                .title("WS Activate")
+                .format(Precision(1))
+                .suffix(" pages/s"),
+            WorkingsetRestorePerSec => rc
+                .title("WS Restore")
+                .format(Precision(1))
+                .suffix(" pages/s"),
+            WorkingsetNodereclaimPerSec => {
+                rc.title("WS Nodereclaim").format(Precision(1)).suffix("/s")
+            }
+            KswapdReclaimEfficiencyPct => rc
+                .title("Kswapd Efficiency")
+                .format(Precision(2))
+                .suffix("%"),
+            DirectReclaimEfficiencyPct => rc
+                .title("Direct Efficiency")
+                .format(Precision(2))
+                .suffix("%"),
         }
     }
 }
@@ -546,3 +774,114 @@ impl HasRenderConfig for model::SingleDiskModel {
         }
     }
 }
+
+impl HasRenderConfig for model::SingleThermalZoneModel {
+    fn get_render_config_builder(field_id: &Self::FieldId) -> RenderConfigBuilder {
+        use model::SingleThermalZoneModelFieldId::*;
+        let rc = RenderConfigBuilder::new();
+        match field_id {
+            Idx => rc.title("Zone"),
+            ZoneType => rc.title("Type").width(20),
+            TempCelsius => rc.title("Temp").format(Precision(1)).suffix(" C"),
+        }
+    }
+}
+
+impl HasRenderConfig for BTreeMap<u32, model::SingleThermalZoneModel> {
+    fn get_render_config_builder(field_id: &Self::FieldId) -> RenderConfigBuilder {
+        let mut rc =
+            model::SingleThermalZoneModel::get_render_config_builder(&field_id.subquery_id).get();
+        rc.title = rc
+            .title
+            .map(|title| format!("Thermal Zone {} {}", field_id.key, title));
+        rc.into()
+    }
+}
+
+impl HasRenderConfig for model::SingleInterruptModel {
+    fn get_render_config_builder(field_id: &Self::FieldId) -> RenderConfigBuilder {
+        use model::SingleInterruptModelFieldId::*;
+        let rc = RenderConfigBuilder::new();
+        match field_id {
+            Name => rc.title("Name").width(15),
+            TotalPerSec => rc.title("Total").format(Precision(1)).suffix("/s"),
+            TopCpu => rc.title("Top CPU"),
+            TopCpuPerSec => rc.title("Top CPU Rate").format(Precision(1)).suffix("/s"),
+            Description => rc.title("Description").width(40),
+        }
+    }
+}
+
+impl HasRenderConfig for model::SingleSoftnetModel {
+    fn get_render_config_builder(field_id: &Self::FieldId) -> RenderConfigBuilder {
+        use model::SingleSoftnetModelFieldId::*;
+        let rc = RenderConfigBuilder::new();
+        match field_id {
+            Idx => rc.title("Idx"),
+            ProcessedPerSec => rc.title("Processed").format(Precision(1)).suffix("/s"),
+            DroppedPerSec => rc.title("Dropped").format(Precision(1)).suffix("/s"),
+            TimeSqueezePerSec => rc.title("Time Squeeze").format(Precision(1)).suffix("/s"),
+        }
+    }
+}
+
+impl HasRenderConfig for BTreeMap<u32, model::SingleSoftnetModel> {
+    fn get_render_config_builder(field_id: &Self::FieldId) -> RenderConfigBuilder {
+        let mut rc =
+            model::SingleSoftnetModel::get_render_config_builder(&field_id.subquery_id).get();
+        rc.title = rc
+            .title
+            .map(|title| format!("Softnet {} {}", field_id.key, title));
+        rc.into()
+    }
+}
+
+impl HasRenderConfig for model::OomKillModel {
+    fn get_render_config_builder(field_id: &Self::FieldId) -> RenderConfigBuilder {
+        use model::OomKillModelFieldId::*;
+        let rc = RenderConfigBuilder::new();
+        match field_id {
+            Pid => rc.title("Pid"),
+            Comm => rc.title("Comm").width(30),
+            Cgroup => rc.title("Cgroup").width(50).fold(FoldOption::Path),
+            RssBytes => rc.title("RSS").format(ReadableSize),
+            OomScoreAdj => rc.title("OOM Score Adj"),
+            Constraint => rc.title("Constraint").width(15),
+            OomCgroup => rc.title("OOM Cgroup").width(50).fold(FoldOption::Path),
+            TotalBytes => rc.title("Total").format(ReadableSize),
+            TriggerPid => rc.title("Trigger Pid"),
+            TriggerComm => rc.title("Trigger Comm").width(30),
+        }
+    }
+}
+
+impl HasRenderConfig for model::ProcEventModel {
+    fn get_render_config_builder(field_id: &Self::FieldId) -> RenderConfigBuilder {
+        use model::ProcEventModelFieldId::*;
+        let rc = RenderConfigBuilder::new();
+        match field_id {
+            Event => rc.title("Event").width(6),
+            TimeEpochMs => rc.title("Time Epoch").suffix(" ms"),
+            Pid => rc.title("Pid"),
+            Ppid => rc.title("Ppid"),
+            Comm => rc.title("Comm").width(30),
+            Cmdline => rc.title("Cmdline").width(50),
+            Cgroup => rc.title("Cgroup").width(50).fold(FoldOption::Path),
+            StartEpochMs => rc.title("Start Epoch").suffix(" ms"),
+            EndEpochMs => rc.title("End Epoch").suffix(" ms"),
+            ExitCode => rc.title("Exit Code"),
+        }
+    }
+}
+
+impl HasRenderConfig for model::EventModel {
+    fn get_render_config_builder(field_id: &Self::FieldId) -> RenderConfigBuilder {
+        use model::EventModelFieldId::*;
+        let rc = RenderConfigBuilder::new();
+        match field_id {
+            Kind => rc.title("Kind").width(16),
+            Subject => rc.title("Subject").width(50).fold(FoldOption::Path),
+            Detail => rc.title("Detail").width(50),
+        }
+    }
+}
@@ -231,6 +231,47 @@ impl RenderConfig {
     pub fn render(&self, field: Option<Field>, fixed_width: bool) -> String {
         self.render_indented(field, fixed_width, 0)
     }
+
+    /// Renders the difference from the base Field to the target Field with
+    /// format and suffix applied, e.g. "+1.5 MB". Non-numeric Fields are
+    /// rendered as "changed" if they differ, and empty otherwise.
+    pub fn render_delta(
+        &self,
+        base: Option<Field>,
+        target: Option<Field>,
+        fixed_width: bool,
+    ) -> String {
+        let is_max = |field: &Field| match (&self.format, field) {
+            (Some(RenderFormat::MaxOrReadableSize), Field::I64(v)) => *v == -1,
+            _ => false,
+        };
+        let res = match (base, target) {
+            (Some(base), Some(target)) if is_max(&base) || is_max(&target) => {
+                if base == target {
+                    "0".to_owned()
+                } else {
+                    "changed".to_owned()
+                }
+            }
+            (Some(base), Some(target)) => match model::field_delta(&base, &target) {
+                Some((_, delta)) if f64::from(delta.clone()) == 0.0 => "0".to_owned(),
+                Some((negative, delta)) => format!(
+                    "{}{}{}",
+                    if negative { "-" } else { "+" },
+                    self.format(delta),
+                    self.suffix.as_deref().unwrap_or("")
+                ),
+                None if base == target => "".to_owned(),
+                None => "changed".to_owned(),
+            },
+            _ => "?".to_owned(),
+        };
+        if fixed_width {
+            get_fixed_width(&res, self.get_width())
+        } else {
+            res
+        }
+    }
 }
 
 /// Provide default RenderConfig for each Field in a Model
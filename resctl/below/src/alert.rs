@@ -0,0 +1,149 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use slog::{error, warn};
+
+use crate::below_config::AlertConfig;
+
+struct Rule {
+    rule: model::AlertRule,
+    command: Option<String>,
+    pipe: Option<PathBuf>,
+}
+
+/// Evaluates the alerting rules of below.conf against each recorded sample.
+/// Every alert is logged and appended to the alert log as a JSON line, then
+/// passed to the command or written to the named pipe of its rule.
+pub struct Alerter {
+    rules: Vec<Rule>,
+    alert_log: File,
+}
+
+impl Alerter {
+    pub fn new(alert_log: &PathBuf, configs: &[AlertConfig]) -> Result<Self> {
+        let rules = configs
+            .iter()
+            .map(|config| {
+                let for_duration = match config.for_duration.as_ref() {
+                    Some(d) => humantime::parse_duration(d).with_context(|| {
+                        format!("Invalid duration of alert {}: {}", config.name, d)
+                    })?,
+                    None => Duration::default(),
+                };
+                let rule = model::AlertRule::new(
+                    &config.name,
+                    &config.entity,
+                    &config.condition,
+                    for_duration,
+                )
+                .with_context(|| format!("Invalid alert {}", config.name))?;
+                Ok(Rule {
+                    rule,
+                    command: config.command.clone(),
+                    pipe: config.pipe.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let alert_log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(alert_log)
+            .with_context(|| format!("Failed to open {}", alert_log.to_string_lossy()))?;
+        Ok(Self { rules, alert_log })
+    }
+
+    pub fn evaluate(&mut self, model: &model::Model, logger: &slog::Logger) {
+        for rule in &mut self.rules {
+            for alert in rule.rule.evaluate(model) {
+                warn!(
+                    logger,
+                    "Alert {} {} for {}: {}",
+                    alert.rule,
+                    alert.state,
+                    alert.subject,
+                    alert.condition
+                );
+                let record = match serde_json::to_string(&alert) {
+                    Ok(record) => record,
+                    Err(e) => {
+                        error!(logger, "Failed to serialize alert: {}", e);
+                        continue;
+                    }
+                };
+                if let Err(e) = writeln!(self.alert_log, "{}", record) {
+                    error!(logger, "Failed to write alert log: {}", e);
+                }
+                if let Some(command) = rule.command.as_ref() {
+                    if let Err(e) = run_command(command, &alert, &record) {
+                        error!(
+                            logger,
+                            "Failed to run command of alert {}: {:#}", alert.rule, e
+                        );
+                    }
+                }
+                if let Some(pipe) = rule.pipe.as_ref() {
+                    if let Err(e) = write_pipe(pipe, &record) {
+                        warn!(logger, "Failed to write alert {}: {:#}", alert.rule, e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Run the command in the background, with the alert in environment
+/// variables and its record on stdin.
+fn run_command(command: &str, alert: &model::Alert, record: &str) -> Result<()> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("BELOW_ALERT_RULE", &alert.rule)
+        .env("BELOW_ALERT_SUBJECT", &alert.subject)
+        .env("BELOW_ALERT_STATE", alert.state.to_string())
+        .env("BELOW_ALERT_TIMESTAMP", alert.timestamp.to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let record = format!("{}\n", record);
+    // Recording must not wait for the command
+    thread::spawn(move || {
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(record.as_bytes()).ok();
+        }
+        child.wait().ok();
+    });
+    Ok(())
+}
+
+/// Write the record to the named pipe. Fails instead of blocking when no one
+/// reads from the pipe.
+fn write_pipe(pipe: &PathBuf, record: &str) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(pipe)
+        .with_context(|| format!("Failed to open {}", pipe.to_string_lossy()))?;
+    writeln!(file, "{}", record)?;
+    Ok(())
+}
@@ -23,6 +23,7 @@ use toml;
 pub const BELOW_DEFAULT_CONF: &str = "/etc/below/below.conf";
 const BELOW_DEFAULT_LOG: &str = "/var/log/below";
 const BELOW_DEFAULT_STORE: &str = "/var/log/below/store";
+const BELOW_DEFAULT_ALERT_LOG: &str = "/var/log/below/alerts";
 
 #[derive(Serialize, Deserialize, Debug)]
 // If value is missing during deserialization, use the Default::default()
@@ -31,6 +32,38 @@ pub struct BelowConfig {
     pub log_dir: PathBuf,
     pub store_dir: PathBuf,
     pub cgroup_filter_out: String,
+    pub alert_log: PathBuf,
+    pub alert: Vec<AlertConfig>,
+    /// Account bytes sent and received over sockets per process and per
+    /// cgroup with bpf.
+    pub enable_netbytes: bool,
+    /// Record run-queue latency histograms per cgroup with bpf.
+    pub enable_runqlat: bool,
+    /// Report each process killed by the OOM killer with bpf.
+    pub enable_oomkill: bool,
+    /// Report processes forked, exec'd or exited, with their cmdline, with
+    /// bpf.
+    pub enable_procevents: bool,
+}
+
+/// Alerting rule evaluated by `below record` against each new sample.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AlertConfig {
+    pub name: String,
+    /// One of "system", "cgroup" or "process".
+    pub entity: String,
+    /// Filter expression over the fields of the entity, same as the
+    /// `--where` option of `below dump`.
+    pub condition: String,
+    /// How long the condition must hold before the alert fires, e.g. "30s".
+    #[serde(default, rename = "for")]
+    pub for_duration: Option<String>,
+    /// Shell command to run when the alert fires or resolves.
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Named pipe to write the alert record to.
+    #[serde(default)]
+    pub pipe: Option<PathBuf>,
 }
 
 impl Default for BelowConfig {
@@ -39,6 +72,12 @@ impl Default for BelowConfig {
             log_dir: BELOW_DEFAULT_LOG.into(),
             store_dir: BELOW_DEFAULT_STORE.into(),
             cgroup_filter_out: String::new(),
+            alert_log: BELOW_DEFAULT_ALERT_LOG.into(),
+            alert: Vec::new(),
+            enable_netbytes: false,
+            enable_runqlat: false,
+            enable_oomkill: false,
+            enable_procevents: false,
         }
     }
 }
@@ -23,6 +23,7 @@ struct metadata {
   uint32_t sid; // session ID
   uint32_t cpu; // CPU task is running on
   char comm[TASK_COMM_LEN]; // process name
+  uint64_t cgroup_id; // cgroup v2 id, the inode number of the cgroup
 };
 
 struct exitstats {
@@ -61,6 +62,7 @@ int tracepoint__sched__sched_process_exit(
   data.meta.sid = BPF_CORE_READ(task, sessionid);
   data.meta.cpu = BPF_CORE_READ(task, cpu);
   bpf_get_current_comm(&data.meta.comm, sizeof(data.meta.comm));
+  data.meta.cgroup_id = bpf_get_current_cgroup_id();
 
   /* read/calculate exitstats */
   data.stats.min_flt = BPF_CORE_READ(task, min_flt);
@@ -0,0 +1,31 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Generated by build.rs from the *.bpf.c files
+#[path = "exitstat.skel.rs"]
+mod exitstat_skel;
+#[path = "netbytes.skel.rs"]
+mod netbytes_skel;
+#[path = "oomkill.skel.rs"]
+mod oomkill_skel;
+#[path = "procevents.skel.rs"]
+mod procevents_skel;
+#[path = "runqlat.skel.rs"]
+mod runqlat_skel;
+
+pub use exitstat_skel::*;
+pub use netbytes_skel::*;
+pub use oomkill_skel::*;
+pub use procevents_skel::*;
+pub use runqlat_skel::*;
@@ -0,0 +1,138 @@
+#ifdef FBCODE_BUILD
+#include <bpf/vmlinux/vmlinux.h>
+#else
+#include "../open_source/vmlinux.h"
+#endif // FBCODE_BUILD
+
+#include <bpf/bpf_core_read.h>
+#include <bpf/bpf_helpers.h>
+#include <bpf/bpf_tracing.h>
+
+// Not in vmlinux.h as they are macros
+#define AF_INET 2
+#define AF_INET6 10
+
+// Enough for the processes and cgroups sending or receiving between two
+// drains by userspace
+#define MAX_ENTRIES 16384
+
+// Bytes sent and received over sockets since the entry was last drained
+struct net_bytes {
+  uint64_t tcp_send_bytes;
+  uint64_t tcp_recv_bytes;
+  uint64_t udp_send_bytes;
+  uint64_t udp_recv_bytes;
+};
+
+// Keyed by tgid
+struct {
+  __uint(type, BPF_MAP_TYPE_HASH);
+  __uint(max_entries, MAX_ENTRIES);
+  __type(key, u32);
+  __type(value, struct net_bytes);
+} tgid_bytes SEC(".maps");
+
+// Keyed by cgroup v2 id, the inode number of the cgroup
+struct {
+  __uint(type, BPF_MAP_TYPE_HASH);
+  __uint(max_entries, MAX_ENTRIES);
+  __type(key, u64);
+  __type(value, struct net_bytes);
+} cgroup_bytes SEC(".maps");
+
+enum net_direction {
+  TCP_SEND,
+  TCP_RECV,
+  UDP_SEND,
+  UDP_RECV,
+};
+
+static __always_inline struct net_bytes* lookup_or_init(void* map, void* key) {
+  struct net_bytes* bytes = bpf_map_lookup_elem(map, key);
+  if (bytes) {
+    return bytes;
+  }
+
+  struct net_bytes zero = {};
+  // Another CPU may have inserted the entry meanwhile, keep theirs
+  bpf_map_update_elem(map, key, &zero, BPF_NOEXIST);
+  return bpf_map_lookup_elem(map, key);
+}
+
+static __always_inline void add_bytes(
+    struct net_bytes* bytes,
+    enum net_direction dir,
+    u64 len) {
+  if (!bytes) {
+    return;
+  }
+
+  switch (dir) {
+    case TCP_SEND:
+      __sync_fetch_and_add(&bytes->tcp_send_bytes, len);
+      break;
+    case TCP_RECV:
+      __sync_fetch_and_add(&bytes->tcp_recv_bytes, len);
+      break;
+    case UDP_SEND:
+      __sync_fetch_and_add(&bytes->udp_send_bytes, len);
+      break;
+    case UDP_RECV:
+      __sync_fetch_and_add(&bytes->udp_recv_bytes, len);
+      break;
+  }
+}
+
+// Both hooks run in the context of the task sending or receiving, so the
+// bytes are accounted to the current process and cgroup. Only inet sockets
+// are accounted, stream sockets as TCP and datagram sockets as UDP.
+static __always_inline void account(struct socket* sock, bool send, int len) {
+  if (len <= 0) {
+    return;
+  }
+
+  u16 family = BPF_CORE_READ(sock, sk, __sk_common.skc_family);
+  if (family != AF_INET && family != AF_INET6) {
+    return;
+  }
+
+  enum net_direction dir;
+  switch (BPF_CORE_READ(sock, type)) {
+    case SOCK_STREAM:
+      dir = send ? TCP_SEND : TCP_RECV;
+      break;
+    case SOCK_DGRAM:
+      dir = send ? UDP_SEND : UDP_RECV;
+      break;
+    default:
+      return;
+  }
+
+  u32 tgid = bpf_get_current_pid_tgid() >> 32;
+  u64 cgroup_id = bpf_get_current_cgroup_id();
+
+  add_bytes(lookup_or_init(&tgid_bytes, &tgid), dir, len);
+  add_bytes(lookup_or_init(&cgroup_bytes, &cgroup_id), dir, len);
+}
+
+// Every send on a socket goes through sock_sendmsg, e.g. send(2), sendmsg(2)
+// and write(2). It returns the number of bytes sent, or a negative errno.
+SEC("fexit/sock_sendmsg")
+int BPF_PROG(sock_sendmsg_exit, struct socket* sock, struct msghdr* msg, int ret) {
+  account(sock, true, ret);
+  return 0;
+}
+
+// Likewise for receives
+SEC("fexit/sock_recvmsg")
+int BPF_PROG(
+    sock_recvmsg_exit,
+    struct socket* sock,
+    struct msghdr* msg,
+    int flags,
+    int ret) {
+  account(sock, false, ret);
+  return 0;
+}
+
+char _license[] SEC("license") = "GPL";
@@ -0,0 +1,98 @@
+#ifdef FBCODE_BUILD
+#include <bpf/vmlinux/vmlinux.h>
+#else
+#include "../open_source/vmlinux.h"
+#endif // FBCODE_BUILD
+
+#include <bpf/bpf_core_read.h>
+#include <bpf/bpf_helpers.h>
+#include <bpf/bpf_tracing.h>
+
+#define TASK_COMM_LEN 16
+
+struct {
+  __uint(type, BPF_MAP_TYPE_PERF_EVENT_ARRAY);
+  __uint(key_size, sizeof(u32));
+  __uint(value_size, sizeof(u32));
+} events SEC(".maps");
+
+struct oom_kill_event {
+  pid_t pid; // victim process ID
+  pid_t trigger_pid; // process ID of the task whose allocation failed
+  char comm[TASK_COMM_LEN]; // victim process name
+  char trigger_comm[TASK_COMM_LEN]; // process name of the triggering task
+  uint64_t cgroup_id; // cgroup v2 id of the victim
+  uint64_t memcg_id; // cgroup v2 id of the cgroup out of memory, 0 if global
+  uint64_t rss_pages; // victim RSS, pages
+  uint64_t total_pages; // memory the OOM killer chose from, pages
+  int32_t oom_score_adj; // victim oom_score_adj
+  uint32_t constraint; // enum oom_constraint
+};
+
+// Since kernel 6.2, rss_stat is an array of per-cpu counters
+struct mm_struct___new {
+  struct percpu_counter rss_stat[NR_MM_COUNTERS];
+} __attribute__((preserve_access_index));
+
+// Before kernel 6.2, rss_stat is a struct of atomic counters
+struct mm_rss_stat___old {
+  atomic_long_t count[NR_MM_COUNTERS];
+} __attribute__((preserve_access_index));
+
+struct mm_struct___old {
+  struct mm_rss_stat___old rss_stat;
+} __attribute__((preserve_access_index));
+
+// Per-cpu counters are read without summing up the counts of each cpu, so
+// the result may be off by a batch per cpu, even below zero.
+static __always_inline u64 get_rss_pages(const struct mm_struct* mm) {
+  s64 pages;
+  if (bpf_core_field_exists(((struct mm_struct___new*)0)->rss_stat)) {
+    const struct mm_struct___new* new_mm = (void*)mm;
+    pages = BPF_CORE_READ(new_mm, rss_stat[MM_FILEPAGES].count) +
+        BPF_CORE_READ(new_mm, rss_stat[MM_ANONPAGES].count) +
+        BPF_CORE_READ(new_mm, rss_stat[MM_SHMEMPAGES].count);
+  } else {
+    const struct mm_struct___old* old_mm = (void*)mm;
+    pages = BPF_CORE_READ(old_mm, rss_stat.count[MM_FILEPAGES].counter) +
+        BPF_CORE_READ(old_mm, rss_stat.count[MM_ANONPAGES].counter) +
+        BPF_CORE_READ(old_mm, rss_stat.count[MM_SHMEMPAGES].counter);
+  }
+  return pages > 0 ? pages : 0;
+}
+
+// oom_kill_process is called once the OOM killer has chosen its victim and
+// right before the victim is killed, in the context of the task whose
+// allocation failed. The victim is still alive with its memory.
+SEC("kprobe/oom_kill_process")
+int BPF_KPROBE(oom_kill_process, struct oom_control* oc, const char* message) {
+  struct task_struct* victim = BPF_CORE_READ(oc, chosen);
+  if (!victim) {
+    return 0;
+  }
+
+  struct oom_kill_event data = {};
+  data.pid = BPF_CORE_READ(victim, tgid);
+  data.trigger_pid = bpf_get_current_pid_tgid() >> 32;
+  BPF_CORE_READ_STR_INTO(&data.comm, victim, comm);
+  bpf_get_current_comm(&data.trigger_comm, sizeof(data.trigger_comm));
+  data.cgroup_id = BPF_CORE_READ(victim, cgroups, dfl_cgrp, kn, id);
+  struct mem_cgroup* memcg = BPF_CORE_READ(oc, memcg);
+  if (memcg) {
+    data.memcg_id = BPF_CORE_READ(memcg, css.cgroup, kn, id);
+  }
+
+  const struct mm_struct* mm = BPF_CORE_READ(victim, mm);
+  if (mm) {
+    data.rss_pages = get_rss_pages(mm);
+  }
+  data.total_pages = BPF_CORE_READ(oc, totalpages);
+  data.oom_score_adj = BPF_CORE_READ(victim, signal, oom_score_adj);
+  data.constraint = BPF_CORE_READ(oc, constraint);
+
+  bpf_perf_event_output(
+      ctx, &events, BPF_F_CURRENT_CPU, &data, sizeof(struct oom_kill_event));
+  return 0;
+}
+
+char _license[] SEC("license") = "GPL";
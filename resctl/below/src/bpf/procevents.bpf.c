@@ -0,0 +1,117 @@
+#ifdef FBCODE_BUILD
+#include <bpf/vmlinux/vmlinux.h>
+#else
+#include "../open_source/vmlinux.h"
+#endif // FBCODE_BUILD
+
+#include <bpf/bpf_core_read.h>
+#include <bpf/bpf_helpers.h>
+#include <bpf/bpf_tracing.h>
+
+#define TASK_COMM_LEN 16
+// Longer cmdlines are truncated. Must be a power of 2 to bound reads.
+#define MAX_CMDLINE_LEN 256
+
+// Must match PROC_EVENT_* of the driver
+#define PROC_EVENT_FORK 0
+#define PROC_EVENT_EXEC 1
+#define PROC_EVENT_EXIT 2
+
+struct {
+  __uint(type, BPF_MAP_TYPE_PERF_EVENT_ARRAY);
+  __uint(key_size, sizeof(u32));
+  __uint(value_size, sizeof(u32));
+} events SEC(".maps");
+
+struct proc_event {
+  uint32_t kind; // PROC_EVENT_*
+  pid_t pid; // process ID
+  pid_t ppid; // parent process ID
+  int32_t exit_code; // wait status of the process, exit events only
+  char comm[TASK_COMM_LEN]; // process name
+  uint64_t cgroup_id; // cgroup v2 id, the inode number of the cgroup
+  uint64_t start_ns; // when the process was forked, CLOCK_MONOTONIC
+  uint64_t ts_ns; // when the event happened, CLOCK_MONOTONIC
+  uint32_t cmdline_len; // bytes of cmdline, exec events only
+  char cmdline[MAX_CMDLINE_LEN]; // nul separated arguments
+};
+
+static __always_inline void fill_event(
+    struct proc_event* data,
+    struct task_struct* task,
+    uint32_t kind) {
+  data->kind = kind;
+  data->pid = BPF_CORE_READ(task, tgid);
+  data->ppid = BPF_CORE_READ(task, real_parent, tgid);
+  BPF_CORE_READ_STR_INTO(&data->comm, task, comm);
+  data->cgroup_id = BPF_CORE_READ(task, cgroups, dfl_cgrp, kn, id);
+  data->start_ns = BPF_CORE_READ(task, start_time);
+  data->ts_ns = bpf_ktime_get_ns();
+}
+
+// Fired for new threads too, which are skipped.
+SEC("tp_btf/sched_process_fork")
+int BPF_PROG(
+    sched_process_fork,
+    struct task_struct* parent,
+    struct task_struct* child) {
+  if (BPF_CORE_READ(child, pid) != BPF_CORE_READ(child, tgid)) {
+    return 0;
+  }
+
+  struct proc_event data = {};
+  fill_event(&data, child, PROC_EVENT_FORK);
+  bpf_perf_event_output(
+      ctx, &events, BPF_F_CURRENT_CPU, &data, sizeof(struct proc_event));
+  return 0;
+}
+
+// Fired in the context of the process once the new program is loaded, so the
+// arguments are already in its memory. They can't be read later on exit, as
+// the memory of the process is released by then.
+SEC("tp_btf/sched_process_exec")
+int BPF_PROG(
+    sched_process_exec,
+    struct task_struct* p,
+    pid_t old_pid,
+    struct linux_binprm* bprm) {
+  struct proc_event data = {};
+  fill_event(&data, p, PROC_EVENT_EXEC);
+
+  const struct mm_struct* mm = BPF_CORE_READ(p, mm);
+  if (mm) {
+    u64 arg_start = BPF_CORE_READ(mm, arg_start);
+    u64 arg_end = BPF_CORE_READ(mm, arg_end);
+    u32 len = arg_end - arg_start;
+    if (len > MAX_CMDLINE_LEN - 1) {
+      len = MAX_CMDLINE_LEN - 1;
+    }
+    // Mask for the verifier, which can't tell len is bounded otherwise
+    len &= MAX_CMDLINE_LEN - 1;
+    if (len && !bpf_probe_read_user(&data.cmdline, len, (void*)arg_start)) {
+      data.cmdline_len = len;
+    }
+  }
+
+  bpf_perf_event_output(
+      ctx, &events, BPF_F_CURRENT_CPU, &data, sizeof(struct proc_event));
+  return 0;
+}
+
+// Fired for each exiting thread. The process exits with its last thread, when
+// signal->live drops to 0 right before this hook.
+SEC("tp_btf/sched_process_exit")
+int BPF_PROG(sched_process_exit, struct task_struct* p) {
+  if (BPF_CORE_READ(p, signal, live.counter) != 0) {
+    return 0;
+  }
+
+  struct proc_event data = {};
+  fill_event(&data, p, PROC_EVENT_EXIT);
+  data.exit_code = BPF_CORE_READ(p, exit_code);
+  bpf_perf_event_output(
+      ctx, &events, BPF_F_CURRENT_CPU, &data, sizeof(struct proc_event));
+  return 0;
+}
+
+char _license[] SEC("license") = "GPL";
@@ -0,0 +1,162 @@
+#ifdef FBCODE_BUILD
+#include <bpf/vmlinux/vmlinux.h>
+#else
+#include "../open_source/vmlinux.h"
+#endif // FBCODE_BUILD
+
+#include <bpf/bpf_core_read.h>
+#include <bpf/bpf_helpers.h>
+#include <bpf/bpf_tracing.h>
+
+// Not in vmlinux.h as it is a macro
+#define TASK_RUNNING 0
+
+// Enough for the tasks waiting to run at any time
+#define MAX_TASKS 65536
+// Enough for the cgroups with tasks running between two drains by userspace
+#define MAX_CGROUPS 16384
+// Bucket i counts waits of [2^i, 2^(i+1)) usecs, the last one also counts
+// longer waits
+#define MAX_SLOTS 27
+
+// Run-queue latencies since the entry was last drained, in log2 buckets
+struct hist {
+  uint64_t slots[MAX_SLOTS];
+};
+
+// When each task was enqueued, keyed by pid (tid)
+struct {
+  __uint(type, BPF_MAP_TYPE_HASH);
+  __uint(max_entries, MAX_TASKS);
+  __type(key, u32);
+  __type(value, u64);
+} start SEC(".maps");
+
+// Keyed by cgroup v2 id, the inode number of the cgroup
+struct {
+  __uint(type, BPF_MAP_TYPE_HASH);
+  __uint(max_entries, MAX_CGROUPS);
+  __type(key, u64);
+  __type(value, struct hist);
+} cgroup_hists SEC(".maps");
+
+// task_struct::state was renamed to __state in 5.14
+struct task_struct___pre_5_14 {
+  long state;
+} __attribute__((preserve_access_index));
+
+struct task_struct___5_14 {
+  unsigned int __state;
+} __attribute__((preserve_access_index));
+
+static __always_inline long get_task_state(struct task_struct* task) {
+  if (bpf_core_field_exists(((struct task_struct___5_14*)task)->__state)) {
+    return BPF_CORE_READ((struct task_struct___5_14*)task, __state);
+  }
+  return BPF_CORE_READ((struct task_struct___pre_5_14*)task, state);
+}
+
+static __always_inline u64 log2_u32(u32 v) {
+  u32 shift, r;
+
+  r = (v > 0xFFFF) << 4;
+  v >>= r;
+  shift = (v > 0xFF) << 3;
+  v >>= shift;
+  r |= shift;
+  shift = (v > 0xF) << 2;
+  v >>= shift;
+  r |= shift;
+  shift = (v > 0x3) << 1;
+  v >>= shift;
+  r |= shift;
+  r |= (v >> 1);
+  return r;
+}
+
+static __always_inline u64 log2_u64(u64 v) {
+  u32 hi = v >> 32;
+  if (hi) {
+    return log2_u32(hi) + 32;
+  }
+  return log2_u32(v);
+}
+
+static __always_inline struct hist* lookup_or_init(u64 cgroup_id) {
+  struct hist* hist = bpf_map_lookup_elem(&cgroup_hists, &cgroup_id);
+  if (hist) {
+    return hist;
+  }
+
+  struct hist zero = {};
+  // Another CPU may have inserted the entry meanwhile, keep theirs
+  bpf_map_update_elem(&cgroup_hists, &cgroup_id, &zero, BPF_NOEXIST);
+  return bpf_map_lookup_elem(&cgroup_hists, &cgroup_id);
+}
+
+static __always_inline void trace_enqueue(struct task_struct* task) {
+  u32 pid = BPF_CORE_READ(task, pid);
+  // The idle tasks of all CPUs have pid 0
+  if (!pid) {
+    return;
+  }
+
+  u64 ts = bpf_ktime_get_ns();
+  bpf_map_update_elem(&start, &pid, &ts, BPF_ANY);
+}
+
+// A task is enqueued when woken up, or when created.
+SEC("tp_btf/sched_wakeup")
+int BPF_PROG(sched_wakeup, struct task_struct* p) {
+  trace_enqueue(p);
+  return 0;
+}
+
+SEC("tp_btf/sched_wakeup_new")
+int BPF_PROG(sched_wakeup_new, struct task_struct* p) {
+  trace_enqueue(p);
+  return 0;
+}
+
+// The previous task is enqueued again if it was preempted. The next task stops
+// waiting, so its wait is accounted to its cgroup. The hook runs in the
+// context of the previous task, so the cgroup id is read from the next task
+// rather than with bpf_get_current_cgroup_id().
+SEC("tp_btf/sched_switch")
+int BPF_PROG(
+    sched_switch,
+    bool preempt,
+    struct task_struct* prev,
+    struct task_struct* next) {
+  if (get_task_state(prev) == TASK_RUNNING) {
+    trace_enqueue(prev);
+  }
+
+  u32 pid = BPF_CORE_READ(next, pid);
+  u64* tsp = bpf_map_lookup_elem(&start, &pid);
+  if (!tsp) {
+    // Enqueued before the program was attached
+    return 0;
+  }
+
+  s64 delta_ns = bpf_ktime_get_ns() - *tsp;
+  bpf_map_delete_elem(&start, &pid);
+  if (delta_ns < 0) {
+    return 0;
+  }
+
+  u64 cgroup_id = BPF_CORE_READ(next, cgroups, dfl_cgrp, kn, id);
+  struct hist* hist = lookup_or_init(cgroup_id);
+  if (!hist) {
+    return 0;
+  }
+
+  u64 slot = log2_u64(delta_ns / 1000);
+  if (slot >= MAX_SLOTS) {
+    slot = MAX_SLOTS - 1;
+  }
+  __sync_fetch_and_add(&hist->slots[slot], 1);
+  return 0;
+}
+
+char _license[] SEC("license") = "GPL";
@@ -0,0 +1,72 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use core::time::Duration;
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use libbpf_rs::{Map, MapFlags};
+use plain::Plain;
+
+/// Exposed by kernels built with CONFIG_DEBUG_INFO_BTF
+const KERNEL_BTF_PATH: &str = "/sys/kernel/btf/vmlinux";
+
+/// How often bpf maps are drained. Bounds how many keys can be added in
+/// between before a map is full.
+pub const DRAIN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// bpf programs are relocated against the BTF of the running kernel (CO-RE),
+/// and fentry/fexit and tp_btf programs are attached by its BTF ids.
+pub fn check_kernel_btf() -> Result<()> {
+    if !Path::new(KERNEL_BTF_PATH).exists() {
+        bail!(
+            "kernel has no BTF ({} not found, needs CONFIG_DEBUG_INFO_BTF)",
+            KERNEL_BTF_PATH
+        );
+    }
+    Ok(())
+}
+
+/// Opens, loads and attaches the skeleton built by `$builder`, named `$name`
+/// in errors.
+macro_rules! load_skel {
+    ($builder:ty, $debug:expr, $name:expr) => {{
+        let mut skel_builder = <$builder>::default();
+        skel_builder.obj_builder.debug($debug);
+        let mut skel = skel_builder
+            .open()
+            .and_then(|skel| skel.load())
+            .with_context(|| format!("Failed to load {} bpf program", $name))?;
+        skel.attach()
+            .with_context(|| format!("Failed to attach {} bpf program", $name))?;
+        skel
+    }};
+}
+
+/// Removes all entries of map, returning their keys and values. Values added
+/// to an entry between its lookup and its deletion are lost, which is rare
+/// enough to not be worth a per-cpu map.
+pub fn drain_map<T: Default + Plain>(map: &Map) -> Result<Vec<(Vec<u8>, T)>> {
+    let keys: Vec<Vec<u8>> = map.keys().collect();
+    let mut entries = Vec::with_capacity(keys.len());
+    for key in keys {
+        if let Some(bytes) = map.lookup(&key, MapFlags::ANY)? {
+            let mut value = T::default();
+            plain::copy_from_bytes(&mut value, &bytes).expect("Data buffer was too short");
+            map.delete(&key)?;
+            entries.push((key, value));
+        }
+    }
+    Ok(entries)
+}
@@ -0,0 +1,206 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::VecDeque;
+use std::time::{Duration, SystemTime};
+
+use anyhow::Result;
+use slog::info;
+
+use common::util::get_unix_timestamp;
+use model::{Model, Sample};
+use store::{DataFrame, StoreWriter};
+
+/// Interval of collection when high resolution capture is enabled.
+pub const HIGH_RES_INTERVAL: Duration = Duration::from_secs(1);
+/// Full memory pressure of any cgroup that triggers a capture.
+const MEMORY_FULL_PRESSURE_PCT: f64 = 10.0;
+/// Total CPU usage that triggers a capture.
+const CPU_SATURATION_PCT: f64 = 95.0;
+
+/// Records at high resolution around incidents. Samples are collected every
+/// HIGH_RES_INTERVAL and kept in a ring. Once they leave the ring, they are
+/// stored at the regular record interval and the others are dropped. When
+/// memory pressure, an OOM kill or CPU saturation is detected, the ring is
+/// stored and so is every sample during the capture window.
+pub struct HighResCapture {
+    interval: Duration,
+    ring_len: Duration,
+    window: Duration,
+    /// Samples not stored yet, from oldest to newest. The latest sample is
+    /// not in the ring.
+    ring: VecDeque<(SystemTime, Sample)>,
+    /// The latest sample and whether it is stored.
+    latest: Option<(SystemTime, Sample, bool)>,
+    last_stored: Option<SystemTime>,
+    capture_until: Option<SystemTime>,
+    oom_kill: Option<u64>,
+}
+
+impl HighResCapture {
+    pub fn new(interval: Duration, ring_len: Duration, window: Duration) -> Self {
+        Self {
+            interval,
+            ring_len,
+            window,
+            ring: VecDeque::new(),
+            latest: None,
+            last_stored: None,
+            capture_until: None,
+            oom_kill: None,
+        }
+    }
+
+    fn is_capturing(&self, timestamp: SystemTime) -> bool {
+        self.capture_until.map_or(false, |until| timestamp <= until)
+    }
+
+    /// Describe why the Model triggers a capture, if it does.
+    fn get_trigger(&mut self, model: &Model) -> Option<String> {
+        let oom_kill = model.system.vm.oom_kill;
+        if let (Some(prev_count), Some(count)) =
+            (std::mem::replace(&mut self.oom_kill, oom_kill), oom_kill)
+        {
+            if count > prev_count {
+                return Some(format!("{} processes OOM killed", count - prev_count));
+            }
+        }
+        if let Some(pct) = model
+            .system
+            .total_cpu
+            .usage_pct
+            .filter(|pct| *pct >= CPU_SATURATION_PCT)
+        {
+            return Some(format!("cpu usage {:.2}%", pct));
+        }
+        model::flatten_cgroups(&model.cgroup)
+            .into_iter()
+            .find_map(|(path, cgroup)| {
+                cgroup
+                    .pressure
+                    .as_ref()
+                    .and_then(|pressure| pressure.memory_full_pct)
+                    .filter(|pct| *pct >= MEMORY_FULL_PRESSURE_PCT)
+                    .map(|pct| {
+                        format!(
+                            "memory full pressure {:.2}% in {}",
+                            pct,
+                            if path.is_empty() { "<root>" } else { path }
+                        )
+                    })
+            })
+    }
+
+    fn store(
+        &mut self,
+        store: &mut StoreWriter,
+        timestamp: SystemTime,
+        mut sample: Sample,
+        high_res: bool,
+        logger: &slog::Logger,
+    ) -> Result<Sample> {
+        sample.high_res = high_res;
+        let frame = DataFrame { sample };
+        // The store is indexed by seconds and read assuming increasing
+        // timestamps.
+        if self.last_stored.map_or(true, |last| {
+            get_unix_timestamp(timestamp) > get_unix_timestamp(last)
+        }) {
+            store.put(timestamp, &frame, logger.clone())?;
+            self.last_stored = Some(timestamp);
+        }
+        Ok(frame.sample)
+    }
+
+    /// Handle a newly collected sample, storing it and the samples in the
+    /// ring as needed.
+    pub fn put(
+        &mut self,
+        store: &mut StoreWriter,
+        timestamp: SystemTime,
+        sample: Sample,
+        logger: &slog::Logger,
+    ) -> Result<()> {
+        let model = Model::new(
+            timestamp,
+            &sample,
+            self.latest.as_ref().map(|(latest_timestamp, latest, _)| {
+                (
+                    latest,
+                    timestamp
+                        .duration_since(*latest_timestamp)
+                        .unwrap_or_default(),
+                )
+            }),
+        );
+        if let Some(trigger) = self.get_trigger(&model) {
+            if !self.is_capturing(timestamp) {
+                info!(
+                    logger,
+                    "Recording at high resolution for {}: {}",
+                    humantime::format_duration(self.window),
+                    trigger
+                );
+            }
+            self.capture_until = Some(timestamp + self.window);
+        }
+
+        if let Some((latest_timestamp, latest, false)) = self.latest.take() {
+            self.ring.push_back((latest_timestamp, latest));
+        }
+
+        if self.is_capturing(timestamp) {
+            while let Some((ring_timestamp, ring_sample)) = self.ring.pop_front() {
+                self.store(store, ring_timestamp, ring_sample, true, logger)?;
+            }
+            let sample = self.store(store, timestamp, sample, true, logger)?;
+            self.latest = Some((timestamp, sample, true));
+            return Ok(());
+        }
+
+        self.latest = Some((timestamp, sample, false));
+        while let Some((ring_timestamp, _)) = self.ring.front() {
+            if timestamp
+                .duration_since(*ring_timestamp)
+                .unwrap_or_default()
+                <= self.ring_len
+            {
+                break;
+            }
+            let (ring_timestamp, ring_sample) = self.ring.pop_front().unwrap();
+            let regular = self.last_stored.map_or(true, |last| {
+                ring_timestamp.duration_since(last).unwrap_or_default() >= self.interval
+            });
+            if regular {
+                self.store(store, ring_timestamp, ring_sample, false, logger)?;
+            } else {
+                // Processes that exited after the dropped sample would be
+                // missing from the store. Keep them in the next sample, the
+                // same way exitstat does.
+                let next = match self.ring.front_mut() {
+                    Some((_, next)) => next,
+                    None => &mut self.latest.as_mut().unwrap().1,
+                };
+                for (pid, process) in ring_sample.processes {
+                    next.processes.entry(pid).or_insert(process);
+                }
+                // Likewise for OOM kills and process events, which are only
+                // in the sample after them.
+                next.oom_kills.splice(0..0, ring_sample.oom_kills);
+                next.proc_events.splice(0..0, ring_sample.proc_events);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use core::time::Duration;
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Unknown cgroup ids trigger a rescan of the cgroup tree at most this often.
+const CGROUP_RESCAN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Map the inode number of each cgroup under reader to its path.
+fn read_cgroup_paths(reader: &cgroupfs::CgroupReader, paths: &mut HashMap<u64, String>) {
+    if let Ok(inode_number) = reader.read_inode_number() {
+        paths.insert(inode_number, format!("/{}", reader.name().display()));
+    }
+    if let Ok(children) = reader.child_cgroup_iter() {
+        for child in children {
+            read_cgroup_paths(&child, paths);
+        }
+    }
+}
+
+/// Resolves cgroup ids of bpf events into paths. The cgroup of an event is
+/// often removed by the next sample, so ids are resolved as events come in.
+/// The cgroup tree is only walked again when an id is not known.
+#[derive(Default)]
+pub struct CgroupPaths {
+    paths: HashMap<u64, String>,
+    last_scan: Option<Instant>,
+}
+
+impl CgroupPaths {
+    fn get(&self, id: u64) -> Option<String> {
+        // Before kernel 5.5, the cgroup id has the inode generation in the
+        // upper 32 bits.
+        self.paths
+            .get(&id)
+            .or_else(|| self.paths.get(&(id & u32::MAX as u64)))
+            .cloned()
+    }
+
+    pub fn resolve(&mut self, id: u64) -> Option<String> {
+        if id == 0 {
+            return None;
+        }
+        if let Some(path) = self.get(id) {
+            return Some(path);
+        }
+        if self.last_scan.map_or(false, |last_scan| {
+            last_scan.elapsed() < CGROUP_RESCAN_INTERVAL
+        }) {
+            return None;
+        }
+        self.last_scan = Some(Instant::now());
+        self.paths.clear();
+        if let Ok(root) = cgroupfs::CgroupReader::root() {
+            read_cgroup_paths(&root, &mut self.paths);
+        }
+        self.get(id)
+    }
+}
@@ -8,6 +8,7 @@ use once_cell::sync::Lazy;
 use plain::Plain;
 use slog::warn;
 
+use crate::bpfmap::check_kernel_btf;
 use crate::ExitstatSkelBuilder;
 
 static PAGE_SIZE: Lazy<u64> = Lazy::new(page_size);
@@ -21,6 +22,7 @@ pub struct Metadata {
     pub sid: i32,
     pub cpu: i32,
     pub comm: [u8; 16],
+    pub cgroup_id: u64,
 }
 
 // See bpf prog for comments on what each field is
@@ -112,12 +114,13 @@ impl ExitstatDriver {
                 rbytes: Some(event.stats.io_read_bytes),
                 wbytes: Some(event.stats.io_write_bytes),
             },
-            // It seems to be somewhat tricky to get a cgroup name using bpf. It might be possible
-            // with the bpf_get_current_cgroup_id() helper, but that returns what looks like an
-            // inode number. I'm not sure if it's easy/possible to translate an inode # to a path.
+            // bpf can only get the cgroup id, which the collector resolves into a path with the
+            // inode numbers of cgroups. The id is 0 without cgroup v2.
             cgroup: "?".to_string(),
+            cgroup_id: Some(event.meta.cgroup_id).filter(|id| *id != 0),
             // We can't access cmdline b/c it requires taking mmap_sem and a
-            // bunch of memory management helpers.
+            // bunch of memory management helpers. The procevents prog
+            // captures it on exec instead.
             ..Default::default()
         };
 
@@ -132,10 +135,10 @@ impl ExitstatDriver {
 
     /// Loops forever unless an error is hit
     pub fn drive(&mut self) -> Result<()> {
-        let mut skel_builder = ExitstatSkelBuilder::default();
-        skel_builder.obj_builder.debug(self.debug);
-        let mut skel = skel_builder.open()?.load()?;
-        skel.attach()?;
+        // The bpf program is relocated against the running kernel with BTF
+        // (CO-RE). Without it loading fails with an obscure libbpf error.
+        check_kernel_btf()?;
+        let skel = load_skel!(ExitstatSkelBuilder, self.debug, "exitstat");
 
         // Set up perf ring buffer
         let buffer = self.get_buffer();
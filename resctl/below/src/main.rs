@@ -36,8 +36,17 @@ use users::{get_current_uid, get_user_by_uid};
 // Open source dep -- silence linter warning
 use slog_term as _;
 
+mod alert;
 mod below_config;
+#[macro_use]
+mod bpfmap;
+mod capture;
+mod cgrouppaths;
 mod exitstat;
+mod netbytes;
+mod oomkill;
+mod procevents;
+mod runqlat;
 #[cfg(test)]
 mod test;
 
@@ -114,6 +123,18 @@ enum Command {
         /// used. This is also CBOR in the case of open source build.
         #[structopt(long, hidden = true)]
         use_cbor: bool,
+        /// Record every second for this long after memory pressure, an OOM
+        /// kill or CPU saturation is detected
+        ///
+        /// Samples are then collected every second and kept in memory for
+        /// --high_res_ring_s, so the seconds before the incident are
+        /// recorded too. Other samples are stored at --interval_s once they
+        /// leave the ring, which delays them by --high_res_ring_s.
+        #[structopt(long)]
+        high_res_window_s: Option<u64>,
+        /// Seconds of samples kept in memory for high resolution capture
+        #[structopt(long, default_value = "30")]
+        high_res_ring_s: u64,
     },
     /// Replay historical data (interactive)
     Replay {
@@ -185,17 +206,13 @@ pub enum RedirectLogOnFail {
 }
 
 fn bump_memlock_rlimit() -> Result<()> {
-    // TODO(T78976996) remove the fbcode_gate once we can exit stats is
-    // enabled for opensource
-    if cfg!(fbcode_build) {
-        let rlimit = libc::rlimit {
-            rlim_cur: 128 << 20,
-            rlim_max: 128 << 20,
-        };
+    let rlimit = libc::rlimit {
+        rlim_cur: 128 << 20,
+        rlim_max: 128 << 20,
+    };
 
-        if unsafe { libc::setrlimit(libc::RLIMIT_MEMLOCK, &rlimit) } != 0 {
-            bail!("Failed to increase rlimit");
-        }
+    if unsafe { libc::setrlimit(libc::RLIMIT_MEMLOCK, &rlimit) } != 0 {
+        bail!("Failed to increase rlimit");
     }
 
     Ok(())
@@ -258,12 +275,90 @@ fn start_exitstat(
     (exit_buffer, Some(bpf_err_recv))
 }
 
-/// Returns true if other end disconnected, false otherwise
-fn check_for_exitstat_errors(logger: &slog::Logger, receiver: &Receiver<Error>) -> bool {
+// Netbytes runs a bpf program that accounts bytes sent and received over sockets, which procfs
+// doesn't tell per process or cgroup.
+fn start_netbytes(debug: bool) -> (Arc<Mutex<procfs::NetBytesMaps>>, Receiver<Error>) {
+    let mut netbytes_driver = netbytes::NetbytesDriver::new(debug);
+    let netbytes_buffer = netbytes_driver.get_buffer();
+    let (bpf_err_send, bpf_err_recv) = channel();
+    thread::spawn(move || {
+        match netbytes_driver.drive() {
+            Ok(_) => {}
+            Err(e) => bpf_err_send.send(e).unwrap(),
+        };
+    });
+
+    (netbytes_buffer, bpf_err_recv)
+}
+
+// Oomkill runs a bpf program that reports each process killed by the OOM killer, of which procfs
+// and cgroupfs only keep counts.
+fn start_oomkill(
+    logger: slog::Logger,
+    debug: bool,
+) -> (Arc<Mutex<Vec<procfs::OomKill>>>, Receiver<Error>) {
+    let mut oomkill_driver = oomkill::OomkillDriver::new(logger, debug);
+    let oomkill_buffer = oomkill_driver.get_buffer();
+    let (bpf_err_send, bpf_err_recv) = channel();
+    thread::spawn(move || {
+        match oomkill_driver.drive() {
+            Ok(_) => {}
+            Err(e) => bpf_err_send.send(e).unwrap(),
+        };
+    });
+
+    (oomkill_buffer, bpf_err_recv)
+}
+
+// Procevents runs a bpf program that reports processes forked, exec'd or exited with their cmdline,
+// including those that procfs never sees as they start and end in between two samples.
+fn start_procevents(
+    logger: slog::Logger,
+    debug: bool,
+) -> (Arc<Mutex<Vec<procfs::ProcEvent>>>, Receiver<Error>) {
+    let mut procevents_driver = procevents::ProceventsDriver::new(logger, debug);
+    let procevents_buffer = procevents_driver.get_buffer();
+    let (bpf_err_send, bpf_err_recv) = channel();
+    thread::spawn(move || {
+        match procevents_driver.drive() {
+            Ok(_) => {}
+            Err(e) => bpf_err_send.send(e).unwrap(),
+        };
+    });
+
+    (procevents_buffer, bpf_err_recv)
+}
+
+// Runqlat runs a bpf program that records how long tasks wait to run per cgroup, which cgroupfs
+// only tells as pressure.
+fn start_runqlat(debug: bool) -> (Arc<Mutex<procfs::RunqLatencyMap>>, Receiver<Error>) {
+    let mut runqlat_driver = runqlat::RunqlatDriver::new(debug);
+    let runqlat_buffer = runqlat_driver.get_buffer();
+    let (bpf_err_send, bpf_err_recv) = channel();
+    thread::spawn(move || {
+        match runqlat_driver.drive() {
+            Ok(_) => {}
+            Err(e) => bpf_err_send.send(e).unwrap(),
+        };
+    });
+
+    (runqlat_buffer, bpf_err_recv)
+}
+
+/// Returns true if other end disconnected, false otherwise. The error, if
+/// any, is also passed to on_error.
+fn check_for_bpf_errors(
+    logger: &slog::Logger,
+    receiver: &Receiver<Error>,
+    on_error: impl FnOnce(&Error),
+) -> bool {
     // Print an error but don't exit on bpf issues. Do this b/c we can't always
     // be sure what kind of kernel we're running on and if it's new enough.
     match receiver.try_recv() {
-        Ok(e) => error!(logger, "{:#}", e),
+        Ok(e) => {
+            error!(logger, "{:#}", e);
+            on_error(&e);
+        }
         Err(TryRecvError::Empty) => {}
         Err(TryRecvError::Disconnected) => {
             warn!(logger, "bpf error channel disconnected");
@@ -437,6 +532,8 @@ fn real_main(init: init::InitToken) {
             ref disable_exitstats,
             ref compress,
             ref use_cbor,
+            ref high_res_window_s,
+            ref high_res_ring_s,
         } => {
             logutil::set_current_log_target(logutil::TargetLog::Term);
             run(
@@ -459,6 +556,12 @@ fn real_main(init: init::InitToken) {
                         *disable_exitstats,
                         *compress,
                         *use_cbor,
+                        high_res_window_s.map(|window| {
+                            (
+                                Duration::from_secs(*high_res_ring_s),
+                                Duration::from_secs(window),
+                            )
+                        }),
                     )
                 },
             )
@@ -592,10 +695,16 @@ fn record(
     disable_exitstats: bool,
     compress: bool,
     use_cbor: bool,
+    high_res: Option<(Duration, Duration)>,
 ) -> Result<()> {
     debug!(logger, "Starting up!");
 
-    if !disable_exitstats {
+    if !disable_exitstats
+        || below_config.enable_netbytes
+        || below_config.enable_runqlat
+        || below_config.enable_oomkill
+        || below_config.enable_procevents
+    {
         bump_memlock_rlimit()?;
     }
 
@@ -615,6 +724,38 @@ fn record(
     };
     let mut bpf_err_warned = false;
 
+    let (mut net_bytes, netbytes_errs) = if below_config.enable_netbytes {
+        let (net_bytes, netbytes_errs) = start_netbytes(debug);
+        (Some(net_bytes), Some(netbytes_errs))
+    } else {
+        (None, None)
+    };
+    let mut netbytes_err_warned = false;
+
+    let (mut runq_latency, runqlat_errs) = if below_config.enable_runqlat {
+        let (runq_latency, runqlat_errs) = start_runqlat(debug);
+        (Some(runq_latency), Some(runqlat_errs))
+    } else {
+        (None, None)
+    };
+    let mut runqlat_err_warned = false;
+
+    let (mut oom_kills, oomkill_errs) = if below_config.enable_oomkill {
+        let (oom_kills, oomkill_errs) = start_oomkill(logger.clone(), debug);
+        (Some(oom_kills), Some(oomkill_errs))
+    } else {
+        (None, None)
+    };
+    let mut oomkill_err_warned = false;
+
+    let (mut proc_events, procevents_errs) = if below_config.enable_procevents {
+        let (proc_events, procevents_errs) = start_procevents(logger.clone(), debug);
+        (Some(proc_events), Some(procevents_errs))
+    } else {
+        (None, None)
+    };
+    let mut procevents_err_warned = false;
+
     // Handle cgroup filter from conf and generate Regex
     let cgroup_re = if !below_config.cgroup_filter_out.is_empty() {
         Some(
@@ -625,6 +766,25 @@ fn record(
         None
     };
 
+    let mut alerter = if below_config.alert.is_empty() {
+        None
+    } else {
+        Some(alert::Alerter::new(
+            &below_config.alert_log,
+            &below_config.alert,
+        )?)
+    };
+    // Alerts are evaluated against the Model of the last two samples
+    let mut last_sample: Option<(model::Sample, Instant)> = None;
+
+    let mut capture =
+        high_res.map(|(ring_len, window)| capture::HighResCapture::new(interval, ring_len, window));
+    let collect_interval = if capture.is_some() {
+        capture::HIGH_RES_INTERVAL
+    } else {
+        interval
+    };
+
     loop {
         if !disable_exitstats {
             // Anything that comes over the error channel is an error
@@ -635,24 +795,57 @@ fn record(
             };
 
             if !bpf_err_warned {
-                bpf_err_warned = check_for_exitstat_errors(
+                bpf_err_warned = check_for_bpf_errors(
                     &logger,
                     bpf_errs
                         .as_ref()
                         .expect("Failed to unwrap bpf_errs receiver"),
+                    |_| {},
                 );
             }
         }
 
+        if let (Some(netbytes_errs), false) = (netbytes_errs.as_ref(), netbytes_err_warned) {
+            // Stop collecting counters that are no longer updated
+            netbytes_err_warned =
+                check_for_bpf_errors(&logger, netbytes_errs, |_| net_bytes = None);
+        }
+
+        if let (Some(runqlat_errs), false) = (runqlat_errs.as_ref(), runqlat_err_warned) {
+            // Stop collecting histograms that are no longer updated
+            runqlat_err_warned =
+                check_for_bpf_errors(&logger, runqlat_errs, |_| runq_latency = None);
+        }
+
+        if let (Some(oomkill_errs), false) = (oomkill_errs.as_ref(), oomkill_err_warned) {
+            oomkill_err_warned = check_for_bpf_errors(&logger, oomkill_errs, |_| oom_kills = None);
+        }
+
+        if let (Some(procevents_errs), false) = (procevents_errs.as_ref(), procevents_err_warned) {
+            procevents_err_warned =
+                check_for_bpf_errors(&logger, procevents_errs, |_| proc_events = None);
+        }
+
         let collect_instant = Instant::now();
 
         let collected_sample = model::collect_sample(
             &exit_buffer,
+            net_bytes.as_ref(),
+            runq_latency.as_ref(),
             collect_io_stat,
             &logger,
             disable_disk_stat,
             &cgroup_re,
-        );
+        )
+        .map(|mut sample| {
+            if let Some(oom_kills) = oom_kills.as_ref() {
+                sample.oom_kills = std::mem::take(&mut *oom_kills.lock().unwrap());
+            }
+            if let Some(proc_events) = proc_events.as_ref() {
+                sample.proc_events = std::mem::take(&mut *proc_events.lock().unwrap());
+            }
+            sample
+        });
         let post_collect_sys_time = SystemTime::now();
         let post_collect_instant = Instant::now();
 
@@ -670,11 +863,26 @@ fn record(
 
         match collected_sample {
             Ok(s) => {
-                if let Err(e) = store.put(
-                    post_collect_sys_time,
-                    &DataFrame { sample: s },
-                    logger.clone(),
-                ) {
+                if let Some(alerter) = alerter.as_mut() {
+                    let model = model::Model::new(
+                        post_collect_sys_time,
+                        &s,
+                        last_sample
+                            .as_ref()
+                            .map(|(s, i)| (s, collect_instant.duration_since(*i))),
+                    );
+                    alerter.evaluate(&model, &logger);
+                    last_sample = Some((s.clone(), collect_instant));
+                }
+                let res = match capture.as_mut() {
+                    Some(capture) => capture.put(&mut store, post_collect_sys_time, s, &logger),
+                    None => store.put(
+                        post_collect_sys_time,
+                        &DataFrame { sample: s },
+                        logger.clone(),
+                    ),
+                };
+                if let Err(e) = res {
                     error!(logger, "{:#}", e);
                 }
             }
@@ -699,8 +907,8 @@ fn record(
         stats.report_store_size(below_config.store_dir.as_path());
 
         let collect_duration = Instant::now().duration_since(collect_instant);
-        if collect_duration < interval {
-            std::thread::sleep(interval - collect_duration);
+        if collect_duration < collect_interval {
+            std::thread::sleep(collect_interval - collect_duration);
         }
     }
 }
@@ -729,6 +937,38 @@ fn live_local(
     let mut bpf_err_warned = false;
 
     let mut collector = model::Collector::new(exit_buffer);
+    let netbytes_errs = if below_config.enable_netbytes {
+        let (net_bytes, netbytes_errs) = start_netbytes(debug);
+        collector.set_net_bytes(Some(net_bytes));
+        Some(netbytes_errs)
+    } else {
+        None
+    };
+    let mut netbytes_err_warned = false;
+    let runqlat_errs = if below_config.enable_runqlat {
+        let (runq_latency, runqlat_errs) = start_runqlat(debug);
+        collector.set_runq_latency(Some(runq_latency));
+        Some(runqlat_errs)
+    } else {
+        None
+    };
+    let mut runqlat_err_warned = false;
+    let oomkill_errs = if below_config.enable_oomkill {
+        let (oom_kills, oomkill_errs) = start_oomkill(logger.clone(), debug);
+        collector.set_oom_kills(Some(oom_kills));
+        Some(oomkill_errs)
+    } else {
+        None
+    };
+    let mut oomkill_err_warned = false;
+    let procevents_errs = if below_config.enable_procevents {
+        let (proc_events, procevents_errs) = start_procevents(logger.clone(), debug);
+        collector.set_proc_events(Some(proc_events));
+        Some(procevents_errs)
+    } else {
+        None
+    };
+    let mut procevents_err_warned = false;
     logutil::set_current_log_target(logutil::TargetLog::File);
     // Prepare advance obj for pause mode
     let mut adv = new_advance_local(logger.clone(), below_config.store_dir, SystemTime::now());
@@ -743,14 +983,49 @@ fn live_local(
     thread::spawn(move || {
         loop {
             if !bpf_err_warned {
-                bpf_err_warned = check_for_exitstat_errors(
+                bpf_err_warned = check_for_bpf_errors(
                     &logger,
                     bpf_errs
                         .as_ref()
                         .expect("Failed to unwrap bpf_errs receiver"),
+                    |e| {
+                        // Let the status bar tell why short-lived processes are missing
+                        let reason = format!("{:#}", e);
+                        sink.send(Box::new(move |s: &mut Cursive| {
+                            let view_state = s.user_data::<ViewState>().expect("user data not set");
+                            view_state.exitstat_error = Some(reason);
+                        }))
+                        .ok();
+                    },
                 );
             }
 
+            if let (Some(netbytes_errs), false) = (netbytes_errs.as_ref(), netbytes_err_warned) {
+                // Stop collecting counters that are no longer updated
+                netbytes_err_warned =
+                    check_for_bpf_errors(&logger, netbytes_errs, |_| collector.set_net_bytes(None));
+            }
+
+            if let (Some(runqlat_errs), false) = (runqlat_errs.as_ref(), runqlat_err_warned) {
+                // Stop collecting histograms that are no longer updated
+                runqlat_err_warned = check_for_bpf_errors(&logger, runqlat_errs, |_| {
+                    collector.set_runq_latency(None)
+                });
+            }
+
+            if let (Some(oomkill_errs), false) = (oomkill_errs.as_ref(), oomkill_err_warned) {
+                oomkill_err_warned =
+                    check_for_bpf_errors(&logger, oomkill_errs, |_| collector.set_oom_kills(None));
+            }
+
+            if let (Some(procevents_errs), false) =
+                (procevents_errs.as_ref(), procevents_err_warned)
+            {
+                procevents_err_warned = check_for_bpf_errors(&logger, procevents_errs, |_| {
+                    collector.set_proc_events(None)
+                });
+            }
+
             // Rely on timeout to guarantee interval between samples
             match errs.recv_timeout(interval) {
                 Ok(e) => {
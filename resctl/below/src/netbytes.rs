@@ -0,0 +1,91 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::convert::TryInto;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use plain::Plain;
+
+use crate::bpfmap::{check_kernel_btf, drain_map, DRAIN_INTERVAL};
+use crate::NetbytesSkelBuilder;
+
+// See bpf prog for comments on what each field is
+#[repr(C)]
+#[derive(Default)]
+pub struct NetBytes {
+    pub tcp_send_bytes: u64,
+    pub tcp_recv_bytes: u64,
+    pub udp_send_bytes: u64,
+    pub udp_recv_bytes: u64,
+}
+
+unsafe impl Plain for NetBytes {}
+
+fn accumulate(total: &mut procfs::NetBytes, bytes: &NetBytes) {
+    let add = |total: &mut Option<u64>, bytes: u64| *total = Some(total.unwrap_or(0) + bytes);
+    add(&mut total.tcp_send_bytes, bytes.tcp_send_bytes);
+    add(&mut total.tcp_recv_bytes, bytes.tcp_recv_bytes);
+    add(&mut total.udp_send_bytes, bytes.udp_send_bytes);
+    add(&mut total.udp_recv_bytes, bytes.udp_recv_bytes);
+}
+
+/// Accounts bytes sent and received over sockets per process and per cgroup
+/// with bpf. The bpf maps are drained periodically into cumulative counters
+/// shared with the collector, which also drops the counters of processes and
+/// cgroups that are gone.
+pub struct NetbytesDriver {
+    debug: bool,
+    buffer: Arc<Mutex<procfs::NetBytesMaps>>,
+}
+
+impl NetbytesDriver {
+    pub fn new(debug: bool) -> Self {
+        Self {
+            debug,
+            buffer: Arc::new(Mutex::new(procfs::NetBytesMaps::default())),
+        }
+    }
+
+    pub fn get_buffer(&self) -> Arc<Mutex<procfs::NetBytesMaps>> {
+        self.buffer.clone()
+    }
+
+    /// Loops forever unless an error is hit
+    pub fn drive(&mut self) -> Result<()> {
+        check_kernel_btf()?;
+        let mut skel = load_skel!(NetbytesSkelBuilder, self.debug, "netbytes");
+
+        loop {
+            std::thread::sleep(DRAIN_INTERVAL);
+
+            let tgids: Vec<(Vec<u8>, NetBytes)> =
+                drain_map(skel.maps().tgid_bytes()).context("Failed to drain tgid_bytes")?;
+            let cgroups: Vec<(Vec<u8>, NetBytes)> =
+                drain_map(skel.maps().cgroup_bytes()).context("Failed to drain cgroup_bytes")?;
+
+            // buffer.lock() only fails if a thread holding the lock panic'd, in
+            // which case we should probably panic too.
+            let mut buffer = self.buffer.lock().unwrap();
+            for (key, bytes) in tgids {
+                let tgid = u32::from_ne_bytes(key[..].try_into()?) as i32;
+                accumulate(buffer.tgids.entry(tgid).or_default(), &bytes);
+            }
+            for (key, bytes) in cgroups {
+                let cgroup_id = u64::from_ne_bytes(key[..].try_into()?);
+                accumulate(buffer.cgroups.entry(cgroup_id).or_default(), &bytes);
+            }
+        }
+    }
+}
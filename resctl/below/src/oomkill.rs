@@ -0,0 +1,141 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use core::time::Duration;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{bail, Context, Result};
+use libbpf_rs::PerfBufferBuilder;
+use plain::Plain;
+use slog::warn;
+
+use crate::bpfmap::check_kernel_btf;
+use crate::cgrouppaths::CgroupPaths;
+use crate::OomkillSkelBuilder;
+
+// See bpf prog for comments on what each field is
+#[repr(C)]
+#[derive(Default)]
+pub struct Event {
+    pub pid: i32,
+    pub trigger_pid: i32,
+    pub comm: [u8; 16],
+    pub trigger_comm: [u8; 16],
+    pub cgroup_id: u64,
+    pub memcg_id: u64,
+    pub rss_pages: u64,
+    pub total_pages: u64,
+    pub oom_score_adj: i32,
+    pub constraint: u32,
+}
+
+unsafe impl Plain for Event {}
+
+fn comm_to_string(comm: &[u8]) -> String {
+    let len = comm.iter().position(|b| *b == 0).unwrap_or(comm.len());
+    String::from_utf8_lossy(&comm[..len]).into_owned()
+}
+
+/// Names of enum oom_constraint
+fn constraint_to_string(constraint: u32) -> String {
+    match constraint {
+        0 => "none".to_owned(),
+        1 => "cpuset".to_owned(),
+        2 => "memory_policy".to_owned(),
+        3 => "memcg".to_owned(),
+        _ => format!("unknown ({})", constraint),
+    }
+}
+
+/// Reports the processes killed by the OOM killer with bpf. Events are
+/// buffered until the collector takes them into the next sample.
+pub struct OomkillDriver {
+    logger: slog::Logger,
+    debug: bool,
+    buffer: Arc<Mutex<Vec<procfs::OomKill>>>,
+}
+
+impl OomkillDriver {
+    pub fn new(logger: slog::Logger, debug: bool) -> Self {
+        Self {
+            logger,
+            debug,
+            buffer: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    pub fn get_buffer(&self) -> Arc<Mutex<Vec<procfs::OomKill>>> {
+        self.buffer.clone()
+    }
+
+    fn handle_event(
+        handle: &Arc<Mutex<Vec<procfs::OomKill>>>,
+        cgroup_paths: &mut CgroupPaths,
+        page_size: u64,
+        data: &[u8],
+    ) {
+        let mut event = Event::default();
+        plain::copy_from_bytes(&mut event, data).expect("Data buffer was too short");
+
+        // Resolve the cgroups now: a cgroup whose processes got OOM killed
+        // is often removed before the next sample is collected.
+        let oom_kill = procfs::OomKill {
+            pid: Some(event.pid),
+            comm: Some(comm_to_string(&event.comm)),
+            cgroup: cgroup_paths.resolve(event.cgroup_id),
+            rss_bytes: Some(event.rss_pages * page_size),
+            oom_score_adj: Some(event.oom_score_adj),
+            constraint: Some(constraint_to_string(event.constraint)),
+            oom_cgroup: cgroup_paths.resolve(event.memcg_id),
+            total_bytes: Some(event.total_pages * page_size),
+            trigger_pid: Some(event.trigger_pid),
+            trigger_comm: Some(comm_to_string(&event.trigger_comm)),
+        };
+
+        // handle.lock() only fails if a thread holding the lock panic'd, in which
+        // case we should probably panic too.
+        handle.lock().unwrap().push(oom_kill);
+    }
+
+    fn handle_lost_events(logger: &slog::Logger, cpu: i32, count: u64) {
+        warn!(logger, "Lost {} OOM kill events on CPU {}", count, cpu);
+    }
+
+    /// Loops forever unless an error is hit
+    pub fn drive(&mut self) -> Result<()> {
+        check_kernel_btf()?;
+
+        let page_size = match unsafe { libc::sysconf(libc::_SC_PAGESIZE) } {
+            -1 => bail!("Failed to query page size"),
+            x => x as u64,
+        };
+
+        let skel = load_skel!(OomkillSkelBuilder, self.debug, "oomkill");
+
+        let buffer = self.get_buffer();
+        let mut cgroup_paths = CgroupPaths::default();
+        let logger_clone = self.logger.clone();
+        let perf = PerfBufferBuilder::new(skel.maps().events())
+            .sample_cb(move |_, data: &[u8]| {
+                Self::handle_event(&buffer, &mut cgroup_paths, page_size, data)
+            })
+            .lost_cb(move |cpu, count| Self::handle_lost_events(&logger_clone, cpu, count))
+            .build()?;
+
+        loop {
+            perf.poll(Duration::from_millis(100))
+                .context("Error polling perf buffer")?;
+        }
+    }
+}
@@ -19,4 +19,7 @@ pub mod init;
 pub mod logging;
 pub mod statistics;
 
-pub use bpf::ExitstatSkelBuilder;
+pub use bpf::{
+    ExitstatSkelBuilder, NetbytesSkelBuilder, OomkillSkelBuilder, ProceventsSkelBuilder,
+    RunqlatSkelBuilder,
+};
@@ -0,0 +1,260 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use core::time::Duration;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use libbpf_rs::PerfBufferBuilder;
+use plain::Plain;
+use slog::warn;
+
+use crate::bpfmap::check_kernel_btf;
+use crate::cgrouppaths::CgroupPaths;
+use crate::ProceventsSkelBuilder;
+
+/// Must match MAX_CMDLINE_LEN of the bpf prog
+const MAX_CMDLINE_LEN: usize = 256;
+
+/// Must match PROC_EVENT_* of the bpf prog
+const PROC_EVENT_FORK: u32 = 0;
+const PROC_EVENT_EXEC: u32 = 1;
+const PROC_EVENT_EXIT: u32 = 2;
+
+/// Events beyond this many in between two samples are dropped, so that a
+/// fork bomb doesn't take all memory.
+const MAX_BUFFERED_EVENTS: usize = 50000;
+
+/// Remembered cmdlines of processes that exited without us seeing it are
+/// dropped once there are this many, then again each time the remaining ones
+/// double.
+const MAX_CMDLINES: usize = 65536;
+
+// See bpf prog for comments on what each field is
+#[repr(C)]
+pub struct Event {
+    pub kind: u32,
+    pub pid: i32,
+    pub ppid: i32,
+    pub exit_code: i32,
+    pub comm: [u8; 16],
+    pub cgroup_id: u64,
+    pub start_ns: u64,
+    pub ts_ns: u64,
+    pub cmdline_len: u32,
+    pub cmdline: [u8; MAX_CMDLINE_LEN],
+}
+
+impl Default for Event {
+    fn default() -> Self {
+        Self {
+            kind: 0,
+            pid: 0,
+            ppid: 0,
+            exit_code: 0,
+            comm: [0; 16],
+            cgroup_id: 0,
+            start_ns: 0,
+            ts_ns: 0,
+            cmdline_len: 0,
+            cmdline: [0; MAX_CMDLINE_LEN],
+        }
+    }
+}
+
+unsafe impl Plain for Event {}
+
+fn comm_to_string(comm: &[u8]) -> String {
+    let len = comm.iter().position(|b| *b == 0).unwrap_or(comm.len());
+    String::from_utf8_lossy(&comm[..len]).into_owned()
+}
+
+/// Split nul separated arguments the same way as /proc/pid/cmdline.
+fn parse_cmdline(cmdline: &[u8]) -> Vec<String> {
+    cmdline
+        .split(|c| *c == 0)
+        .filter(|s| !s.is_empty())
+        .map(|s| String::from_utf8_lossy(s).into_owned())
+        .collect()
+}
+
+fn read_cmdline(pid: i32) -> Option<Vec<String>> {
+    std::fs::read(format!("/proc/{}/cmdline", pid))
+        .ok()
+        .filter(|cmdline| !cmdline.is_empty())
+        .map(|cmdline| parse_cmdline(&cmdline))
+}
+
+/// Convert a wait status into the exit code reported by shells.
+fn wait_status_to_exit_code(status: i32) -> i32 {
+    match status & 0x7f {
+        0 => (status >> 8) & 0xff,
+        signal => 128 + signal,
+    }
+}
+
+/// Nanoseconds from the epoch to CLOCK_MONOTONIC 0, to convert timestamps of
+/// bpf. Computed once so that the same timestamp always converts to the same
+/// time, which tells the events of a process apart from those of a process
+/// reusing its pid.
+fn get_monotonic_epoch_offset_ns() -> Option<u64> {
+    let mut now = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    if unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut now) } != 0 {
+        return None;
+    }
+    let now_ns = now.tv_sec as u64 * 1_000_000_000 + now.tv_nsec as u64;
+    let epoch_ns = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_nanos() as u64;
+    epoch_ns.checked_sub(now_ns)
+}
+
+/// Reports processes forked, exec'd or exited with bpf, including those
+/// that start and end in between two samples. The cmdline is captured on
+/// exec and carried over to the exit of the process. Events are buffered
+/// until the collector takes them into the next sample.
+pub struct ProceventsDriver {
+    logger: slog::Logger,
+    debug: bool,
+    buffer: Arc<Mutex<Vec<procfs::ProcEvent>>>,
+    cmdlines: HashMap<i32, Vec<String>>,
+    max_cmdlines: usize,
+    cgroup_paths: CgroupPaths,
+    epoch_offset_ns: Option<u64>,
+}
+
+impl ProceventsDriver {
+    pub fn new(logger: slog::Logger, debug: bool) -> Self {
+        Self {
+            logger,
+            debug,
+            buffer: Arc::new(Mutex::new(Vec::new())),
+            cmdlines: HashMap::new(),
+            max_cmdlines: MAX_CMDLINES,
+            cgroup_paths: CgroupPaths::default(),
+            epoch_offset_ns: get_monotonic_epoch_offset_ns(),
+        }
+    }
+
+    pub fn get_buffer(&self) -> Arc<Mutex<Vec<procfs::ProcEvent>>> {
+        self.buffer.clone()
+    }
+
+    /// Cmdline of the process after the event
+    fn update_cmdline(&mut self, event: &Event) -> Option<Vec<String>> {
+        match event.kind {
+            // A forked process runs the program of its parent until it execs
+            PROC_EVENT_FORK => {
+                let cmdline = match self.cmdlines.get(&event.ppid) {
+                    Some(cmdline) => Some(cmdline.clone()),
+                    None => read_cmdline(event.ppid),
+                };
+                if let Some(cmdline) = cmdline.as_ref() {
+                    self.cmdlines.insert(event.pid, cmdline.clone());
+                }
+                cmdline
+            }
+            PROC_EVENT_EXEC => {
+                let len = (event.cmdline_len as usize).min(MAX_CMDLINE_LEN);
+                // Empty if the arguments could not be read
+                let cmdline = Some(parse_cmdline(&event.cmdline[..len]))
+                    .filter(|cmdline| !cmdline.is_empty());
+                match cmdline.as_ref() {
+                    Some(cmdline) => self.cmdlines.insert(event.pid, cmdline.clone()),
+                    None => self.cmdlines.remove(&event.pid),
+                };
+                cmdline
+            }
+            _ => self.cmdlines.remove(&event.pid),
+        }
+    }
+
+    fn handle_event(&mut self, data: &[u8]) {
+        let mut event = Event::default();
+        plain::copy_from_bytes(&mut event, data).expect("Data buffer was too short");
+
+        let cmdline_vec = self.update_cmdline(&event);
+        if self.cmdlines.len() > self.max_cmdlines {
+            self.cmdlines
+                .retain(|pid, _| Path::new(&format!("/proc/{}", pid)).exists());
+            self.max_cmdlines = std::cmp::max(MAX_CMDLINES, self.cmdlines.len() * 2);
+        }
+
+        let epoch_offset_ns = self.epoch_offset_ns;
+        let to_epoch_ms = |ns: u64| epoch_offset_ns.map(|offset| (offset + ns) / 1_000_000);
+        let time_epoch_ms = to_epoch_ms(event.ts_ns);
+        let exited = event.kind == PROC_EVENT_EXIT;
+        let proc_event = procfs::ProcEvent {
+            event: Some(
+                match event.kind {
+                    PROC_EVENT_FORK => "fork",
+                    PROC_EVENT_EXEC => "exec",
+                    _ => "exit",
+                }
+                .to_owned(),
+            ),
+            time_epoch_ms,
+            pid: Some(event.pid),
+            ppid: Some(event.ppid),
+            comm: Some(comm_to_string(&event.comm)),
+            cmdline_vec,
+            cgroup: self.cgroup_paths.resolve(event.cgroup_id),
+            start_epoch_ms: to_epoch_ms(event.start_ns),
+            end_epoch_ms: time_epoch_ms.filter(|_| exited),
+            exit_code: Some(wait_status_to_exit_code(event.exit_code)).filter(|_| exited),
+        };
+
+        // buffer.lock() only fails if a thread holding the lock panic'd, in which
+        // case we should probably panic too.
+        let mut buffer = self.buffer.lock().unwrap();
+        if buffer.len() < MAX_BUFFERED_EVENTS {
+            buffer.push(proc_event);
+            if buffer.len() == MAX_BUFFERED_EVENTS {
+                warn!(
+                    self.logger,
+                    "{} process events since the last sample, dropping more until the next sample",
+                    MAX_BUFFERED_EVENTS
+                );
+            }
+        }
+    }
+
+    fn handle_lost_events(logger: &slog::Logger, cpu: i32, count: u64) {
+        warn!(logger, "Lost {} process events on CPU {}", count, cpu);
+    }
+
+    /// Loops forever unless an error is hit
+    pub fn drive(&mut self) -> Result<()> {
+        check_kernel_btf()?;
+        let skel = load_skel!(ProceventsSkelBuilder, self.debug, "procevents");
+
+        let logger_clone = self.logger.clone();
+        let perf = PerfBufferBuilder::new(skel.maps().events())
+            .sample_cb(|_, data: &[u8]| self.handle_event(data))
+            .lost_cb(move |cpu, count| Self::handle_lost_events(&logger_clone, cpu, count))
+            .build()?;
+
+        loop {
+            perf.poll(Duration::from_millis(100))
+                .context("Error polling perf buffer")?;
+        }
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::convert::TryInto;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use plain::Plain;
+
+use crate::bpfmap::{check_kernel_btf, drain_map, DRAIN_INTERVAL};
+use crate::RunqlatSkelBuilder;
+
+/// Must match MAX_SLOTS of the bpf prog
+const MAX_SLOTS: usize = 27;
+
+// See bpf prog for comments on what each field is
+#[repr(C)]
+pub struct Hist {
+    pub slots: [u64; MAX_SLOTS],
+}
+
+impl Default for Hist {
+    fn default() -> Self {
+        Self {
+            slots: [0; MAX_SLOTS],
+        }
+    }
+}
+
+unsafe impl Plain for Hist {}
+
+fn accumulate(total: &mut procfs::RunqLatency, hist: &Hist) {
+    // Keep the stored form compact by omitting trailing empty buckets
+    let len = hist
+        .slots
+        .iter()
+        .rposition(|count| *count > 0)
+        .map_or(0, |last| last + 1);
+    if total.buckets.len() < len {
+        total.buckets.resize(len, 0);
+    }
+    for (total, count) in total.buckets.iter_mut().zip(&hist.slots) {
+        *total += count;
+    }
+}
+
+/// Records run-queue latency, how long tasks wait to run once woken up, per
+/// cgroup with bpf. The bpf histograms are drained periodically into
+/// cumulative histograms shared with the collector, which also folds the
+/// histograms of cgroups that are gone into their parent.
+pub struct RunqlatDriver {
+    debug: bool,
+    buffer: Arc<Mutex<procfs::RunqLatencyMap>>,
+}
+
+impl RunqlatDriver {
+    pub fn new(debug: bool) -> Self {
+        Self {
+            debug,
+            buffer: Arc::new(Mutex::new(procfs::RunqLatencyMap::default())),
+        }
+    }
+
+    pub fn get_buffer(&self) -> Arc<Mutex<procfs::RunqLatencyMap>> {
+        self.buffer.clone()
+    }
+
+    /// Loops forever unless an error is hit
+    pub fn drive(&mut self) -> Result<()> {
+        check_kernel_btf()?;
+        let mut skel = load_skel!(RunqlatSkelBuilder, self.debug, "runqlat");
+
+        loop {
+            std::thread::sleep(DRAIN_INTERVAL);
+
+            let hists: Vec<(Vec<u8>, Hist)> =
+                drain_map(skel.maps().cgroup_hists()).context("Failed to drain cgroup_hists")?;
+
+            // buffer.lock() only fails if a thread holding the lock panic'd, in
+            // which case we should probably panic too.
+            let mut buffer = self.buffer.lock().unwrap();
+            for (key, hist) in hists {
+                let cgroup_id = u64::from_ne_bytes(key[..].try_into()?);
+                accumulate(buffer.cgroups.entry(cgroup_id).or_default(), &hist);
+            }
+        }
+    }
+}
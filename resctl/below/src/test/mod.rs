@@ -26,6 +26,7 @@ use crate::model::{collect_sample, CgroupModel, CgroupPressureModel, Collector,
 use crate::store::{self, advance::new_advance_local, DataFrame};
 
 mod fake_view;
+mod test_capture;
 mod test_config;
 mod test_controllers;
 mod test_dump;
@@ -0,0 +1,83 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::capture::HighResCapture;
+
+#[test]
+fn high_res_capture_around_oom_kill() {
+    let dir = TempDir::new("below_high_res_capture_test").expect("tempdir failed");
+    let mut store =
+        store::StoreWriter::new(&dir, false, store::Format::Cbor).expect("Failed to create store");
+    let logger = get_logger();
+    // Fixed pressure well below the capture threshold, so only the OOM kill
+    // triggers a capture
+    let mut sample = Sample::default();
+    sample.cgroup.pressure = Some(cgroupfs::Pressure {
+        memory: cgroupfs::MemoryPressure {
+            some: cgroupfs::PressureMetrics {
+                avg10: Some(1.0),
+                ..Default::default()
+            },
+            full: cgroupfs::PressureMetrics {
+                avg10: Some(0.5),
+                ..Default::default()
+            },
+        },
+        ..Default::default()
+    });
+
+    // Record interval of 5s, ring of 3s and capture window of 2s
+    let mut capture = HighResCapture::new(
+        Duration::from_secs(5),
+        Duration::from_secs(3),
+        Duration::from_secs(2),
+    );
+    let unix_ts = UNIX_EPOCH + Duration::from_secs(554433);
+    for i in 0..30 {
+        let mut sample = sample.clone();
+        // An OOM kill happens right before the sample at 20s
+        sample.system.vmstat.oom_kill = Some(if i < 20 { 0 } else { 1 });
+        capture
+            .put(
+                &mut store,
+                unix_ts + Duration::from_secs(i),
+                sample,
+                &logger,
+            )
+            .expect("failed to put sample");
+    }
+
+    // Regular and high resolution samples are read back with the actual
+    // elapsed time to the previous one
+    let mut advance = new_advance_local(logger, dir.as_ref().to_path_buf(), unix_ts);
+    advance.initialize();
+    let mut stored = Vec::new();
+    while let Some(model) = advance.advance(store::Direction::Forward) {
+        stored.push((
+            model
+                .timestamp
+                .duration_since(unix_ts)
+                .expect("time went backwards")
+                .as_secs(),
+            model.time_elapsed.as_secs(),
+            model.high_res,
+        ));
+    }
+
+    // Samples after 22s are still in the ring or dropped
+    let mut expected = vec![(5, 5, false), (10, 5, false), (15, 5, false)];
+    expected.extend((16..=22).map(|i| (i, 1, true)));
+    assert_eq!(stored, expected);
+}
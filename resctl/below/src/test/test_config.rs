@@ -27,6 +27,15 @@ fn test_config_default() {
         "/var/log/below/store"
     );
     assert_eq!(below_config.cgroup_filter_out, String::new());
+    assert_eq!(
+        below_config.alert_log.to_string_lossy(),
+        "/var/log/below/alerts"
+    );
+    assert!(below_config.alert.is_empty());
+    assert!(!below_config.enable_netbytes);
+    assert!(!below_config.enable_runqlat);
+    assert!(!below_config.enable_oomkill);
+    assert!(!below_config.enable_procevents);
 }
 
 #[test]
@@ -67,6 +76,10 @@ fn test_config_load_success() {
         log_dir = '/var/log/below'
         store_dir = '/var/log/below'
         cgroup_filter_out = 'user.slice'
+        enable_netbytes = true
+        enable_runqlat = true
+        enable_oomkill = true
+        enable_procevents = true
         # I'm a comment
         something_else = "demacia"
     "#;
@@ -81,6 +94,10 @@ fn test_config_load_success() {
     assert_eq!(below_config.log_dir.to_string_lossy(), "/var/log/below");
     assert_eq!(below_config.store_dir.to_string_lossy(), "/var/log/below");
     assert_eq!(below_config.cgroup_filter_out, "user.slice");
+    assert!(below_config.enable_netbytes);
+    assert!(below_config.enable_runqlat);
+    assert!(below_config.enable_oomkill);
+    assert!(below_config.enable_procevents);
 }
 
 #[test]
@@ -214,3 +231,49 @@ fn test_viewrc_default_view() {
             assert_eq!(current_state, expected);
         });
 }
+
+#[test]
+fn test_config_load_alert() {
+    let tempdir = TempDir::new("below_config_load_alert").expect("Failed to create temp dir");
+    let path = tempdir.path().join("below.config");
+
+    let mut file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .truncate(true)
+        .create(true)
+        .open(&path)
+        .expect("Fail to open below.conf in tempdir");
+    let config_str = r#"
+        [[alert]]
+        name = "memory_pressure"
+        entity = "cgroup"
+        condition = "pressure.memory_full_pct > 20"
+        for = "30s"
+        command = "true"
+
+        [[alert]]
+        name = "low_memory"
+        entity = "system"
+        condition = "mem.available < 5% of mem.total"
+    "#;
+    file.write_all(config_str.as_bytes())
+        .expect("Faild to write temp conf file during testing ignore");
+    file.flush().expect("Failed to flush during testing ignore");
+
+    let below_config = match BelowConfig::load(&path) {
+        Ok(b) => b,
+        Err(e) => panic!("{:#}", e),
+    };
+    assert_eq!(below_config.alert.len(), 2);
+    assert_eq!(below_config.alert[0].name, "memory_pressure");
+    assert_eq!(below_config.alert[0].for_duration.as_deref(), Some("30s"));
+    assert_eq!(below_config.alert[0].command.as_deref(), Some("true"));
+    assert_eq!(below_config.alert[1].for_duration, None);
+    assert_eq!(below_config.alert[1].pipe, None);
+
+    let alert_log = tempdir.path().join("alerts");
+    if let Err(e) = crate::alert::Alerter::new(&alert_log, &below_config.alert) {
+        panic!("{:#}", e);
+    }
+}
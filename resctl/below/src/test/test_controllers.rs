@@ -19,7 +19,9 @@ use toml::Value;
 
 use super::fake_view::FakeView;
 use super::TempDir;
+use view::cgroup_view::CgroupView;
 use view::controllers::*;
+use view::stats_view::StatsView;
 use view::{View, ViewState};
 
 #[test]
@@ -328,3 +330,37 @@ next_col = 'd'
         Some(&Controllers::Cgroup)
     );
 }
+
+#[test]
+fn test_column_controllers() {
+    let mut fake_view = FakeView::new();
+    fake_view.add_cgroup_view();
+    let mut view = fake_view
+        .inner
+        .find_name::<StatsView<CgroupView>>("cgroup_view")
+        .expect("Failed to find cgroup view");
+    let titles = view.get_title_view().tabs.clone();
+
+    // Name column is always the first one
+    Controllers::MoveColRight.handle(&mut *view, &[]);
+    assert_eq!(view.get_title_view().tabs, titles);
+
+    // Move the first column after name to the right
+    Controllers::NextCol.handle(&mut *view, &[]);
+    Controllers::MoveColRight.handle(&mut *view, &[]);
+    assert_eq!(view.get_title_view().current_selected, 2);
+    assert_eq!(view.get_title_view().tabs[1], titles[2]);
+    assert_eq!(view.get_title_view().tabs[2], titles[1]);
+
+    // Hide it
+    Controllers::HideCol.handle(&mut *view, &[]);
+    assert_eq!(view.get_title_view().tabs.len(), titles.len() - 1);
+    assert!(!view.get_title_view().tabs.contains(&titles[1]));
+
+    // Show a column after the selected one
+    Controllers::ShowCol.handle(&mut *view, &["show_col", "mem.anon"]);
+    let title_view = view.get_title_view();
+    assert_eq!(title_view.tabs.len(), titles.len());
+    assert_eq!(title_view.current_selected, 3);
+    assert_eq!(title_view.tabs[3].trim(), "Anon");
+}
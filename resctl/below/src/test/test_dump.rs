@@ -12,14 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use serde_json::Value;
+use serde_json::{json, Value};
 
 use super::*;
 use command::{expand_fields, GeneralOpt, OutputFormat};
 use dump::*;
-use model::Queriable;
 use print::HasRenderConfigForDump;
-use tmain::Dumper;
+use tmain::{Dumper, IterExecResult};
 
 #[test]
 // Test correctness of system decoration
@@ -39,13 +38,18 @@ fn test_dump_sys_content() {
         )));
     }
     opts.output_format = Some(OutputFormat::Json);
-    let system_dumper = system::System::new(&opts, fields.clone());
+    let system_dumper =
+        system::System::new(&opts, fields.clone()).expect("Failed to create dumper");
 
     // update model again to populate cpu and io data
-    let model = collector.update_model(&logger).expect("Fail to get model");
+    let mut model = collector.update_model(&logger).expect("Fail to get model");
+    model.system.hostname = "below-test".into();
+    model.system.total_cpu.usage_pct = Some(12.5);
+    model.system.total_cpu.idle_pct = None;
+    model.system.mem.total = Some(4096);
     let mut system_content: Vec<u8> = Vec::new();
     let mut round = 0;
-    let ctx = CommonFieldContext { timestamp: 0 };
+    let ctx = CommonFieldContext::default();
     system_dumper
         .dump_model(&ctx, &model, &mut system_content, &mut round, false)
         .expect("Failed to dump system model");
@@ -55,22 +59,21 @@ fn test_dump_sys_content() {
     let jval: Value =
         serde_json::from_slice(&system_content).expect("Fail parse json of system dump");
 
+    // Numbers are typed, strings are rendered and missing values are null
+    assert_eq!(jval["Hostname"], json!("below-test"));
+    assert_eq!(jval["Usage"], json!(12.5));
+    assert_eq!(jval["Idle"], Value::Null);
+    assert_eq!(jval["Total"], json!(4096));
+
     for dump_field in fields.iter() {
         match dump_field {
             DumpField::Common(_) => continue,
             DumpField::FieldId(field_id) => {
                 let rc = model::SystemModel::get_render_config_for_dump(&field_id);
-                assert_eq!(
-                    rc.render(model.system.query(&field_id), false),
-                    jval[rc.render_title(false)]
-                        .as_str()
-                        .unwrap_or_else(|| panic!(
-                            "Key not found in Json: {}",
-                            rc.render_title(false)
-                        ))
-                        .to_owned(),
-                    "Model value and json value do not match for field: {}",
-                    field_id.to_string(),
+                assert!(
+                    jval.get(rc.render_title(false)).is_some(),
+                    "Key not found in Json: {}",
+                    rc.render_title(false)
                 );
             }
         }
@@ -110,6 +113,9 @@ fn test_dump_sys_titles() {
         "Stolen",
         "Guest",
         "Guest Nice",
+        "Freq",
+        "Core Throttle",
+        "Pkg Throttle",
         "Total",
         "Free",
         "Available",
@@ -158,6 +164,42 @@ fn test_dump_sys_titles() {
         "Pgscan Kswapd",
         "Pgscan Direct",
         "OOM Kills",
+        "Page Faults",
+        "Major Faults",
+        "Pgactivate",
+        "Pgdeactivate",
+        "Pgrefill",
+        "Direct Throttle",
+        "Kswapd Inodesteal",
+        "Kswapd Low Wmark",
+        "Kswapd High Wmark",
+        "Kswapd Runs",
+        "Allocstall",
+        "Compact Migrate Scan",
+        "Compact Free Scan",
+        "Compact Isolated",
+        "Compact Stall",
+        "Compact Fail",
+        "Compact Success",
+        "Kcompactd Wake",
+        "THP Fault Alloc",
+        "THP Fault Fallback",
+        "THP Collapse Alloc",
+        "THP Collapse Failed",
+        "THP Split",
+        "THP Split Failed",
+        "THP Deferred Split",
+        "THP Split PMD",
+        "THP Swap Out",
+        "THP Swpout Fallback",
+        "WS Refault",
+        "WS Refault Anon",
+        "WS Refault File",
+        "WS Activate",
+        "WS Restore",
+        "WS Nodereclaim",
+        "Kswapd Efficiency",
+        "Direct Efficiency",
         "Kernel Version",
         "OS Release",
         "Total Interrupts",
@@ -178,10 +220,63 @@ fn test_dump_sys_titles() {
         "CPU 31 Stolen",
         "CPU 31 Guest",
         "CPU 31 Guest Nice",
+        "CPU 31 Freq",
+        "CPU 31 Core Throttle",
+        "CPU 31 Pkg Throttle",
     ];
     assert_eq!(titles, expected_titles);
 }
 
+#[test]
+fn test_dump_sys_vm_titles() {
+    let vm_titles = |detail| {
+        expand_fields(
+            &[command::DumpOptionField::Agg(command::SystemAggField::Vm)],
+            detail,
+        )
+        .into_iter()
+        .filter_map(|dump_field| match dump_field {
+            DumpField::Common(_) => None,
+            DumpField::FieldId(field_id) => {
+                let rc = model::SystemModel::get_render_config_for_dump(&field_id);
+                Some(rc.render_title(false))
+            }
+        })
+        .collect::<Vec<_>>()
+    };
+
+    // Only the headline reclaim fields by default
+    let expected_default_titles = vec![
+        "Page In",
+        "Page Out",
+        "Swap In",
+        "Swap Out",
+        "Pgsteal Kswapd",
+        "Pgsteal Direct",
+        "Pgscan Kswapd",
+        "Pgscan Direct",
+        "OOM Kills",
+        "Kswapd Efficiency",
+        "Direct Efficiency",
+    ];
+    assert_eq!(vm_titles(false), expected_default_titles);
+
+    // Every vm field with --detail
+    let detail_titles = vm_titles(true);
+    assert_eq!(
+        detail_titles.len(),
+        model::VmModelFieldId::unit_variant_iter().count()
+    );
+    let detail_only_titles = ["Compact Stall", "THP Fault Alloc", "WS Refault"];
+    for title in expected_default_titles.iter().chain(&detail_only_titles) {
+        assert!(
+            detail_titles.iter().any(|t| t == title),
+            "Missing {} with --detail",
+            title
+        );
+    }
+}
+
 #[test]
 // Test correctness of process decoration
 // This test will also test JSON correctness.
@@ -193,13 +288,31 @@ fn test_dump_process_content() {
     let mut opts: GeneralOpt = Default::default();
     let fields = command::expand_fields(command::DEFAULT_PROCESS_FIELDS, true);
     opts.output_format = Some(OutputFormat::Json);
-    let process_dumper = process::Process::new(&opts, None, fields.clone());
+    let process_dumper =
+        process::Process::new(&opts, None, fields.clone(), false).expect("Failed to create dumper");
 
     // update model again to populate cpu and io data
-    let model = collector.update_model(&logger).expect("Fail to get model");
+    let mut model = collector.update_model(&logger).expect("Fail to get model");
+    let (pid, spm) = model
+        .process
+        .processes
+        .iter_mut()
+        .next()
+        .expect("No process in model");
+    let pid = *pid;
+    spm.comm = Some("below-test".into());
+    spm.state = Some(procfs::PidState::Running);
+    spm.cpu = Some(model::ProcessCpuModel {
+        usage_pct: Some(12.5),
+        ..Default::default()
+    });
+    spm.mem = Some(model::ProcessMemoryModel {
+        rss_bytes: Some(4096),
+        ..Default::default()
+    });
     let mut process_content: Vec<u8> = Vec::new();
     let mut round = 0;
-    let ctx = CommonFieldContext { timestamp: 0 };
+    let ctx = CommonFieldContext::default();
     process_dumper
         .dump_model(&ctx, &model, &mut process_content, &mut round, false)
         .expect("Failed to dump process model");
@@ -209,32 +322,37 @@ fn test_dump_process_content() {
     let jval: Value =
         serde_json::from_slice(&process_content).expect("Fail parse json of process dump");
 
+    // Numbers are typed, strings are rendered and missing values are null
+    let value = jval
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|value| value["Pid"] == json!(pid))
+        .expect("Process not found in Json");
+    assert_eq!(value["Comm"], json!("below-test"));
+    assert_eq!(value["State"], json!("RUNNING"));
+    assert_eq!(value["CPU"], json!(12.5));
+    assert_eq!(value["User CPU"], Value::Null);
+    assert_eq!(value["RSS"], json!(4096));
+
     // verify content correctness, test first 5 should be enough
     let mut count = 5;
     for value in jval.as_array().unwrap() {
-        let pid = value["Pid"].as_str().unwrap();
-        let spm = model
-            .process
-            .processes
-            .get(&pid.parse().unwrap())
-            .expect("Json pid and spm pid not match");
+        let pid = value["Pid"].as_i64().unwrap() as i32;
+        assert!(
+            model.process.processes.contains_key(&pid),
+            "Json pid and spm pid not match"
+        );
 
         for dump_field in fields.iter() {
             match dump_field {
                 DumpField::Common(_) => continue,
                 DumpField::FieldId(field_id) => {
                     let rc = model::SingleProcessModel::get_render_config_for_dump(&field_id);
-                    assert_eq!(
-                        rc.render(spm.query(&field_id), false),
-                        value[rc.render_title(false)]
-                            .as_str()
-                            .unwrap_or_else(|| panic!(
-                                "Key not found in Json: {}",
-                                rc.render_title(false)
-                            ))
-                            .to_owned(),
-                        "Model value and json value do not match for field: {}",
-                        field_id.to_string(),
+                    assert!(
+                        value.get(rc.render_title(false)).is_some(),
+                        "Key not found in Json: {}",
+                        rc.render_title(false)
                     );
                 }
             }
@@ -310,11 +428,13 @@ fn test_dump_proc_select() {
         &opts,
         Some(model::SingleProcessModelFieldId::Pid),
         fields.clone(),
-    );
+        false,
+    )
+    .expect("Failed to create dumper");
 
     let mut process_content: Vec<u8> = Vec::new();
     let mut round = 0;
-    let ctx = CommonFieldContext { timestamp: 0 };
+    let ctx = CommonFieldContext::default();
     process_dumper
         .dump_model(&ctx, &model, &mut process_content, &mut round, false)
         .expect("Failed to dump process model");
@@ -332,7 +452,9 @@ fn test_dump_proc_select() {
         &opts,
         Some(model::SingleProcessModelFieldId::Pid),
         fields.clone(),
-    );
+        false,
+    )
+    .expect("Failed to create dumper");
 
     process_content = Vec::new();
     round = 0;
@@ -346,8 +468,7 @@ fn test_dump_proc_select() {
 
     let mut prev_id = 0;
     for item in jval.as_array().unwrap() {
-        let pid = item["Pid"].as_str().unwrap();
-        let cur_id = pid.parse::<i32>().unwrap();
+        let cur_id = item["Pid"].as_i64().unwrap();
         if prev_id > 0 {
             assert!(prev_id < cur_id, "prev_id: {}, cur_id: {}", prev_id, cur_id);
         }
@@ -357,8 +478,13 @@ fn test_dump_proc_select() {
     // test select sort top
     opts.sort = false;
     opts.rsort = true;
-    let process_dumper =
-        process::Process::new(&opts, Some(model::SingleProcessModelFieldId::Pid), fields);
+    let process_dumper = process::Process::new(
+        &opts,
+        Some(model::SingleProcessModelFieldId::Pid),
+        fields,
+        false,
+    )
+    .expect("Failed to create dumper");
 
     process_content = Vec::new();
     round = 0;
@@ -372,8 +498,7 @@ fn test_dump_proc_select() {
 
     prev_id = 0;
     for item in jval.as_array().unwrap() {
-        let pid = item["Pid"].as_str().unwrap();
-        let cur_id = pid.parse::<i32>().unwrap();
+        let cur_id = item["Pid"].as_i64().unwrap();
         if prev_id > 0 {
             assert!(prev_id > cur_id, "prev_id: {}, cur_id: {}", prev_id, cur_id);
         }
@@ -381,23 +506,401 @@ fn test_dump_proc_select() {
     }
 }
 
+#[test]
+fn test_dump_proc_where() {
+    let mut collector = Collector::new(get_dummy_exit_data());
+    let logger = get_logger();
+    collector.update_model(&logger).expect("Fail to get model");
+    let model = collector.update_model(&logger).expect("Fail to get model");
+
+    let fields = command::expand_fields(command::DEFAULT_PROCESS_FIELDS, true);
+    let mut opts: GeneralOpt = Default::default();
+    opts.output_format = Some(OutputFormat::Json);
+    let (pid, spm) = model.process.processes.iter().last().unwrap();
+    opts.where_expr = Some(format!(
+        "pid == {} && comm =~ \"{}\"",
+        pid,
+        regex::escape(spm.comm.as_ref().unwrap())
+    ));
+    let process_dumper =
+        process::Process::new(&opts, None, fields.clone(), false).expect("Failed to create dumper");
+
+    let mut process_content: Vec<u8> = Vec::new();
+    let mut round = 0;
+    let ctx = CommonFieldContext::default();
+    process_dumper
+        .dump_model(&ctx, &model, &mut process_content, &mut round, false)
+        .expect("Failed to dump process model");
+
+    let jval: Value =
+        serde_json::from_slice(&process_content).expect("Fail parse json of process dump");
+    assert_eq!(jval.as_array().unwrap().len(), 1);
+    assert_eq!(jval[0]["Pid"], *pid);
+
+    // Nothing matches, the sample should be skipped
+    opts.where_expr = Some("pid < 0".to_owned());
+    let process_dumper =
+        process::Process::new(&opts, None, fields.clone(), false).expect("Failed to create dumper");
+    process_content = Vec::new();
+    let res = process_dumper
+        .dump_model(&ctx, &model, &mut process_content, &mut round, false)
+        .expect("Failed to dump process model");
+    assert!(res == IterExecResult::Skip);
+    assert!(process_content.is_empty());
+
+    // Invalid expressions are reported as errors
+    opts.where_expr = Some("pid <".to_owned());
+    assert!(process::Process::new(&opts, None, fields, false).is_err());
+}
+
+#[test]
+fn test_dump_proc_ndjson() {
+    let mut collector = Collector::new(get_dummy_exit_data());
+    let logger = get_logger();
+    collector.update_model(&logger).expect("Fail to get model");
+    let model = collector.update_model(&logger).expect("Fail to get model");
+
+    let fields = command::expand_fields(command::DEFAULT_PROCESS_FIELDS, true);
+    let mut opts: GeneralOpt = Default::default();
+    opts.output_format = Some(OutputFormat::NdJson);
+    let process_dumper =
+        process::Process::new(&opts, None, fields, false).expect("Failed to create dumper");
+
+    let mut process_content: Vec<u8> = Vec::new();
+    let mut round = 0;
+    let ctx = CommonFieldContext::default();
+    process_dumper
+        .dump_model(&ctx, &model, &mut process_content, &mut round, false)
+        .expect("Failed to dump process model");
+
+    // One json object per process, one process per line
+    let content = String::from_utf8(process_content).expect("Invalid utf8 in ndjson dump");
+    let lines = content.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), round);
+    assert_eq!(lines.len(), model.process.processes.len());
+    for line in lines {
+        let jval: Value = serde_json::from_str(line).expect("Fail parse line of ndjson dump");
+        let pid = jval["Pid"].as_i64().expect("Pid is not a number") as i32;
+        assert!(model.process.processes.contains_key(&pid));
+    }
+}
+
+#[test]
+fn test_dump_proc_columnar() {
+    let mut collector = Collector::new(get_dummy_exit_data());
+    let logger = get_logger();
+    collector.update_model(&logger).expect("Fail to get model");
+    let model = collector.update_model(&logger).expect("Fail to get model");
+
+    let fields = command::expand_fields(command::DEFAULT_PROCESS_FIELDS, true);
+    let mut opts: GeneralOpt = Default::default();
+    opts.output_format = Some(OutputFormat::Parquet);
+    let process_dumper =
+        process::Process::new(&opts, None, fields, false).expect("Failed to create dumper");
+
+    let mut process_content: Vec<u8> = Vec::new();
+    let mut round = 0;
+    let ctx = CommonFieldContext::default();
+    process_dumper
+        .dump_model(&ctx, &model, &mut process_content, &mut round, false)
+        .expect("Failed to dump process model");
+
+    // One row per process with typed fields and without CommonFields, and
+    // nothing written to the output
+    assert!(process_content.is_empty());
+    let rows = ctx.rows.replace(Vec::new());
+    assert_eq!(rows.len(), round);
+    for row in &rows {
+        assert!(row.iter().all(|(title, _, _)| title != "Datetime"));
+        let (_, pid_type, pid) = row
+            .iter()
+            .find(|(title, _, _)| title == "Pid")
+            .expect("Pid not found in row");
+        assert_eq!(*pid_type, model::FieldType::I32);
+        assert!(matches!(pid, Some(model::Field::I32(_))));
+    }
+
+    for (format, magic) in &[
+        (OutputFormat::Parquet, &b"PAR1"[..]),
+        (OutputFormat::Arrow, &b"ARROW1"[..]),
+    ] {
+        let mut writer = columnar::ColumnarWriter::new(*format);
+        let mut file: Vec<u8> = Vec::new();
+        writer
+            .push_rows(rows.clone(), ctx.timestamp, "hostname", &mut file)
+            .expect("Failed to write rows");
+        writer.finish(&mut file).expect("Failed to finish file");
+        assert!(file.starts_with(magic), "Bad header of {:?} file", format);
+        assert!(file.ends_with(magic), "Bad footer of {:?} file", format);
+    }
+}
+
+#[test]
+fn test_dump_proc_csv() {
+    assert_eq!(print::escape_csv_value("below", ','), "below");
+    assert_eq!(print::escape_csv_value("a,b", ','), "\"a,b\"");
+    assert_eq!(
+        print::escape_csv_value("say \"hi\"", ','),
+        "\"say \"\"hi\"\"\""
+    );
+    assert_eq!(print::escape_csv_value("a\nb", ','), "\"a\nb\"");
+    assert_eq!(print::escape_csv_value("a\tb", ','), "a\tb");
+    assert_eq!(print::escape_csv_value("a,\"b\"", '\t'), "a,\"b\"");
+    assert_eq!(
+        print::escape_csv_value("a\tb\\c\nd", '\t'),
+        "a\\tb\\\\c\\nd"
+    );
+
+    let mut collector = Collector::new(get_dummy_exit_data());
+    let logger = get_logger();
+    collector.update_model(&logger).expect("Fail to get model");
+    let model = collector.update_model(&logger).expect("Fail to get model");
+
+    let fields = command::expand_fields(command::DEFAULT_PROCESS_FIELDS, true);
+    let mut opts: GeneralOpt = Default::default();
+    opts.output_format = Some(OutputFormat::Tsv);
+    opts.entity_header = true;
+    let process_dumper =
+        process::Process::new(&opts, None, fields.clone(), false).expect("Failed to create dumper");
+
+    let mut process_content: Vec<u8> = Vec::new();
+    let mut round = 0;
+    let ctx = CommonFieldContext::default();
+    process_dumper
+        .dump_model(&ctx, &model, &mut process_content, &mut round, false)
+        .expect("Failed to dump process model");
+
+    // A title line and a line per process, each with a value per field
+    let content = String::from_utf8(process_content).expect("Invalid utf8 in tsv dump");
+    let lines = content.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), round + 1);
+    assert!(lines[0].starts_with("Entity\t"));
+    for line in &lines[1..] {
+        assert!(line.starts_with("process\t"));
+        assert_eq!(line.split('\t').count(), fields.len() + 1);
+    }
+}
+
+#[test]
+fn test_dump_all() {
+    let mut collector = Collector::new(get_dummy_exit_data());
+    let logger = get_logger();
+    collector.update_model(&logger).expect("Fail to get model");
+    let model = collector.update_model(&logger).expect("Fail to get model");
+    let models = vec![command::DumpModel::System, command::DumpModel::Process];
+    let ctx = CommonFieldContext::default();
+
+    let dump_all = |output_format| {
+        let mut opts: GeneralOpt = Default::default();
+        opts.output_format = Some(output_format);
+        let all_dumper = all::All::new(&opts, models.clone()).expect("Failed to create dumper");
+        let mut content: Vec<u8> = Vec::new();
+        let mut round = 0;
+        all_dumper
+            .dump_model(&ctx, &model, &mut content, &mut round, false)
+            .expect("Failed to dump all models");
+        // One system and a process per round
+        assert_eq!(round, model.process.processes.len() + 1);
+        String::from_utf8(content).expect("Invalid utf8 in dump")
+    };
+
+    // Each model is under its own key
+    let jval: Value =
+        serde_json::from_str(&dump_all(OutputFormat::Json)).expect("Fail parse json of dump all");
+    assert!(jval["system"].is_object());
+    assert_eq!(
+        jval["process"].as_array().unwrap().len(),
+        model.process.processes.len()
+    );
+
+    // Each object is tagged with its model
+    let content = dump_all(OutputFormat::NdJson);
+    let entities = content
+        .lines()
+        .map(|line| {
+            let jval: Value = serde_json::from_str(line).expect("Fail parse line of ndjson dump");
+            jval["Entity"].as_str().unwrap().to_owned()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(entities[0], "system");
+    assert!(entities[1..].iter().all(|entity| entity == "process"));
+
+    // A title line per model, and each line starts with its model
+    let content = dump_all(OutputFormat::Csv);
+    let lines = content.lines().collect::<Vec<_>>();
+    assert!(lines[0].starts_with("Entity,"));
+    assert!(lines[1].starts_with("system,"));
+    assert!(lines[2].starts_with("Entity,"));
+    assert!(lines[3..].iter().all(|line| line.starts_with("process,")));
+}
+
+#[test]
+fn test_dump_oom() {
+    let mut collector = Collector::new(get_dummy_exit_data());
+    let logger = get_logger();
+    let mut model = collector.update_model(&logger).expect("Fail to get model");
+    let fields = command::expand_fields(command::DEFAULT_OOM_FIELDS, true);
+    let ctx = CommonFieldContext::default();
+    let mut opts: GeneralOpt = Default::default();
+    opts.output_format = Some(OutputFormat::Json);
+    opts.where_expr = Some("constraint == \"memcg\"".to_owned());
+    let oom_dumper = oom::Oom::new(&opts, None, fields).expect("Failed to create dumper");
+
+    // Most samples have no OOM kill
+    let mut content: Vec<u8> = Vec::new();
+    let mut round = 0;
+    assert!(
+        oom_dumper
+            .dump_model(&ctx, &model, &mut content, &mut round, false)
+            .expect("Failed to dump oom model")
+            == IterExecResult::Skip
+    );
+
+    for (pid, constraint) in &[(10, "none"), (11, "memcg")] {
+        model.oom_kills.push(model::OomKillModel {
+            pid: Some(*pid),
+            comm: Some("stress".to_owned()),
+            cgroup: Some("/system.slice/stress.service".to_owned()),
+            constraint: Some(constraint.to_string()),
+            oom_cgroup: Some("/system.slice".to_owned()),
+            ..Default::default()
+        });
+    }
+    oom_dumper
+        .dump_model(&ctx, &model, &mut content, &mut round, false)
+        .expect("Failed to dump oom model");
+    let jval: Value = serde_json::from_slice(&content).expect("Fail parse json of oom dump");
+    let oom_kills = jval.as_array().expect("oom dump is not an array");
+    assert_eq!(oom_kills.len(), 1);
+    assert_eq!(round, 1);
+    assert_eq!(oom_kills[0]["Pid"], json!(11));
+    assert_eq!(oom_kills[0]["Comm"], json!("stress"));
+    assert_eq!(oom_kills[0]["OOM Cgroup"], json!("/system.slice"));
+}
+
+#[test]
+fn test_dump_procevents() {
+    let mut collector = Collector::new(get_dummy_exit_data());
+    let logger = get_logger();
+    let mut model = collector.update_model(&logger).expect("Fail to get model");
+    let fields = command::expand_fields(command::DEFAULT_PROC_EVENT_FIELDS, true);
+    let ctx = CommonFieldContext::default();
+    let mut opts: GeneralOpt = Default::default();
+    opts.output_format = Some(OutputFormat::Json);
+    opts.where_expr = Some("event == \"exit\" && exit_code != 0".to_owned());
+    let procevents_dumper =
+        procevents::Procevents::new(&opts, None, fields).expect("Failed to create dumper");
+
+    // No process event unless enabled
+    let mut content: Vec<u8> = Vec::new();
+    let mut round = 0;
+    assert!(
+        procevents_dumper
+            .dump_model(&ctx, &model, &mut content, &mut round, false)
+            .expect("Failed to dump procevents model")
+            == IterExecResult::Skip
+    );
+
+    for (event, pid, exit_code) in &[
+        ("exec", 10, None),
+        ("exit", 10, Some(1)),
+        ("exit", 11, Some(0)),
+    ] {
+        model.proc_events.push(model::ProcEventModel {
+            event: Some(event.to_string()),
+            pid: Some(*pid),
+            ppid: Some(1),
+            comm: Some("false".to_owned()),
+            cmdline: Some("/bin/false --verbose".to_owned()),
+            cgroup: Some("/system.slice/foo.service".to_owned()),
+            exit_code: *exit_code,
+            ..Default::default()
+        });
+    }
+    procevents_dumper
+        .dump_model(&ctx, &model, &mut content, &mut round, false)
+        .expect("Failed to dump procevents model");
+    let jval: Value = serde_json::from_slice(&content).expect("Fail parse json of procevents dump");
+    let proc_events = jval.as_array().expect("procevents dump is not an array");
+    assert_eq!(proc_events.len(), 1);
+    assert_eq!(round, 1);
+    assert_eq!(proc_events[0]["Pid"], json!(10));
+    assert_eq!(proc_events[0]["Exit Code"], json!(1));
+    assert_eq!(proc_events[0]["Cmdline"], json!("/bin/false --verbose"));
+}
+
+#[test]
+fn test_dump_proc_tree() {
+    let mut collector = Collector::new(get_dummy_exit_data());
+    let logger = get_logger();
+    collector.update_model(&logger).expect("Fail to get model");
+    let model = collector.update_model(&logger).expect("Fail to get model");
+    let tree = model::ProcessTree::new(&model.process);
+
+    let fields = command::expand_fields(command::DEFAULT_PROCESS_FIELDS, true);
+    let mut opts: GeneralOpt = Default::default();
+    opts.output_format = Some(OutputFormat::Json);
+    let process_dumper =
+        process::Process::new(&opts, None, fields.clone(), true).expect("Failed to create dumper");
+
+    let mut process_content: Vec<u8> = Vec::new();
+    let mut round = 0;
+    let ctx = CommonFieldContext::default();
+    process_dumper
+        .dump_model(&ctx, &model, &mut process_content, &mut round, false)
+        .expect("Failed to dump process model");
+    assert_eq!(round, tree.processes.len());
+
+    // Every process is nested under its parent
+    fn check_children(jval: &Value, ppid: &Value) {
+        for child in jval["children"].as_array().unwrap() {
+            assert_eq!(child["Ppid"], *ppid);
+            check_children(child, &child["Pid"]);
+        }
+    }
+    let jval: Value =
+        serde_json::from_slice(&process_content).expect("Fail parse json of process dump");
+    let roots = jval.as_array().unwrap();
+    assert_eq!(roots.len(), tree.roots.len());
+    for root in roots {
+        check_children(root, &root["Pid"]);
+    }
+
+    // Ancestors of a matching process are kept in json
+    let (pid, spm) = tree
+        .processes
+        .iter()
+        .max_by_key(|(_, spm)| spm.depth)
+        .unwrap();
+    opts.where_expr = Some(format!("pid == {}", pid));
+    let process_dumper =
+        process::Process::new(&opts, None, fields, true).expect("Failed to create dumper");
+    process_content = Vec::new();
+    round = 0;
+    process_dumper
+        .dump_model(&ctx, &model, &mut process_content, &mut round, false)
+        .expect("Failed to dump process model");
+    assert_eq!(round, 1);
+    let mut jval: Value =
+        serde_json::from_slice(&process_content).expect("Fail parse json of process dump");
+    jval = jval[0].clone();
+    for _ in 0..spm.depth {
+        assert_eq!(jval["children"].as_array().unwrap().len(), 1);
+        jval = jval["children"][0].clone();
+    }
+    assert_eq!(jval["Pid"], *pid);
+}
+
 fn traverse_cgroup_tree(model: &CgroupModel, jval: &Value) {
     for dump_field in expand_fields(command::DEFAULT_CGROUP_FIELDS, true) {
         match dump_field {
             DumpField::Common(_) => continue,
             DumpField::FieldId(field_id) => {
                 let rc = CgroupModel::get_render_config_for_dump(&field_id);
-                assert_eq!(
-                    rc.render(model.query(&field_id), false),
-                    jval[rc.render_title(false)]
-                        .as_str()
-                        .unwrap_or_else(|| panic!(
-                            "Key not found in Json: {}",
-                            rc.render_title(false)
-                        ))
-                        .to_owned(),
-                    "Model value and json value do not match for field: {}",
-                    field_id.to_string(),
+                assert!(
+                    jval.get(rc.render_title(false)).is_some(),
+                    "Key not found in Json: {}",
+                    rc.render_title(false)
                 );
             }
         }
@@ -419,13 +922,22 @@ fn test_dump_cgroup_content() {
     let mut opts: GeneralOpt = Default::default();
     let fields = command::expand_fields(command::DEFAULT_CGROUP_FIELDS, true);
     opts.output_format = Some(OutputFormat::Json);
-    let cgroup_dumper = cgroup::Cgroup::new(&opts, None, fields);
+    let cgroup_dumper = cgroup::Cgroup::new(&opts, None, fields).expect("Failed to create dumper");
 
     // update model again to populate cpu and io data
-    let model = collector.update_model(&logger).expect("Fail to get model");
+    let mut model = collector.update_model(&logger).expect("Fail to get model");
+    model.cgroup.inode_number = Some(42);
+    model.cgroup.cpu = Some(model::CgroupCpuModel {
+        usage_pct: Some(12.5),
+        ..Default::default()
+    });
+    model.cgroup.memory = Some(model::CgroupMemoryModel {
+        total: Some(4096),
+        ..Default::default()
+    });
     let mut cgroup_content: Vec<u8> = Vec::new();
     let mut round = 0;
-    let ctx = CommonFieldContext { timestamp: 0 };
+    let ctx = CommonFieldContext::default();
     cgroup_dumper
         .dump_model(&ctx, &model, &mut cgroup_content, &mut round, false)
         .expect("Failed to dump cgroup model");
@@ -434,6 +946,13 @@ fn test_dump_cgroup_content() {
     assert!(!cgroup_content.is_empty());
     let mut jval: Value =
         serde_json::from_slice(&cgroup_content).expect("Fail parse json of process dump");
+
+    // Numbers are typed and missing values are null
+    assert_eq!(jval["Inode Number"], json!(42));
+    assert_eq!(jval["CPU Usage"], json!(12.5));
+    assert_eq!(jval["CPU User"], Value::Null);
+    assert_eq!(jval["Mem Total"], json!(4096));
+
     traverse_cgroup_tree(&model.cgroup, &mut jval);
 }
 
@@ -477,6 +996,13 @@ fn test_dump_cgroup_titles() {
         "Unevictable",
         "Slab Reclaimable",
         "Slab Unreclaimable",
+        "Page Tables",
+        "Percpu",
+        "Swap Cached",
+        "Zswap",
+        "Zswapped",
+        "File THP",
+        "Shmem THP",
         "Pgfault",
         "Pgmajfault",
         "Workingset Refault",
@@ -491,12 +1017,34 @@ fn test_dump_cgroup_titles() {
         "Pglazyfreed",
         "THP Fault Alloc",
         "THP Collapse Alloc",
+        "Refault Anon",
+        "Refault File",
+        "Activate Anon",
+        "Activate File",
+        "Restore Anon",
+        "Restore File",
+        "Pgscan Kswapd",
+        "Pgscan Direct",
+        "Pgsteal Kswapd",
+        "Pgsteal Direct",
+        "Zswpin",
+        "Zswpout",
+        "THP Swpout",
+        "THP Swpout Fallback",
         "Memory High",
         "Events Low",
         "Events High",
         "Events Max",
         "Events OOM",
         "Events Kill",
+        "Local Low",
+        "Local High",
+        "Local Max",
+        "Local OOM",
+        "Local Kill",
+        "Swap High",
+        "Swap Max",
+        "Swap Fail",
         "RBytes",
         "WBytes",
         "R I/O",
@@ -524,13 +1072,24 @@ fn test_dump_iface_content() {
     let mut opts: GeneralOpt = Default::default();
     let fields = command::expand_fields(command::DEFAULT_IFACE_FIELDS, true);
     opts.output_format = Some(OutputFormat::Json);
-    let iface_dumper = iface::Iface::new(&opts, None, fields.clone());
+    let iface_dumper =
+        iface::Iface::new(&opts, None, fields.clone()).expect("Failed to create dumper");
 
     // update model again to populate net data
-    let model = collector.update_model(&logger).expect("Fail to get model");
+    let mut model = collector.update_model(&logger).expect("Fail to get model");
+    let snm = model
+        .network
+        .interfaces
+        .values_mut()
+        .next()
+        .expect("No interface in model");
+    let iface = snm.interface.clone();
+    snm.rx_bytes_per_sec = Some(12.5);
+    snm.tx_bytes_per_sec = None;
+    snm.collisions = Some(42);
     let mut iface_content: Vec<u8> = Vec::new();
     let mut round = 0;
-    let ctx = CommonFieldContext { timestamp: 0 };
+    let ctx = CommonFieldContext::default();
     iface_dumper
         .dump_model(&ctx, &model, &mut iface_content, &mut round, false)
         .expect("Failed to dump iface model");
@@ -540,32 +1099,35 @@ fn test_dump_iface_content() {
     let jval: Value =
         serde_json::from_slice(&iface_content).expect("Fail parse json of network dump");
 
+    // Numbers are typed, strings are rendered and missing values are null
+    let value = jval
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|value| value["Interface"] == json!(iface))
+        .expect("Interface not found in Json");
+    assert_eq!(value["RX Bytes/s"], json!(12.5));
+    assert_eq!(value["TX Bytes/s"], Value::Null);
+    assert_eq!(value["Collisions"], json!(42));
+
     // verify content correctness, test first 5 should be enough
     let mut count = 5;
     for value in jval.as_array().unwrap() {
         let iface = value["Interface"].as_str().unwrap();
-        let snm = model
-            .network
-            .interfaces
-            .get(iface)
-            .expect("Json iface and snm iface not match");
+        assert!(
+            model.network.interfaces.contains_key(iface),
+            "Json iface and snm iface not match"
+        );
 
         for dump_field in fields.iter() {
             match dump_field {
                 DumpField::Common(_) => continue,
                 DumpField::FieldId(field_id) => {
                     let rc = model::SingleNetModel::get_render_config_for_dump(&field_id);
-                    assert_eq!(
-                        rc.render(snm.query(&field_id), false),
-                        value[rc.render_title(false)]
-                            .as_str()
-                            .unwrap_or_else(|| panic!(
-                                "Key not found in Json: {}",
-                                rc.render_title(false)
-                            ))
-                            .to_owned(),
-                        "Model value and json value do not match for field: {}",
-                        field_id.to_string(),
+                    assert!(
+                        value.get(rc.render_title(false)).is_some(),
+                        "Key not found in Json: {}",
+                        rc.render_title(false)
                     );
                 }
             }
@@ -635,13 +1197,16 @@ fn test_dump_network_content() {
     let mut opts: GeneralOpt = Default::default();
     let fields = command::expand_fields(command::DEFAULT_NETWORK_FIELDS, true);
     opts.output_format = Some(OutputFormat::Json);
-    let network_dumper = network::Network::new(&opts, fields.clone());
+    let network_dumper =
+        network::Network::new(&opts, fields.clone()).expect("Failed to create dumper");
 
     // update model again to populate net data
-    let model = collector.update_model(&logger).expect("Fail to get model");
+    let mut model = collector.update_model(&logger).expect("Fail to get model");
+    model.network.ip.forwarding_pkts_per_sec = Some(42);
+    model.network.ip.in_receives_pkts_per_sec = None;
     let mut network_content: Vec<u8> = Vec::new();
     let mut round = 0;
-    let ctx = CommonFieldContext { timestamp: 0 };
+    let ctx = CommonFieldContext::default();
     network_dumper
         .dump_model(&ctx, &model, &mut network_content, &mut round, false)
         .expect("Failed to dump network model");
@@ -651,22 +1216,19 @@ fn test_dump_network_content() {
     let jval: Value =
         serde_json::from_slice(&network_content).expect("Fail parse json of network dump");
 
+    // Numbers are typed and missing values are null
+    assert_eq!(jval["IpForwPkts/s"], json!(42));
+    assert_eq!(jval["IpInPkts/s"], Value::Null);
+
     for dump_field in fields.iter() {
         match dump_field {
             DumpField::Common(_) => continue,
             DumpField::FieldId(field_id) => {
                 let rc = model::NetworkModel::get_render_config_for_dump(&field_id);
-                assert_eq!(
-                    rc.render(model.network.query(&field_id), false),
-                    jval[rc.render_title(false)]
-                        .as_str()
-                        .unwrap_or_else(|| panic!(
-                            "Key not found in Json: {}",
-                            rc.render_title(false),
-                        ))
-                        .to_owned(),
-                    "Model value and json value do not match for field: {}",
-                    field_id.to_string(),
+                assert!(
+                    jval.get(rc.render_title(false)).is_some(),
+                    "Key not found in Json: {}",
+                    rc.render_title(false)
                 );
             }
         }
@@ -749,13 +1311,16 @@ fn test_dump_transport_content() {
     let mut opts: GeneralOpt = Default::default();
     let fields = command::expand_fields(command::DEFAULT_TRANSPORT_FIELDS, true);
     opts.output_format = Some(OutputFormat::Json);
-    let transport_dumper = transport::Transport::new(&opts, fields.clone());
+    let transport_dumper =
+        transport::Transport::new(&opts, fields.clone()).expect("Failed to create dumper");
 
     // update model again to populate net data
-    let model = collector.update_model(&logger).expect("Fail to get model");
+    let mut model = collector.update_model(&logger).expect("Fail to get model");
+    model.network.tcp.active_opens_per_sec = Some(42);
+    model.network.tcp.passive_opens_per_sec = None;
     let mut transport_content: Vec<u8> = Vec::new();
     let mut round = 0;
-    let ctx = CommonFieldContext { timestamp: 0 };
+    let ctx = CommonFieldContext::default();
     transport_dumper
         .dump_model(&ctx, &model, &mut transport_content, &mut round, false)
         .expect("Failed to dump transport model");
@@ -765,22 +1330,19 @@ fn test_dump_transport_content() {
     let jval: Value =
         serde_json::from_slice(&transport_content).expect("Fail parse json of network dump");
 
+    // Numbers are typed and missing values are null
+    assert_eq!(jval["TcpActiveOpens/s"], json!(42));
+    assert_eq!(jval["TcpPassiveOpens/s"], Value::Null);
+
     for dump_field in fields.iter() {
         match dump_field {
             DumpField::Common(_) => continue,
             DumpField::FieldId(field_id) => {
                 let rc = model::NetworkModel::get_render_config_for_dump(&field_id);
-                assert_eq!(
-                    rc.render(model.network.query(&field_id), false),
-                    jval[rc.render_title(false)]
-                        .as_str()
-                        .unwrap_or_else(|| panic!(
-                            "Key not found in Json: {}",
-                            rc.render_title(false),
-                        ))
-                        .to_owned(),
-                    "Model value and json value do not match for field: {}",
-                    field_id.to_string(),
+                assert!(
+                    jval.get(rc.render_title(false)).is_some(),
+                    "Key not found in Json: {}",
+                    rc.render_title(false)
                 );
             }
         }
@@ -842,13 +1404,24 @@ fn test_dump_disk_content() {
     let mut opts: GeneralOpt = Default::default();
     let fields = command::expand_fields(command::DEFAULT_DISK_FIELDS, true);
     opts.output_format = Some(OutputFormat::Json);
-    let disk_dumper = disk::Disk::new(&opts, None, fields.clone());
+    let disk_dumper =
+        disk::Disk::new(&opts, None, fields.clone()).expect("Failed to create dumper");
 
     // update model again to populate disk data
-    let model = collector.update_model(&logger).expect("Fail to get model");
+    let mut model = collector.update_model(&logger).expect("Fail to get model");
+    let sdm = model
+        .system
+        .disks
+        .values_mut()
+        .next()
+        .expect("No disk in model");
+    let name = sdm.name.clone();
+    sdm.read_bytes_per_sec = Some(12.5);
+    sdm.write_bytes_per_sec = None;
+    sdm.read_completed = Some(42);
     let mut disk_content: Vec<u8> = Vec::new();
     let mut round = 0;
-    let ctx = CommonFieldContext { timestamp: 0 };
+    let ctx = CommonFieldContext::default();
     disk_dumper
         .dump_model(&ctx, &model, &mut disk_content, &mut round, false)
         .expect("Failed to dump disk model");
@@ -857,32 +1430,35 @@ fn test_dump_disk_content() {
     assert!(!disk_content.is_empty());
     let jval: Value = serde_json::from_slice(&disk_content).expect("Fail parse json of disk dump");
 
+    // Numbers are typed, strings are rendered and missing values are null
+    let value = jval
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|value| value["Name"] == json!(name))
+        .expect("Disk not found in Json");
+    assert_eq!(value["Read"], json!(12.5));
+    assert_eq!(value["Write"], Value::Null);
+    assert_eq!(value["Read Completed"], json!(42));
+
     // verify content correctness, test first 5 should be enough
     let mut count = 5;
     for value in jval.as_array().unwrap() {
         let name = value["Name"].as_str().unwrap();
-        let sdm = model
-            .system
-            .disks
-            .get(name)
-            .expect("Json pid and sdm pid not match");
+        assert!(
+            model.system.disks.contains_key(name),
+            "Json pid and sdm pid not match"
+        );
 
         for dump_field in fields.iter() {
             match dump_field {
                 DumpField::Common(_) => continue,
                 DumpField::FieldId(field_id) => {
                     let rc = model::SingleDiskModel::get_render_config_for_dump(&field_id);
-                    assert_eq!(
-                        rc.render(sdm.query(&field_id), false),
-                        value[rc.render_title(false)]
-                            .as_str()
-                            .unwrap_or_else(|| panic!(
-                                "Key not found in Json: {}",
-                                rc.render_title(false),
-                            ))
-                            .to_owned(),
-                        "Model value and json value do not match for field: {}",
-                        field_id.to_string(),
+                    assert!(
+                        value.get(rc.render_title(false)).is_some(),
+                        "Key not found in Json: {}",
+                        rc.render_title(false)
                     );
                 }
             }
@@ -989,3 +1565,65 @@ proc = ["datetime", "mem.anon"]
         ))
     );
 }
+
+#[test]
+fn test_dump_process_compare() {
+    let mut collector = Collector::new(get_dummy_exit_data());
+    let logger = get_logger();
+    let base = collector.update_model(&logger).expect("Fail to get model");
+    let mut target = collector.update_model(&logger).expect("Fail to get model");
+
+    // Fake a process that exited and a process that started in between.
+    let exited = *base.process.processes.keys().next().unwrap();
+    target.process.processes.remove(&exited);
+    let started = i32::MAX;
+    target.process.processes.insert(
+        started,
+        model::SingleProcessModel {
+            pid: Some(started),
+            comm: Some("started".into()),
+            ..Default::default()
+        },
+    );
+
+    let fields = command::expand_fields(command::DEFAULT_PROCESS_FIELDS, false);
+    let mut opts: GeneralOpt = Default::default();
+    opts.output_format = Some(OutputFormat::Json);
+    let process_dumper =
+        process::Process::new(&opts, None, fields, false).expect("Failed to create dumper");
+
+    let mut process_content: Vec<u8> = Vec::new();
+    let ctx = CommonFieldContext::default();
+    process_dumper
+        .dump_compare((&ctx, &base), (&ctx, &target), &mut process_content)
+        .expect("Failed to compare process models");
+
+    let jval: Value =
+        serde_json::from_slice(&process_content).expect("Fail parse json of process compare");
+    let presence_of = |pid: i32| {
+        jval.as_array()
+            .unwrap()
+            .iter()
+            .find(|item| {
+                item["Pid"]["base"] == pid.to_string() || item["Pid"]["target"] == pid.to_string()
+            })
+            .map(|item| item["Presence"].as_str().unwrap().to_owned())
+    };
+    assert_eq!(presence_of(exited), Some("disappeared".to_owned()));
+    assert_eq!(presence_of(started), Some("appeared".to_owned()));
+
+    let pid = *target
+        .process
+        .processes
+        .keys()
+        .find(|pid| base.process.processes.contains_key(pid))
+        .unwrap();
+    let item = jval
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|item| item["Pid"]["target"] == pid.to_string())
+        .expect("Process not found in comparison");
+    assert_eq!(item["Pid"]["base"], item["Pid"]["target"]);
+    assert_eq!(item["Pid"]["delta"], "0");
+}
@@ -23,8 +23,16 @@ fn record_replay_integration() {
 
     // Collect a sample
     let logger = get_logger();
-    let sample = collect_sample(&get_dummy_exit_data(), true, &logger, false, &None)
-        .expect("failed to collect sample");
+    let sample = collect_sample(
+        &get_dummy_exit_data(),
+        None,
+        None,
+        true,
+        &logger,
+        false,
+        &None,
+    )
+    .expect("failed to collect sample");
 
     // Validate some data in the sample
     assert!(
@@ -113,8 +121,16 @@ fn advance_forward_and_reverse() {
     let timestamp = 554433;
     let unix_ts = UNIX_EPOCH + Duration::from_secs(timestamp);
     let logger = get_logger();
-    let sample = collect_sample(&get_dummy_exit_data(), true, &logger, false, &None)
-        .expect("failed to collect sample");
+    let sample = collect_sample(
+        &get_dummy_exit_data(),
+        None,
+        None,
+        true,
+        &logger,
+        false,
+        &None,
+    )
+    .expect("failed to collect sample");
     for i in 0..3 {
         let df = DataFrame {
             sample: sample.clone(),
@@ -160,8 +176,16 @@ fn advance_forward_and_reverse() {
 #[test]
 fn disable_io_stat() {
     let logger = get_logger();
-    let sample = collect_sample(&get_dummy_exit_data(), false, &logger, false, &None)
-        .expect("failed to collect sample");
+    let sample = collect_sample(
+        &get_dummy_exit_data(),
+        None,
+        None,
+        false,
+        &logger,
+        false,
+        &None,
+    )
+    .expect("failed to collect sample");
 
     assert_eq!(sample.cgroup.io_stat, None);
 }
@@ -169,8 +193,16 @@ fn disable_io_stat() {
 #[test]
 fn disable_disk_stat() {
     let logger = get_logger();
-    let sample = collect_sample(&get_dummy_exit_data(), false, &logger, true, &None)
-        .expect("failed to collect sample");
+    let sample = collect_sample(
+        &get_dummy_exit_data(),
+        None,
+        None,
+        false,
+        &logger,
+        true,
+        &None,
+    )
+    .expect("failed to collect sample");
     assert!(sample.system.disks.is_empty());
 }
 
@@ -14,7 +14,7 @@
 
 use std::boxed::Box;
 use std::path::PathBuf;
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use anyhow::Result;
 use slog::{self, error};
@@ -382,6 +382,73 @@ impl<FrameType, ModelType> Advance<FrameType, ModelType> {
         self.jump_sample_to(self.target_timestamp - gap)
     }
 
+    /// Get up to `count` models ending at the sample at or before timestamp,
+    /// ordered from oldest to newest.
+    // This function queries the store directly and will not touch the cached
+    // sample or the moving direction, so the caller can keep advancing from
+    // wherever it is after building a history from the models.
+    pub fn get_models_before(&mut self, timestamp: SystemTime, count: usize) -> Vec<ModelType> {
+        self.get_models_backward_until(timestamp, |_, len| len >= count)
+    }
+
+    /// Get the models covering the `window` ending at the sample at or before
+    /// timestamp, ordered from oldest to newest. Same as get_models_before,
+    /// the current position is not changed.
+    pub fn get_models_in_window(
+        &mut self,
+        timestamp: SystemTime,
+        window: Duration,
+    ) -> Vec<ModelType> {
+        let start = timestamp.checked_sub(window).unwrap_or(UNIX_EPOCH);
+        self.get_models_backward_until(timestamp, |newer_timestamp, _| newer_timestamp <= start)
+    }
+
+    /// Walk the store backward from the sample at or before timestamp, until
+    /// `done` returns true given the timestamp of the newer sample of the next
+    /// model and the number of models collected so far.
+    fn get_models_backward_until<F>(&mut self, timestamp: SystemTime, done: F) -> Vec<ModelType>
+    where
+        F: Fn(SystemTime, usize) -> bool,
+    {
+        let mut models = Vec::new();
+        let (mut newer_timestamp, mut newer_sample) =
+            match self
+                .store
+                .extract_sample_and_log(timestamp, Direction::Reverse, &self.logger)
+            {
+                Some(res) => res,
+                None => return models,
+            };
+
+        while !done(newer_timestamp, models.len()) {
+            let (older_timestamp, older_sample) = match self.store.extract_sample_and_log(
+                newer_timestamp - Duration::from_secs(1),
+                Direction::Reverse,
+                &self.logger,
+            ) {
+                Some(res) => res,
+                None => break,
+            };
+
+            let sample_package = SamplePackage::<FrameType>::new(
+                Some(older_sample),
+                older_timestamp,
+                newer_sample,
+                newer_timestamp,
+            );
+            if let Some(model) = self.store.to_model(&sample_package) {
+                models.push(model);
+            }
+            newer_sample = sample_package
+                .older_sample
+                .expect("Older sample should be set on construction");
+            newer_timestamp = older_timestamp;
+        }
+
+        models.reverse();
+        models
+    }
+
     // Convenience function will be used by dump and scuba dump
     pub fn get_next_ts(&self) -> SystemTime {
         // timestamp for initial advance if initialize didn't setup cached_sample
@@ -862,4 +929,52 @@ mod tests {
         advance.advance(Direction::Reverse);
         assert_eq!(advance.get_next_ts(), util::get_system_time(2));
     }
+
+    #[test]
+    fn advance_test_get_models_before() {
+        // Samples: [3, 10, 20, 50]
+        let mut advance = get_advance_with_fake_store(3);
+        advance.initialize();
+        advance.advance(Direction::Forward);
+
+        assert_eq!(
+            advance.get_models_before(util::get_system_time(30), 5),
+            vec!["3_10_10_7", "10_20_20_10"] /*old_new_ts_dur*/
+        );
+        assert_eq!(
+            advance.get_models_before(util::get_system_time(50), 1),
+            vec!["20_50_50_30"] /*old_new_ts_dur*/
+        );
+        assert!(advance
+            .get_models_before(util::get_system_time(1), 5)
+            .is_empty());
+
+        // Querying history should not move the current position
+        assert_eq!(advance.cached_sample, Some(10));
+        assert_eq!(advance.target_timestamp, util::get_system_time(10));
+        assert_eq!(
+            advance.advance(Direction::Forward),
+            Some("10_20_20_10".into()) /*old_new_ts_dur*/
+        );
+    }
+
+    #[test]
+    fn advance_test_get_models_in_window() {
+        // Samples: [3, 10, 20, 50]
+        let mut advance = get_advance_with_fake_store(3);
+        advance.initialize();
+
+        assert_eq!(
+            advance.get_models_in_window(util::get_system_time(50), Duration::from_secs(35)),
+            vec!["10_20_20_10", "20_50_50_30"] /*old_new_ts_dur*/
+        );
+        assert_eq!(
+            advance.get_models_in_window(util::get_system_time(25), Duration::from_secs(10)),
+            vec!["10_20_20_10"] /*old_new_ts_dur*/
+        );
+        assert_eq!(
+            advance.get_models_in_window(util::get_system_time(50), Duration::from_secs(3600)),
+            vec!["3_10_10_7", "10_20_20_10", "20_50_50_30"] /*old_new_ts_dur*/
+        );
+    }
 }
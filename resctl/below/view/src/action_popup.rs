@@ -0,0 +1,257 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Popups for acting on the live system: signaling the selected process and
+//! writing cgroup interface files of the selected cgroup. Every action asks
+//! for confirmation first and reports its result in the command palette.
+
+use std::io::ErrorKind;
+
+use anyhow::{anyhow, bail, Result};
+use cgroupfs::{CgroupReader, Knob};
+use cursive::event::{EventTrigger, Key};
+use cursive::view::{Identifiable, Scrollable, View};
+use cursive::views::{Dialog, EditView, LinearLayout, OnEventView, SelectView, TextView};
+use cursive::Cursive;
+
+use crate::MainViewState;
+
+/// Signals offered for the selected process, most commonly used first.
+const SIGNALS: &[(&str, libc::c_int)] = &[
+    ("TERM", libc::SIGTERM),
+    ("KILL", libc::SIGKILL),
+    ("INT", libc::SIGINT),
+    ("HUP", libc::SIGHUP),
+    ("QUIT", libc::SIGQUIT),
+    ("STOP", libc::SIGSTOP),
+    ("CONT", libc::SIGCONT),
+    ("USR1", libc::SIGUSR1),
+    ("USR2", libc::SIGUSR2),
+];
+
+/// Parse a signal name with or without the SIG prefix (e.g. "kill" or
+/// "SIGKILL"), or a signal number. Returns the display name and the signal.
+pub fn parse_signal(s: &str) -> Option<(String, libc::c_int)> {
+    if let Ok(signal) = s.parse::<libc::c_int>() {
+        if !(1..=64).contains(&signal) {
+            return None;
+        }
+        let name = SIGNALS
+            .iter()
+            .find(|(_, sig)| *sig == signal)
+            .map_or_else(|| signal.to_string(), |(name, _)| format!("SIG{}", name));
+        return Some((name, signal));
+    }
+    let upper = s.to_uppercase();
+    let name = upper.trim_start_matches("SIG");
+    SIGNALS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(n, signal)| (format!("SIG{}", n), *signal))
+}
+
+fn send_signal(pid: i32, signal: libc::c_int) -> Result<()> {
+    // kill() treats 0 and negative pids as process groups
+    if pid <= 0 {
+        bail!("Invalid pid: {}", pid);
+    }
+    // Safe since kill() only takes integers
+    if unsafe { libc::kill(pid, signal) } == 0 {
+        return Ok(());
+    }
+    let err = std::io::Error::last_os_error();
+    match err.raw_os_error() {
+        Some(libc::EPERM) => bail!(
+            "Permission denied to signal pid {}, below must run as root or as its owner",
+            pid
+        ),
+        Some(libc::ESRCH) => bail!("Process {} no longer exists", pid),
+        _ => Err(anyhow!(err).context(format!("Failed to signal pid {}", pid))),
+    }
+}
+
+fn write_knob(cgroup: &str, knob: Knob, value: &str) -> Result<()> {
+    let reader =
+        CgroupReader::new_with_relative_path(cgroupfs::DEFAULT_CG_ROOT.into(), cgroup.into())?;
+    match reader.write_knob(knob, value) {
+        Err(cgroupfs::Error::IoError(_, e)) if e.kind() == ErrorKind::PermissionDenied => bail!(
+            "Permission denied to write {} of {}, below must run as root",
+            knob.file_name(),
+            cgroup
+        ),
+        Err(cgroupfs::Error::IoError(_, e)) if e.kind() == ErrorKind::NotFound => bail!(
+            "{} does not exist for {}, is the controller enabled?",
+            knob.file_name(),
+            cgroup
+        ),
+        Err(cgroupfs::Error::InvalidValue(_, value)) => bail!(
+            "Invalid value for {}: \"{}\", expect {}",
+            knob.file_name(),
+            value,
+            knob.value_hint()
+        ),
+        res => Ok(res?),
+    }
+}
+
+// Set command palette info of the current view
+fn set_cp_info(c: &mut Cursive, msg: &str) {
+    let state = c
+        .user_data::<crate::ViewState>()
+        .expect("No user data")
+        .main_view_state
+        .clone();
+    match state {
+        MainViewState::Cgroup => crate::cgroup_view::ViewType::get_view(c)
+            .get_cmd_palette()
+            .set_info(msg),
+        MainViewState::Process | MainViewState::ProcessZoomedIntoCgroup => {
+            crate::process_view::ViewType::get_view(c)
+                .get_cmd_palette()
+                .set_info(msg)
+        }
+        MainViewState::Core => crate::core_view::ViewType::get_view(c)
+            .get_cmd_palette()
+            .set_info(msg),
+    }
+}
+
+/// Ask before running action. Cancel is focused so that a stray <Enter>
+/// does nothing.
+fn confirm<F>(prompt: String, action: F) -> impl View
+where
+    F: 'static + Fn() -> Result<String>,
+{
+    OnEventView::new(
+        Dialog::text(prompt)
+            .title("Confirm")
+            .dismiss_button("Cancel")
+            .button("Confirm", move |c| {
+                c.pop_layer();
+                let res = action();
+                // Refresh first as it overrides the info with the selection
+                crate::refresh(c);
+                match res {
+                    Ok(msg) => set_cp_info(c, &msg),
+                    Err(e) => view_warn!(c, "{:#}", e),
+                }
+            }),
+    )
+    .on_event(Key::Esc, |s| {
+        s.pop_layer();
+    })
+}
+
+pub fn new_signal_confirm(pid: i32, comm: String, name: String, signal: libc::c_int) -> impl View {
+    confirm(
+        format!("Send {} to {} (pid {})?", name, comm, pid),
+        move || {
+            send_signal(pid, signal)?;
+            Ok(format!("Sent {} to {} (pid {})", name, comm, pid))
+        },
+    )
+}
+
+/// List of signals to send to the selected process.
+pub fn new_signal(pid: i32, comm: String) -> impl View {
+    let title = format!("Send a signal to {} (pid {})", comm, pid);
+    let mut select = SelectView::<(String, libc::c_int)>::new();
+    for (name, signal) in SIGNALS {
+        select.add_item(
+            format!("SIG{:<6} {:>2}", name, signal),
+            (format!("SIG{}", name), *signal),
+        );
+    }
+    select.set_on_submit(move |c, (name, signal): &(String, libc::c_int)| {
+        c.pop_layer();
+        c.add_layer(new_signal_confirm(pid, comm.clone(), name.clone(), *signal));
+    });
+
+    OnEventView::new(
+        Dialog::around(select.scrollable())
+            .title(title)
+            .dismiss_button("Close"),
+    )
+    .on_event(EventTrigger::from('q').or(Key::Esc), |s| {
+        s.pop_layer();
+    })
+}
+
+pub fn new_knob_confirm(cgroup: String, knob: Knob, value: String) -> impl View {
+    let value = value.trim().to_string();
+    confirm(
+        format!("Write \"{}\" to {} of {}?", value, knob.file_name(), cgroup),
+        move || {
+            write_knob(&cgroup, knob, &value)?;
+            Ok(format!(
+                "Wrote \"{}\" to {} of {}",
+                value,
+                knob.file_name(),
+                cgroup
+            ))
+        },
+    )
+}
+
+fn new_knob_value(cgroup: String, knob: Knob) -> impl View {
+    let title = format!("Write {} of {}", knob.file_name(), cgroup);
+    OnEventView::new(
+        Dialog::new()
+            .title(title)
+            .padding_lrtb(1, 1, 1, 0)
+            .content(
+                LinearLayout::vertical()
+                    .child(
+                        EditView::new()
+                            .on_submit(move |c, value| {
+                                c.pop_layer();
+                                c.add_layer(new_knob_confirm(
+                                    cgroup.clone(),
+                                    knob,
+                                    value.to_string(),
+                                ));
+                            })
+                            .with_name("knob_value_popup"),
+                    )
+                    .child(TextView::new(format!("Value: {}", knob.value_hint()))),
+            )
+            .dismiss_button("Close"),
+    )
+    .on_event(Key::Esc, |s| {
+        s.pop_layer();
+    })
+}
+
+/// List of cgroup interface files to write for the selected cgroup.
+pub fn new_knob(cgroup: String) -> impl View {
+    let title = format!("Adjust {}", cgroup);
+    let mut select = SelectView::<Knob>::new();
+    for knob in Knob::ALL.iter() {
+        select.add_item(
+            format!("{:<14} {}", knob.file_name(), knob.value_hint()),
+            *knob,
+        );
+    }
+    select.set_on_submit(move |c, knob: &Knob| {
+        c.pop_layer();
+        c.add_layer(new_knob_value(cgroup.clone(), *knob));
+    });
+
+    OnEventView::new(Dialog::around(select).title(title).dismiss_button("Close")).on_event(
+        EventTrigger::from('q').or(Key::Esc),
+        |s| {
+            s.pop_layer();
+        },
+    )
+}
@@ -19,7 +19,7 @@ use crate::cgroup_view::CgroupState;
 use crate::render::ViewItem;
 use crate::stats_view::StateCommon;
 
-use model::{sort_queriables, CgroupModel};
+use model::{sort_queriables, CgroupModel, CgroupModelFieldId, FilterExpr};
 
 use cursive::utils::markup::StyledString;
 
@@ -39,6 +39,18 @@ impl CgroupTab {
         Self { view_items }
     }
 
+    /// Create a tab of the given columns, e.g. from belowrc. Name is always
+    /// the first column so it's left out.
+    pub fn from_fields(fields: Vec<CgroupModelFieldId>) -> Self {
+        Self::new(
+            fields
+                .into_iter()
+                .filter(|field_id| *field_id != CgroupModelFieldId::Name)
+                .map(default_tabs::get_view_item)
+                .collect(),
+        )
+    }
+
     fn get_line(
         &self,
         model: &CgroupModel,
@@ -132,7 +144,11 @@ impl CgroupTab {
         offset: Option<usize>,
     ) -> Vec<(StyledString, String)> {
         let filter_out_set = if let Some(f) = &state.filter {
-            Some(calculate_filter_out_set(&state.get_model(), &f))
+            Some(calculate_filter_out_set(
+                &state.get_model(),
+                f,
+                state.filter_expr.as_ref(),
+            ))
         } else {
             None
         };
@@ -149,16 +165,26 @@ impl CgroupTab {
     }
 }
 
-/// Returns a set of full cgroup paths that should be filtered out.
+/// Returns a set of full cgroup paths that should be filtered out. The parsed
+/// filter expression (e.g. "mem.anon > 1G") is evaluated against each cgroup
+/// if there is one, otherwise the filter is matched against the full path.
 ///
 /// Note that this algorithm recursively whitelists parents of cgroups that are
 /// whitelisted. The reason for this is because cgroups are inherently tree-like
 /// and displaying a lone cgroup without its ancestors doesn't make much sense.
-pub fn calculate_filter_out_set(cgroup: &CgroupModel, filter: &str) -> HashSet<String> {
-    fn should_filter_out(cgroup: &CgroupModel, filter: &str, set: &mut HashSet<String>) -> bool {
+pub fn calculate_filter_out_set(
+    cgroup: &CgroupModel,
+    filter: &str,
+    filter_expr: Option<&FilterExpr<CgroupModelFieldId>>,
+) -> HashSet<String> {
+    fn should_filter_out(
+        cgroup: &CgroupModel,
+        is_match: &dyn Fn(&CgroupModel) -> bool,
+        set: &mut HashSet<String>,
+    ) -> bool {
         // No children
         if cgroup.count == 1 {
-            if !cgroup.full_path.contains(filter) {
+            if !is_match(cgroup) {
                 set.insert(cgroup.full_path.clone());
                 return true;
             }
@@ -167,7 +193,7 @@ pub fn calculate_filter_out_set(cgroup: &CgroupModel, filter: &str) -> HashSet<S
 
         let mut filter_cgroup = true;
         for child in &cgroup.children {
-            if should_filter_out(&child, &filter, set) {
+            if should_filter_out(child, is_match, set) {
                 set.insert(child.full_path.clone());
             } else {
                 // We found a child that's not filtered out. That means
@@ -176,15 +202,22 @@ pub fn calculate_filter_out_set(cgroup: &CgroupModel, filter: &str) -> HashSet<S
             }
         }
 
-        if filter_cgroup {
+        // Keep a parent that matches by itself even if none of its children do
+        if filter_cgroup && !is_match(cgroup) {
             set.insert(cgroup.full_path.clone());
+        } else {
+            filter_cgroup = false;
         }
 
         filter_cgroup
     }
 
+    let is_match = |cgroup: &CgroupModel| match filter_expr {
+        Some(expr) => expr.eval(cgroup),
+        None => cgroup.full_path.contains(filter),
+    };
     let mut set = HashSet::new();
-    should_filter_out(&cgroup, &filter, &mut set);
+    should_filter_out(cgroup, &is_match, &mut set);
     set
 }
 
@@ -196,21 +229,37 @@ pub mod default_tabs {
     use model::CgroupCpuModelFieldId::{
         NrPeriodsPerSec, NrThrottledPerSec, SystemPct, ThrottledPct, UsagePct, UserPct,
     };
+    use model::CgroupHugetlbModelFieldId::{
+        Current as HugetlbCurrent, EventsMax as HugetlbEventsMax,
+    };
     use model::CgroupIoModelFieldId::{
         DbytesPerSec, DiosPerSec, RbytesPerSec, RiosPerSec, RwbytesPerSec, WbytesPerSec, WiosPerSec,
     };
     use model::CgroupMemoryModelFieldId::{
-        ActiveAnon, ActiveFile, Anon, AnonThp, EventsHigh, EventsLow, EventsMax, EventsOom,
-        EventsOomKill, File, FileDirty, FileMapped, FileWriteback, InactiveAnon, InactiveFile,
-        KernelStack, Pgactivate, Pgdeactivate, Pgfault, Pglazyfree, Pglazyfreed, Pgmajfault,
-        Pgrefill, Pgscan, Pgsteal, Shmem, Slab, SlabReclaimable, SlabUnreclaimable, Sock, Swap,
-        ThpCollapseAlloc, ThpFaultAlloc, Total, Unevictable, WorkingsetActivate,
-        WorkingsetNodereclaim, WorkingsetRefault,
+        ActiveAnon, ActiveFile, Anon, AnonThp, EventsHigh, EventsLocalHigh, EventsLocalLow,
+        EventsLocalMax, EventsLocalOom, EventsLocalOomKill, EventsLow, EventsMax, EventsOom,
+        EventsOomKill, File, FileDirty, FileMapped, FileThp, FileWriteback, InactiveAnon,
+        InactiveFile, KernelStack, Pagetables, Percpu, Pgactivate, Pgdeactivate, Pgfault,
+        Pglazyfree, Pglazyfreed, Pgmajfault, Pgrefill, Pgscan, PgscanDirect, PgscanKswapd, Pgsteal,
+        PgstealDirect, PgstealKswapd, Shmem, ShmemThp, Slab, SlabReclaimable, SlabUnreclaimable,
+        Sock, Swap, SwapEventsFail, SwapEventsHigh, SwapEventsMax, Swapcached, ThpCollapseAlloc,
+        ThpFaultAlloc, ThpSwpout, ThpSwpoutFallback, Total, Unevictable, WorkingsetActivate,
+        WorkingsetActivateAnon, WorkingsetActivateFile, WorkingsetNodereclaim, WorkingsetRefault,
+        WorkingsetRefaultAnon, WorkingsetRefaultFile, WorkingsetRestoreAnon, WorkingsetRestoreFile,
+        Zswap, Zswapped, Zswpin, Zswpout,
+    };
+    use model::CgroupModelFieldId::{
+        Cpu, Hugetlb, Io, Mem, Misc, Name, Net, Pressure, Rdma, RunqLat,
     };
-    use model::CgroupModelFieldId::{Cpu, Io, Mem, Name, Pressure};
     use model::CgroupPressureModelFieldId::{
         CpuSomePct, IoFullPct, IoSomePct, MemoryFullPct, MemorySomePct,
     };
+    use model::CgroupRdmaModelFieldId::{HcaHandle, HcaObject};
+    use model::CgroupRunqLatModelFieldId::{MaxUsec, P50Usec, P99Usec};
+    use model::NetBytesModelFieldId::{
+        RecvBytesPerSec, SendBytesPerSec, TcpRecvBytesPerSec, TcpSendBytesPerSec,
+        UdpRecvBytesPerSec, UdpSendBytesPerSec,
+    };
 
     use once_cell::sync::Lazy;
 
@@ -266,6 +315,13 @@ pub mod default_tabs {
             ViewItem::from_default(Mem(Unevictable)),
             ViewItem::from_default(Mem(SlabReclaimable)),
             ViewItem::from_default(Mem(SlabUnreclaimable)),
+            ViewItem::from_default(Mem(Pagetables)),
+            ViewItem::from_default(Mem(Percpu)),
+            ViewItem::from_default(Mem(Swapcached)),
+            ViewItem::from_default(Mem(Zswap)),
+            ViewItem::from_default(Mem(Zswapped)),
+            ViewItem::from_default(Mem(FileThp)),
+            ViewItem::from_default(Mem(ShmemThp)),
             ViewItem::from_default(Mem(Pgfault)),
             ViewItem::from_default(Mem(Pgmajfault)),
             ViewItem::from_default(Mem(WorkingsetRefault)),
@@ -280,11 +336,33 @@ pub mod default_tabs {
             ViewItem::from_default(Mem(Pglazyfreed)),
             ViewItem::from_default(Mem(ThpFaultAlloc)),
             ViewItem::from_default(Mem(ThpCollapseAlloc)),
+            ViewItem::from_default(Mem(WorkingsetRefaultAnon)),
+            ViewItem::from_default(Mem(WorkingsetRefaultFile)),
+            ViewItem::from_default(Mem(WorkingsetActivateAnon)),
+            ViewItem::from_default(Mem(WorkingsetActivateFile)),
+            ViewItem::from_default(Mem(WorkingsetRestoreAnon)),
+            ViewItem::from_default(Mem(WorkingsetRestoreFile)),
+            ViewItem::from_default(Mem(PgscanKswapd)),
+            ViewItem::from_default(Mem(PgscanDirect)),
+            ViewItem::from_default(Mem(PgstealKswapd)),
+            ViewItem::from_default(Mem(PgstealDirect)),
+            ViewItem::from_default(Mem(Zswpin)),
+            ViewItem::from_default(Mem(Zswpout)),
+            ViewItem::from_default(Mem(ThpSwpout)),
+            ViewItem::from_default(Mem(ThpSwpoutFallback)),
             ViewItem::from_default(Mem(EventsLow)),
             ViewItem::from_default(Mem(EventsHigh)),
             ViewItem::from_default(Mem(EventsMax)),
             ViewItem::from_default(Mem(EventsOom)),
             ViewItem::from_default(Mem(EventsOomKill)),
+            ViewItem::from_default(Mem(EventsLocalLow)),
+            ViewItem::from_default(Mem(EventsLocalHigh)),
+            ViewItem::from_default(Mem(EventsLocalMax)),
+            ViewItem::from_default(Mem(EventsLocalOom)),
+            ViewItem::from_default(Mem(EventsLocalOomKill)),
+            ViewItem::from_default(Mem(SwapEventsHigh)),
+            ViewItem::from_default(Mem(SwapEventsMax)),
+            ViewItem::from_default(Mem(SwapEventsFail)),
         ])
     });
 
@@ -309,4 +387,67 @@ pub mod default_tabs {
             ViewItem::from_default(Pressure(IoFullPct)),
         ])
     });
+
+    pub static CGROUP_NET_TAB: Lazy<CgroupTab> = Lazy::new(|| {
+        CgroupTab::new(vec![
+            ViewItem::from_default(Net(SendBytesPerSec)),
+            ViewItem::from_default(Net(RecvBytesPerSec)),
+            ViewItem::from_default(Net(TcpSendBytesPerSec)),
+            ViewItem::from_default(Net(TcpRecvBytesPerSec)),
+            ViewItem::from_default(Net(UdpSendBytesPerSec)),
+            ViewItem::from_default(Net(UdpRecvBytesPerSec)),
+        ])
+    });
+
+    pub static CGROUP_SCHED_TAB: Lazy<CgroupTab> = Lazy::new(|| {
+        CgroupTab::new(vec![
+            ViewItem::from_default(Pressure(CpuSomePct)),
+            ViewItem::from_default(RunqLat(P50Usec)),
+            ViewItem::from_default(RunqLat(P99Usec)),
+            ViewItem::from_default(RunqLat(MaxUsec)),
+        ])
+    });
+
+    pub static CGROUP_HUGETLB_TAB: Lazy<CgroupTab> = Lazy::new(|| {
+        CgroupTab::new(vec![
+            ViewItem::from_default(Hugetlb(HugetlbCurrent)),
+            ViewItem::from_default(Hugetlb(HugetlbEventsMax)),
+            ViewItem::from_default(Rdma(HcaHandle)),
+            ViewItem::from_default(Rdma(HcaObject)),
+            // Encrypted VM ASIDs, the only misc resources so far
+            ViewItem::from_default(Misc(model::BTreeMapFieldId {
+                key: "sev".to_owned(),
+                subquery_id: model::CgroupMiscModelFieldId::Current,
+            })),
+            ViewItem::from_default(Misc(model::BTreeMapFieldId {
+                key: "sev_es".to_owned(),
+                subquery_id: model::CgroupMiscModelFieldId::Current,
+            })),
+        ])
+    });
+
+    /// Built-in tabs in the order they are shown.
+    pub static CGROUP_TABS: Lazy<Vec<(&'static str, &'static CgroupTab)>> = Lazy::new(|| {
+        vec![
+            ("General", &*CGROUP_GENERAL_TAB),
+            ("CPU", &*CGROUP_CPU_TAB),
+            ("Mem", &*CGROUP_MEM_TAB),
+            ("I/O", &*CGROUP_IO_TAB),
+            ("Pressure", &*CGROUP_PRESSURE_TAB),
+            ("Net", &*CGROUP_NET_TAB),
+            ("Sched", &*CGROUP_SCHED_TAB),
+            ("Hugetlb", &*CGROUP_HUGETLB_TAB),
+        ]
+    });
+
+    /// The column of field_id as shown in the built-in tabs, or its default
+    /// if no built-in tab shows it.
+    pub fn get_view_item(field_id: CgroupModelFieldId) -> CgroupViewItem {
+        CGROUP_TABS
+            .iter()
+            .flat_map(|(_, tab)| tab.view_items.iter())
+            .find(|item| item.field_id == field_id)
+            .cloned()
+            .unwrap_or_else(|| ViewItem::from_default(field_id))
+    }
 }
@@ -21,17 +21,17 @@ use cursive::view::Identifiable;
 use cursive::views::{NamedView, SelectView, ViewRef};
 use cursive::Cursive;
 
-use crate::cgroup_tabs::{
-    default_tabs::{
-        CGROUP_CPU_TAB, CGROUP_GENERAL_TAB, CGROUP_IO_TAB, CGROUP_MEM_TAB, CGROUP_PRESSURE_TAB,
-    },
-    CgroupTab,
-};
+use anyhow::{anyhow, bail, Result};
+
+use crate::cgroup_tabs::{default_tabs, default_tabs::CGROUP_TABS, CgroupTab};
+use crate::filter_popup::parse_filter_expr;
+use crate::graph_view::GraphQuery;
+use crate::render::{hide_view_item, move_view_item, show_view_item};
 use crate::stats_view::{StateCommon, StatsView, ViewBridge};
 use crate::ViewState;
 use model::{
     CgroupCpuModelFieldId, CgroupIoModelFieldId, CgroupMemoryModelFieldId, CgroupModel,
-    CgroupModelFieldId,
+    CgroupModelFieldId, FilterExpr, Queriable,
 };
 
 pub type ViewType = StatsView<CgroupView>;
@@ -44,8 +44,11 @@ pub struct CgroupState {
     pub collapsed_cgroups: Rc<RefCell<HashSet<String>>>,
     pub current_selected_cgroup: String,
     pub filter: Option<String>,
+    // The filter parsed once when it is set, None if it is a plain name
+    pub filter_expr: Option<FilterExpr<CgroupModelFieldId>>,
     pub sort_order: Option<CgroupModelFieldId>,
-    pub sort_tags: HashMap<String, &'static CgroupTab>,
+    // Shared with the tabs of CgroupView so that columns can be changed at runtime
+    pub sort_tags: HashMap<String, Rc<RefCell<CgroupTab>>>,
     pub reverse: bool,
     pub model: Rc<RefCell<CgroupModel>>,
     pub collapse_all_top_level_cgroup: bool,
@@ -58,6 +61,15 @@ impl StateCommon for CgroupState {
         &mut self.filter
     }
 
+    fn set_filter(&mut self, filter: Option<String>) -> Result<()> {
+        self.filter_expr = match &filter {
+            Some(f) => parse_filter_expr(f)?,
+            None => None,
+        };
+        self.filter = filter;
+        Ok(())
+    }
+
     fn set_sort_tag(&mut self, sort_order: Self::TagType, reverse: &mut bool) -> bool {
         let sort_order = Some(sort_order);
         if self.sort_order == sort_order {
@@ -77,6 +89,7 @@ impl StateCommon for CgroupState {
                 .sort_tags
                 .get(tab)
                 .unwrap_or_else(|| panic!("Fail to find tab: {}", tab))
+                .borrow()
                 .view_items
                 .get(idx - 1)
                 .expect("Out of title scope")
@@ -95,6 +108,74 @@ impl StateCommon for CgroupState {
         }
     }
 
+    fn get_graph_query(&self, tab: &str, idx: usize, selection: &str) -> Option<GraphQuery> {
+        // The name column is not a value to graph
+        let item = self
+            .sort_tags
+            .get(tab)?
+            .borrow()
+            .view_items
+            .get(idx.checked_sub(1)?)?
+            .clone();
+        let field_id = item.field_id.clone();
+        let full_path = selection.trim_start_matches("[RECREATED] ").to_string();
+        Some(GraphQuery {
+            title: format!(
+                "{} of {}",
+                item.config.render_title().trim(),
+                if full_path.is_empty() {
+                    "<root>"
+                } else {
+                    full_path.as_str()
+                }
+            ),
+            getter: Box::new(move |model: &model::Model| {
+                get_cgroup_by_path(&model.cgroup, &full_path)?.query(&field_id)
+            }),
+            render_config: item.config.render_config.clone(),
+        })
+    }
+
+    fn move_column(&mut self, tab: &str, idx: usize, right: bool) -> Result<usize> {
+        let idx = idx
+            .checked_sub(1)
+            .ok_or_else(|| anyhow!("Name column cannot be moved"))?;
+        Ok(move_view_item(&mut self.get_tab(tab)?.borrow_mut().view_items, idx, right)? + 1)
+    }
+
+    fn hide_column(&mut self, tab: &str, idx: usize) -> Result<()> {
+        let idx = idx
+            .checked_sub(1)
+            .ok_or_else(|| anyhow!("Name column cannot be hidden"))?;
+        hide_view_item(&mut self.get_tab(tab)?.borrow_mut().view_items, idx)
+    }
+
+    fn show_column(&mut self, tab: &str, idx: usize, field: &str) -> Result<usize> {
+        use std::str::FromStr;
+        let field_id = Self::TagType::from_str(field)
+            .map_err(|_| anyhow!("Unknown cgroup field: {}", field))?;
+        if field_id == Self::TagType::Name {
+            bail!("Name column is always shown");
+        }
+        Ok(show_view_item(
+            &mut self.get_tab(tab)?.borrow_mut().view_items,
+            idx.checked_sub(1),
+            default_tabs::get_view_item(field_id),
+        )? + 1)
+    }
+
+    fn get_columns(&self, tab: &str) -> Option<Vec<String>> {
+        Some(
+            self.sort_tags
+                .get(tab)?
+                .borrow()
+                .view_items
+                .iter()
+                .map(|item| item.field_id.to_string())
+                .collect(),
+        )
+    }
+
     fn get_model(&self) -> Ref<Self::ModelType> {
         self.model.borrow()
     }
@@ -104,16 +185,15 @@ impl StateCommon for CgroupState {
     }
 
     fn new(model: Rc<RefCell<Self::ModelType>>) -> Self {
-        let mut sort_tags = HashMap::new();
-        sort_tags.insert("General".into(), &*CGROUP_GENERAL_TAB);
-        sort_tags.insert("CPU".into(), &*CGROUP_CPU_TAB);
-        sort_tags.insert("Mem".into(), &*CGROUP_MEM_TAB);
-        sort_tags.insert("I/O".into(), &*CGROUP_IO_TAB);
-        sort_tags.insert("Pressure".into(), &*CGROUP_PRESSURE_TAB);
+        let sort_tags = CGROUP_TABS
+            .iter()
+            .map(|(name, tab)| (name.to_string(), Rc::new(RefCell::new((*tab).clone()))))
+            .collect();
         Self {
             collapsed_cgroups: Rc::new(RefCell::new(HashSet::new())),
             current_selected_cgroup: "<root>".into(),
             filter: None,
+            filter_expr: None,
             sort_order: None,
             sort_tags,
             reverse: false,
@@ -135,11 +215,28 @@ impl CgroupState {
     fn toggle_collapse_root_flag(&mut self) {
         self.collapse_all_top_level_cgroup = !self.collapse_all_top_level_cgroup;
     }
+
+    fn get_tab(&self, tab: &str) -> Result<&Rc<RefCell<CgroupTab>>> {
+        self.sort_tags
+            .get(tab)
+            .ok_or_else(|| anyhow!("Fail to find tab: {}", tab))
+    }
+}
+
+/// Find the cgroup with the full path in the cgroup tree.
+fn get_cgroup_by_path<'a>(cgroup: &'a CgroupModel, full_path: &str) -> Option<&'a CgroupModel> {
+    if cgroup.full_path == full_path {
+        return Some(cgroup);
+    }
+    cgroup
+        .children
+        .iter()
+        .filter(|child| full_path.starts_with(&child.full_path))
+        .find_map(|child| get_cgroup_by_path(child, full_path))
 }
 
-// TODO: Make CgroupView a collection of CgroupTab
 pub struct CgroupView {
-    tab: &'static CgroupTab,
+    tab: Rc<RefCell<CgroupTab>>,
 }
 
 impl CgroupView {
@@ -191,55 +288,28 @@ impl CgroupView {
             });
         });
 
-        let tabs = vec![
-            "General".into(),
-            "CPU".into(),
-            "Mem".into(),
-            "I/O".into(),
-            "Pressure".into(),
-        ];
-        let mut tabs_map: HashMap<String, CgroupView> = HashMap::new();
-        tabs_map.insert(
-            "General".into(),
-            CgroupView {
-                tab: &*CGROUP_GENERAL_TAB,
-            },
-        );
-        tabs_map.insert(
-            "CPU".into(),
-            CgroupView {
-                tab: &*CGROUP_CPU_TAB,
-            },
-        );
-        tabs_map.insert(
-            "Mem".into(),
-            CgroupView {
-                tab: &*CGROUP_MEM_TAB,
-            },
-        );
-        tabs_map.insert(
-            "I/O".into(),
-            CgroupView {
-                tab: &*CGROUP_IO_TAB,
-            },
-        );
-        tabs_map.insert(
-            "Pressure".into(),
-            CgroupView {
-                tab: &*CGROUP_PRESSURE_TAB,
-            },
-        );
         let user_data = c
             .user_data::<ViewState>()
             .expect("No data stored in Cursive Object!");
+        let state = CgroupState::new(user_data.cgroup.clone());
+        let tabs = CGROUP_TABS
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect();
+        let tabs_map: HashMap<String, CgroupView> = state
+            .sort_tags
+            .iter()
+            .map(|(name, tab)| (name.clone(), CgroupView { tab: tab.clone() }))
+            .collect();
         StatsView::new(
             "Cgroup",
             tabs,
             tabs_map,
             list,
-            CgroupState::new(user_data.cgroup.clone()),
+            state,
             user_data.event_controllers.clone(),
             user_data.cmd_controllers.clone(),
+            user_data.graph.clone(),
         )
         .feed_data(c)
         .on_event('C', |c| {
@@ -273,6 +343,29 @@ impl CgroupView {
         ViewType::get_view(c)
     }
 
+    /// Add a tab of the given columns, or replace the columns of the tab of
+    /// the same name.
+    pub fn set_tab(c: &mut Cursive, name: &str, fields: Vec<CgroupModelFieldId>) {
+        let mut view = Self::get_cgroup_view(c);
+        let tab = CgroupTab::from_fields(fields);
+        let existing = view.state.borrow().sort_tags.get(name).cloned();
+        let tab = match existing {
+            Some(existing) => {
+                *existing.borrow_mut() = tab;
+                existing
+            }
+            None => {
+                let tab = Rc::new(RefCell::new(tab));
+                view.state
+                    .borrow_mut()
+                    .sort_tags
+                    .insert(name.to_string(), tab.clone());
+                tab
+            }
+        };
+        view.set_tab(name.to_string(), CgroupView { tab });
+    }
+
     pub fn refresh(c: &mut Cursive) {
         let mut view = Self::get_cgroup_view(c);
         view.refresh(c);
@@ -295,7 +388,7 @@ impl ViewBridge for CgroupView {
         "cgroup_view"
     }
     fn get_title_vec(&self) -> Vec<String> {
-        self.tab.get_title_vec()
+        self.tab.borrow().get_title_vec()
     }
 
     fn get_rows(
@@ -303,6 +396,6 @@ impl ViewBridge for CgroupView {
         state: &Self::StateType,
         offset: Option<usize>,
     ) -> Vec<(StyledString, String)> {
-        self.tab.get_rows(state, offset)
+        self.tab.borrow().get_rows(state, offset)
     }
 }
@@ -0,0 +1,350 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compare view shows the displayed sample side by side with another sample:
+//! the value of each field at both times with the delta, and the cgroups and
+//! processes that appeared, disappeared or were recreated in between.
+use std::collections::BTreeSet;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Local};
+use cursive::event::{EventTrigger, Key};
+use cursive::theme::{BaseColor, Color};
+use cursive::utils::markup::StyledString;
+use cursive::view::{Identifiable, Scrollable, View};
+use cursive::views::{Dialog, EditView, LinearLayout, OnEventView, ResizedView, TextView};
+use cursive::Cursive;
+
+use base_render::HasRenderConfig;
+use common::dateutil;
+use model::{CgroupModel, Model, Presence, ProcessModel, SystemModel};
+
+use crate::{MainViewState, ViewMode, ViewState};
+
+const TITLE_WIDTH: usize = 40;
+const VALUE_WIDTH: usize = 16;
+
+/// Models of one side of a comparison.
+struct Side<'a> {
+    timestamp: SystemTime,
+    system: &'a SystemModel,
+    cgroup: &'a CgroupModel,
+    process: &'a ProcessModel,
+}
+
+/// Parse the time to compare with. Relative time is counted backward from
+/// the displayed sample.
+fn parse_time(input: &str, timestamp: SystemTime) -> Result<SystemTime> {
+    let input = input.trim();
+    if let Ok(d) = input.parse::<humantime::Duration>() {
+        return timestamp
+            .checked_sub(d.into())
+            .ok_or_else(|| anyhow!("Time value is too large: {}", input));
+    }
+    match dateutil::HgTime::parse(input) {
+        Some(pt) => Ok(std::time::UNIX_EPOCH + Duration::from_secs(pt.unixtime)),
+        None => bail!("Failed to parse time value: {}", input),
+    }
+}
+
+fn format_time(timestamp: SystemTime) -> String {
+    DateTime::<Local>::from(timestamp)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+fn append_section(content: &mut StyledString, title: &str) {
+    content.append_plain("\n");
+    content.append_styled(title, Color::Light(BaseColor::Cyan));
+    content.append_plain("\n");
+    content.append_plain(format!(
+        "{:<title_width$} {:>value_width$} {:>value_width$} {:>value_width$}\n",
+        "Field",
+        "Base",
+        "Target",
+        "Delta",
+        title_width = TITLE_WIDTH,
+        value_width = VALUE_WIDTH
+    ));
+}
+
+/// Append one line per field with its value in base and target, and the delta.
+/// Changed fields are highlighted.
+fn append_fields<T: HasRenderConfig>(
+    content: &mut StyledString,
+    field_ids: impl Iterator<Item = T::FieldId>,
+    base: Option<&T>,
+    target: Option<&T>,
+) where
+    T::FieldId: ToString,
+{
+    for field_id in field_ids {
+        let rc = T::get_render_config(&field_id);
+        let base_field = base.and_then(|model| model.query(&field_id));
+        let target_field = target.and_then(|model| model.query(&field_id));
+        let delta = rc.render_delta(base_field.clone(), target_field.clone(), false);
+        let line = format!(
+            "{:<title_width$} {:>value_width$} {:>value_width$} {:>value_width$}\n",
+            field_id.to_string(),
+            rc.render(base_field, false),
+            rc.render(target_field, false),
+            delta,
+            title_width = TITLE_WIDTH,
+            value_width = VALUE_WIDTH
+        );
+        if delta.is_empty() || delta == "0" {
+            content.append_plain(line);
+        } else {
+            content.append_styled(line, Color::Light(BaseColor::Yellow));
+        }
+    }
+}
+
+/// Append the entities that did not exist at both times, grouped by Presence.
+fn append_presence(content: &mut StyledString, title: &str, entities: &[(Presence, String)]) {
+    for (presence, color) in &[
+        (Presence::Appeared, Color::Light(BaseColor::Green)),
+        (Presence::Disappeared, Color::Light(BaseColor::Red)),
+        (Presence::Recreated, Color::Light(BaseColor::Yellow)),
+    ] {
+        let names: Vec<&String> = entities
+            .iter()
+            .filter(|(p, _)| p == presence)
+            .map(|(_, name)| name)
+            .collect();
+        content.append_plain("\n");
+        content.append_styled(
+            format!("{} {} ({})\n", title, presence, names.len()),
+            *color,
+        );
+        for name in names {
+            content.append_styled(format!("  {}\n", name), *color);
+        }
+    }
+}
+
+fn cgroup_presence(base: &CgroupModel, target: &CgroupModel) -> Vec<(Presence, String)> {
+    let base_cgroups = model::flatten_cgroups(base);
+    let target_cgroups = model::flatten_cgroups(target);
+    let paths: BTreeSet<&str> = base_cgroups
+        .keys()
+        .chain(target_cgroups.keys())
+        .copied()
+        .collect();
+    paths
+        .into_iter()
+        .map(|path| {
+            (
+                Presence::of_cgroup(
+                    base_cgroups.get(path).copied(),
+                    target_cgroups.get(path).copied(),
+                ),
+                if path.is_empty() { "<root>" } else { path }.to_owned(),
+            )
+        })
+        .filter(|(presence, _)| *presence != Presence::Both)
+        .collect()
+}
+
+fn process_presence(
+    base: &ProcessModel,
+    target: &ProcessModel,
+    elapsed: Duration,
+) -> Vec<(Presence, String)> {
+    let pids: BTreeSet<&i32> = base
+        .processes
+        .keys()
+        .chain(target.processes.keys())
+        .collect();
+    pids.into_iter()
+        .filter_map(|pid| {
+            let base_process = base.processes.get(pid);
+            let target_process = target.processes.get(pid);
+            let presence = Presence::of_process(base_process, target_process, elapsed);
+            let comm = target_process
+                .or(base_process)
+                .and_then(|spm| spm.comm.as_deref())
+                .unwrap_or("?");
+            if presence == Presence::Both {
+                None
+            } else {
+                Some((presence, format!("{:<8} {}", pid, comm)))
+            }
+        })
+        .collect()
+}
+
+/// Render the comparison. `selection` is the selected row of the main view,
+/// i.e. a cgroup path or a pid, whose fields are compared in full.
+fn get_content(
+    base: &Side,
+    target: &Side,
+    main_view_state: &MainViewState,
+    selection: Option<&str>,
+) -> StyledString {
+    let elapsed = target
+        .timestamp
+        .duration_since(base.timestamp)
+        .unwrap_or_default();
+    let mut content = StyledString::plain(format!(
+        "Base:   {}\nTarget: {}\nElapsed: {}\n",
+        format_time(base.timestamp),
+        format_time(target.timestamp),
+        humantime::format_duration(elapsed)
+    ));
+
+    match (main_view_state, selection) {
+        (MainViewState::Cgroup, Some(selection)) => {
+            let path = selection.trim_start_matches("[RECREATED] ");
+            let base_cgroups = model::flatten_cgroups(base.cgroup);
+            let target_cgroups = model::flatten_cgroups(target.cgroup);
+            append_section(
+                &mut content,
+                &format!("Cgroup {}", if path.is_empty() { "<root>" } else { path }),
+            );
+            append_fields(
+                &mut content,
+                model::CgroupModelFieldId::all_variant_iter(),
+                base_cgroups.get(path).copied(),
+                target_cgroups.get(path).copied(),
+            );
+        }
+        (MainViewState::Process, Some(selection))
+        | (MainViewState::ProcessZoomedIntoCgroup, Some(selection)) => {
+            if let Ok(pid) = selection.trim().parse::<i32>() {
+                append_section(&mut content, &format!("Process {}", pid));
+                append_fields(
+                    &mut content,
+                    model::SingleProcessModelFieldId::all_variant_iter(),
+                    base.process.processes.get(&pid),
+                    target.process.processes.get(&pid),
+                );
+            }
+        }
+        _ => {}
+    }
+
+    append_section(&mut content, "System");
+    append_fields(
+        &mut content,
+        model::SystemModelFieldId::all_variant_iter(),
+        Some(base.system),
+        Some(target.system),
+    );
+
+    append_presence(
+        &mut content,
+        "Cgroups",
+        &cgroup_presence(base.cgroup, target.cgroup),
+    );
+    append_presence(
+        &mut content,
+        "Processes",
+        &process_presence(base.process, target.process, elapsed),
+    );
+
+    content
+}
+
+/// Compare the displayed sample with the sample at the given time. The
+/// earlier of the two is the base of the comparison.
+pub fn compare(c: &mut Cursive, input: &str, selection: Option<String>) -> Result<()> {
+    let view_state = c
+        .user_data::<ViewState>()
+        .expect("No data stored in Cursive object!");
+    let timestamp = parse_time(input, view_state.timestamp)?;
+    let adv = match &view_state.mode {
+        ViewMode::Live(adv) | ViewMode::Pause(adv) | ViewMode::Replay(adv) => adv.clone(),
+    };
+    // Do not move the Advance away from the displayed sample.
+    let other_model: Model = adv
+        .borrow_mut()
+        .get_models_before(timestamp, 1)
+        .pop()
+        .ok_or_else(|| anyhow!("Cannot find available data sample"))?;
+
+    let (system, cgroup, process) = (
+        view_state.system.borrow(),
+        view_state.cgroup.borrow(),
+        view_state.process.borrow(),
+    );
+    let displayed = Side {
+        timestamp: view_state.timestamp,
+        system: &system,
+        cgroup: &cgroup,
+        process: &process,
+    };
+    let other = Side {
+        timestamp: other_model.timestamp,
+        system: &other_model.system,
+        cgroup: &other_model.cgroup,
+        process: &other_model.process,
+    };
+    let (base, target) = if other.timestamp <= displayed.timestamp {
+        (&other, &displayed)
+    } else {
+        (&displayed, &other)
+    };
+    let content = get_content(
+        base,
+        target,
+        &view_state.main_view_state,
+        selection.as_deref(),
+    );
+    drop((system, cgroup, process));
+
+    c.add_fullscreen_layer(ResizedView::with_full_screen(
+        OnEventView::new(
+            Dialog::around(TextView::new(content).scrollable())
+                .title("Compare")
+                .dismiss_button("Close"),
+        )
+        .on_event(EventTrigger::from('q').or(Key::Esc), |c| {
+            c.pop_layer();
+        }),
+    ));
+    Ok(())
+}
+
+/// Popup asking for the time to compare the displayed sample with.
+pub fn new(selection: Option<String>) -> impl View {
+    OnEventView::new(
+        Dialog::new()
+            .title("Compare with which sample?")
+            .padding_lrtb(1, 1, 1, 0)
+            .content(
+                LinearLayout::vertical()
+                    .child(
+                        EditView::new()
+                            .on_submit(move |c, input| {
+                                c.pop_layer();
+                                if let Err(e) = compare(c, input, selection.clone()) {
+                                    view_warn!(c, "{}", e);
+                                }
+                            })
+                            .with_name("compare_popup"),
+                    )
+                    .child(TextView::new("e.g:"))
+                    .child(TextView::new(
+                        "  Relative Time (backward): 10s or 3h5m or 2d",
+                    ))
+                    .child(TextView::new("  Absolute time: 10:00am")),
+            )
+            .dismiss_button("Close"),
+    )
+    .on_event(Key::Esc, |s| {
+        s.pop_layer();
+    })
+}
@@ -13,7 +13,12 @@
 // limitations under the License.
 
 use super::*;
-use crate::filter_popup;
+use std::time::Duration;
+
+use cgroupfs::Knob;
+use model::WindowStat;
+
+use crate::{action_popup, compare_view, filter_popup, graph_view, MainViewState, ViewMode};
 
 // Sort by selected column
 make_event_controller!(
@@ -60,10 +65,8 @@ make_event_controller!(
     |_view: &mut StatsView<T>, _cmd_vec: &[&str]| {},
     |c: &mut Cursive, cmd_vec: &[&str]| {
         if cmd_vec.len() > 1 {
-            let state = StatsView::<T>::get_view(c).state.clone();
             let text = cmd_vec[1..].join(" ");
-            *state.borrow_mut().get_filter() = Some(text.to_string());
-            StatsView::<T>::cp_filter(c, Some(text));
+            StatsView::<T>::set_filter(c, Some(text));
             StatsView::<T>::refresh_myself(c);
         } else {
             let state = StatsView::<T>::get_view(c).state.clone();
@@ -72,6 +75,42 @@ make_event_controller!(
     }
 );
 
+fn search_row<T: 'static + ViewBridge>(c: &mut Cursive, text: &str) {
+    let cb = StatsView::<T>::get_view(c).search(text);
+    match cb {
+        Some(cb) => cb(c),
+        None => StatsView::<T>::cp_warn(c, &format!("No row matches \"{}\"", text)),
+    }
+}
+
+// Jump to the next row that contains the text
+make_event_controller!(
+    SearchImpl,
+    "search",
+    "sr",
+    Event::CtrlChar('f'),
+    |_view: &mut StatsView<T>, _cmd_vec: &[&str]| {},
+    |c: &mut Cursive, cmd_vec: &[&str]| {
+        if cmd_vec.len() > 1 {
+            search_row::<T>(c, &cmd_vec[1..].join(" "));
+        } else {
+            let last_search = StatsView::<T>::get_view(c)
+                .last_search
+                .clone()
+                .unwrap_or_default();
+            c.add_layer(crate::input_popup::new(
+                "Jump to the next row containing",
+                &last_search,
+                |c, text| {
+                    if !text.is_empty() {
+                        search_row::<T>(c, text);
+                    }
+                },
+            ));
+        }
+    }
+);
+
 // Clear filter
 make_event_controller!(
     ClearFilter,
@@ -80,9 +119,279 @@ make_event_controller!(
     Event::CtrlChar('l'),
     |_view: &mut StatsView<T>, _cmd_vec: &[&str]| {},
     |c: &mut Cursive, _cmd_vec: &[&str]| {
-        let state = StatsView::<T>::get_view(c).state.clone();
-        *state.borrow_mut().get_filter() = None;
-        StatsView::<T>::cp_filter(c, None);
+        StatsView::<T>::set_filter(c, None);
         StatsView::<T>::refresh_myself(c);
     }
 );
+
+// Graph the selected column of the selected row over previous samples
+make_event_controller!(
+    GraphImpl,
+    "graph",
+    "gr",
+    Event::Char('G'),
+    |_view: &mut StatsView<T>, _cmd_vec: &[&str]| {},
+    |c: &mut Cursive, cmd_vec: &[&str]| {
+        let samples = match cmd_vec.get(1).map(|n| n.parse::<usize>()) {
+            None => graph_view::DEFAULT_GRAPH_SAMPLES,
+            Some(Ok(n)) if n > 0 => n,
+            _ => {
+                StatsView::<T>::cp_warn(c, "Number of samples should be a positive integer");
+                return;
+            }
+        };
+
+        let query = {
+            let mut view = StatsView::<T>::get_view(c);
+            let tab = view.get_tab_view().get_cur_selected().to_string();
+            let title_view = view.get_title_view();
+            let title_idx = title_view.current_selected;
+            let title = title_view.get_cur_selected().trim().to_string();
+            let selection = view
+                .get_detail_view()
+                .selection()
+                .map_or("".into(), |selection| selection.to_string());
+            let query = view
+                .state
+                .borrow()
+                .get_graph_query(&tab, title_idx, &selection);
+            if query.is_none() {
+                view.set_alert(&format!("\"{}\" cannot be graphed.", title));
+            }
+            query
+        };
+
+        if let Some(query) = query {
+            graph_view::toggle(c, T::get_view_name(), query, samples);
+            StatsView::<T>::refresh_myself(c);
+        }
+    }
+);
+
+// Compare the displayed sample with another sample
+make_event_controller!(
+    CompareImpl,
+    "compare",
+    "cmp",
+    Event::Char('x'),
+    |_view: &mut StatsView<T>, _cmd_vec: &[&str]| {},
+    |c: &mut Cursive, cmd_vec: &[&str]| {
+        let selection = StatsView::<T>::get_view(c)
+            .get_detail_view()
+            .selection()
+            .map(|selection| selection.to_string());
+        if cmd_vec.len() > 1 {
+            if let Err(e) = compare_view::compare(c, &cmd_vec[1..].join(" "), selection) {
+                StatsView::<T>::cp_warn(c, &e.to_string());
+            }
+        } else {
+            c.add_layer(compare_view::new(selection));
+        }
+    }
+);
+
+/// Window length used when the window view is toggled on.
+const DEFAULT_WINDOW: Duration = Duration::from_secs(600);
+
+fn set_window<T: 'static + ViewBridge>(c: &mut Cursive, window: Option<(Duration, WindowStat)>) {
+    let res = c
+        .user_data::<ViewState>()
+        .expect("No data stored in Cursive object!")
+        .set_window(window);
+    if let Err(e) = res {
+        StatsView::<T>::cp_warn(c, &e.to_string());
+    }
+    crate::refresh(c);
+}
+
+fn get_window(c: &mut Cursive) -> Option<(Duration, WindowStat)> {
+    c.user_data::<ViewState>()
+        .expect("No data stored in Cursive object!")
+        .window
+}
+
+// Toggle statistics over a window of samples in the cgroup and process views
+make_event_controller!(
+    WindowImpl,
+    "window",
+    "win",
+    Event::Char('w'),
+    |_view: &mut StatsView<T>, _cmd_vec: &[&str]| {},
+    |c: &mut Cursive, cmd_vec: &[&str]| {
+        let window = get_window(c);
+        if cmd_vec.len() == 1 {
+            set_window::<T>(
+                c,
+                match window {
+                    Some(_) => None,
+                    None => Some((DEFAULT_WINDOW, WindowStat::Mean)),
+                },
+            );
+            return;
+        }
+
+        let (mut length, mut stat) = window.unwrap_or((DEFAULT_WINDOW, WindowStat::Mean));
+        for arg in &cmd_vec[1..] {
+            if *arg == "off" {
+                set_window::<T>(c, None);
+                return;
+            } else if let Ok(s) = arg.parse::<WindowStat>() {
+                stat = s;
+            } else if let Ok(d) = arg.parse::<humantime::Duration>() {
+                length = d.into();
+            } else {
+                StatsView::<T>::cp_warn(
+                    c,
+                    &format!("Expect a window length, mean, max, p95 or off: {}", arg),
+                );
+                return;
+            }
+        }
+        set_window::<T>(c, Some((length, stat)));
+    }
+);
+
+// Double the window length
+make_event_controller!(
+    WindowExtendImpl,
+    "window_extend",
+    "we",
+    Event::Char('+'),
+    |_view: &mut StatsView<T>, _cmd_vec: &[&str]| {},
+    |c: &mut Cursive, _cmd_vec: &[&str]| {
+        if let Some((length, stat)) = get_window(c) {
+            set_window::<T>(c, Some((length * 2, stat)));
+        }
+    }
+);
+
+// Halve the window length
+make_event_controller!(
+    WindowShrinkImpl,
+    "window_shrink",
+    "ws",
+    Event::Char('-'),
+    |_view: &mut StatsView<T>, _cmd_vec: &[&str]| {},
+    |c: &mut Cursive, _cmd_vec: &[&str]| {
+        if let Some((length, stat)) = get_window(c) {
+            let length = std::cmp::max(length / 2, Duration::from_secs(1));
+            set_window::<T>(c, Some((length, stat)));
+        }
+    }
+);
+
+// Actions change the running system, so they are only offered on live
+// samples, never on history.
+fn is_live(c: &mut Cursive) -> bool {
+    matches!(
+        c.user_data::<ViewState>()
+            .expect("No data stored in Cursive object!")
+            .mode,
+        ViewMode::Live(_)
+    )
+}
+
+fn get_main_view_state(c: &mut Cursive) -> MainViewState {
+    c.user_data::<ViewState>()
+        .expect("No data stored in Cursive object!")
+        .main_view_state
+        .clone()
+}
+
+// Send a signal to the selected process
+make_event_controller!(
+    SignalImpl,
+    "signal",
+    "sig",
+    Event::Char('K'),
+    |_view: &mut StatsView<T>, _cmd_vec: &[&str]| {},
+    |c: &mut Cursive, cmd_vec: &[&str]| {
+        if !is_live(c) {
+            StatsView::<T>::cp_warn(c, "Signals can only be sent in live mode");
+            return;
+        }
+        match get_main_view_state(c) {
+            MainViewState::Process | MainViewState::ProcessZoomedIntoCgroup => {}
+            _ => {
+                StatsView::<T>::cp_warn(c, "Select a process in the process view to signal it");
+                return;
+            }
+        }
+        let pid = StatsView::<T>::get_view(c)
+            .get_detail_view()
+            .selection()
+            .and_then(|selection| selection.parse::<i32>().ok());
+        let pid = match pid {
+            Some(pid) => pid,
+            None => {
+                StatsView::<T>::cp_warn(c, "No process selected");
+                return;
+            }
+        };
+        let comm = c
+            .user_data::<ViewState>()
+            .expect("No data stored in Cursive object!")
+            .process
+            .borrow()
+            .processes
+            .get(&pid)
+            .and_then(|spm| spm.comm.clone())
+            .unwrap_or_else(|| "?".to_string());
+
+        match cmd_vec.get(1) {
+            Some(arg) => match action_popup::parse_signal(arg) {
+                Some((name, signal)) => {
+                    c.add_layer(action_popup::new_signal_confirm(pid, comm, name, signal))
+                }
+                None => StatsView::<T>::cp_warn(c, &format!("Unknown signal: {}", arg)),
+            },
+            None => c.add_layer(action_popup::new_signal(pid, comm)),
+        }
+    }
+);
+
+// Write memory.high, memory.max, cpu.max, cpu.weight or cgroup.freeze of the
+// selected cgroup
+make_event_controller!(
+    SetKnobImpl,
+    "set_knob",
+    "set",
+    Event::Char('W'),
+    |_view: &mut StatsView<T>, _cmd_vec: &[&str]| {},
+    |c: &mut Cursive, cmd_vec: &[&str]| {
+        if !is_live(c) {
+            StatsView::<T>::cp_warn(c, "Cgroups can only be adjusted in live mode");
+            return;
+        }
+        if get_main_view_state(c) != MainViewState::Cgroup {
+            StatsView::<T>::cp_warn(c, "Select a cgroup in the cgroup view to adjust it");
+            return;
+        }
+        let cgroup = StatsView::<T>::get_view(c)
+            .get_detail_view()
+            .selection()
+            .map_or("".into(), |selection| {
+                selection.trim_start_matches("[RECREATED] ").to_string()
+            });
+        if cgroup.is_empty() {
+            StatsView::<T>::cp_warn(c, "The root cgroup cannot be adjusted");
+            return;
+        }
+
+        match cmd_vec.len() {
+            1 => c.add_layer(action_popup::new_knob(cgroup)),
+            2 => StatsView::<T>::cp_warn(
+                c,
+                "Expect a file and a value, e.g. set_knob memory.high 4G",
+            ),
+            _ => match cmd_vec[1].parse::<Knob>() {
+                Ok(knob) => c.add_layer(action_popup::new_knob_confirm(
+                    cgroup,
+                    knob,
+                    cmd_vec[2..].join(" "),
+                )),
+                Err(e) => StatsView::<T>::cp_warn(c, &e),
+            },
+        }
+    }
+);
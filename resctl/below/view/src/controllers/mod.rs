@@ -99,13 +99,28 @@ make_controllers!(
     PrevTab: PrevTabImpl,
     NextCol: NextColImpl,
     PrevCol: PrevColImpl,
+    MoveColLeft: MoveColLeftImpl,
+    MoveColRight: MoveColRightImpl,
+    HideCol: HideColImpl,
+    ShowCol: ShowColImpl,
+    SaveLayout: SaveLayoutImpl,
     Right: RightImpl,
     Left: LeftImpl,
     SortCol: SortByColumn,
     Filter: FilterPopup,
+    Search: SearchImpl,
     CFilter: ClearFilter,
+    Graph: GraphImpl,
+    Compare: CompareImpl,
+    Window: WindowImpl,
+    WindowExtend: WindowExtendImpl,
+    WindowShrink: WindowShrinkImpl,
+    Signal: SignalImpl,
+    SetKnob: SetKnobImpl,
     JForward: JumpForward,
     JBackward: JumpBackward,
+    Events: EventsImpl,
+    Lived: LivedImpl,
     NSample: NextSample,
     PSample: PrevSample,
     Pause: PauseImpl,
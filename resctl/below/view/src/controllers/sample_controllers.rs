@@ -14,7 +14,7 @@
 
 use super::*;
 
-use crate::{jump_popup, ViewMode};
+use crate::{jump_popup, procevents_popup, ViewMode};
 use store::Direction;
 
 // Jump forward
@@ -67,6 +67,54 @@ make_event_controller!(
     }
 );
 
+// List events before the current sample
+make_event_controller!(
+    EventsImpl,
+    "events",
+    "ev",
+    Event::Char('E'),
+    |_view: &mut StatsView<T>, _cmd_vec: &[&str]| {},
+    |c: &mut Cursive, cmd_vec: &[&str]| {
+        let range = match cmd_vec
+            .get(1)
+            .map(|range| range.parse::<humantime::Duration>())
+        {
+            None => jump_popup::DEFAULT_EVENT_RANGE,
+            Some(Ok(range)) => range.into(),
+            Some(Err(_)) => {
+                StatsView::<T>::cp_warn(c, &format!("Expect a time range: {}", cmd_vec[1]));
+                return;
+            }
+        };
+        let (mode, timestamp) = {
+            let view_state = c.user_data::<ViewState>().expect("user data not set");
+            (view_state.mode.clone(), view_state.timestamp)
+        };
+        match mode {
+            ViewMode::Pause(adv) | ViewMode::Replay(adv) => {
+                c.add_layer(jump_popup::new_events(adv, timestamp, range));
+            }
+            _ => {}
+        }
+    }
+);
+
+// List processes that lived during the current sample
+make_event_controller!(
+    LivedImpl,
+    "lived_processes",
+    "lp",
+    Event::Char('X'),
+    |_view: &mut StatsView<T>, _cmd_vec: &[&str]| {},
+    |c: &mut Cursive, _cmd_vec: &[&str]| {
+        let popup = {
+            let view_state = c.user_data::<ViewState>().expect("user data not set");
+            procevents_popup::new(&view_state.proc_events, view_state.time_elapsed)
+        };
+        c.add_layer(popup);
+    }
+);
+
 // Next sample
 make_event_controller!(
     NextSample,
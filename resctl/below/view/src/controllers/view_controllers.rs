@@ -88,6 +88,118 @@ make_event_controller!(
     }
 );
 
+fn move_col<T: 'static + ViewBridge>(view: &mut StatsView<T>, right: bool) {
+    let tab = view.get_tab_view().get_cur_selected().to_string();
+    let idx = view.get_title_view().current_selected;
+    let res = view.state.borrow_mut().move_column(&tab, idx, right);
+    match res {
+        Ok(idx) => view.reload_title(idx),
+        Err(e) => view.set_alert(&e.to_string()),
+    }
+}
+
+fn show_col<T: 'static + ViewBridge>(view: &mut StatsView<T>, field: &str) {
+    let tab = view.get_tab_view().get_cur_selected().to_string();
+    let idx = view.get_title_view().current_selected;
+    let res = view.state.borrow_mut().show_column(&tab, idx, field);
+    match res {
+        Ok(idx) => view.reload_title(idx),
+        Err(e) => view.set_alert(&e.to_string()),
+    }
+}
+
+// Move the selected column to the left
+make_event_controller!(
+    MoveColLeftImpl,
+    "move_col_left",
+    "mcl",
+    Event::Char('<'),
+    |view: &mut StatsView<T>, _cmd_vec: &[&str]| {
+        move_col(view, false);
+    },
+    |c: &mut Cursive, _cmd_vec: &[&str]| {
+        StatsView::<T>::refresh_myself(c);
+    }
+);
+
+// Move the selected column to the right
+make_event_controller!(
+    MoveColRightImpl,
+    "move_col_right",
+    "mcr",
+    Event::Char('>'),
+    |view: &mut StatsView<T>, _cmd_vec: &[&str]| {
+        move_col(view, true);
+    },
+    |c: &mut Cursive, _cmd_vec: &[&str]| {
+        StatsView::<T>::refresh_myself(c);
+    }
+);
+
+// Hide the selected column
+make_event_controller!(
+    HideColImpl,
+    "hide_col",
+    "hc",
+    Event::Char('H'),
+    |view: &mut StatsView<T>, _cmd_vec: &[&str]| {
+        let tab = view.get_tab_view().get_cur_selected().to_string();
+        let idx = view.get_title_view().current_selected;
+        let res = view.state.borrow_mut().hide_column(&tab, idx);
+        match res {
+            Ok(()) => view.reload_title(idx),
+            Err(e) => view.set_alert(&e.to_string()),
+        }
+    },
+    |c: &mut Cursive, _cmd_vec: &[&str]| {
+        StatsView::<T>::refresh_myself(c);
+    }
+);
+
+// Show the column of a field id after the selected column
+make_event_controller!(
+    ShowColImpl,
+    "show_col",
+    "sc",
+    Event::Char('A'),
+    |view: &mut StatsView<T>, cmd_vec: &[&str]| {
+        if cmd_vec.len() > 1 {
+            show_col(view, &cmd_vec[1..].join(" "));
+        }
+    },
+    |c: &mut Cursive, cmd_vec: &[&str]| {
+        if cmd_vec.len() > 1 {
+            StatsView::<T>::refresh_myself(c);
+        } else {
+            c.add_layer(crate::input_popup::new(
+                "Show column of field (e.g. mem.anon)",
+                "",
+                |c, field| {
+                    show_col(&mut StatsView::<T>::get_view(c), field);
+                    StatsView::<T>::refresh_myself(c);
+                },
+            ));
+        }
+    }
+);
+
+// Save the columns of the cgroup and process view tabs to belowrc
+make_event_controller!(
+    SaveLayoutImpl,
+    "save_layout",
+    "sl",
+    Event::Char('L'),
+    |_view: &mut StatsView<T>, _cmd_vec: &[&str]| {},
+    |c: &mut Cursive, _cmd_vec: &[&str]| {
+        match crate::viewrc::ViewRc::save_layout(c) {
+            Ok(filename) => StatsView::<T>::get_view(c)
+                .get_cmd_palette()
+                .set_info(format!("Saved tab layout to {}", filename)),
+            Err(e) => StatsView::<T>::cp_warn(c, &format!("{:#}", e)),
+        }
+    }
+);
+
 // Right handler impl
 make_event_controller!(
     RightImpl,
@@ -18,7 +18,9 @@ use crate::stats_view::StateCommon;
 use base_render::{get_fixed_width, RenderConfigBuilder as Rc};
 use common::util::get_prefix;
 use model::system::{
-    MemoryModelFieldId, SingleCpuModelFieldId, SingleDiskModelFieldId, VmModelFieldId,
+    MemoryModelFieldId, SingleCpuModelFieldId, SingleDiskModelFieldId, SingleInterruptModel,
+    SingleInterruptModelFieldId, SingleSoftnetModelFieldId, SingleThermalZoneModelFieldId,
+    VmModelFieldId,
 };
 
 use cursive::utils::markup::StyledString;
@@ -80,6 +82,34 @@ impl CoreTab for CoreCpu {
                     "".to_owned(),
                 )
             })
+            .chain(
+                // Thermal zones are not tied to a CPU so list them after total
+                model
+                    .thermal_zones
+                    .values()
+                    .filter(|stzm| {
+                        if let Some(f) = &state.filter {
+                            stzm.zone_type
+                                .as_ref()
+                                .map_or(false, |zone_type| zone_type.starts_with(f))
+                        } else {
+                            true
+                        }
+                    })
+                    .map(|stzm| {
+                        (
+                            SingleThermalZoneModelFieldId::unit_variant_iter().fold(
+                                StyledString::new(),
+                                |mut line, field_id| {
+                                    line.append(ViewItem::from_default(field_id).render(stzm));
+                                    line.append_plain(" ");
+                                    line
+                                },
+                            ),
+                            "".to_owned(),
+                        )
+                    }),
+            )
             .collect()
     }
 }
@@ -193,3 +223,97 @@ impl CoreTab for CoreDisk {
             .collect()
     }
 }
+
+#[derive(Default, Clone)]
+pub struct CoreIrq;
+
+impl CoreIrq {
+    // Busiest sources first so the hot IRQ/CPU pair is at the top
+    fn sorted<'a>(
+        irqs: impl Iterator<Item = &'a SingleInterruptModel>,
+    ) -> Vec<&'a SingleInterruptModel> {
+        let mut irqs: Vec<_> = irqs.collect();
+        irqs.sort_by(|lhs, rhs| {
+            rhs.total_per_sec
+                .partial_cmp(&lhs.total_per_sec)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        irqs
+    }
+}
+
+impl CoreTab for CoreIrq {
+    fn get_title_vec(&self) -> Vec<String> {
+        SingleInterruptModelFieldId::unit_variant_iter()
+            .map(|field_id| ViewItem::from_default(field_id).config.render_title())
+            .collect()
+    }
+
+    fn get_rows(&self, state: &CoreState) -> Vec<(StyledString, String)> {
+        let model = state.get_model();
+        // Softirqs are listed before hardware interrupts
+        Self::sorted(model.softirqs.values())
+            .into_iter()
+            .chain(Self::sorted(model.interrupts.values()).into_iter())
+            .filter(|sim| {
+                if let Some(f) = &state.filter {
+                    sim.name.as_ref().map_or(false, |name| name.starts_with(f))
+                } else {
+                    true
+                }
+            })
+            .map(|sim| {
+                (
+                    SingleInterruptModelFieldId::unit_variant_iter().fold(
+                        StyledString::new(),
+                        |mut line, field_id| {
+                            line.append(ViewItem::from_default(field_id).render(sim));
+                            line.append_plain(" ");
+                            line
+                        },
+                    ),
+                    sim.name.clone().unwrap_or_default(),
+                )
+            })
+            .collect()
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct CoreSoftnet;
+
+impl CoreTab for CoreSoftnet {
+    fn get_title_vec(&self) -> Vec<String> {
+        SingleSoftnetModelFieldId::unit_variant_iter()
+            .map(|field_id| ViewItem::from_default(field_id).config.render_title())
+            .collect()
+    }
+
+    fn get_rows(&self, state: &CoreState) -> Vec<(StyledString, String)> {
+        let model = state.get_model();
+        model
+            .softnet
+            .values()
+            .filter(|ssm| {
+                if let Some(f) = &state.filter {
+                    ssm.idx.to_string().starts_with(f)
+                } else {
+                    true
+                }
+            })
+            .map(|ssm| {
+                (
+                    SingleSoftnetModelFieldId::unit_variant_iter().fold(
+                        StyledString::new(),
+                        |mut line, field_id| {
+                            line.append(ViewItem::from_default(field_id).render(ssm));
+                            line.append_plain(" ");
+                            line
+                        },
+                    ),
+                    "".to_owned(),
+                )
+            })
+            .collect()
+    }
+}
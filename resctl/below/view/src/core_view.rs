@@ -67,6 +67,8 @@ pub enum CoreView {
     Mem(CoreMem),
     Vm(CoreVm),
     Disk(CoreDisk),
+    Irq(CoreIrq),
+    Softnet(CoreSoftnet),
 }
 
 impl CoreView {
@@ -89,12 +91,21 @@ impl CoreView {
             }
         });
 
-        let tabs = vec!["CPU".into(), "Mem".into(), "Vm".into(), "Disk".into()];
+        let tabs = vec![
+            "CPU".into(),
+            "Mem".into(),
+            "Vm".into(),
+            "Disk".into(),
+            "Irq".into(),
+            "Softnet".into(),
+        ];
         let mut tabs_map: HashMap<String, CoreView> = HashMap::new();
         tabs_map.insert("CPU".into(), CoreView::Cpu(Default::default()));
         tabs_map.insert("Mem".into(), CoreView::Mem(Default::default()));
         tabs_map.insert("Vm".into(), CoreView::Vm(Default::default()));
         tabs_map.insert("Disk".into(), CoreView::Disk(Default::default()));
+        tabs_map.insert("Irq".into(), CoreView::Irq(Default::default()));
+        tabs_map.insert("Softnet".into(), CoreView::Softnet(Default::default()));
         let user_data = c
             .user_data::<ViewState>()
             .expect("No data stored in Cursive Object!");
@@ -106,6 +117,7 @@ impl CoreView {
             CoreState::new(user_data.system.clone()),
             user_data.event_controllers.clone(),
             user_data.cmd_controllers.clone(),
+            user_data.graph.clone(),
         )
         .feed_data(c)
         .with_name(Self::get_view_name())
@@ -125,6 +137,8 @@ impl CoreView {
             Self::Mem(inner) => Box::new(inner.clone()),
             Self::Vm(inner) => Box::new(inner.clone()),
             Self::Disk(inner) => Box::new(inner.clone()),
+            Self::Irq(inner) => Box::new(inner.clone()),
+            Self::Softnet(inner) => Box::new(inner.clone()),
         }
     }
 }
@@ -88,3 +88,9 @@ impl HasViewStyle for model::SingleCpuModel {}
 impl HasViewStyle for model::VmModel {}
 
 impl HasViewStyle for model::SingleDiskModel {}
+
+impl HasViewStyle for model::SingleInterruptModel {}
+
+impl HasViewStyle for model::SingleSoftnetModel {}
+
+impl HasViewStyle for model::SingleThermalZoneModel {}
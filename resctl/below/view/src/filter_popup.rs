@@ -14,6 +14,9 @@
 
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::str::FromStr;
+
+use anyhow::Result;
 
 use cursive::event::Key;
 use cursive::view::{Identifiable, View};
@@ -22,20 +25,33 @@ use cursive::Cursive;
 
 use crate::stats_view::StateCommon;
 use crate::MainViewState;
+use model::FilterExpr;
 
-// Set command palette filter
-fn set_cp_filter(c: &mut Cursive, text: Option<String>) {
+// Set filter of the current view and command palette
+fn set_filter(c: &mut Cursive, text: Option<String>) {
     let state = c
         .user_data::<crate::ViewState>()
         .expect("No user data")
         .main_view_state
         .clone();
     match state {
-        MainViewState::Cgroup => crate::cgroup_view::ViewType::cp_filter(c, text),
+        MainViewState::Cgroup => crate::cgroup_view::ViewType::set_filter(c, text),
         MainViewState::Process | MainViewState::ProcessZoomedIntoCgroup => {
-            crate::process_view::ViewType::cp_filter(c, text)
+            crate::process_view::ViewType::set_filter(c, text)
         }
-        MainViewState::Core => crate::core_view::ViewType::cp_filter(c, text),
+        MainViewState::Core => crate::core_view::ViewType::set_filter(c, text),
+    }
+}
+
+/// Parse a filter containing a comparison operator (e.g. "cpu.usage_pct > 50")
+/// as an expression. Anything else is a plain name filter and returns None.
+pub fn parse_filter_expr<F: FromStr<Err = anyhow::Error>>(
+    filter: &str,
+) -> Result<Option<FilterExpr<F>>> {
+    if filter.contains(|c| matches!(c, '=' | '<' | '>' | '~' | '&' | '|')) {
+        Ok(Some(filter.parse()?))
+    } else {
+        Ok(None)
     }
 }
 
@@ -43,16 +59,13 @@ pub fn new<F>(state: Rc<RefCell<impl StateCommon + 'static>>, refresh: F) -> imp
 where
     F: 'static + Copy + Fn(&mut Cursive),
 {
-    let submit_state = state.clone();
     let mut editview = EditView::new()
         // Run cb and close popup when user presses "Enter"
         .on_submit(move |c, text| {
             if text.is_empty() {
-                *submit_state.borrow_mut().get_filter() = None;
-                set_cp_filter(c, None);
+                set_filter(c, None);
             } else {
-                *submit_state.borrow_mut().get_filter() = Some(text.to_string());
-                set_cp_filter(c, Some(text.to_string()));
+                set_filter(c, Some(text.to_string()));
             }
             refresh(c);
             c.pop_layer();
@@ -68,7 +81,7 @@ where
 
     OnEventView::new(
         Dialog::new()
-            .title("Filter by name")
+            .title("Filter by name or expression (e.g. cpu.usage_pct > 50)")
             .padding_lrtb(1, 1, 1, 0)
             .content(editview.with_name("filter_popup"))
             .dismiss_button("Close")
@@ -78,11 +91,9 @@ where
                     .expect("Unable to find filter_popup");
 
                 if text.is_empty() {
-                    *state.borrow_mut().get_filter() = None;
-                    set_cp_filter(c, None);
+                    set_filter(c, None);
                 } else {
-                    *state.borrow_mut().get_filter() = Some(text.to_string());
-                    set_cp_filter(c, Some(text.to_string()));
+                    set_filter(c, Some(text.to_string()));
                 }
 
                 refresh(c);
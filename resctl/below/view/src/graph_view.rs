@@ -0,0 +1,281 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Graph view draws the value of the selected row and column over the
+//! previous samples below the detail stats, so a trend can be seen without
+//! stepping through time.
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Local};
+use cursive::view::View;
+use cursive::{Cursive, Printer, Vec2};
+
+use base_render::RenderConfig;
+use model::{Field, Model};
+use store::Advance;
+
+use crate::{ViewMode, ViewState};
+
+/// Number of samples to graph when not specified by the command.
+pub const DEFAULT_GRAPH_SAMPLES: usize = 60;
+/// Number of lines used to draw the bars.
+const GRAPH_HEIGHT: usize = 8;
+const BARS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Retrieve the graphed Field from a Model.
+pub type GraphGetter = Box<dyn Fn(&Model) -> Option<Field>>;
+
+/// Describe what to graph: a getter for the selected row and column, and
+/// how to render the value of that column.
+pub struct GraphQuery {
+    pub title: String,
+    pub getter: GraphGetter,
+    pub render_config: RenderConfig,
+}
+
+/// The graphed values of the latest samples. New samples are appended as
+/// they are shown, so the history is only read from the store when the graph
+/// is opened or the shown sample jumps.
+pub struct GraphData {
+    // Name of the StatsView the graph is shown in
+    view: &'static str,
+    query: GraphQuery,
+    samples: usize,
+    series: VecDeque<(SystemTime, Option<Field>)>,
+}
+
+fn field_to_f64(field: &Field) -> Option<f64> {
+    match field {
+        Field::Str(_) | Field::PidState(_) => None,
+        _ => Some(f64::from(field.clone())),
+    }
+}
+
+/// Render values as vertical bars, one column per value and `height` lines
+/// from top to bottom. Each line has eight levels of resolution.
+fn render_bars(values: &[Option<f64>], height: usize) -> Vec<String> {
+    let (lo, hi) = values
+        .iter()
+        .flatten()
+        .fold((0.0, 0.0), |(lo, hi): (f64, f64), v| {
+            (lo.min(*v), hi.max(*v))
+        });
+    let levels: Vec<Option<usize>> = values
+        .iter()
+        .map(|v| {
+            v.map(|v| {
+                if hi > lo {
+                    ((v - lo) / (hi - lo) * (height * 8) as f64).round() as usize
+                } else {
+                    0
+                }
+            })
+        })
+        .collect();
+
+    (0..height)
+        .map(|row| {
+            let base = (height - 1 - row) * 8;
+            levels
+                .iter()
+                .map(|level| match level {
+                    Some(level) => BARS[level.saturating_sub(base).min(8)],
+                    None => ' ',
+                })
+                .collect()
+        })
+        .collect()
+}
+
+impl GraphData {
+    fn new(
+        view: &'static str,
+        query: GraphQuery,
+        samples: usize,
+        adv: &Rc<RefCell<Advance>>,
+        timestamp: SystemTime,
+    ) -> Self {
+        let mut graph = Self {
+            view,
+            query,
+            samples,
+            series: VecDeque::new(),
+        };
+        graph.reload(adv, timestamp);
+        graph
+    }
+
+    /// Read the values of the samples up to timestamp from the store.
+    fn reload(&mut self, adv: &Rc<RefCell<Advance>>, timestamp: SystemTime) {
+        let getter = &self.query.getter;
+        self.series = adv
+            .borrow_mut()
+            .get_models_before(timestamp, self.samples)
+            .iter()
+            .map(|model| (model.timestamp, getter(model)))
+            .collect();
+    }
+
+    /// Append the value of the newly shown sample and drop the oldest one.
+    /// Reload from the store instead if the sample does not follow the last
+    /// one, e.g. after a jump or stepping backward in replay.
+    pub fn update(&mut self, model: &Model, adv: &Rc<RefCell<Advance>>) {
+        let last = self.series.back().map(|(timestamp, _)| *timestamp);
+        if last == Some(model.timestamp) {
+            return;
+        }
+        // Timestamps are in seconds, so allow a second of rounding
+        let follows = last.map_or(false, |last| {
+            model.timestamp.duration_since(last).map_or(false, |gap| {
+                gap <= model.time_elapsed + Duration::from_secs(1)
+            })
+        });
+        if !follows {
+            self.reload(adv, model.timestamp);
+            return;
+        }
+
+        self.series
+            .push_back((model.timestamp, (self.query.getter)(model)));
+        while self.series.len() > self.samples {
+            self.series.pop_front();
+        }
+    }
+
+    fn render(&self, field: Option<Field>) -> String {
+        self.query
+            .render_config
+            .render(field, false)
+            .trim()
+            .to_string()
+    }
+
+    fn get_summary(&self) -> String {
+        let fields = self.series.iter().filter_map(|(_, field)| field.as_ref());
+        let min = fields
+            .clone()
+            .min_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let max = fields.max_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let last = self.series.back().and_then(|(_, field)| field.clone());
+        let format_time = |timestamp: &SystemTime| {
+            DateTime::<Local>::from(*timestamp)
+                .format("%H:%M:%S")
+                .to_string()
+        };
+
+        format!(
+            "{}   Min: {}   Max: {}   Last: {}   {} samples from {} to {}",
+            self.query.title,
+            self.render(min.cloned()),
+            self.render(max.cloned()),
+            self.render(last),
+            self.series.len(),
+            self.series
+                .front()
+                .map_or("?".into(), |(ts, _)| format_time(ts)),
+            self.series
+                .back()
+                .map_or("?".into(), |(ts, _)| format_time(ts)),
+        )
+    }
+}
+
+/// Pane of a StatsView that draws the graph if it was opened in that view.
+pub struct GraphView {
+    view: &'static str,
+    graph: Rc<RefCell<Option<GraphData>>>,
+}
+
+impl GraphView {
+    pub fn new(view: &'static str, graph: Rc<RefCell<Option<GraphData>>>) -> Self {
+        Self { view, graph }
+    }
+
+    fn is_shown(&self) -> bool {
+        self.graph
+            .borrow()
+            .as_ref()
+            .map_or(false, |graph| graph.view == self.view)
+    }
+}
+
+impl View for GraphView {
+    fn draw(&self, printer: &Printer) {
+        let graph = self.graph.borrow();
+        let graph = match graph.as_ref() {
+            Some(graph) if graph.view == self.view => graph,
+            _ => return,
+        };
+        if graph.series.is_empty() {
+            printer.print(
+                (0, 0),
+                &format!(
+                    "{}   No recorded data available before this sample.",
+                    graph.query.title
+                ),
+            );
+            return;
+        }
+
+        printer.print((0, 0), &graph.get_summary());
+        // Only graph the latest samples if the screen is not wide enough.
+        let start = graph.series.len().saturating_sub(printer.size.x);
+        let values: Vec<Option<f64>> = graph
+            .series
+            .iter()
+            .skip(start)
+            .map(|(_, field)| field.as_ref().and_then(field_to_f64))
+            .collect();
+        for (idx, line) in render_bars(&values, GRAPH_HEIGHT).iter().enumerate() {
+            printer.print((0, idx + 1), line);
+        }
+    }
+
+    fn required_size(&mut self, constraint: Vec2) -> Vec2 {
+        if self.is_shown() {
+            Vec2::new(constraint.x, GRAPH_HEIGHT + 1)
+        } else {
+            Vec2::zero()
+        }
+    }
+}
+
+/// Show the graph of query in the view, or hide it if the view already shows
+/// the graph of the same query.
+pub fn toggle(c: &mut Cursive, view: &'static str, query: GraphQuery, samples: usize) {
+    let view_state = c
+        .user_data::<ViewState>()
+        .expect("No data stored in Cursive object!");
+    let adv = match &view_state.mode {
+        ViewMode::Live(adv) | ViewMode::Pause(adv) | ViewMode::Replay(adv) => adv.clone(),
+    };
+    let mut graph = view_state.graph.borrow_mut();
+    let shown = graph.as_ref().map_or(false, |graph| {
+        graph.view == view && graph.query.title == query.title
+    });
+    *graph = if shown {
+        None
+    } else {
+        Some(GraphData::new(
+            view,
+            query,
+            samples,
+            &adv,
+            view_state.timestamp,
+        ))
+    };
+}
@@ -56,19 +56,60 @@ fn get_description(controller: &Controllers) -> &'static str {
         Controllers::PrevTab => "Reverse cycle topic tabs.",
         Controllers::NextCol => "Cycle columns.",
         Controllers::PrevCol => "Reverse cycle columns.",
+        Controllers::MoveColLeft => {
+            "Move the selected column to the left (cgroup view and process view only)."
+        }
+        Controllers::MoveColRight => {
+            "Move the selected column to the right (cgroup view and process view only)."
+        }
+        Controllers::HideCol => "Hide the selected column (cgroup view and process view only).",
+        Controllers::ShowCol => {
+            "Show the column of a field id like 'mem.anon' after the selected column (cgroup view and process view only)."
+        }
+        Controllers::SaveLayout => {
+            "Save the columns of the cgroup view and process view tabs to belowrc, so they are restored next time."
+        }
         Controllers::Right => "Scroll right primary display.",
         Controllers::Left => "Scroll left primary display.",
         Controllers::SortCol => {
             "Sort by the selected title, reverse the result by hitting 'S' again(cgroup view and process view only)."
         }
-        Controllers::Filter => "Filter by name.",
+        Controllers::Filter => {
+            "Filter by name, or by an expression like 'cpu.usage_pct > 50 && comm =~ java' (cgroup view and process view only)."
+        }
         Controllers::CFilter => "Clear the current filter.",
+        Controllers::Search => {
+            "Jump to the next row containing the text, e.g. a cgroup name, comm or pid. Hit it again to repeat the last search."
+        }
+        Controllers::Graph => {
+            "Graph the selected column of the selected row over the previous samples below the stats, or hide the graph if it is already shown (cgroup view and process view only)."
+        }
+        Controllers::Compare => {
+            "Compare the current sample with the sample at a relative (backward) or absolute time, including the selected cgroup or process."
+        }
+        Controllers::Window => {
+            "Toggle mean, max or p95 of each rate and gauge field over a window of samples ending at the current sample (cgroup view and process view only)."
+        }
+        Controllers::WindowExtend => "Double the window length.",
+        Controllers::WindowShrink => "Halve the window length.",
+        Controllers::Signal => {
+            "Send a signal, picked from a list or given by name or number, to the selected process after confirmation (process view in live mode only)."
+        }
+        Controllers::SetKnob => {
+            "Write memory.high, memory.max, cpu.max, cpu.weight or cgroup.freeze of the selected cgroup after confirmation (cgroup view in live mode only)."
+        }
         Controllers::JForward => {
             "Jump time by a specific amount forward or to a specific timestamp (replay and live-paused mode)."
         }
         Controllers::JBackward => {
             "Jump time by a specific amount backward or to a specific timestamp (replay and live-paused mode)."
         }
+        Controllers::Events => {
+            "List OOM kills, cgroup and process changes, pressure spikes, throttling and collection skew in the given range (1h by default) before the current sample, and jump to the selected one (replay and live-paused mode)."
+        }
+        Controllers::Lived => {
+            "List the processes that started or exited since the previous sample, with their cmdline, exit code and cgroup (needs enable_procevents in below.conf)."
+        }
         Controllers::NSample => "Show next sample (replay and live-paused mode).",
         Controllers::PSample => "Show previous sample (replay and live-paused mode).",
         Controllers::Pause => {
@@ -88,8 +129,16 @@ fn get_args(controller: &Controllers) -> &'static str {
     match controller {
         Controllers::SortCol => "SortKey",
         Controllers::Filter => "Name",
+        Controllers::Search => "Text",
+        Controllers::ShowCol => "Field",
         Controllers::JForward => "Time",
         Controllers::JBackward => "Time",
+        Controllers::Graph => "Samples",
+        Controllers::Compare => "Time",
+        Controllers::Events => "Range",
+        Controllers::Window => "Length Stat",
+        Controllers::Signal => "Signal",
+        Controllers::SetKnob => "File Value",
         _ => "-",
     }
 }
@@ -141,14 +190,29 @@ fn fill_controllers(
         cmd_map.get(&Controllers::PrevTab).unwrap().to_string(),
         cmd_map.get(&Controllers::NextCol).unwrap().to_string(),
         cmd_map.get(&Controllers::PrevCol).unwrap().to_string(),
+        cmd_map.get(&Controllers::MoveColLeft).unwrap().to_string(),
+        cmd_map.get(&Controllers::MoveColRight).unwrap().to_string(),
+        cmd_map.get(&Controllers::HideCol).unwrap().to_string(),
+        cmd_map.get(&Controllers::ShowCol).unwrap().to_string(),
+        cmd_map.get(&Controllers::SaveLayout).unwrap().to_string(),
         cmd_map.get(&Controllers::JForward).unwrap().to_string(),
         cmd_map.get(&Controllers::JBackward).unwrap().to_string(),
+        cmd_map.get(&Controllers::Events).unwrap().to_string(),
+        cmd_map.get(&Controllers::Lived).unwrap().to_string(),
         cmd_map.get(&Controllers::NSample).unwrap().to_string(),
         cmd_map.get(&Controllers::PSample).unwrap().to_string(),
         cmd_map.get(&Controllers::Pause).unwrap().to_string(),
         cmd_map.get(&Controllers::SortCol).unwrap().to_string(),
         cmd_map.get(&Controllers::Filter).unwrap().to_string(),
         cmd_map.get(&Controllers::CFilter).unwrap().to_string(),
+        cmd_map.get(&Controllers::Search).unwrap().to_string(),
+        cmd_map.get(&Controllers::Graph).unwrap().to_string(),
+        cmd_map.get(&Controllers::Compare).unwrap().to_string(),
+        cmd_map.get(&Controllers::Window).unwrap().to_string(),
+        cmd_map.get(&Controllers::WindowExtend).unwrap().to_string(),
+        cmd_map.get(&Controllers::WindowShrink).unwrap().to_string(),
+        cmd_map.get(&Controllers::Signal).unwrap().to_string(),
+        cmd_map.get(&Controllers::SetKnob).unwrap().to_string(),
         cmd_map.get(&Controllers::Zoom).unwrap().to_string(),
         cmd_map.get(&Controllers::Process).unwrap().to_string(),
         cmd_map.get(&Controllers::Cgroup).unwrap().to_string(),
@@ -166,13 +230,15 @@ fn fill_reserved(v: &mut LinearLayout) {
         " <PgUp>         - scroll up 15 lines primary display\n",
         " <Home>         - scroll to top of primary display\n",
         " <End>          - scroll to end of primary display\n",
-        " <Enter>        - collapse/expand cgroup tree, submit command if command palette acticated\n",
+        " <Enter>        - collapse/expand cgroup or process tree, submit command if command palette acticated\n",
         " <Ctrl>-r       - refresh the screen",
         " 'P'            - sort by pid (process view only)\n",
         " 'N'            - sort by name (process view only)\n",
         " 'C'            - sort by cpu (cgroup view and process view only)\n",
         " 'M'            - sort by memory (cgroup view and process view only)\n",
         " 'D'            - sort by total disk activity(cgroup view and process view only)\n",
+        " 'F'            - toggle process tree, like pstree (process view only)\n",
+        " <Click>        - switch to the clicked tab, sort by the clicked column\n",
     ];
 
     for line in lines {
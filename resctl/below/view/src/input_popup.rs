@@ -0,0 +1,55 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::rc::Rc;
+
+use cursive::event::Key;
+use cursive::view::{Identifiable, View};
+use cursive::views::{Dialog, EditView, OnEventView};
+use cursive::Cursive;
+
+/// Popup asking for a line of text, e.g. a field id or a search string.
+/// on_submit is called with the text after the popup is closed.
+pub fn new<F>(title: &str, content: &str, on_submit: F) -> impl View
+where
+    F: 'static + Fn(&mut Cursive, &str),
+{
+    let on_submit = Rc::new(on_submit);
+    let on_button = on_submit.clone();
+    OnEventView::new(
+        Dialog::new()
+            .title(title)
+            .padding_lrtb(1, 1, 1, 0)
+            .content(
+                EditView::new()
+                    .content(content)
+                    .on_submit(move |c, text| {
+                        c.pop_layer();
+                        on_submit(c, text);
+                    })
+                    .with_name("input_popup"),
+            )
+            .dismiss_button("Close")
+            .button("OK", move |c| {
+                let text = c
+                    .call_on_name("input_popup", |view: &mut EditView| view.get_content())
+                    .expect("Unable to find input_popup");
+                c.pop_layer();
+                on_button(c, &text);
+            }),
+    )
+    .on_event(Key::Esc, |s| {
+        s.pop_layer();
+    })
+}
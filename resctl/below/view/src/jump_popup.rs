@@ -14,13 +14,16 @@
 
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::time::{Duration, SystemTime};
 
-use cursive::event::Key;
-use cursive::view::{Identifiable, View};
-use cursive::views::{Dialog, EditView, LinearLayout, OnEventView, TextView};
+use chrono::{DateTime, Local};
+use cursive::event::{EventTrigger, Key};
+use cursive::view::{Identifiable, Scrollable, View};
+use cursive::views::{Dialog, EditView, LinearLayout, OnEventView, SelectView, TextView};
 use cursive::Cursive;
 
 use common::dateutil;
+use model::{EventDetector, EventModel};
 use store::{Advance, Direction};
 
 use crate::ViewState;
@@ -112,3 +115,71 @@ pub fn new(adv: Rc<RefCell<Advance>>, direction: Direction) -> impl View {
         s.pop_layer();
     })
 }
+
+/// How far back from the displayed sample the event list looks for events,
+/// unless given to the events command.
+pub const DEFAULT_EVENT_RANGE: Duration = Duration::from_secs(60 * 60);
+
+/// Detect events in the samples within range before timestamp, ordered from
+/// newest to oldest. The position of the Advance is not changed.
+fn get_events(
+    adv: &Rc<RefCell<Advance>>,
+    timestamp: SystemTime,
+    range: Duration,
+) -> Vec<(SystemTime, EventModel)> {
+    let mut detector = EventDetector::default();
+    let mut events = Vec::new();
+    let models = adv.borrow_mut().get_models_in_window(timestamp, range);
+    for model in models {
+        for event in detector.detect(&model) {
+            events.push((model.timestamp, event));
+        }
+    }
+    events.reverse();
+    events
+}
+
+/// List of the events before the displayed sample. Submitting an event jumps
+/// to the sample it was detected in.
+pub fn new_events(adv: Rc<RefCell<Advance>>, timestamp: SystemTime, range: Duration) -> impl View {
+    let events = get_events(&adv, timestamp, range);
+    let title = format!(
+        "Events in the {} before the displayed sample ({})",
+        humantime::format_duration(range),
+        events.len()
+    );
+
+    let mut select = SelectView::<SystemTime>::new();
+    for (event_timestamp, event) in events {
+        select.add_item(
+            format!(
+                "{} {:<16} {:<50} {}",
+                DateTime::<Local>::from(event_timestamp).format("%m/%d %H:%M:%S"),
+                event.kind.to_string(),
+                event.subject,
+                event.detail
+            ),
+            event_timestamp,
+        );
+    }
+    select.set_on_submit(move |c, event_timestamp: &SystemTime| {
+        c.pop_layer();
+        match adv.borrow_mut().jump_sample_to(*event_timestamp) {
+            Some(data) => c
+                .user_data::<ViewState>()
+                .expect("No user data set")
+                .update(data),
+            None => view_warn!(c, "Cannot find available data sample"),
+        }
+        crate::refresh(c);
+    });
+
+    OnEventView::new(
+        Dialog::around(select.scrollable())
+            .title(title)
+            .dismiss_button("Close"),
+    )
+    .on_event(EventTrigger::from('q').or(Key::Esc), |s| {
+        s.pop_layer();
+    })
+}
@@ -51,12 +51,11 @@
 ///   the following selectable view. A user can press `,` or `.` to switch between different columns and press `s`
 ///   or `S` to sort in ascending or descending order.
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::rc::Rc;
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use anyhow::Result;
-use crossterm::{event::DisableMouseCapture, execute};
+use anyhow::{bail, Result};
 use cursive::event::Event;
 use cursive::views::{LinearLayout, OnEventView, Panel, ResizedView, StackView};
 use cursive::Cursive;
@@ -72,7 +71,9 @@ use common::open_source_shim;
 use common::util::{
     get_belowrc_cmd_section_key, get_belowrc_filename, get_belowrc_view_section_key,
 };
-use model::{CgroupModel, Model, NetworkModel, ProcessModel, SystemModel};
+use model::{
+    CgroupModel, Model, NetworkModel, ProcEventModel, ProcessModel, SystemModel, WindowStat,
+};
 use store::Advance;
 extern crate render as base_render;
 
@@ -85,7 +86,9 @@ mod core_tabs;
 mod core_view;
 mod default_styles;
 mod filter_popup;
+pub mod graph_view;
 mod help_menu;
+mod input_popup;
 mod process_tabs;
 mod process_view;
 mod render;
@@ -141,8 +144,11 @@ macro_rules! view_warn {
 // controllers depends on Advance
 pub mod controllers;
 pub mod viewrc;
-// Jump popup depends on view_warn
+// Jump popup, compare view and action popup depend on view_warn
+mod action_popup;
+mod compare_view;
 mod jump_popup;
+mod procevents_popup;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum MainViewState {
@@ -184,41 +190,160 @@ pub struct ViewState {
     /// elapsed times. Below will never go faster than the requested interval rate but
     /// can certainly go higher (b/c of a loaded system or other delays).
     pub lowest_time_elapsed: Duration,
+    /// Whether the current sample was recorded at high resolution. Such
+    /// samples are closer to each other than the record interval.
+    pub high_res: bool,
     pub timestamp: SystemTime,
     pub system: Rc<RefCell<SystemModel>>,
     pub cgroup: Rc<RefCell<CgroupModel>>,
     pub process: Rc<RefCell<ProcessModel>>,
     pub network: Rc<RefCell<NetworkModel>>,
+    /// Processes forked, exec'd or exited since the previous sample.
+    pub proc_events: Vec<ProcEventModel>,
     pub main_view_state: MainViewState,
     pub mode: ViewMode,
+    /// Length and statistic of the window when the cgroup and process views
+    /// show statistics over the samples in a window instead of one sample.
+    pub window: Option<(Duration, WindowStat)>,
+    // Cgroup and process models of the current sample, stored while the
+    // views show window statistics.
+    window_raw: Option<(CgroupModel, ProcessModel)>,
+    // Cgroup and process models of the samples in the window, oldest first.
+    // A new sample that follows the newest one is appended instead of
+    // reading the whole window from the store again.
+    window_samples: VecDeque<(SystemTime, CgroupModel, ProcessModel)>,
+    /// Why exitstats are inactive, if so. Processes exiting between samples
+    /// are missing from the views then.
+    pub exitstat_error: Option<String>,
+    /// Graph shown below the detail stats of a view, if any.
+    pub graph: Rc<RefCell<Option<graph_view::GraphData>>>,
     pub event_controllers: Rc<RefCell<HashMap<Event, controllers::Controllers>>>,
     pub cmd_controllers: Rc<RefCell<HashMap<&'static str, controllers::Controllers>>>,
 }
 
 impl ViewState {
     pub fn update(&mut self, model: Model) {
+        if let Some(graph) = self.graph.borrow_mut().as_mut() {
+            let adv = match &self.mode {
+                ViewMode::Live(adv) | ViewMode::Pause(adv) | ViewMode::Replay(adv) => adv,
+            };
+            graph.update(&model, adv);
+        }
         self.time_elapsed = model.time_elapsed;
-        if model.time_elapsed.as_secs() != 0 && model.time_elapsed < self.lowest_time_elapsed {
+        if model.time_elapsed.as_secs() != 0
+            && !model.high_res
+            && (self.lowest_time_elapsed.as_secs() == 0
+                || model.time_elapsed < self.lowest_time_elapsed)
+        {
             self.lowest_time_elapsed = model.time_elapsed;
         }
+        self.high_res = model.high_res;
         self.timestamp = model.timestamp;
         self.system.replace(model.system);
         self.cgroup.replace(model.cgroup);
         self.process.replace(model.process);
         self.network.replace(model.network);
+        self.proc_events = model.proc_events;
+        self.window_raw = None;
+        // Keep showing the current sample if the window cannot be aggregated.
+        // The status bar tells the difference.
+        self.apply_window().ok();
+    }
+
+    /// Show statistics over the window ending at the current sample in the
+    /// cgroup and process views, or the current sample if window is None.
+    pub fn set_window(&mut self, window: Option<(Duration, WindowStat)>) -> Result<()> {
+        if let Some((cgroup, process)) = self.window_raw.take() {
+            self.cgroup.replace(cgroup);
+            self.process.replace(process);
+        }
+        self.window = window;
+        self.window_samples.clear();
+        self.apply_window()
+    }
+
+    /// Whether the cgroup and process views show window statistics.
+    pub fn is_window_applied(&self) -> bool {
+        self.window_raw.is_some()
+    }
+
+    fn apply_window(&mut self) -> Result<()> {
+        let (length, stat) = match self.window {
+            Some(window) => window,
+            None => return Ok(()),
+        };
+        // Timestamps are in seconds, so allow a second of rounding
+        let follows = self.window_samples.back().map_or(false, |(last, _, _)| {
+            self.timestamp.duration_since(*last).map_or(false, |gap| {
+                gap > Duration::from_secs(0) && gap <= self.time_elapsed + Duration::from_secs(1)
+            })
+        });
+        if follows {
+            self.window_samples.push_back((
+                self.timestamp,
+                self.cgroup.borrow().clone(),
+                self.process.borrow().clone(),
+            ));
+            let start = self.timestamp.checked_sub(length).unwrap_or(UNIX_EPOCH);
+            while self
+                .window_samples
+                .front()
+                .map_or(false, |(timestamp, _, _)| *timestamp <= start)
+            {
+                self.window_samples.pop_front();
+            }
+        } else {
+            let adv = match &self.mode {
+                ViewMode::Live(adv) | ViewMode::Pause(adv) | ViewMode::Replay(adv) => adv.clone(),
+            };
+            self.window_samples = adv
+                .borrow_mut()
+                .get_models_in_window(self.timestamp, length)
+                .into_iter()
+                .map(|model| (model.timestamp, model.cgroup, model.process))
+                .collect();
+        }
+        if self.window_samples.is_empty() {
+            bail!("No recorded data available in the window");
+        }
+        let cgroups: Vec<&CgroupModel> = self
+            .window_samples
+            .iter()
+            .map(|(_, cgroup, _)| cgroup)
+            .collect();
+        let processes: Vec<&ProcessModel> = self
+            .window_samples
+            .iter()
+            .map(|(_, _, process)| process)
+            .collect();
+        let cgroup = model::aggregate_cgroups(&cgroups, stat)?;
+        let process = model::aggregate_processes(&processes, stat)?;
+        self.window_raw = Some((self.cgroup.replace(cgroup), self.process.replace(process)));
+        Ok(())
     }
 
     pub fn new_with_advance(main_view_state: MainViewState, model: Model, mode: ViewMode) -> Self {
         Self {
             time_elapsed: model.time_elapsed,
-            lowest_time_elapsed: model.time_elapsed,
+            lowest_time_elapsed: if model.high_res {
+                Duration::default()
+            } else {
+                model.time_elapsed
+            },
+            high_res: model.high_res,
             timestamp: model.timestamp,
             system: Rc::new(RefCell::new(model.system)),
             cgroup: Rc::new(RefCell::new(model.cgroup)),
             process: Rc::new(RefCell::new(model.process)),
             network: Rc::new(RefCell::new(model.network)),
+            proc_events: model.proc_events,
             main_view_state,
             mode,
+            window: None,
+            window_raw: None,
+            window_samples: VecDeque::new(),
+            exitstat_error: None,
+            graph: Rc::new(RefCell::new(None)),
             event_controllers: Rc::new(RefCell::new(HashMap::new())),
             cmd_controllers: Rc::new(RefCell::new(controllers::make_cmd_controller_map())),
         }
@@ -242,14 +367,14 @@ impl ViewState {
 
 impl View {
     pub fn new_with_advance(model: model::Model, mode: ViewMode) -> View {
+        // The crossterm backend captures the mouse so tabs and column headers
+        // can be clicked. Terminals then only select text with shift held.
         let mut inner = cursive::CursiveRunnable::new(|| {
-            let backend = cursive::backends::crossterm::Backend::init().map(|termion_backend| {
+            cursive::backends::crossterm::Backend::init().map(|termion_backend| {
                 Box::new(cursive_buffered_backend::BufferedBackend::new(
                     termion_backend,
                 )) as Box<(dyn cursive::backend::Backend)>
-            });
-            execute!(std::io::stdout(), DisableMouseCapture).expect("Failed to disable mouse.");
-            backend
+            })
         });
         inner.set_user_data(ViewState::new_with_advance(
             MainViewState::Cgroup,
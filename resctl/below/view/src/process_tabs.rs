@@ -15,7 +15,7 @@
 use crate::process_view::ProcessState;
 use crate::render::ViewItem;
 use crate::stats_view::StateCommon;
-use model::SingleProcessModel;
+use model::{ProcessTree, SingleProcessModel, SingleProcessModelFieldId};
 
 use cursive::utils::markup::StyledString;
 
@@ -34,13 +34,33 @@ impl ProcessTab {
         Self { view_items }
     }
 
+    /// Create a tab of the given columns, e.g. from belowrc. Comm and cgroup
+    /// are always the first columns so they are left out.
+    pub fn from_fields(fields: Vec<SingleProcessModelFieldId>) -> Self {
+        Self::new(
+            fields
+                .into_iter()
+                .filter(|field_id| {
+                    *field_id != SingleProcessModelFieldId::Comm
+                        && *field_id != SingleProcessModelFieldId::Cgroup
+                })
+                .map(default_tabs::get_view_item)
+                .collect(),
+        )
+    }
+
     fn get_process_field_line(
         &self,
         model: &SingleProcessModel,
+        collapsed: Option<bool>,
         offset: Option<usize>,
     ) -> StyledString {
-        let mut line = StyledString::new();
-        line.append(default_tabs::COMM_VIEW_ITEM.render(model));
+        // collapsed is only set in tree mode, where comm is indented by depth
+        let mut line = match collapsed {
+            Some(true) => default_tabs::COMM_VIEW_ITEM_COLLAPSED.render_indented(model),
+            Some(false) => default_tabs::COMM_VIEW_ITEM_INDENTED.render_indented(model),
+            None => default_tabs::COMM_VIEW_ITEM.render(model),
+        };
         line.append_plain(" ");
 
         for item in std::iter::once(&*default_tabs::CGROUP_VIEW_ITEM)
@@ -69,6 +89,27 @@ impl ProcessTab {
     ) -> Vec<(StyledString, String)> {
         let unknown = "?".to_string();
         let process_model = state.get_model();
+        let is_match = |spm: &SingleProcessModel| {
+            // If we're in zoomed cgroup mode, only show processes belonging to
+            // our zoomed cgroup
+            if let Some(f) = &state.cgroup_filter {
+                if !spm.cgroup.as_ref().unwrap_or(&unknown).starts_with(f) {
+                    return false;
+                }
+            }
+            // If we're filtering, only show processes who pass the filter
+            // expression, or whose comm contains a plain name filter
+            match (&state.filter_expr, &state.filter) {
+                (Some(expr), _) => expr.eval(spm),
+                (None, Some(f)) => spm.comm.as_ref().unwrap_or(&unknown).contains(f),
+                (None, None) => true,
+            }
+        };
+
+        if state.tree {
+            return self.get_tree_rows(&ProcessTree::new(&process_model), state, is_match, offset);
+        }
+
         let mut processes: Vec<&SingleProcessModel> =
             process_model.processes.iter().map(|(_, spm)| spm).collect();
 
@@ -77,36 +118,66 @@ impl ProcessTab {
         }
         processes
             .iter()
-            .filter(|spm| {
-                // If we're in zoomed cgroup mode, only show processes belonging to
-                // our zoomed cgroup
-                if let Some(f) = &state.cgroup_filter {
-                    spm.cgroup.as_ref().unwrap_or(&unknown).starts_with(f)
-                } else {
-                    true
-                }
-            })
-            .filter(|spm| {
-                // If we're filtering by name, only show processes who pass the filter
-                if let Some(f) = &state.filter {
-                    spm.comm.as_ref().unwrap_or(&unknown).contains(f)
-                } else {
-                    true
-                }
-            })
+            .filter(|spm| is_match(spm))
             .map(|spm| {
                 (
-                    self.get_process_field_line(&spm, offset),
+                    self.get_process_field_line(spm, None, offset),
                     spm.pid.unwrap_or(0).to_string(),
                 )
             })
             .collect()
     }
+
+    /// Rows of processes nested under their parents. Values of each row are
+    /// the totals of its subtree. When filtering, ancestors of matching
+    /// processes are kept so that every match is shown in place.
+    fn get_tree_rows(
+        &self,
+        tree: &ProcessTree,
+        state: &ProcessState,
+        is_match: impl Fn(&SingleProcessModel) -> bool,
+        offset: Option<usize>,
+    ) -> Vec<(StyledString, String)> {
+        let filtered = state.cgroup_filter.is_some() || state.filter.is_some();
+        let keep = if filtered {
+            Some(
+                tree.with_ancestors(
+                    tree.processes
+                        .values()
+                        .filter(|spm| is_match(spm))
+                        .filter_map(|spm| spm.pid),
+                ),
+            )
+        } else {
+            None
+        };
+
+        let mut rows = Vec::new();
+        tree.walk(state.sort_order.as_ref(), state.reverse, |spm| {
+            let pid = spm.pid.unwrap_or(0);
+            if let Some(keep) = &keep {
+                if !keep.contains(&pid) {
+                    return false;
+                }
+            }
+            let collapsed = state.collapsed_pids.contains(&pid);
+            rows.push((
+                self.get_process_field_line(spm, Some(collapsed), offset),
+                pid.to_string(),
+            ));
+            !collapsed
+        });
+        rows
+    }
 }
 
 pub mod default_tabs {
     use super::*;
 
+    use model::NetBytesModelFieldId::{
+        RecvBytesPerSec, SendBytesPerSec, TcpRecvBytesPerSec, TcpSendBytesPerSec,
+        UdpRecvBytesPerSec, UdpSendBytesPerSec,
+    };
     use model::ProcessCpuModelFieldId::{NumThreads, SystemPct, UsagePct, UserPct};
     use model::ProcessIoModelFieldId::{RbytesPerSec, RwbytesPerSec, WbytesPerSec};
     use model::ProcessMemoryModelFieldId::{
@@ -114,12 +185,20 @@ pub mod default_tabs {
         Swap, VmSize,
     };
     use model::SingleProcessModelFieldId::{
-        Cgroup, Cmdline, Comm, Cpu, Io, Mem, Pid, Ppid, State, UptimeSecs,
+        Cgroup, Cmdline, Comm, Cpu, Io, Mem, Net, Pid, Ppid, State, UptimeSecs,
     };
 
+    use base_render::RenderConfigBuilder as Rc;
+    use common::util::get_prefix;
     use once_cell::sync::Lazy;
 
     pub static COMM_VIEW_ITEM: Lazy<ProcessViewItem> = Lazy::new(|| ViewItem::from_default(Comm));
+    pub static COMM_VIEW_ITEM_INDENTED: Lazy<ProcessViewItem> = Lazy::new(|| {
+        ViewItem::from_default(Comm).update(Rc::new().indented_prefix(get_prefix(false)))
+    });
+    pub static COMM_VIEW_ITEM_COLLAPSED: Lazy<ProcessViewItem> = Lazy::new(|| {
+        ViewItem::from_default(Comm).update(Rc::new().indented_prefix(get_prefix(true)))
+    });
     pub static CGROUP_VIEW_ITEM: Lazy<ProcessViewItem> =
         Lazy::new(|| ViewItem::from_default(Cgroup));
 
@@ -176,4 +255,37 @@ pub mod default_tabs {
             ViewItem::from_default(Io(RwbytesPerSec)),
         ])
     });
+
+    pub static PROCESS_NET_TAB: Lazy<ProcessTab> = Lazy::new(|| {
+        ProcessTab::new(vec![
+            ViewItem::from_default(Net(SendBytesPerSec)),
+            ViewItem::from_default(Net(RecvBytesPerSec)),
+            ViewItem::from_default(Net(TcpSendBytesPerSec)),
+            ViewItem::from_default(Net(TcpRecvBytesPerSec)),
+            ViewItem::from_default(Net(UdpSendBytesPerSec)),
+            ViewItem::from_default(Net(UdpRecvBytesPerSec)),
+        ])
+    });
+
+    /// Built-in tabs in the order they are shown.
+    pub static PROCESS_TABS: Lazy<Vec<(&'static str, &'static ProcessTab)>> = Lazy::new(|| {
+        vec![
+            ("General", &*PROCESS_GENERAL_TAB),
+            ("CPU", &*PROCESS_CPU_TAB),
+            ("Mem", &*PROCESS_MEM_TAB),
+            ("I/O", &*PROCESS_IO_TAB),
+            ("Net", &*PROCESS_NET_TAB),
+        ]
+    });
+
+    /// The column of field_id as shown in the built-in tabs, or its default
+    /// if no built-in tab shows it.
+    pub fn get_view_item(field_id: SingleProcessModelFieldId) -> ProcessViewItem {
+        PROCESS_TABS
+            .iter()
+            .flat_map(|(_, tab)| tab.view_items.iter())
+            .find(|item| item.field_id == field_id)
+            .cloned()
+            .unwrap_or_else(|| ViewItem::from_default(field_id))
+    }
 }
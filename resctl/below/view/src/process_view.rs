@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use std::cell::{Ref, RefCell, RefMut};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
 use cursive::utils::markup::StyledString;
@@ -22,14 +22,16 @@ use cursive::views::{NamedView, SelectView, ViewRef};
 use cursive::Cursive;
 
 use model::{
-    ProcessCpuModelFieldId, ProcessIoModelFieldId, ProcessMemoryModelFieldId, ProcessModel,
-    SingleProcessModelFieldId,
+    FilterExpr, ProcessCpuModelFieldId, ProcessIoModelFieldId, ProcessMemoryModelFieldId,
+    ProcessModel, Queriable, SingleProcessModelFieldId,
 };
 
-use crate::process_tabs::{
-    default_tabs::{PROCESS_CPU_TAB, PROCESS_GENERAL_TAB, PROCESS_IO_TAB, PROCESS_MEM_TAB},
-    ProcessTab,
-};
+use anyhow::{anyhow, bail, Result};
+
+use crate::filter_popup::parse_filter_expr;
+use crate::graph_view::GraphQuery;
+use crate::process_tabs::{default_tabs, default_tabs::PROCESS_TABS, ProcessTab};
+use crate::render::{hide_view_item, move_view_item, show_view_item};
 use crate::stats_view::{StateCommon, StatsView, ViewBridge};
 use crate::ViewState;
 
@@ -38,13 +40,19 @@ pub type ViewType = StatsView<ProcessView>;
 #[derive(Default)]
 pub struct ProcessState {
     pub filter: Option<String>,
+    // The filter parsed once when it is set, None if it is a plain name
+    pub filter_expr: Option<FilterExpr<SingleProcessModelFieldId>>,
     pub cgroup_filter: Option<String>,
     // For zoomed view, we should save current filter to here and reset the
     // filter when go back to cgroup or process view.
     pub filter_cache_for_zoom: Option<String>,
     pub sort_order: Option<SingleProcessModelFieldId>,
-    pub sort_tags: HashMap<String, &'static ProcessTab>,
+    // Shared with the tabs of ProcessView so that columns can be changed at runtime
+    pub sort_tags: HashMap<String, Rc<RefCell<ProcessTab>>>,
     pub reverse: bool,
+    // Nest processes under their parents instead of a flat list
+    pub tree: bool,
+    pub collapsed_pids: HashSet<i32>,
     pub model: Rc<RefCell<ProcessModel>>,
 }
 
@@ -55,6 +63,15 @@ impl StateCommon for ProcessState {
         &mut self.filter
     }
 
+    fn set_filter(&mut self, filter: Option<String>) -> Result<()> {
+        self.filter_expr = match &filter {
+            Some(f) => parse_filter_expr(f)?,
+            None => None,
+        };
+        self.filter = filter;
+        Ok(())
+    }
+
     fn set_sort_tag(&mut self, sort_order: Self::TagType, reverse: &mut bool) -> bool {
         let sort_order = Some(sort_order);
         if self.sort_order == sort_order {
@@ -75,6 +92,7 @@ impl StateCommon for ProcessState {
                 .sort_tags
                 .get(tab)
                 .unwrap_or_else(|| panic!("Fail to find tab: {}", tab))
+                .borrow()
                 .view_items
                 .get(idx - 2)
                 .expect("Out of title scope")
@@ -93,6 +111,66 @@ impl StateCommon for ProcessState {
         }
     }
 
+    fn get_graph_query(&self, tab: &str, idx: usize, selection: &str) -> Option<GraphQuery> {
+        // The comm and cgroup columns are not values to graph
+        let item = self
+            .sort_tags
+            .get(tab)?
+            .borrow()
+            .view_items
+            .get(idx.checked_sub(2)?)?
+            .clone();
+        let field_id = item.field_id.clone();
+        let pid = selection.parse::<i32>().ok()?;
+        Some(GraphQuery {
+            title: format!("{} of pid {}", item.config.render_title().trim(), pid),
+            getter: Box::new(move |model: &model::Model| {
+                model.process.processes.get(&pid)?.query(&field_id)
+            }),
+            render_config: item.config.render_config.clone(),
+        })
+    }
+
+    fn move_column(&mut self, tab: &str, idx: usize, right: bool) -> Result<usize> {
+        let idx = idx
+            .checked_sub(2)
+            .ok_or_else(|| anyhow!("Comm and cgroup columns cannot be moved"))?;
+        Ok(move_view_item(&mut self.get_tab(tab)?.borrow_mut().view_items, idx, right)? + 2)
+    }
+
+    fn hide_column(&mut self, tab: &str, idx: usize) -> Result<()> {
+        let idx = idx
+            .checked_sub(2)
+            .ok_or_else(|| anyhow!("Comm and cgroup columns cannot be hidden"))?;
+        hide_view_item(&mut self.get_tab(tab)?.borrow_mut().view_items, idx)
+    }
+
+    fn show_column(&mut self, tab: &str, idx: usize, field: &str) -> Result<usize> {
+        use std::str::FromStr;
+        let field_id = Self::TagType::from_str(field)
+            .map_err(|_| anyhow!("Unknown process field: {}", field))?;
+        if field_id == Self::TagType::Comm || field_id == Self::TagType::Cgroup {
+            bail!("Comm and cgroup columns are always shown");
+        }
+        Ok(show_view_item(
+            &mut self.get_tab(tab)?.borrow_mut().view_items,
+            idx.checked_sub(2),
+            default_tabs::get_view_item(field_id),
+        )? + 2)
+    }
+
+    fn get_columns(&self, tab: &str) -> Option<Vec<String>> {
+        Some(
+            self.sort_tags
+                .get(tab)?
+                .borrow()
+                .view_items
+                .iter()
+                .map(|item| item.field_id.to_string())
+                .collect(),
+        )
+    }
+
     fn get_model(&self) -> Ref<Self::ModelType> {
         self.model.borrow()
     }
@@ -102,18 +180,20 @@ impl StateCommon for ProcessState {
     }
 
     fn new(model: Rc<RefCell<Self::ModelType>>) -> Self {
-        let mut sort_tags = HashMap::new();
-        sort_tags.insert("General".into(), &*PROCESS_GENERAL_TAB);
-        sort_tags.insert("CPU".into(), &*PROCESS_CPU_TAB);
-        sort_tags.insert("Mem".into(), &*PROCESS_MEM_TAB);
-        sort_tags.insert("I/O".into(), &*PROCESS_IO_TAB);
+        let sort_tags = PROCESS_TABS
+            .iter()
+            .map(|(name, tab)| (name.to_string(), Rc::new(RefCell::new((*tab).clone()))))
+            .collect();
         Self {
             cgroup_filter: None,
             filter: None,
+            filter_expr: None,
             filter_cache_for_zoom: None,
             sort_order: None,
             sort_tags,
             reverse: false,
+            tree: false,
+            collapsed_pids: HashSet::new(),
             model,
         }
     }
@@ -128,26 +208,55 @@ impl ProcessState {
         self.reverse = reverse;
     }
 
+    fn toggle_tree(&mut self) {
+        self.tree = !self.tree;
+    }
+
+    fn toggle_collapse(&mut self, pid: i32) {
+        if !self.collapsed_pids.remove(&pid) {
+            self.collapsed_pids.insert(pid);
+        }
+    }
+
+    fn get_tab(&self, tab: &str) -> Result<&Rc<RefCell<ProcessTab>>> {
+        self.sort_tags
+            .get(tab)
+            .ok_or_else(|| anyhow!("Fail to find tab: {}", tab))
+    }
+
     pub fn handle_state_for_entering_zoom(&mut self, current_selection: String) {
         self.cgroup_filter = Some(current_selection);
         std::mem::swap(&mut self.filter_cache_for_zoom, &mut self.filter);
         self.filter = None;
+        self.filter_expr = None;
     }
 
     pub fn reset_state_for_quiting_zoom(&mut self) {
-        std::mem::swap(&mut self.filter, &mut self.filter_cache_for_zoom);
+        let filter = self.filter_cache_for_zoom.take();
+        // The cached filter was accepted when it was set
+        let _ = self.set_filter(filter);
         self.cgroup_filter = None;
-        self.filter_cache_for_zoom = None;
     }
 }
 
 pub struct ProcessView {
-    tab: &'static ProcessTab,
+    tab: Rc<RefCell<ProcessTab>>,
 }
 
 impl ProcessView {
     pub fn new(c: &mut Cursive) -> NamedView<ViewType> {
         let mut list = SelectView::<String>::new();
+        list.set_on_submit(|c, pid: &String| {
+            let mut view = Self::get_process_view(c);
+            // Only subtrees in tree mode can be collapsed
+            if !view.state.borrow().tree {
+                return;
+            }
+            if let Ok(pid) = pid.parse::<i32>() {
+                view.state.borrow_mut().toggle_collapse(pid);
+            }
+            view.refresh(c);
+        });
         list.set_on_select(|c, pid: &String| {
             c.call_on_name(Self::get_view_name(), |view: &mut ViewType| {
                 let cmdline = view
@@ -163,43 +272,28 @@ impl ProcessView {
             });
         });
 
-        let tabs = vec!["General".into(), "CPU".into(), "Mem".into(), "I/O".into()];
-        let mut tabs_map: HashMap<String, ProcessView> = HashMap::new();
-        tabs_map.insert(
-            "General".into(),
-            Self {
-                tab: &*PROCESS_GENERAL_TAB,
-            },
-        );
-        tabs_map.insert(
-            "CPU".into(),
-            Self {
-                tab: &*PROCESS_CPU_TAB,
-            },
-        );
-        tabs_map.insert(
-            "Mem".into(),
-            Self {
-                tab: &*PROCESS_MEM_TAB,
-            },
-        );
-        tabs_map.insert(
-            "I/O".into(),
-            Self {
-                tab: &*PROCESS_IO_TAB,
-            },
-        );
         let user_data = c
             .user_data::<ViewState>()
             .expect("No data stored in Cursive Object!");
+        let state = ProcessState::new(user_data.process.clone());
+        let tabs = PROCESS_TABS
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect();
+        let tabs_map: HashMap<String, ProcessView> = state
+            .sort_tags
+            .iter()
+            .map(|(name, tab)| (name.clone(), Self { tab: tab.clone() }))
+            .collect();
         StatsView::new(
             "process",
             tabs,
             tabs_map,
             list,
-            ProcessState::new(user_data.process.clone()),
+            state,
             user_data.event_controllers.clone(),
             user_data.cmd_controllers.clone(),
+            user_data.graph.clone(),
         )
         .feed_data(c)
         .on_event('P', |c| {
@@ -248,6 +342,11 @@ impl ProcessView {
             view.state.borrow_mut().set_reverse(true);
             view.refresh(c)
         })
+        .on_event('F', |c| {
+            let mut view = Self::get_process_view(c);
+            view.state.borrow_mut().toggle_tree();
+            view.refresh(c)
+        })
         .with_name(Self::get_view_name())
     }
 
@@ -255,6 +354,29 @@ impl ProcessView {
         ViewType::get_view(c)
     }
 
+    /// Add a tab of the given columns, or replace the columns of the tab of
+    /// the same name.
+    pub fn set_tab(c: &mut Cursive, name: &str, fields: Vec<SingleProcessModelFieldId>) {
+        let mut view = Self::get_process_view(c);
+        let tab = ProcessTab::from_fields(fields);
+        let existing = view.state.borrow().sort_tags.get(name).cloned();
+        let tab = match existing {
+            Some(existing) => {
+                *existing.borrow_mut() = tab;
+                existing
+            }
+            None => {
+                let tab = Rc::new(RefCell::new(tab));
+                view.state
+                    .borrow_mut()
+                    .sort_tags
+                    .insert(name.to_string(), tab.clone());
+                tab
+            }
+        };
+        view.set_tab(name.to_string(), Self { tab });
+    }
+
     pub fn refresh(c: &mut Cursive) {
         let mut view = Self::get_process_view(c);
         view.refresh(c);
@@ -287,7 +409,7 @@ impl ViewBridge for ProcessView {
         "process_view"
     }
     fn get_title_vec(&self) -> Vec<String> {
-        self.tab.get_title_vec()
+        self.tab.borrow().get_title_vec()
     }
 
     fn get_rows(
@@ -295,6 +417,6 @@ impl ViewBridge for ProcessView {
         state: &Self::StateType,
         offset: Option<usize>,
     ) -> Vec<(StyledString, String)> {
-        self.tab.get_rows(state, offset)
+        self.tab.borrow().get_rows(state, offset)
     }
 }
@@ -0,0 +1,87 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{Duration, UNIX_EPOCH};
+
+use chrono::{DateTime, Local};
+use cursive::event::{EventTrigger, Key};
+use cursive::view::{Scrollable, View};
+use cursive::views::{Dialog, LinearLayout, OnEventView, TextView};
+
+use model::ProcEventModel;
+
+fn format_epoch_ms(epoch_ms: Option<u64>) -> String {
+    match epoch_ms {
+        Some(epoch_ms) => DateTime::<Local>::from(UNIX_EPOCH + Duration::from_millis(epoch_ms))
+            .format("%H:%M:%S%.3f")
+            .to_string(),
+        None => "?".to_owned(),
+    }
+}
+
+fn format_row(process: &ProcEventModel) -> String {
+    format!(
+        "{:<12} {:<12} {:<8} {:<8} {:<5} {:<40} {}",
+        format_epoch_ms(process.start_epoch_ms),
+        match process.event.as_deref() {
+            Some("exit") => format_epoch_ms(process.end_epoch_ms),
+            _ => "running".to_owned(),
+        },
+        process.pid.map_or("?".to_owned(), |v| v.to_string()),
+        process.ppid.map_or("?".to_owned(), |v| v.to_string()),
+        process.exit_code.map_or("".to_owned(), |v| v.to_string()),
+        process.cgroup.as_deref().unwrap_or("?"),
+        process
+            .cmdline
+            .as_ref()
+            .or_else(|| process.comm.as_ref())
+            .map_or("?", |v| v.as_str()),
+    )
+}
+
+/// List of the processes that started or exited in between the previous
+/// sample and the displayed one, including those that never showed up in a
+/// sample.
+pub fn new(proc_events: &[ProcEventModel], time_elapsed: Duration) -> impl View {
+    let processes = model::get_lived_processes(proc_events);
+    let title = format!(
+        "Processes that lived during the {} before the displayed sample ({})",
+        humantime::format_duration(time_elapsed),
+        processes.len()
+    );
+
+    let mut content = LinearLayout::vertical();
+    if processes.is_empty() {
+        content.add_child(TextView::new(
+            "No process started or exited. Process events are only recorded with enable_procevents in below.conf.",
+        ));
+    } else {
+        content.add_child(TextView::new(format!(
+            "{:<12} {:<12} {:<8} {:<8} {:<5} {:<40} {}",
+            "Start", "End", "Pid", "Ppid", "Exit", "Cgroup", "Cmdline"
+        )));
+        for process in &processes {
+            content.add_child(TextView::new(format_row(process)));
+        }
+    }
+
+    OnEventView::new(
+        Dialog::around(content.scrollable())
+            .title(title)
+            .dismiss_button("Close"),
+    )
+    .on_event(EventTrigger::from('q').or(Key::Esc), |s| {
+        s.pop_layer();
+    })
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use anyhow::{bail, Result};
 use base_render::{HasRenderConfig, RenderConfig};
 use model::{Field, FieldId, Queriable, Recursive};
 
@@ -147,3 +148,55 @@ where
             .render_indented(model.query(&self.field_id), model.get_depth())
     }
 }
+
+/// Move the item at idx of a tab's columns one position to the left or right.
+/// Return the new index of the item.
+pub fn move_view_item<F: FieldId>(
+    items: &mut [ViewItem<F>],
+    idx: usize,
+    right: bool,
+) -> Result<usize> {
+    let to = if right {
+        Some(idx + 1)
+    } else {
+        idx.checked_sub(1)
+    };
+    match to {
+        Some(to) if idx < items.len() && to < items.len() => {
+            items.swap(idx, to);
+            Ok(to)
+        }
+        _ => bail!(
+            "Column is already the {} one",
+            if right { "last" } else { "first" }
+        ),
+    }
+}
+
+/// Remove the item at idx of a tab's columns. The last column is kept so that
+/// a tab is never empty.
+pub fn hide_view_item<F: FieldId>(items: &mut Vec<ViewItem<F>>, idx: usize) -> Result<()> {
+    if idx >= items.len() {
+        bail!("Column does not exist");
+    }
+    if items.len() == 1 {
+        bail!("Cannot hide the last column of a tab");
+    }
+    items.remove(idx);
+    Ok(())
+}
+
+/// Insert item to a tab's columns after idx, or at the end if idx is out of
+/// the columns. Return the index of the item.
+pub fn show_view_item<F: FieldId + PartialEq>(
+    items: &mut Vec<ViewItem<F>>,
+    idx: Option<usize>,
+    item: ViewItem<F>,
+) -> Result<usize> {
+    if items.iter().any(|i| i.field_id == item.field_id) {
+        bail!("Column is already shown");
+    }
+    let idx = idx.map_or(items.len(), |idx| (idx + 1).min(items.len()));
+    items.insert(idx, item);
+    Ok(idx)
+}
@@ -17,7 +17,8 @@ use std::collections::HashMap;
 use std::rc::Rc;
 
 use ::cursive::view::{Identifiable, Scrollable, View};
-use cursive::event::{Event, EventResult, EventTrigger};
+use anyhow::{bail, Result};
+use cursive::event::{Callback, Event, EventResult, EventTrigger};
 use cursive::utils::markup::StyledString;
 use cursive::view::ViewWrapper;
 use cursive::views::{
@@ -27,6 +28,7 @@ use cursive::Cursive;
 
 use crate::command_palette::CommandPalette;
 use crate::controllers::Controllers;
+use crate::graph_view::{GraphData, GraphQuery, GraphView};
 use crate::tab_view::TabView;
 use common::logutil::{get_last_log_to_display, CPMsgRecord};
 
@@ -40,6 +42,12 @@ pub trait StateCommon {
     type TagType;
     /// Expose the filter data for StatsView to implement common '/' fitlering.
     fn get_filter(&mut self) -> &mut Option<String>;
+    /// Set the filter. Views that accept filter expressions parse it here
+    /// once and return the error if it is an invalid expression.
+    fn set_filter(&mut self, filter: Option<String>) -> Result<()> {
+        *self.get_filter() = filter;
+        Ok(())
+    }
     /// Set the sorting tag to common state
     /// Return true on success, false if current tab doest support sorting.
     fn set_sort_tag(&mut self, _tag: Self::TagType, _reverse: &mut bool) -> bool {
@@ -51,6 +59,31 @@ pub trait StateCommon {
     fn set_sort_tag_from_tab_idx(&mut self, _tab: &str, _idx: usize, _reverse: &mut bool) -> bool {
         false
     }
+    /// Return the query to graph the value at the selected row and the
+    /// column of the given tab and title index.
+    /// Return None if the view or the column doesn't support graphing.
+    fn get_graph_query(&self, _tab: &str, _idx: usize, _selection: &str) -> Option<GraphQuery> {
+        None
+    }
+    /// Move the column at the title index of tab one position to the left or
+    /// right. Return the new title index of the column.
+    fn move_column(&mut self, _tab: &str, _idx: usize, _right: bool) -> Result<usize> {
+        bail!("Columns of this view cannot be moved")
+    }
+    /// Hide the column at the title index of tab.
+    fn hide_column(&mut self, _tab: &str, _idx: usize) -> Result<()> {
+        bail!("Columns of this view cannot be hidden")
+    }
+    /// Show the column of field id after the title index of tab. Return the
+    /// title index of the column.
+    fn show_column(&mut self, _tab: &str, _idx: usize, _field: &str) -> Result<usize> {
+        bail!("Columns of this view cannot be added")
+    }
+    /// Return the field ids of the columns of tab that can be configured in
+    /// belowrc, or None if the tab does not support it.
+    fn get_columns(&self, _tab: &str) -> Option<Vec<String>> {
+        None
+    }
     fn get_model(&self) -> Ref<Self::ModelType>;
     fn get_model_mut(&self) -> RefMut<Self::ModelType>;
     fn new(model: Rc<RefCell<Self::ModelType>>) -> Self;
@@ -110,7 +143,8 @@ pub trait ViewBridge {
 ///            --> LinearLayout::Vertical
 ///            --> child 0: A TabView that represent the title header tab
 ///            --> child 1: A SelectView that represents the detail stats
-///          --> child 2: Command palette
+///          --> child 2: Graph of the selected row and column, if shown
+///          --> child 3: Command palette
 ///
 /// `state` defines the state of a view. Filters, sorting orders will be defined here.
 pub struct StatsView<V: 'static + ViewBridge> {
@@ -119,6 +153,8 @@ pub struct StatsView<V: 'static + ViewBridge> {
     detailed_view: OnEventView<Panel<LinearLayout>>,
     pub state: Rc<RefCell<V::StateType>>,
     pub reverse_sort: bool,
+    // Text of the last search, to search for it again
+    pub last_search: Option<String>,
     pub event_controllers: Rc<RefCell<HashMap<Event, Controllers>>>,
 }
 
@@ -147,6 +183,25 @@ impl<V: 'static + ViewBridge> ViewWrapper for StatsView<V> {
             .unwrap_or(&Controllers::Unknown)
             .clone();
 
+        // Clicking a tab switches to it and clicking a column header sorts by it
+        if let Event::Mouse { .. } = ch {
+            let res = self
+                .with_view_mut(|v| v.on_event(ch))
+                .unwrap_or(EventResult::Ignored);
+            if self.get_tab_view().take_clicked() {
+                self.update_title();
+                return EventResult::with_cb(Self::refresh_myself);
+            }
+            if self.get_title_view().take_clicked() {
+                let x = self.get_title_view().cur_length;
+                self.set_horizontal_offset(x);
+                let controller = Controllers::SortCol;
+                controller.handle(self, &[]);
+                return EventResult::with_cb(move |c| controller.callback::<V>(c, &[]));
+            }
+            return res;
+        }
+
         // Unmapped event goes to the parent view.
         if controller == Controllers::Unknown {
             self.with_view_mut(|v| v.on_event(ch))
@@ -168,6 +223,7 @@ impl<V: 'static + ViewBridge> StatsView<V> {
         state: V::StateType,
         event_controllers: Rc<RefCell<HashMap<Event, Controllers>>>,
         cmd_controllers: Rc<RefCell<HashMap<&'static str, Controllers>>>,
+        graph: Rc<RefCell<Option<GraphData>>>,
     ) -> Self {
         let mut tab_titles_map = HashMap::new();
         // Generating titles. The get_title_vec will call BelowDerive's get_title_pipe()
@@ -206,6 +262,7 @@ impl<V: 'static + ViewBridge> StatsView<V> {
                         .scroll_x(true)
                         .scroll_y(false),
                 )
+                .child(GraphView::new(V::get_view_name(), graph))
                 .child(
                     CommandPalette::new::<V>(name, "<root>", cmd_controllers)
                         .with_name(format!("{}_cmd_palette", &name)),
@@ -218,6 +275,7 @@ impl<V: 'static + ViewBridge> StatsView<V> {
             detailed_view,
             state: Rc::new(RefCell::new(state)),
             reverse_sort: true,
+            last_search: None,
             event_controllers,
         }
     }
@@ -238,6 +296,60 @@ impl<V: 'static + ViewBridge> StatsView<V> {
         title_view.cur_length = title_view.tabs[0].len();
     }
 
+    /// Add a tab, or replace the tab of the same name.
+    pub fn set_tab(&mut self, tab: String, bridge: V) {
+        self.tab_titles_map
+            .insert(tab.clone(), bridge.get_title_vec());
+        self.tab_view_map.insert(tab.clone(), bridge);
+        let mut tab_view = self.get_tab_view();
+        if !tab_view.tabs.contains(&tab) {
+            tab_view.tabs.push(tab);
+            tab_view.total_length = tab_view.tabs.iter().fold(0, |acc, x| acc + x.len() + 1);
+        }
+        drop(tab_view);
+        self.update_title();
+    }
+
+    /// Names of the tabs in the order they are shown.
+    pub fn get_tabs(&mut self) -> Vec<String> {
+        self.get_tab_view().tabs.clone()
+    }
+
+    /// Regenerate the titles of the current tab after its columns changed,
+    /// and select the title at idx.
+    pub fn reload_title(&mut self, idx: usize) {
+        let cur_tab = self.get_tab_view().get_cur_selected().to_string();
+        let title_vec = self
+            .tab_view_map
+            .get(&cur_tab)
+            .unwrap_or_else(|| panic!("Fail to query data from tab {}", cur_tab))
+            .get_title_vec();
+        self.tab_titles_map.insert(cur_tab, title_vec);
+        self.update_title();
+        let x = self.get_title_view().select(idx);
+        self.set_horizontal_offset(x);
+    }
+
+    /// Select the next row after the current selection whose text contains
+    /// needle, ignoring case. Wraps around to the first row. Return the
+    /// on_select callback of the row, or None if no row matches.
+    pub fn search(&mut self, needle: &str) -> Option<Callback> {
+        self.last_search = Some(needle.to_string());
+        let needle = needle.to_lowercase();
+        let mut select_view = self.get_detail_view();
+        let len = select_view.len();
+        let start = select_view.selected_id().map_or(0, |id| id + 1);
+        let idx = (start..len).chain(0..start).find(|&idx| {
+            select_view.get_item(idx).map_or(false, |(label, value)| {
+                label.to_lowercase().contains(&needle) || value.to_lowercase().contains(&needle)
+            })
+        })?;
+        let cb = select_view.set_selection(idx);
+        drop(select_view);
+        self.get_detail_scroll_view().scroll_to_important_area();
+        Some(cb)
+    }
+
     // Expose the OnEventView API.
     pub fn on_event<F, E>(mut self, trigger: E, cb: F) -> Self
     where
@@ -287,8 +399,8 @@ impl<V: 'static + ViewBridge> StatsView<V> {
         title_named.get_mut()
     }
 
-    // A convenience function to get the detail stats SelectView
-    pub fn get_detail_view(&mut self) -> ViewRef<SelectView> {
+    // Helping method to downcast the scroll view of the detail stats.
+    fn get_detail_scroll_view(&mut self) -> &mut ScrollView<NamedView<SelectView>> {
         let scroll_view = self.get_scroll_view();
 
         let select_named: &mut ResizedView<ScrollView<NamedView<SelectView>>> = scroll_view
@@ -298,7 +410,12 @@ impl<V: 'static + ViewBridge> StatsView<V> {
             .downcast_mut()
             .expect("Fail to downcast to title, StatsView may not properly init");
 
-        select_named.get_inner_mut().get_inner_mut().get_mut()
+        select_named.get_inner_mut()
+    }
+
+    // A convenience function to get the detail stats SelectView
+    pub fn get_detail_view(&mut self) -> ViewRef<SelectView> {
+        self.get_detail_scroll_view().get_inner_mut().get_mut()
     }
 
     // A convenience function to get the command palette
@@ -307,7 +424,7 @@ impl<V: 'static + ViewBridge> StatsView<V> {
             .detailed_view // OnEventView
             .get_inner_mut() // PanelView
             .get_inner_mut() // LinearLayout
-            .get_child_mut(2) // NamedView
+            .get_child_mut(3) // NamedView
             .expect("Fail to get cmd palette, StatsView may not properly init")
             .downcast_mut()
             .expect("Fail to downcast to cmd palette, StatsView may not properly init");
@@ -385,4 +502,15 @@ impl<V: 'static + ViewBridge> StatsView<V> {
     pub fn cp_filter(c: &mut Cursive, filter: Option<String>) {
         Self::get_view(c).get_cmd_palette().set_filter(filter);
     }
+
+    /// Set filter to the state and CommandPalette, or raise a warning if the
+    /// state rejects it.
+    pub fn set_filter(c: &mut Cursive, filter: Option<String>) {
+        let state = Self::get_view(c).state.clone();
+        let res = state.borrow_mut().set_filter(filter.clone());
+        match res {
+            Ok(()) => Self::cp_filter(c, filter),
+            Err(e) => Self::cp_warn(c, &format!("Invalid filter: {:#}", e)),
+        }
+    }
 }
@@ -40,7 +40,9 @@ fn get_content(c: &mut Cursive) -> impl Into<StyledString> {
     let lowest = view_state.lowest_time_elapsed.as_secs();
     let this = view_state.time_elapsed.as_secs();
     // 1 second jitter happens pretty often due to integer rounding
-    if lowest != 0 && this >= (lowest + 2) {
+    if view_state.high_res {
+        header_str.append_plain(format!("{} (high-res)", elapsed_rendered));
+    } else if lowest != 0 && this >= (lowest + 2) {
         header_str.append_styled(
             elapsed_rendered,
             cursive::theme::Color::Light(cursive::theme::BaseColor::Red),
@@ -60,6 +62,30 @@ fn get_content(c: &mut Cursive) -> impl Into<StyledString> {
     header_str.append_plain(get_spacing());
     header_str.append_plain(view_state.view_mode_str());
 
+    if let Some((length, stat)) = view_state.window {
+        let window_rendered = format!(
+            "{}window: {} {}",
+            get_spacing(),
+            humantime::format_duration(length),
+            stat
+        );
+        if view_state.is_window_applied() {
+            header_str.append_plain(window_rendered);
+        } else {
+            header_str.append_styled(
+                format!("{} (no data)", window_rendered),
+                cursive::theme::Color::Light(cursive::theme::BaseColor::Red),
+            );
+        }
+    }
+
+    if let Some(reason) = &view_state.exitstat_error {
+        header_str.append_styled(
+            format!("{}exitstats inactive: {}", get_spacing(), reason),
+            cursive::theme::Color::Light(cursive::theme::BaseColor::Red),
+        );
+    }
+
     header_str
 }
 
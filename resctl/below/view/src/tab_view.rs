@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use cursive::event::{Event, EventResult, MouseButton, MouseEvent};
 use cursive::theme::Effect;
 use cursive::vec::Vec2;
 use cursive::Printer;
@@ -21,10 +22,11 @@ use anyhow::{bail, Result};
 
 /// TextView that has a vector of string as tabs
 ///
-/// TabView will not implement any event. Instead, it will provide handler functions
-/// on how the view should change on specific event like tab and shift-tab. cur_length and
-/// total_length is used to calculate the selection offset. We use the selection offset to
-/// automatically horizontal scroll the tab view.
+/// TabView will only handle mouse clicks, which select the clicked tab. Instead, it will
+/// provide handler functions on how the view should change on specific event like tab
+/// and shift-tab. The owner of the view is responsible to react on the selection change.
+/// cur_length and total_length is used to calculate the selection offset. We use the selection
+/// offset to automatically horizontal scroll the tab view.
 pub struct TabView {
     pub tabs: Vec<String>,
     pub current_selected: usize,
@@ -33,6 +35,8 @@ pub struct TabView {
     pub cur_length: usize,
     pub cur_offset: usize,
     pub total_length: usize,
+    // Set when a tab is selected by a mouse click
+    clicked: bool,
 }
 
 impl View for TabView {
@@ -72,6 +76,25 @@ impl View for TabView {
     fn required_size(&mut self, _constraint: Vec2) -> Vec2 {
         Vec2::new(1, 2)
     }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        if let Event::Mouse {
+            offset,
+            position,
+            event: MouseEvent::Press(MouseButton::Left),
+        } = event
+        {
+            // Only the first line has tabs, the second one is the separator line
+            if let Some(pos) = position.checked_sub(offset).filter(|pos| pos.y == 0) {
+                if let Some(idx) = self.get_idx_at(pos.x) {
+                    self.select(idx);
+                    self.clicked = true;
+                    return EventResult::Consumed(None);
+                }
+            }
+        }
+        EventResult::Ignored
+    }
 }
 
 impl TabView {
@@ -94,6 +117,7 @@ impl TabView {
             cur_length,
             cur_offset: 0,
             total_length,
+            clicked: false,
         })
     }
 
@@ -102,6 +126,40 @@ impl TabView {
         &self.tabs[self.current_selected]
     }
 
+    /// Return whether a tab was clicked since the last call.
+    pub fn take_clicked(&mut self) -> bool {
+        std::mem::replace(&mut self.clicked, false)
+    }
+
+    /// Index of the tab drawn at the horizontal position x.
+    pub fn get_idx_at(&self, x: usize) -> Option<usize> {
+        let mut current_offset = 0;
+        let mut current_offset_idx = self.current_offset_idx;
+        for (idx, tab) in self.tabs.iter().enumerate() {
+            // Skip the hide item, same as draw
+            if idx != 0 && current_offset_idx > 0 {
+                current_offset_idx -= 1;
+                continue;
+            }
+            let end = current_offset + tab.len() + self.separator.len();
+            if x < end {
+                return Some(idx);
+            }
+            current_offset = end;
+        }
+        None
+    }
+
+    /// Select the tab at idx, return the selection offset like on_tab.
+    pub fn select(&mut self, idx: usize) -> usize {
+        self.current_selected = idx.min(self.tabs.len() - 1);
+        self.cur_length = self.tabs[..=self.current_selected]
+            .iter()
+            .fold(0, |acc, x| acc + x.len() + 1)
+            - 1;
+        self.cur_length
+    }
+
     /// Forward selection handler.
     pub fn on_tab(&mut self) -> usize {
         self.current_selected += 1;
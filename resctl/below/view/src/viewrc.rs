@@ -12,11 +12,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io::ErrorKind;
+use std::path::Path;
+use std::str::FromStr;
+
+use super::cgroup_tabs::default_tabs::CGROUP_TABS;
 use super::cgroup_view::CgroupView;
 use super::controllers::Controllers;
+use super::process_tabs::default_tabs::PROCESS_TABS;
+use super::process_view::ProcessView;
+use super::stats_view::{StateCommon, StatsView, ViewBridge};
 use super::{get_belowrc_filename, get_belowrc_view_section_key};
 
+use anyhow::{anyhow, Context, Result};
 use cursive::Cursive;
+use model::{CgroupModelFieldId, SingleProcessModelFieldId};
 use serde::Deserialize;
 
 /// Enum of supported front view.
@@ -30,6 +40,15 @@ pub enum DefaultFrontView {
     System,
 }
 
+/// Columns of a tab in the cgroup or process view, as field ids like the
+/// ones of `below dump`. A tab with the name of a built-in tab replaces its
+/// columns.
+#[derive(Deserialize)]
+pub struct TabRc {
+    pub name: String,
+    pub columns: Vec<String>,
+}
+
 /// Runtime configuration on the below view.
 #[derive(Default, Deserialize)]
 pub struct ViewRc {
@@ -39,6 +58,78 @@ pub struct ViewRc {
     // If we want to collapse all top level cgroups. If this field is not set,
     // it will be treated as false
     pub collapse_cgroups: Option<bool>,
+    // Tabs of the cgroup view, in addition to or replacing the built-in ones
+    pub cgroup_tabs: Option<Vec<TabRc>>,
+    // Tabs of the process view, in addition to or replacing the built-in ones
+    pub process_tabs: Option<Vec<TabRc>>,
+}
+
+/// Parse the columns of a tab, warning about the unknown ones. Return None if
+/// no column is valid.
+fn parse_columns<F: FromStr>(c: &mut Cursive, key: &str, tab: &TabRc) -> Option<Vec<F>> {
+    let mut fields = Vec::new();
+    for column in &tab.columns {
+        match column.parse::<F>() {
+            Ok(field) => fields.push(field),
+            Err(_) => view_warn!(
+                c,
+                "Unknown field in belowrc::{}.{} tab {}: {}",
+                get_belowrc_view_section_key(),
+                key,
+                tab.name,
+                column
+            ),
+        }
+    }
+    if fields.is_empty() {
+        view_warn!(
+            c,
+            "No valid column in belowrc::{}.{} tab {}",
+            get_belowrc_view_section_key(),
+            key,
+            tab.name
+        );
+        return None;
+    }
+    Some(fields)
+}
+
+/// Current columns of the tabs of a view, in the order they are shown,
+/// skipping the built-in tabs that still have their default columns.
+fn get_tab_rcs<V: 'static + ViewBridge>(
+    c: &mut Cursive,
+    get_default_columns: impl Fn(&str) -> Option<Vec<String>>,
+) -> Vec<TabRc> {
+    let mut view = StatsView::<V>::get_view(c);
+    let tabs = view.get_tabs();
+    let state = view.state.borrow();
+    tabs.into_iter()
+        .filter_map(|name| {
+            let columns = state.get_columns(&name)?;
+            if get_default_columns(&name).as_ref() == Some(&columns) {
+                return None;
+            }
+            Some(TabRc { name, columns })
+        })
+        .collect()
+}
+
+/// Set key of the view section to an array of tables of the tabs, or remove
+/// it if there is none.
+fn set_tab_rcs(viewrc: &mut toml_edit::Table, key: &str, tab_rcs: Vec<TabRc>) {
+    if tab_rcs.is_empty() {
+        viewrc.remove(key);
+        return;
+    }
+    let mut tables = toml_edit::ArrayOfTables::new();
+    for tab_rc in tab_rcs {
+        let mut table = toml_edit::Table::new();
+        table["name"] = toml_edit::value(tab_rc.name);
+        table["columns"] =
+            toml_edit::value(tab_rc.columns.into_iter().collect::<toml_edit::Value>());
+        tables.append(table);
+    }
+    viewrc[key] = toml_edit::Item::ArrayOfTables(tables);
 }
 
 impl ViewRc {
@@ -102,9 +193,78 @@ impl ViewRc {
         }
     }
 
+    /// Add the tabs of cgroup_tabs and process_tabs.
+    pub fn process_tabs(&self, c: &mut Cursive) {
+        for tab in self.cgroup_tabs.iter().flatten() {
+            if let Some(fields) = parse_columns::<CgroupModelFieldId>(c, "cgroup_tabs", tab) {
+                CgroupView::set_tab(c, &tab.name, fields);
+            }
+        }
+        for tab in self.process_tabs.iter().flatten() {
+            if let Some(fields) = parse_columns::<SingleProcessModelFieldId>(c, "process_tabs", tab)
+            {
+                ProcessView::set_tab(c, &tab.name, fields);
+            }
+        }
+    }
+
+    /// Save the columns of the tabs of the cgroup and process views that
+    /// differ from the built-in ones to the view section of belowrc. The rest
+    /// of the file is kept as is. Return the belowrc filename.
+    pub fn save_layout(c: &mut Cursive) -> Result<String> {
+        let cgroup_tabs = get_tab_rcs::<CgroupView>(c, |name| {
+            let (_, tab) = CGROUP_TABS.iter().find(|(tab_name, _)| *tab_name == name)?;
+            Some(
+                tab.view_items
+                    .iter()
+                    .map(|item| item.field_id.to_string())
+                    .collect(),
+            )
+        });
+        let process_tabs = get_tab_rcs::<ProcessView>(c, |name| {
+            let (_, tab) = PROCESS_TABS
+                .iter()
+                .find(|(tab_name, _)| *tab_name == name)?;
+            Some(
+                tab.view_items
+                    .iter()
+                    .map(|item| item.field_id.to_string())
+                    .collect(),
+            )
+        });
+
+        let filename = get_belowrc_filename();
+        let mut belowrc = match std::fs::read_to_string(&filename) {
+            // Refuse to overwrite a belowrc we fail to parse
+            Ok(belowrc_str) => belowrc_str
+                .parse::<toml_edit::Document>()
+                .context("Failed to parse belowrc")?,
+            Err(e) if e.kind() == ErrorKind::NotFound => toml_edit::Document::new(),
+            Err(e) => return Err(e).context("Failed to read belowrc"),
+        };
+        let key = get_belowrc_view_section_key();
+        if belowrc[key].is_none() {
+            belowrc[key] = toml_edit::table();
+        }
+        let viewrc = belowrc[key]
+            .as_table_mut()
+            .ok_or_else(|| anyhow!("belowrc::{} is not a table", key))?;
+        set_tab_rcs(viewrc, "cgroup_tabs", cgroup_tabs);
+        set_tab_rcs(viewrc, "process_tabs", process_tabs);
+
+        if let Some(dir) = Path::new(&filename).parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        std::fs::write(&filename, belowrc.to_string())
+            .with_context(|| format!("Failed to write {}", filename))?;
+        Ok(filename)
+    }
+
     /// Syntactic sugar for processing the belowrc file.
     pub fn process(c: &mut Cursive) {
         let viewrc = Self::new(c);
+        viewrc.process_tabs(c);
         viewrc.process_default_view(c);
         viewrc.process_collapse_cgroups(c);
         super::refresh(c);
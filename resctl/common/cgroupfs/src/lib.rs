@@ -15,7 +15,7 @@
 #![deny(clippy::all)]
 use std::collections::BTreeMap;
 use std::ffi::OsStr;
-use std::io::{BufRead, BufReader, ErrorKind};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
 use std::path::{Path, PathBuf};
 
 use nix::sys::statfs::{fstatfs, CGROUP2_SUPER_MAGIC};
@@ -45,10 +45,86 @@ pub enum Error {
     UnexpectedLine(PathBuf, String),
     #[error("Not cgroup2 filesystem: {0:?}")]
     NotCgroup2(PathBuf),
+    #[error("Invalid value ({1}) for file: {0:?}")]
+    InvalidValue(PathBuf, String),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Interface files that can be written to adjust a cgroup
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Knob {
+    MemoryHigh,
+    MemoryMax,
+    CpuMax,
+    CpuWeight,
+    Freeze,
+}
+
+impl Knob {
+    pub const ALL: [Knob; 5] = [
+        Knob::MemoryHigh,
+        Knob::MemoryMax,
+        Knob::CpuMax,
+        Knob::CpuWeight,
+        Knob::Freeze,
+    ];
+
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            Knob::MemoryHigh => "memory.high",
+            Knob::MemoryMax => "memory.max",
+            Knob::CpuMax => "cpu.max",
+            Knob::CpuWeight => "cpu.weight",
+            Knob::Freeze => "cgroup.freeze",
+        }
+    }
+
+    /// Format of the values accepted for this knob
+    pub fn value_hint(&self) -> &'static str {
+        match self {
+            Knob::MemoryHigh | Knob::MemoryMax => "bytes with optional K/M/G/T suffix, or max",
+            Knob::CpuMax => "quota in usecs or max, optionally followed by period in usecs",
+            Knob::CpuWeight => "1 to 10000",
+            Knob::Freeze => "1 to freeze, 0 to thaw",
+        }
+    }
+
+    fn is_valid(&self, value: &str) -> bool {
+        let is_bytes = |v: &str| {
+            let digits = v.trim_end_matches(|c| "kKmMgGtT".contains(c));
+            v.len() - digits.len() <= 1 && digits.parse::<u64>().is_ok()
+        };
+        match self {
+            Knob::MemoryHigh | Knob::MemoryMax => value == "max" || is_bytes(value),
+            Knob::CpuMax => {
+                let mut iter = value.split_whitespace();
+                let quota_valid = match iter.next() {
+                    Some("max") => true,
+                    Some(quota) => quota.parse::<u64>().is_ok(),
+                    None => false,
+                };
+                let period_valid = iter.next().map_or(true, |p| p.parse::<u64>().is_ok());
+                quota_valid && period_valid && iter.next().is_none()
+            }
+            Knob::CpuWeight => matches!(value.parse::<u32>(), Ok(1..=10000)),
+            Knob::Freeze => value == "0" || value == "1",
+        }
+    }
+}
+
+impl std::str::FromStr for Knob {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Knob::ALL
+            .iter()
+            .find(|knob| knob.file_name() == s)
+            .copied()
+            .ok_or_else(|| format!("Not a writable cgroup file: {}", s))
+    }
+}
+
 pub struct CgroupReader {
     relative_path: PathBuf,
     dir: Dir,
@@ -129,6 +205,16 @@ impl CgroupReader {
         Err(self.invalid_file_format(file_name))
     }
 
+    /// Read a single line stat file that may also contain "max",
+    /// returning -1 in that case
+    fn read_singleline_max_stat_file(&self, file_name: &str) -> Result<i64> {
+        match self.read_singleline_stat_file(file_name) {
+            Ok(v) => Ok(v as i64),
+            Err(Error::UnexpectedLine(_, line)) if line.starts_with("max") => Ok(-1),
+            Err(e) => Err(e),
+        }
+    }
+
     /// Read memory.current - returning current cgroup memory
     /// consumption in bytes
     pub fn read_memory_current(&self) -> Result<u64> {
@@ -155,7 +241,7 @@ impl CgroupReader {
 
     /// Read cpu.stat - returning assorted cpu consumption statistics
     pub fn read_cpu_stat(&self) -> Result<CpuStat> {
-        CpuStat::read(&self)
+        CpuStat::read(&self, "cpu.stat")
     }
 
     /// Read io.stat - returning assorted io consumption statistics
@@ -166,11 +252,95 @@ impl CgroupReader {
     /// Read memory.stat - returning assorted memory consumption
     /// statistics
     pub fn read_memory_stat(&self) -> Result<MemoryStat> {
-        MemoryStat::read(&self)
+        MemoryStat::read(&self, "memory.stat")
     }
 
     pub fn read_memory_events(&self) -> Result<MemoryEvents> {
-        MemoryEvents::read(&self)
+        MemoryEvents::read(&self, "memory.events")
+    }
+
+    /// Read memory.events.local - same keys as memory.events but
+    /// only counting events that originated in this cgroup
+    pub fn read_memory_events_local(&self) -> Result<MemoryEvents> {
+        MemoryEvents::read(self, "memory.events.local")
+    }
+
+    /// Read memory.swap.events - returning swap limit events
+    pub fn read_memory_swap_events(&self) -> Result<MemorySwapEvents> {
+        MemorySwapEvents::read(self, "memory.swap.events")
+    }
+
+    /// Read hugetlb.<size>.{current,max,events} for every huge page
+    /// size - returning a map keyed by page size (e.g. "2MB"). Returns
+    /// a NotFound IoError if the hugetlb controller is not enabled.
+    pub fn read_hugetlb_stat(&self) -> Result<BTreeMap<String, HugetlbStat>> {
+        let mut sizes = self
+            .dir
+            .list_dir(".")
+            .map_err(|e| self.io_error("", e))?
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let name = entry.file_name().to_str()?;
+                let size = name.strip_prefix("hugetlb.")?.strip_suffix(".current")?;
+                // Skip hugetlb.<size>.rsvd.current
+                if size.contains('.') {
+                    None
+                } else {
+                    Some(size.to_string())
+                }
+            })
+            .peekable();
+        if sizes.peek().is_none() {
+            return Err(self.io_error(
+                "hugetlb.*.current",
+                std::io::Error::from(ErrorKind::NotFound),
+            ));
+        }
+        sizes
+            .map(|size| {
+                let stat = HugetlbStat {
+                    current: Some(
+                        self.read_singleline_stat_file(&format!("hugetlb.{}.current", size))?,
+                    ),
+                    max: Some(
+                        self.read_singleline_max_stat_file(&format!("hugetlb.{}.max", size))?,
+                    ),
+                    events: Some(HugetlbEvents::read(
+                        self,
+                        &format!("hugetlb.{}.events", size),
+                    )?),
+                };
+                Ok((size, stat))
+            })
+            .collect()
+    }
+
+    /// Read rdma.current - returning per-device RDMA resource usage
+    pub fn read_rdma_current(&self) -> Result<BTreeMap<String, RdmaStat>> {
+        RdmaStat::read(self, "rdma.current")
+    }
+
+    /// Read misc.current - returning usage of each misc resource
+    pub fn read_misc_current(&self) -> Result<BTreeMap<String, u64>> {
+        let file_name = "misc.current";
+        let file = self
+            .dir
+            .open_file(file_name)
+            .map_err(|e| self.io_error(file_name, e))?;
+        let buf_reader = BufReader::new(file);
+        let mut map = BTreeMap::new();
+        for line in buf_reader.lines() {
+            let line = line.map_err(|e| self.io_error(file_name, e))?;
+            let items = line.split_whitespace().collect::<Vec<_>>();
+            if items.len() != 2 {
+                return Err(self.unexpected_line(file_name, line));
+            }
+            let val = items[1]
+                .parse::<u64>()
+                .map_err(|_| self.unexpected_line(file_name, line.clone()))?;
+            map.insert(items[0].to_string(), val);
+        }
+        Ok(map)
     }
 
     /// Read cpu.pressure
@@ -227,6 +397,26 @@ impl CgroupReader {
         })
     }
 
+    /// Write a value to an interface file of this cgroup. The value is
+    /// checked first so that a typo is not reported as an opaque EINVAL.
+    pub fn write_knob(&self, knob: Knob, value: &str) -> Result<()> {
+        let file_name = knob.file_name();
+        let value = value.trim();
+        if !knob.is_valid(value) {
+            return Err(self.invalid_value(file_name, value.to_owned()));
+        }
+        // Files of disabled controllers are missing, and we must not create them
+        self.dir
+            .metadata(file_name)
+            .map_err(|e| self.io_error(file_name, e))?;
+        let mut file = self
+            .dir
+            .write_file(file_name, 0o644)
+            .map_err(|e| self.io_error(file_name, e))?;
+        file.write_all(value.as_bytes())
+            .map_err(|e| self.io_error(file_name, e))
+    }
+
     /// Return an iterator over child cgroups
     pub fn child_cgroup_iter(&self) -> Result<impl Iterator<Item = CgroupReader> + '_> {
         Ok(self
@@ -261,6 +451,12 @@ impl CgroupReader {
         Error::IoError(p, e)
     }
 
+    fn invalid_value<P: AsRef<Path>>(&self, file_name: P, value: String) -> Error {
+        let mut p = self.relative_path.clone();
+        p.push(file_name);
+        Error::InvalidValue(p, value)
+    }
+
     fn unexpected_line<P: AsRef<Path>>(&self, file_name: P, line: String) -> Error {
         let mut p = self.relative_path.clone();
         p.push(file_name);
@@ -270,19 +466,19 @@ impl CgroupReader {
 
 // Trait to add a read() method for `key value` formatted files
 trait KVRead: Sized {
-    fn read(reader: &CgroupReader) -> Result<Self>;
+    fn read(reader: &CgroupReader, file_name: &str) -> Result<Self>;
 }
 
-// This macro generates the read() method for the given struct, file
-// name, and keys. If a line does not exist in the file then the
-// corresponding field is left as `None`. If lines include fields that
-// are not listed, they are ignored.
+// This macro generates the read() method for the given struct and
+// keys. If a line does not exist in the file then the corresponding
+// field is left as `None`. If lines include fields that are not
+// listed, they are ignored, or kept in the map field named after the
+// list if any.
 macro_rules! key_values_format {
-    ($struct:ident; $file:expr; [ $( $field:ident ),+ ]) => (
+    ($struct:ident; [ $( $field:ident ),+ ] $(; $other:ident)?) => (
         impl KVRead for $struct {
-            fn read(r: &CgroupReader) -> Result<$struct> {
+            fn read(r: &CgroupReader, file_name: &str) -> Result<$struct> {
                 let mut s = $struct::default();
-                let file_name = stringify!($file);
                 let file = r.dir.open_file(file_name).map_err(|e| r.io_error(file_name, e))?;
                 let buf_reader = BufReader::new(file);
                 for line in buf_reader.lines() {
@@ -295,7 +491,9 @@ macro_rules! key_values_format {
                     let val = items[1].parse::<u64>().map_err(|_| r.unexpected_line(file_name, line.clone()))?;
                     match key.as_ref() {
                         $(stringify!($field) => s.$field = Some(val),)*
-                        _ => (),
+                        _ => {
+                            $(s.$other.insert(key.to_owned(), val);)?
+                        }
                     };
                 }
                 if s == $struct::default() {
@@ -308,7 +506,7 @@ macro_rules! key_values_format {
     )
 }
 
-key_values_format!(CpuStat; cpu.stat; [
+key_values_format!(CpuStat; [
     usage_usec,
     user_usec,
     system_usec,
@@ -317,7 +515,7 @@ key_values_format!(CpuStat; cpu.stat; [
     throttled_usec
 ]);
 
-key_values_format!(MemoryStat; memory.stat; [
+key_values_format!(MemoryStat; [
     anon,
     file,
     kernel_stack,
@@ -348,10 +546,31 @@ key_values_format!(MemoryStat; memory.stat; [
     pglazyfree,
     pglazyfreed,
     thp_fault_alloc,
-    thp_collapse_alloc
-]);
-
-key_values_format!(MemoryEvents; memory.events; [
+    thp_collapse_alloc,
+    pagetables,
+    percpu,
+    zswap,
+    zswapped,
+    file_thp,
+    shmem_thp,
+    swapcached,
+    workingset_refault_anon,
+    workingset_refault_file,
+    workingset_activate_anon,
+    workingset_activate_file,
+    workingset_restore_anon,
+    workingset_restore_file,
+    pgscan_kswapd,
+    pgscan_direct,
+    pgsteal_kswapd,
+    pgsteal_direct,
+    zswpin,
+    zswpout,
+    thp_swpout,
+    thp_swpout_fallback
+]; other);
+
+key_values_format!(MemoryEvents; [
     low,
     high,
     max,
@@ -359,6 +578,14 @@ key_values_format!(MemoryEvents; memory.events; [
     oom_kill
 ]);
 
+key_values_format!(MemorySwapEvents; [
+    high,
+    max,
+    fail
+]);
+
+key_values_format!(HugetlbEvents; [max]);
+
 // Trait to add a read() method for `<string> key=value` formatted files
 trait NameKVRead: Sized {
     fn read<P: AsRef<Path> + AsPath + Clone>(
@@ -423,3 +650,8 @@ name_key_equal_value_format!(PressureMetrics; false; [
     avg300,
     total,
 ]);
+
+name_key_equal_value_format!(RdmaStat; true; [
+    hca_handle,
+    hca_object,
+]);
@@ -22,6 +22,7 @@ use tempfile::TempDir;
 
 use crate::CgroupReader;
 use crate::Error;
+use crate::Knob;
 
 struct TestCgroup {
     tempdir: TempDir,
@@ -174,6 +175,56 @@ fn test_memory_high_failure() {
     assert!(val.is_err());
 }
 
+#[test]
+fn test_write_knob() {
+    let cgroup = TestCgroup::new();
+    cgroup.create_file_with_content("memory.high", b"max\n");
+    cgroup.create_file_with_content("cpu.max", b"max 100000\n");
+
+    let cgroup_reader = cgroup.get_reader();
+    cgroup_reader
+        .write_knob(Knob::MemoryHigh, "2G\n")
+        .expect("Failed to write memory.high");
+    assert_eq!(
+        std::fs::read_to_string(cgroup.path().join("memory.high")).unwrap(),
+        "2G"
+    );
+    cgroup_reader
+        .write_knob(Knob::CpuMax, "50000 100000")
+        .expect("Failed to write cpu.max");
+    assert_eq!(
+        std::fs::read_to_string(cgroup.path().join("cpu.max")).unwrap(),
+        "50000 100000"
+    );
+
+    // Invalid values are rejected before writing
+    for (knob, value) in &[
+        (Knob::MemoryHigh, "2GB"),
+        (Knob::CpuMax, "max max"),
+        (Knob::CpuWeight, "0"),
+        (Knob::Freeze, "yes"),
+    ] {
+        match cgroup_reader.write_knob(*knob, value) {
+            Err(Error::InvalidValue(_, _)) => {}
+            res => panic!("Unexpected result for {}: {:?}", value, res),
+        }
+    }
+    assert_eq!(
+        std::fs::read_to_string(cgroup.path().join("memory.high")).unwrap(),
+        "2G"
+    );
+
+    // Files of disabled controllers are not created
+    match cgroup_reader.write_knob(Knob::Freeze, "1") {
+        Err(Error::IoError(_, e)) if e.kind() == std::io::ErrorKind::NotFound => {}
+        res => panic!("Unexpected result for cgroup.freeze: {:?}", res),
+    }
+    assert!(!cgroup.path().join("cgroup.freeze").exists());
+
+    assert_eq!("cpu.weight".parse::<Knob>(), Ok(Knob::CpuWeight));
+    assert!("memory.current".parse::<Knob>().is_err());
+}
+
 #[test]
 fn test_memory_stat_success() {
     let cgroup = TestCgroup::new();
@@ -231,6 +282,72 @@ fn test_memory_stat_invalid_format() {
     }
 }
 
+#[test]
+fn test_memory_stat_new_keys() {
+    let cgroup = TestCgroup::new();
+    cgroup.create_file_with_content(
+        "memory.stat",
+        b"percpu 4096\nzswap 8192\nzswapped 16384\nworkingset_refault_anon 5\npgscan_kswapd 42\n",
+    );
+
+    let cgroup_reader = cgroup.get_reader();
+    let val = cgroup_reader
+        .read_memory_stat()
+        .expect("Failed to read memory.stat");
+    assert_eq!(val.percpu, Some(4096));
+    assert_eq!(val.zswap, Some(8192));
+    assert_eq!(val.zswapped, Some(16384));
+    assert_eq!(val.workingset_refault_anon, Some(5));
+    assert_eq!(val.pgscan_kswapd, Some(42));
+    assert_eq!(val.pgscan_direct, None);
+    assert!(val.other.is_empty());
+}
+
+#[test]
+fn test_memory_stat_unknown_keys() {
+    let cgroup = TestCgroup::new();
+    cgroup.create_file_with_content("memory.stat", b"slab 1234\nsome_future_key 77\n");
+
+    let cgroup_reader = cgroup.get_reader();
+    let val = cgroup_reader
+        .read_memory_stat()
+        .expect("Failed to read memory.stat");
+    assert_eq!(val.slab, Some(1234));
+    assert_eq!(val.other.get("some_future_key"), Some(&77));
+    assert_eq!(val.other.len(), 1);
+}
+
+#[test]
+fn test_memory_events_local_success() {
+    let cgroup = TestCgroup::new();
+    cgroup.create_file_with_content(
+        "memory.events.local",
+        b"low 0\nhigh 12\nmax 3\noom 1\noom_kill 1\n",
+    );
+
+    let cgroup_reader = cgroup.get_reader();
+    let val = cgroup_reader
+        .read_memory_events_local()
+        .expect("Failed to read memory.events.local");
+    assert_eq!(val.high, Some(12));
+    assert_eq!(val.max, Some(3));
+    assert_eq!(val.oom_kill, Some(1));
+}
+
+#[test]
+fn test_memory_swap_events_success() {
+    let cgroup = TestCgroup::new();
+    cgroup.create_file_with_content("memory.swap.events", b"high 0\nmax 7\nfail 2\n");
+
+    let cgroup_reader = cgroup.get_reader();
+    let val = cgroup_reader
+        .read_memory_swap_events()
+        .expect("Failed to read memory.swap.events");
+    assert_eq!(val.high, Some(0));
+    assert_eq!(val.max, Some(7));
+    assert_eq!(val.fail, Some(2));
+}
+
 #[test]
 fn test_cpu_stat_success() {
     let cgroup = TestCgroup::new();
@@ -425,6 +542,77 @@ fn test_memory_pressure_empty_file() {
     }
 }
 
+#[test]
+fn test_hugetlb_stat_success() {
+    let cgroup = TestCgroup::new();
+    cgroup.create_file_with_content("hugetlb.2MB.current", b"4194304\n");
+    cgroup.create_file_with_content("hugetlb.2MB.max", b"max\n");
+    cgroup.create_file_with_content("hugetlb.2MB.events", b"max 0\n");
+    cgroup.create_file_with_content("hugetlb.2MB.rsvd.current", b"0\n");
+    cgroup.create_file_with_content("hugetlb.1GB.current", b"0\n");
+    cgroup.create_file_with_content("hugetlb.1GB.max", b"1073741824\n");
+    cgroup.create_file_with_content("hugetlb.1GB.events", b"max 3\n");
+
+    let cgroup_reader = cgroup.get_reader();
+    let val = cgroup_reader
+        .read_hugetlb_stat()
+        .expect("Failed to read hugetlb stats");
+    assert_eq!(val.len(), 2);
+    let stat_2mb = val.get("2MB").expect("Failed to find 2MB hugetlb");
+    assert_eq!(stat_2mb.current, Some(4194304));
+    assert_eq!(stat_2mb.max, Some(-1));
+    assert_eq!(stat_2mb.events.as_ref().and_then(|e| e.max), Some(0));
+    let stat_1gb = val.get("1GB").expect("Failed to find 1GB hugetlb");
+    assert_eq!(stat_1gb.current, Some(0));
+    assert_eq!(stat_1gb.max, Some(1073741824));
+    assert_eq!(stat_1gb.events.as_ref().and_then(|e| e.max), Some(3));
+}
+
+#[test]
+fn test_hugetlb_stat_not_enabled() {
+    let cgroup = TestCgroup::new();
+    let cgroup_reader = cgroup.get_reader();
+    let err = cgroup_reader
+        .read_hugetlb_stat()
+        .expect_err("Did not fail to read hugetlb stats");
+    match err {
+        Error::IoError(_, e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        _ => panic!("Got unexpected error type {}", err),
+    }
+}
+
+#[test]
+fn test_rdma_current_success() {
+    let cgroup = TestCgroup::new();
+    cgroup.create_file_with_content(
+        "rdma.current",
+        b"mlx4_0 hca_handle=1 hca_object=20\nocrdma1 hca_handle=0 hca_object=0\n",
+    );
+
+    let cgroup_reader = cgroup.get_reader();
+    let val = cgroup_reader
+        .read_rdma_current()
+        .expect("Failed to read rdma.current");
+    assert_eq!(val.len(), 2);
+    assert_eq!(val["mlx4_0"].hca_handle, Some(1));
+    assert_eq!(val["mlx4_0"].hca_object, Some(20));
+    assert_eq!(val["ocrdma1"].hca_handle, Some(0));
+}
+
+#[test]
+fn test_misc_current_success() {
+    let cgroup = TestCgroup::new();
+    cgroup.create_file_with_content("misc.current", b"sev 2\nsev_es 0\n");
+
+    let cgroup_reader = cgroup.get_reader();
+    let val = cgroup_reader
+        .read_misc_current()
+        .expect("Failed to read misc.current");
+    assert_eq!(val.len(), 2);
+    assert_eq!(val["sev"], 2);
+    assert_eq!(val["sev_es"], 0);
+}
+
 #[test]
 fn test_child_cgroup_iter() {
     let root = TestCgroup::new();
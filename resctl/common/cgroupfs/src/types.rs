@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
@@ -67,6 +69,30 @@ pub struct MemoryStat {
     pub pglazyfreed: Option<u64>,
     pub thp_fault_alloc: Option<u64>,
     pub thp_collapse_alloc: Option<u64>,
+    pub pagetables: Option<u64>,
+    pub percpu: Option<u64>,
+    pub zswap: Option<u64>,
+    pub zswapped: Option<u64>,
+    pub file_thp: Option<u64>,
+    pub shmem_thp: Option<u64>,
+    pub swapcached: Option<u64>,
+    pub workingset_refault_anon: Option<u64>,
+    pub workingset_refault_file: Option<u64>,
+    pub workingset_activate_anon: Option<u64>,
+    pub workingset_activate_file: Option<u64>,
+    pub workingset_restore_anon: Option<u64>,
+    pub workingset_restore_file: Option<u64>,
+    pub pgscan_kswapd: Option<u64>,
+    pub pgscan_direct: Option<u64>,
+    pub pgsteal_kswapd: Option<u64>,
+    pub pgsteal_direct: Option<u64>,
+    pub zswpin: Option<u64>,
+    pub zswpout: Option<u64>,
+    pub thp_swpout: Option<u64>,
+    pub thp_swpout_fallback: Option<u64>,
+    // Keys not listed above, e.g. added by newer kernels
+    #[serde(default)]
+    pub other: BTreeMap<String, u64>,
 }
 
 #[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
@@ -109,3 +135,28 @@ pub struct MemoryEvents {
     pub oom: Option<u64>,
     pub oom_kill: Option<u64>,
 }
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct MemorySwapEvents {
+    pub high: Option<u64>,
+    pub max: Option<u64>,
+    pub fail: Option<u64>,
+}
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct HugetlbEvents {
+    pub max: Option<u64>,
+}
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct HugetlbStat {
+    pub current: Option<u64>,
+    pub max: Option<i64>,
+    pub events: Option<HugetlbEvents>,
+}
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct RdmaStat {
+    pub hca_handle: Option<u64>,
+    pub hca_object: Option<u64>,
+}
@@ -38,6 +38,8 @@ mod test;
 
 pub const NET_SYSFS: &str = "/sys/class/net/";
 pub const NET_PROCFS: &str = "/proc/net";
+pub const CPU_SYSFS: &str = "/sys/devices/system/cpu";
+pub const THERMAL_SYSFS: &str = "/sys/class/thermal";
 
 lazy_static! {
     /// The number of microseconds per clock tick
@@ -140,6 +142,23 @@ macro_rules! parse_kb {
     };
 }
 
+macro_rules! parse_hex {
+    ($path:expr, $rhs:expr, $line:ident) => {
+        if let Some(s) = $rhs {
+            u64::from_str_radix(s, 16)
+                .map_err(|_| Error::ParseError {
+                    line: $line.clone(),
+                    item: s.to_string(),
+                    type_name: "hex u64".to_string(),
+                    path: $path.clone(),
+                })
+                .map(Some)
+        } else {
+            Ok(None)
+        }
+    };
+}
+
 pub struct ProcReader {
     path: PathBuf,
     threadpool: ThreadPool,
@@ -370,11 +389,116 @@ impl ProcReader {
                         vmstat.pgscan_direct = parse_item!(path, items.next(), u64, line)?
                     }
                     "oom_kill" => vmstat.oom_kill = parse_item!(path, items.next(), u64, line)?,
+                    "pgfault" => vmstat.pgfault = parse_item!(path, items.next(), u64, line)?,
+                    "pgmajfault" => vmstat.pgmajfault = parse_item!(path, items.next(), u64, line)?,
+                    "pgactivate" => vmstat.pgactivate = parse_item!(path, items.next(), u64, line)?,
+                    "pgdeactivate" => {
+                        vmstat.pgdeactivate = parse_item!(path, items.next(), u64, line)?
+                    }
+                    "pgrefill" => vmstat.pgrefill = parse_item!(path, items.next(), u64, line)?,
+                    "pgscan_direct_throttle" => {
+                        vmstat.pgscan_direct_throttle = parse_item!(path, items.next(), u64, line)?
+                    }
+                    "kswapd_inodesteal" => {
+                        vmstat.kswapd_inodesteal = parse_item!(path, items.next(), u64, line)?
+                    }
+                    "kswapd_low_wmark_hit_quickly" => {
+                        vmstat.kswapd_low_wmark_hit_quickly =
+                            parse_item!(path, items.next(), u64, line)?
+                    }
+                    "kswapd_high_wmark_hit_quickly" => {
+                        vmstat.kswapd_high_wmark_hit_quickly =
+                            parse_item!(path, items.next(), u64, line)?
+                    }
+                    "pageoutrun" => vmstat.pageoutrun = parse_item!(path, items.next(), u64, line)?,
+                    "compact_migrate_scanned" => {
+                        vmstat.compact_migrate_scanned = parse_item!(path, items.next(), u64, line)?
+                    }
+                    "compact_free_scanned" => {
+                        vmstat.compact_free_scanned = parse_item!(path, items.next(), u64, line)?
+                    }
+                    "compact_isolated" => {
+                        vmstat.compact_isolated = parse_item!(path, items.next(), u64, line)?
+                    }
+                    "compact_stall" => {
+                        vmstat.compact_stall = parse_item!(path, items.next(), u64, line)?
+                    }
+                    "compact_fail" => {
+                        vmstat.compact_fail = parse_item!(path, items.next(), u64, line)?
+                    }
+                    "compact_success" => {
+                        vmstat.compact_success = parse_item!(path, items.next(), u64, line)?
+                    }
+                    "compact_daemon_wake" => {
+                        vmstat.compact_daemon_wake = parse_item!(path, items.next(), u64, line)?
+                    }
+                    "thp_fault_alloc" => {
+                        vmstat.thp_fault_alloc = parse_item!(path, items.next(), u64, line)?
+                    }
+                    "thp_fault_fallback" => {
+                        vmstat.thp_fault_fallback = parse_item!(path, items.next(), u64, line)?
+                    }
+                    "thp_collapse_alloc" => {
+                        vmstat.thp_collapse_alloc = parse_item!(path, items.next(), u64, line)?
+                    }
+                    "thp_collapse_alloc_failed" => {
+                        vmstat.thp_collapse_alloc_failed =
+                            parse_item!(path, items.next(), u64, line)?
+                    }
+                    "thp_split_page" => {
+                        vmstat.thp_split_page = parse_item!(path, items.next(), u64, line)?
+                    }
+                    "thp_split_page_failed" => {
+                        vmstat.thp_split_page_failed = parse_item!(path, items.next(), u64, line)?
+                    }
+                    "thp_deferred_split_page" => {
+                        vmstat.thp_deferred_split_page = parse_item!(path, items.next(), u64, line)?
+                    }
+                    "thp_split_pmd" => {
+                        vmstat.thp_split_pmd = parse_item!(path, items.next(), u64, line)?
+                    }
+                    "thp_swpout" => vmstat.thp_swpout = parse_item!(path, items.next(), u64, line)?,
+                    "thp_swpout_fallback" => {
+                        vmstat.thp_swpout_fallback = parse_item!(path, items.next(), u64, line)?
+                    }
+                    "workingset_refault" => {
+                        vmstat.workingset_refault = parse_item!(path, items.next(), u64, line)?
+                    }
+                    "workingset_refault_anon" => {
+                        vmstat.workingset_refault_anon = parse_item!(path, items.next(), u64, line)?
+                    }
+                    "workingset_refault_file" => {
+                        vmstat.workingset_refault_file = parse_item!(path, items.next(), u64, line)?
+                    }
+                    "workingset_activate" => {
+                        vmstat.workingset_activate = parse_item!(path, items.next(), u64, line)?
+                    }
+                    "workingset_restore" => {
+                        vmstat.workingset_restore = parse_item!(path, items.next(), u64, line)?
+                    }
+                    "workingset_nodereclaim" => {
+                        vmstat.workingset_nodereclaim = parse_item!(path, items.next(), u64, line)?
+                    }
+                    x if x == "allocstall" || x.starts_with("allocstall_") => {
+                        if let Some(v) = parse_item!(path, items.next(), u64, line)? {
+                            vmstat.allocstall = Some(vmstat.allocstall.unwrap_or_default() + v);
+                        }
+                    }
                     _ => {}
                 }
             }
         }
 
+        if vmstat.workingset_refault.is_none() {
+            vmstat.workingset_refault = match (
+                vmstat.workingset_refault_anon,
+                vmstat.workingset_refault_file,
+            ) {
+                (Some(anon), Some(file)) => Some(anon + file),
+                (anon, file) => anon.or(file),
+            };
+        }
+
         if vmstat == Default::default() {
             Err(Error::InvalidFileFormat(path))
         } else {
@@ -430,6 +554,106 @@ impl ProcReader {
         }
     }
 
+    /// Both /proc/interrupts and /proc/softirqs have a `CPU0 CPU1 ...`
+    /// header followed by one line of per-CPU counts per source. Only online
+    /// CPUs are listed, so the counts are keyed by the CPU ids of the header.
+    fn read_interrupt_table(&self, file_name: &str) -> Result<InterruptMap> {
+        let path = self.path.join(file_name);
+        let file = File::open(&path).map_err(|e| Error::IoError(path.clone(), e))?;
+        let mut lines = BufReader::new(file).lines();
+        let cpu_ids = match lines.next() {
+            Some(line) => line
+                .map_err(|e| Error::IoError(path.clone(), e))?
+                .split_whitespace()
+                .filter_map(|item| item.strip_prefix("CPU"))
+                .map(|id| id.parse::<u32>())
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|_| Error::InvalidFileFormat(path.clone()))?,
+            None => return Err(Error::InvalidFileFormat(path)),
+        };
+        let mut interrupt_map: InterruptMap = Default::default();
+
+        for line in lines {
+            let line = line.map_err(|e| Error::IoError(path.clone(), e))?;
+
+            let mut items = line.split_whitespace().peekable();
+            let name = match items.next() {
+                Some(name) => name.trim_end_matches(':').to_string(),
+                None => continue,
+            };
+            // Rows like ERR and MIS only have a single count
+            let mut cpus = BTreeMap::new();
+            for cpu_id in &cpu_ids {
+                match items.peek() {
+                    Some(item) if item.chars().all(|c| c.is_ascii_digit()) => {
+                        if let Some(v) = parse_item!(path, items.next(), u64, line)? {
+                            cpus.insert(*cpu_id, v);
+                        }
+                    }
+                    _ => break,
+                }
+            }
+            let description = items.collect::<Vec<_>>().join(" ");
+
+            interrupt_map.insert(
+                name,
+                InterruptStat {
+                    cpus: Some(cpus),
+                    description: if description.is_empty() {
+                        None
+                    } else {
+                        Some(description)
+                    },
+                },
+            );
+        }
+
+        if interrupt_map.is_empty() {
+            Err(Error::InvalidFileFormat(path))
+        } else {
+            Ok(interrupt_map)
+        }
+    }
+
+    pub fn read_interrupts(&self) -> Result<InterruptMap> {
+        self.read_interrupt_table("interrupts")
+    }
+
+    pub fn read_softirqs(&self) -> Result<InterruptMap> {
+        self.read_interrupt_table("softirqs")
+    }
+
+    /// Returns one entry per online CPU, so the row index is not the CPU id
+    /// if any CPU is offline. Values in the file are hex encoded.
+    pub fn read_softnet_stat(&self) -> Result<Vec<SoftnetStat>> {
+        let path = self.path.join("net/softnet_stat");
+        let file = File::open(&path).map_err(|e| Error::IoError(path.clone(), e))?;
+        let buf_reader = BufReader::new(file);
+        let mut softnet = Vec::new();
+
+        for line in buf_reader.lines() {
+            let line = line.map_err(|e| Error::IoError(path.clone(), e))?;
+
+            let mut items = line.split_whitespace();
+            let stat = SoftnetStat {
+                processed: parse_hex!(path, items.next(), line)?,
+                dropped: parse_hex!(path, items.next(), line)?,
+                time_squeeze: parse_hex!(path, items.next(), line)?,
+                // The 13th column, only printed since kernel 5.10
+                cpu: parse_hex!(path, items.nth(9), line)?,
+            };
+            if stat != Default::default() {
+                softnet.push(stat);
+            }
+        }
+
+        if softnet.is_empty() {
+            Err(Error::InvalidFileFormat(path))
+        } else {
+            Ok(softnet)
+        }
+    }
+
     fn read_pid_stat_from_path<P: AsRef<Path>>(&self, path: P) -> Result<PidStat> {
         let path = path.as_ref().join("stat");
         let file = File::open(&path).map_err(|e| Error::IoError(path.clone(), e))?;
@@ -798,6 +1022,112 @@ impl PidStateExt for PidState {
     }
 }
 
+/// Reads per-CPU frequency and thermal data from sysfs. All files are
+/// optional as they depend on the cpufreq driver and platform.
+pub struct SysfsReader {
+    cpu_path: PathBuf,
+    thermal_path: PathBuf,
+}
+
+impl SysfsReader {
+    pub fn new() -> SysfsReader {
+        Self::new_with_custom_path(CPU_SYSFS.into(), THERMAL_SYSFS.into())
+    }
+
+    pub fn new_with_custom_path(cpu_path: PathBuf, thermal_path: PathBuf) -> SysfsReader {
+        SysfsReader {
+            cpu_path,
+            thermal_path,
+        }
+    }
+
+    /// Returns the first line of the file, or None if it does not exist
+    fn read_first_line(path: &Path) -> Result<Option<String>> {
+        let file = match File::open(path) {
+            Ok(f) => f,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(Error::IoError(path.to_path_buf(), e)),
+        };
+        match BufReader::new(file).lines().next() {
+            Some(line) => line
+                .map(|l| Some(l.trim().to_string()))
+                .map_err(|e| Error::IoError(path.to_path_buf(), e)),
+            None => Err(Error::InvalidFileFormat(path.to_path_buf())),
+        }
+    }
+
+    fn read_u64(path: &Path) -> Result<Option<u64>> {
+        match Self::read_first_line(path)? {
+            Some(line) => parse_item!(path.to_path_buf(), Some(line.as_str()), u64, line),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns (index, path) for each directory entry named `<prefix><index>`
+    fn list_indexed_dirs(path: &Path, prefix: &str) -> Result<Vec<(u32, PathBuf)>> {
+        let entries = match std::fs::read_dir(path) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(Error::IoError(path.to_path_buf(), e)),
+        };
+        let mut dirs = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|e| Error::IoError(path.to_path_buf(), e))?;
+            let file_name = entry.file_name();
+            if let Some(idx) = file_name
+                .to_str()
+                .and_then(|name| name.strip_prefix(prefix))
+                .and_then(|idx| idx.parse::<u32>().ok())
+            {
+                dirs.push((idx, entry.path()));
+            }
+        }
+        Ok(dirs)
+    }
+
+    /// Files that fail to be read are skipped rather than failing all CPUs,
+    /// as some are unreadable depending on the driver.
+    pub fn read_cpu_freq(&self) -> Result<CpuFreqMap> {
+        let mut cpu_freq_map: CpuFreqMap = Default::default();
+        for (idx, path) in Self::list_indexed_dirs(&self.cpu_path, "cpu")? {
+            let read_u64 = |file: &str| Self::read_u64(&path.join(file)).unwrap_or(None);
+            let stat = CpuFreqStat {
+                scaling_cur_freq_khz: read_u64("cpufreq/scaling_cur_freq"),
+                core_throttle_count: read_u64("thermal_throttle/core_throttle_count"),
+                package_throttle_count: read_u64("thermal_throttle/package_throttle_count"),
+                physical_package_id: read_u64("topology/physical_package_id"),
+                core_id: read_u64("topology/core_id"),
+            };
+            if stat != Default::default() {
+                cpu_freq_map.insert(idx, stat);
+            }
+        }
+        Ok(cpu_freq_map)
+    }
+
+    pub fn read_thermal_zones(&self) -> Result<ThermalZoneMap> {
+        let mut thermal_zone_map: ThermalZoneMap = Default::default();
+        for (idx, path) in Self::list_indexed_dirs(&self.thermal_path, "thermal_zone")? {
+            let temp_path = path.join("temp");
+            // Some zones (e.g. wifi adapters that are down) fail reads of
+            // temp with ENODATA, so only treat malformed content as an error.
+            let temp_millicelsius = match Self::read_first_line(&temp_path) {
+                Ok(Some(line)) => parse_item!(temp_path, Some(line.as_str()), i64, line)?,
+                Ok(None) | Err(Error::IoError(_, _)) => None,
+                Err(e) => return Err(e),
+            };
+            thermal_zone_map.insert(
+                idx,
+                ThermalZoneStat {
+                    zone_type: Self::read_first_line(&path.join("type"))?,
+                    temp_millicelsius,
+                },
+            );
+        }
+        Ok(thermal_zone_map)
+    }
+}
+
 macro_rules! parse_interface_stats {
     ($net_stat:ident, $dir:ident, $cur_path: ident, $($stat:ident),*) => {
         $($net_stat.$stat = Self::read_iface_stat(&$dir, &$cur_path, stringify!($stat))?);*
@@ -22,6 +22,7 @@ use tempfile::TempDir;
 use crate::types::*;
 use crate::NetReader;
 use crate::ProcReader;
+use crate::SysfsReader;
 use crate::PAGE_SIZE;
 
 struct TestProcfs {
@@ -455,6 +456,41 @@ swap_ra_hit 139012
     assert_eq!(vmstat.pgscan_kswapd, Some(1_743_683_511));
     assert_eq!(vmstat.pgscan_direct, Some(5_877_901));
     assert_eq!(vmstat.oom_kill, Some(0));
+    assert_eq!(vmstat.pgfault, Some(62_574_548_442));
+    assert_eq!(vmstat.pgmajfault, Some(15_472_187));
+    assert_eq!(vmstat.pgactivate, Some(3_075_441_394));
+    assert_eq!(vmstat.pgdeactivate, Some(795_490_969));
+    assert_eq!(vmstat.pgrefill, Some(820_237_987));
+    assert_eq!(vmstat.kswapd_low_wmark_hit_quickly, Some(222_842));
+    assert_eq!(vmstat.pageoutrun, Some(350_010));
+    assert_eq!(vmstat.allocstall, Some(66_453));
+    assert_eq!(vmstat.compact_stall, Some(80_260));
+    assert_eq!(vmstat.compact_fail, Some(78_220));
+    assert_eq!(vmstat.compact_success, Some(2_040));
+    assert_eq!(vmstat.thp_fault_alloc, Some(290));
+    assert_eq!(vmstat.thp_fault_fallback, Some(397));
+    assert_eq!(vmstat.thp_split_page, Some(5));
+    assert_eq!(vmstat.workingset_refault, Some(353_022_586));
+    assert_eq!(vmstat.workingset_refault_anon, None);
+    assert_eq!(vmstat.workingset_restore, Some(78_012_002));
+}
+
+#[test]
+fn test_vmstat_split_counters() {
+    let vmstat = b"workingset_refault_anon 100
+workingset_refault_file 250
+allocstall 7
+";
+
+    let procfs = TestProcfs::new();
+    procfs.create_file_with_content("vmstat", vmstat);
+    let reader = procfs.get_reader();
+    let vmstat = reader.read_vmstat().expect("Failed to read vmstat file");
+
+    assert_eq!(vmstat.workingset_refault_anon, Some(100));
+    assert_eq!(vmstat.workingset_refault_file, Some(250));
+    assert_eq!(vmstat.workingset_refault, Some(350));
+    assert_eq!(vmstat.allocstall, Some(7));
 }
 
 #[test]
@@ -675,6 +711,228 @@ fn test_disk_stat() {
     assert_eq!(vda_stat.time_spend_discard_ms, Some(0));
 }
 
+#[test]
+fn test_interrupts() {
+    let interrupts = b"           CPU0       CPU1       
+  0:         44          0   IO-APIC   2-edge      timer
+  8:          0          1   IO-APIC   8-edge      rtc0
+ 24:    1203334     987654   PCI-MSI 524288-edge      eth0-TxRx-0
+NMI:          3          5   Non-maskable interrupts
+LOC:  123456789   98765432   Local timer interrupts
+ERR:          0
+MIS:          0
+";
+
+    let procfs = TestProcfs::new();
+    procfs.create_file_with_content("interrupts", interrupts);
+    let reader = procfs.get_reader();
+    let interrupts = reader
+        .read_interrupts()
+        .expect("Failed to read interrupts file");
+
+    assert_eq!(interrupts.len(), 7);
+    let irq = interrupts.get("24").expect("Failed to get IRQ 24");
+    assert_eq!(
+        irq.cpus,
+        Some(vec![(0, 1_203_334), (1, 987_654)].into_iter().collect())
+    );
+    assert_eq!(
+        irq.description,
+        Some("PCI-MSI 524288-edge eth0-TxRx-0".into())
+    );
+    let loc = interrupts.get("LOC").expect("Failed to get LOC");
+    assert_eq!(
+        loc.cpus,
+        Some(
+            vec![(0, 123_456_789), (1, 98_765_432)]
+                .into_iter()
+                .collect()
+        )
+    );
+    assert_eq!(loc.description, Some("Local timer interrupts".into()));
+    let err = interrupts.get("ERR").expect("Failed to get ERR");
+    assert_eq!(err.cpus, Some(vec![(0, 0)].into_iter().collect()));
+    assert_eq!(err.description, None);
+}
+
+#[test]
+fn test_softirqs() {
+    let softirqs = b"                    CPU0       CPU1
+          HI:          1          0
+       TIMER:    2385618    2184539
+      NET_TX:       1102        934
+      NET_RX:   13389483     230111
+       BLOCK:     421372     338520
+    IRQ_POLL:          0          0
+     TASKLET:         45         12
+       SCHED:    5478523    5261447
+     HRTIMER:          0          0
+         RCU:    3489632    3425019
+";
+
+    let procfs = TestProcfs::new();
+    procfs.create_file_with_content("softirqs", softirqs);
+    let reader = procfs.get_reader();
+    let softirqs = reader
+        .read_softirqs()
+        .expect("Failed to read softirqs file");
+
+    assert_eq!(softirqs.len(), 10);
+    let net_rx = softirqs.get("NET_RX").expect("Failed to get NET_RX");
+    assert_eq!(
+        net_rx.cpus,
+        Some(vec![(0, 13_389_483), (1, 230_111)].into_iter().collect())
+    );
+    assert_eq!(net_rx.description, None);
+}
+
+#[test]
+fn test_softirqs_offline_cpu() {
+    // CPU1 is offline
+    let softirqs = b"                    CPU0       CPU2
+          HI:          1          0
+      NET_RX:   13389483     230111
+";
+
+    let procfs = TestProcfs::new();
+    procfs.create_file_with_content("softirqs", softirqs);
+    let reader = procfs.get_reader();
+    let softirqs = reader
+        .read_softirqs()
+        .expect("Failed to read softirqs file");
+
+    let net_rx = softirqs.get("NET_RX").expect("Failed to get NET_RX");
+    assert_eq!(
+        net_rx.cpus,
+        Some(vec![(0, 13_389_483), (2, 230_111)].into_iter().collect())
+    );
+}
+
+#[test]
+fn test_softnet_stat() {
+    let softnet_stat = b"0d2b4c53 00000000 0000005c 00000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000
+00b45e7a 00000002 00000001 00000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000 00000001
+";
+
+    let procfs = TestProcfs::new();
+    procfs.create_dir("net");
+    procfs.create_file_with_content("net/softnet_stat", softnet_stat);
+    let reader = procfs.get_reader();
+    let softnet = reader
+        .read_softnet_stat()
+        .expect("Failed to read softnet_stat file");
+
+    assert_eq!(softnet.len(), 2);
+    assert_eq!(softnet[0].processed, Some(0x0d2b_4c53));
+    assert_eq!(softnet[0].dropped, Some(0));
+    assert_eq!(softnet[0].time_squeeze, Some(0x5c));
+    assert_eq!(softnet[1].processed, Some(0x00b4_5e7a));
+    assert_eq!(softnet[1].dropped, Some(2));
+    assert_eq!(softnet[1].time_squeeze, Some(1));
+    assert_eq!(softnet[1].cpu, Some(1));
+}
+
+#[test]
+fn test_softnet_stat_offline_cpu() {
+    // CPU 1 is offline, and has no row
+    let softnet_stat = b"0d2b4c53 00000000 0000005c 00000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000
+00b45e7a 00000002 00000001 00000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000 00000002
+";
+
+    let procfs = TestProcfs::new();
+    procfs.create_dir("net");
+    procfs.create_file_with_content("net/softnet_stat", softnet_stat);
+    let reader = procfs.get_reader();
+    let softnet = reader
+        .read_softnet_stat()
+        .expect("Failed to read softnet_stat file");
+
+    assert_eq!(softnet.len(), 2);
+    assert_eq!(softnet[0].cpu, Some(0));
+    assert_eq!(softnet[1].cpu, Some(2));
+}
+
+#[test]
+fn test_softnet_stat_no_cpu_column() {
+    // Kernels before 5.10 print 11 columns
+    let softnet_stat = b"0d2b4c53 00000000 0000005c 00000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000
+";
+
+    let procfs = TestProcfs::new();
+    procfs.create_dir("net");
+    procfs.create_file_with_content("net/softnet_stat", softnet_stat);
+    let reader = procfs.get_reader();
+    let softnet = reader
+        .read_softnet_stat()
+        .expect("Failed to read softnet_stat file");
+
+    assert_eq!(softnet.len(), 1);
+    assert_eq!(softnet[0].processed, Some(0x0d2b_4c53));
+    assert_eq!(softnet[0].cpu, None);
+}
+
+#[test]
+fn test_cpu_freq_and_thermal_zones() {
+    let sysfs = TestProcfs::new();
+    sysfs.create_dir("cpu/cpu0/cpufreq");
+    sysfs.create_dir("cpu/cpu0/thermal_throttle");
+    sysfs.create_file_with_content("cpu/cpu0/cpufreq/scaling_cur_freq", b"2400000\n");
+    sysfs.create_file_with_content("cpu/cpu0/thermal_throttle/core_throttle_count", b"3\n");
+    sysfs.create_file_with_content("cpu/cpu0/thermal_throttle/package_throttle_count", b"7\n");
+    // No cpufreq driver for this CPU
+    sysfs.create_dir("cpu/cpu1");
+    sysfs.create_dir("cpu/cpufreq");
+    sysfs.create_dir("thermal/thermal_zone0");
+    sysfs.create_file_with_content("thermal/thermal_zone0/type", b"x86_pkg_temp\n");
+    sysfs.create_file_with_content("thermal/thermal_zone0/temp", b"45000\n");
+    sysfs.create_dir("thermal/thermal_zone1");
+    sysfs.create_file_with_content("thermal/thermal_zone1/type", b"acpitz\n");
+    sysfs.create_dir("thermal/cooling_device0");
+    let reader =
+        SysfsReader::new_with_custom_path(sysfs.path().join("cpu"), sysfs.path().join("thermal"));
+
+    let cpu_freq = reader.read_cpu_freq().expect("Failed to read cpu freq");
+    assert_eq!(cpu_freq.len(), 1);
+    let cpu0 = cpu_freq.get(&0).expect("Failed to get cpu0");
+    assert_eq!(cpu0.scaling_cur_freq_khz, Some(2_400_000));
+    assert_eq!(cpu0.core_throttle_count, Some(3));
+    assert_eq!(cpu0.package_throttle_count, Some(7));
+
+    let thermal_zones = reader
+        .read_thermal_zones()
+        .expect("Failed to read thermal zones");
+    assert_eq!(thermal_zones.len(), 2);
+    let zone0 = thermal_zones.get(&0).expect("Failed to get thermal_zone0");
+    assert_eq!(zone0.zone_type, Some("x86_pkg_temp".into()));
+    assert_eq!(zone0.temp_millicelsius, Some(45000));
+    let zone1 = thermal_zones.get(&1).expect("Failed to get thermal_zone1");
+    assert_eq!(zone1.zone_type, Some("acpitz".into()));
+    assert_eq!(zone1.temp_millicelsius, None);
+}
+
+#[test]
+fn test_cpu_freq_bad_file() {
+    let sysfs = TestProcfs::new();
+    sysfs.create_dir("cpu/cpu0/cpufreq");
+    sysfs.create_dir("cpu/cpu0/thermal_throttle");
+    sysfs.create_dir("cpu/cpu0/topology");
+    sysfs.create_file_with_content("cpu/cpu0/cpufreq/scaling_cur_freq", b"<unsupported>\n");
+    sysfs.create_file_with_content("cpu/cpu0/thermal_throttle/core_throttle_count", b"3\n");
+    sysfs.create_file_with_content("cpu/cpu0/topology/physical_package_id", b"1\n");
+    sysfs.create_file_with_content("cpu/cpu0/topology/core_id", b"4\n");
+    let reader =
+        SysfsReader::new_with_custom_path(sysfs.path().join("cpu"), sysfs.path().join("thermal"));
+
+    let cpu_freq = reader.read_cpu_freq().expect("Failed to read cpu freq");
+    let cpu0 = cpu_freq.get(&0).expect("Failed to get cpu0");
+    // Only the malformed file is skipped
+    assert_eq!(cpu0.scaling_cur_freq_khz, None);
+    assert_eq!(cpu0.core_throttle_count, Some(3));
+    assert_eq!(cpu0.package_throttle_count, None);
+    assert_eq!(cpu0.physical_package_id, Some(1));
+    assert_eq!(cpu0.core_id, Some(4));
+}
+
 #[test]
 fn test_pid_stat() {
     let uptime = b"1631826.55 37530838.66";
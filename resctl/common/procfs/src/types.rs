@@ -267,6 +267,42 @@ pub struct VmStat {
     pub pgscan_kswapd: Option<u64>,
     pub pgscan_direct: Option<u64>,
     pub oom_kill: Option<u64>,
+    pub pgfault: Option<u64>,
+    pub pgmajfault: Option<u64>,
+    pub pgactivate: Option<u64>,
+    pub pgdeactivate: Option<u64>,
+    pub pgrefill: Option<u64>,
+    pub pgscan_direct_throttle: Option<u64>,
+    pub kswapd_inodesteal: Option<u64>,
+    pub kswapd_low_wmark_hit_quickly: Option<u64>,
+    pub kswapd_high_wmark_hit_quickly: Option<u64>,
+    pub pageoutrun: Option<u64>,
+    // Sum of allocstall_<zone> on kernels that split it per zone
+    pub allocstall: Option<u64>,
+    pub compact_migrate_scanned: Option<u64>,
+    pub compact_free_scanned: Option<u64>,
+    pub compact_isolated: Option<u64>,
+    pub compact_stall: Option<u64>,
+    pub compact_fail: Option<u64>,
+    pub compact_success: Option<u64>,
+    pub compact_daemon_wake: Option<u64>,
+    pub thp_fault_alloc: Option<u64>,
+    pub thp_fault_fallback: Option<u64>,
+    pub thp_collapse_alloc: Option<u64>,
+    pub thp_collapse_alloc_failed: Option<u64>,
+    pub thp_split_page: Option<u64>,
+    pub thp_split_page_failed: Option<u64>,
+    pub thp_deferred_split_page: Option<u64>,
+    pub thp_split_pmd: Option<u64>,
+    pub thp_swpout: Option<u64>,
+    pub thp_swpout_fallback: Option<u64>,
+    // Sum of the anon and file counters on kernels that split them
+    pub workingset_refault: Option<u64>,
+    pub workingset_refault_anon: Option<u64>,
+    pub workingset_refault_file: Option<u64>,
+    pub workingset_activate: Option<u64>,
+    pub workingset_restore: Option<u64>,
+    pub workingset_nodereclaim: Option<u64>,
 }
 
 #[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
@@ -288,6 +324,40 @@ pub struct DiskStat {
     pub time_spend_discard_ms: Option<u64>,
 }
 
+// Per-CPU counters for a single line of /proc/interrupts or /proc/softirqs
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct InterruptStat {
+    // Keyed by CPU id, offline CPUs are not listed
+    pub cpus: Option<BTreeMap<u32, u64>>,
+    // Trailing chip/type/device text. Always None for softirqs
+    pub description: Option<String>,
+}
+
+// Per-CPU row of /proc/net/softnet_stat
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SoftnetStat {
+    pub processed: Option<u64>,
+    pub dropped: Option<u64>,
+    pub time_squeeze: Option<u64>,
+    pub cpu: Option<u64>,
+}
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct CpuFreqStat {
+    pub scaling_cur_freq_khz: Option<u64>,
+    pub core_throttle_count: Option<u64>,
+    pub package_throttle_count: Option<u64>,
+    // Topology of the CPU, as throttle counts are per core and per package
+    pub physical_package_id: Option<u64>,
+    pub core_id: Option<u64>,
+}
+
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ThermalZoneStat {
+    pub zone_type: Option<String>,
+    pub temp_millicelsius: Option<i64>,
+}
+
 #[derive(Clone, PartialEq, PartialOrd, Debug, Serialize, Deserialize)]
 pub enum PidState {
     Running,
@@ -347,11 +417,102 @@ pub struct PidInfo {
     pub cmdline_vec: Option<Vec<String>>,
     pub exe_path: Option<String>,
     pub mem: PidMem,
+    // Id of the cgroup of a process captured on exit by bpf, resolved into
+    // cgroup by the collector and thus not stored
+    #[serde(skip)]
+    pub cgroup_id: Option<u64>,
+    // Accounted by bpf rather than read from procfs, None if not enabled
+    pub net_bytes: Option<NetBytes>,
+}
+
+// Bytes sent and received over inet sockets since below started
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct NetBytes {
+    pub tcp_send_bytes: Option<u64>,
+    pub tcp_recv_bytes: Option<u64>,
+    pub udp_send_bytes: Option<u64>,
+    pub udp_recv_bytes: Option<u64>,
+}
+
+// NetBytes accumulated by bpf, keyed by tgid and by cgroup id respectively
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct NetBytesMaps {
+    pub tgids: BTreeMap<i32, NetBytes>,
+    pub cgroups: BTreeMap<u64, NetBytes>,
+    // Last known path of the cgroup ids in cgroups, to fold the bytes of a
+    // removed cgroup into its parent
+    pub cgroup_paths: BTreeMap<u64, String>,
+}
+
+// Histogram of how long tasks waited on a run queue since below started, in
+// log2 buckets: bucket i counts waits of [2^i, 2^(i+1)) usecs, except bucket
+// 0 which counts waits under 2 usecs. Trailing empty buckets are omitted.
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct RunqLatency {
+    pub buckets: Vec<u64>,
+}
+
+// RunqLatency accumulated by bpf, keyed by cgroup id
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct RunqLatencyMap {
+    pub cgroups: BTreeMap<u64, RunqLatency>,
+    // Last known path of the cgroup ids in cgroups, to fold the histogram of
+    // a removed cgroup into its parent
+    pub cgroup_paths: BTreeMap<u64, String>,
+}
+
+// A process killed by the OOM killer, reported by bpf
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct OomKill {
+    pub pid: Option<i32>,
+    pub comm: Option<String>,
+    // Path of the cgroup of the victim, None if it could not be resolved
+    pub cgroup: Option<String>,
+    pub rss_bytes: Option<u64>,
+    pub oom_score_adj: Option<i32>,
+    // Why memory was constrained: none, cpuset, memory_policy or memcg
+    pub constraint: Option<String>,
+    // Path of the cgroup whose memory limit was hit, None unless the
+    // constraint is memcg
+    pub oom_cgroup: Option<String>,
+    // Memory the OOM killer chose the victim from, i.e. the limit of the
+    // cgroup or the memory of the system
+    pub total_bytes: Option<u64>,
+    // The process whose allocation failed
+    pub trigger_pid: Option<i32>,
+    pub trigger_comm: Option<String>,
+}
+
+// A process forked, exec'd or exited, reported by bpf
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ProcEvent {
+    // fork, exec or exit
+    pub event: Option<String>,
+    pub time_epoch_ms: Option<u64>,
+    pub pid: Option<i32>,
+    pub ppid: Option<i32>,
+    pub comm: Option<String>,
+    // Cmdline of the program last exec'd, None if it was exec'd before
+    // recording started. May be truncated.
+    pub cmdline_vec: Option<Vec<String>>,
+    // Path of the cgroup of the process, None if it could not be resolved
+    pub cgroup: Option<String>,
+    pub start_epoch_ms: Option<u64>,
+    // Exit events only
+    pub end_epoch_ms: Option<u64>,
+    // Exit events only. 128 + the signal number if killed by a signal, as
+    // reported by shells.
+    pub exit_code: Option<i32>,
 }
 
 pub type PidMap = BTreeMap<i32, PidInfo>;
 pub type NetMap = BTreeMap<String, InterfaceStat>;
 pub type DiskMap = BTreeMap<String, DiskStat>;
+pub type InterruptMap = BTreeMap<String, InterruptStat>;
+// Keyed by CPU index
+pub type CpuFreqMap = BTreeMap<u32, CpuFreqStat>;
+// Keyed by thermal zone index
+pub type ThermalZoneMap = BTreeMap<u32, ThermalZoneStat>;
 
 #[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct NetStat {